@@ -0,0 +1,81 @@
+// ----------------------------------------------------------------------------
+// Benchmarks for the slow paths of big task lists: reading and writing iCal
+// files, sorting, and showing the list. Run them with `cargo bench`, and
+// compare against a baseline with `cargo bench -- --baseline <name>`.
+// ----------------------------------------------------------------------------
+
+use std::ops::ControlFlow;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use eframe::egui;
+
+use taskmaster_rs::task::{TaskList, TaskSort};
+use taskmaster_rs::task_views::{ClassicView, TaskView, ViewOptions};
+
+// How many tasks are in the lists that are benchmarked
+const SIZES: [usize; 2] = [1_000, 10_000];
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for size in SIZES {
+        let ical = TaskList::generate_random(size).to_ical_string();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &ical, |b, ical| {
+            b.iter(|| {
+                TaskList::from_ical_reader_with_progress(ical.as_bytes(), |_| {
+                    ControlFlow::Continue(())
+                })
+                .expect("could not parse the generated list")
+            });
+        });
+    }
+    group.finish();
+}
+
+fn serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize");
+    for size in SIZES {
+        let list = TaskList::generate_random(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &list, |b, list| {
+            b.iter(|| list.to_ical_string());
+        });
+    }
+    group.finish();
+}
+
+fn sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort");
+    let list = TaskList::generate_random(10_000);
+    for sort_by in TaskSort::iterator() {
+        group.bench_function(format!("{sort_by:?}"), |b| {
+            b.iter(|| list.display_order(*sort_by));
+        });
+    }
+    group.finish();
+}
+
+// Shows the list in the classic view for one frame, without a window
+fn render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+    for size in SIZES {
+        let mut list = TaskList::generate_random(size);
+        let ctx = egui::Context::default();
+        let mut view = ClassicView::default();
+        let options = ViewOptions {
+            show_completed_tasks: true,
+            ..Default::default()
+        };
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| {
+                ctx.run(egui::RawInput::default(), |ctx| {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        view.display(ui, &mut list, &options);
+                    });
+                })
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse, serialize, sort, render);
+criterion_main!(benches);
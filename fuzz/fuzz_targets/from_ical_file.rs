@@ -0,0 +1,22 @@
+// ----------------------------------------------------------------------------
+// Fuzz target for reading iCal files. Whatever a file contains, reading it
+// should give an error rather than crashing, and a list that could be read
+// should be exported in a way that can be read back in.
+// ----------------------------------------------------------------------------
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use taskmaster_rs::task::TaskList;
+
+fn parse(bytes: &[u8]) -> Result<TaskList, taskmaster_rs::parser::ParseFromFileError> {
+    TaskList::from_ical_reader(bytes)
+}
+
+// from_ical_file opens the file and reads it in the same way, so the bytes are
+// read directly rather than being written to a file for every run
+fuzz_target!(|data: &[u8]| {
+    if let Ok(list) = parse(data) {
+        parse(list.to_ical_string().as_bytes()).expect("could not read an exported list");
+    }
+});
@@ -0,0 +1,338 @@
+// ----------------------------------------------------------------------------
+// Daily agenda, which summarises the tasks that are due today or overdue.
+// Once a day, at a time the user picks, the agenda is written to a file
+// and, if the app was built with the `email` feature, emailed over SMTP.
+// ----------------------------------------------------------------------------
+
+use std::{error::Error, fmt::Write, fs, path::Path, thread, time::Duration};
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::json::DATE_FORMAT;
+use crate::network::NetworkSettings;
+use crate::task::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct AgendaSettings {
+    pub enabled: bool,
+    // Local time of day that the agenda is made at
+    pub hour: u32,
+    pub minute: u32,
+    pub format: AgendaFormat,
+    // Folder that agenda files are written to. If empty, no file is written.
+    pub directory: String,
+    pub email: EmailSettings,
+}
+
+impl Default for AgendaSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hour: 7,
+            minute: 0,
+            format: AgendaFormat::default(),
+            directory: String::new(),
+            email: EmailSettings::default(),
+        }
+    }
+}
+
+// Where and how to email the agenda
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailSettings {
+    pub enabled: bool,
+    pub server: String,
+    pub port: u16,
+    pub username: String,
+    // Kept in the keyring, not with the other settings
+    #[serde(skip)]
+    pub password: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server: String::new(),
+            // The usual port for sending mail with STARTTLS
+            port: 587,
+            username: String::new(),
+            password: String::new(),
+            from: String::new(),
+            to: String::new(),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub enum AgendaFormat {
+    #[default]
+    Text,
+    Html,
+}
+
+impl AgendaFormat {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        [Self::Text, Self::Html].iter()
+    }
+
+    // File extension used for agendas in this format
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// Creates an agenda listing every incomplete task in a list which is
+/// overdue or due on the given day.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::agenda::{self, AgendaFormat};
+/// use taskmaster_rs::task::{Task, TaskList};
+///
+/// let today = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap();
+/// let mut list = TaskList {
+///     name: String::from("Work"),
+///     ..Default::default()
+/// };
+/// list.add(Task {
+///     summary: String::from("Send report"),
+///     due: Some(today),
+///     ..Default::default()
+/// });
+/// list.add(Task {
+///     summary: String::from("Book flights"),
+///     due: today.pred_opt(),
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(
+///     agenda::format(&list, today, AgendaFormat::Text),
+///     "Agenda for Work on Monday 7 August 2023
+///
+/// Overdue
+///   - Book flights (due 2023-08-06)
+///
+/// Due today
+///   - Send report
+/// ",
+/// );
+/// ```
+pub fn format(task_list: &TaskList, today: NaiveDate, format: AgendaFormat) -> String {
+    let incomplete = || task_list.tasks.iter().filter(|task| !task.completed);
+    let overdue: Vec<&Task> = incomplete()
+        .filter(|task| task.due.is_some_and(|due| due < today))
+        .collect();
+    let due_today: Vec<&Task> = incomplete()
+        .filter(|task| task.due == Some(today))
+        .collect();
+
+    let title = format!(
+        "Agenda for {} on {}",
+        task_list.name,
+        today.format("%A %-d %B %Y")
+    );
+    let sections = [("Overdue", overdue), ("Due today", due_today)];
+
+    match format {
+        AgendaFormat::Text => text(&title, &sections, today),
+        AgendaFormat::Html => html(&title, &sections, today),
+    }
+}
+
+// Formats an agenda as plain text
+fn text(title: &str, sections: &[(&str, Vec<&Task>)], today: NaiveDate) -> String {
+    let mut text = format!("{title}\n");
+    for (heading, tasks) in sections {
+        let _ = write!(text, "\n{heading}\n");
+        if tasks.is_empty() {
+            text.push_str("  Nothing\n");
+        }
+        for task in tasks {
+            let _ = writeln!(text, "  - {}", describe(task, today, str::to_string));
+        }
+    }
+    text
+}
+
+// Formats an agenda as an HTML page
+fn html(title: &str, sections: &[(&str, Vec<&Task>)], today: NaiveDate) -> String {
+    let title = escape_html(title);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{title}</title></head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    for (heading, tasks) in sections {
+        let _ = writeln!(html, "<h2>{heading}</h2>");
+        if tasks.is_empty() {
+            html.push_str("<p>Nothing</p>\n");
+            continue;
+        }
+        html.push_str("<ul>\n");
+        for task in tasks {
+            let _ = writeln!(html, "<li>{}</li>", describe(task, today, escape_html));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// Describes a task in one line, using the given function to make text safe for the format
+fn describe(task: &Task, today: NaiveDate, escape: impl Fn(&str) -> String) -> String {
+    let mut line = escape(&task.summary);
+    // Tasks due today don't need their due date repeated
+    if let Some(due) = task.due.filter(|due| *due < today) {
+        let _ = write!(line, " (due {})", due.format(DATE_FORMAT));
+    }
+    if task.priority.is_set() {
+        let _ = write!(line, " [priority {}]", task.priority);
+    }
+    line
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Makes the agenda once a day, at the time set in the settings
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct AgendaScheduler {
+    // The last day that an agenda was made for
+    last_made: Option<NaiveDate>,
+}
+
+impl AgendaScheduler {
+    // Makes the agenda if it's due, and makes sure the app wakes up when the next one is.
+    // Should be called once every frame.
+    pub fn check(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        settings: &AgendaSettings,
+        network: &NetworkSettings,
+    ) {
+        if !settings.enabled {
+            return;
+        }
+        let Some(time) = NaiveTime::from_hms_opt(settings.hour, settings.minute, 0) else {
+            return;
+        };
+
+        let now = Local::now().naive_local();
+        let today = now.date();
+        let made_today = self.last_made == Some(today);
+
+        if !made_today && now.time() >= time {
+            self.last_made = Some(today);
+            make(task_list, today, settings, network);
+        }
+
+        // Wake the app up in time for the next agenda, even if nothing else is happening
+        let next_day = if made_today || now.time() >= time {
+            today.succ_opt()
+        } else {
+            Some(today)
+        };
+        if let Some(next) = next_day.map(|day| NaiveDateTime::new(day, time)) {
+            if let Ok(wait) = (next - now).to_std() {
+                ctx.request_repaint_after(wait);
+            }
+        }
+    }
+}
+
+// Makes an agenda and delivers it in the background, so that
+// writing the file or talking to the mail server doesn't freeze the app.
+// Mail servers are given as long to answer as any other server.
+fn make(
+    task_list: &TaskList,
+    today: NaiveDate,
+    settings: &AgendaSettings,
+    network: &NetworkSettings,
+) {
+    let agenda = format(task_list, today, settings.format);
+    let subject = format!("Agenda for {} on {today}", task_list.name);
+    let settings = settings.clone();
+    let timeout = network.timeout();
+
+    thread::spawn(move || {
+        if !settings.directory.trim().is_empty() {
+            let file_name = format!("agenda-{today}.{}", settings.format.extension());
+            let path = Path::new(&settings.directory).join(file_name);
+            if let Err(e) = fs::write(&path, &agenda) {
+                eprintln!("could not write agenda to {}: {e}", path.display());
+            }
+        }
+
+        if settings.email.enabled {
+            if let Err(e) = send_email(&settings.email, settings.format, &subject, agenda, timeout)
+            {
+                eprintln!("could not email agenda: {e}");
+            }
+        }
+    });
+}
+
+#[cfg(feature = "email")]
+fn send_email(
+    email: &EmailSettings,
+    format: AgendaFormat,
+    subject: &str,
+    body: String,
+    timeout: Duration,
+) -> Result<(), Box<dyn Error>> {
+    use lettre::message::header::ContentType;
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{Message, SmtpTransport, Transport};
+
+    let content_type = match format {
+        AgendaFormat::Text => ContentType::TEXT_PLAIN,
+        AgendaFormat::Html => ContentType::TEXT_HTML,
+    };
+    let message = Message::builder()
+        .from(email.from.parse()?)
+        .to(email.to.parse()?)
+        .subject(subject)
+        .header(content_type)
+        .body(body)?;
+
+    let mut transport = SmtpTransport::starttls_relay(&email.server)?
+        .port(email.port)
+        .timeout(Some(timeout));
+    if !email.username.is_empty() {
+        transport = transport.credentials(Credentials::new(
+            email.username.clone(),
+            email.password.clone(),
+        ));
+    }
+    transport.build().send(&message)?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "email"))]
+fn send_email(
+    _email: &EmailSettings,
+    _format: AgendaFormat,
+    _subject: &str,
+    _body: String,
+    _timeout: Duration,
+) -> Result<(), Box<dyn Error>> {
+    Err("this build of the app can't send email, it needs the `email` feature".into())
+}
@@ -4,229 +4,1032 @@
 // and runs the update loop as it is implemented below.
 // ----------------------------------------------------------------------------
 
-use std::fs::File;
-use std::io::Write;
 use std::path::PathBuf;
 
 use eframe::egui;
-use egui_file::FileDialog;
 
-use crate::parser::ParseFromFileError;
+use crate::agenda::AgendaScheduler;
+use crate::backup::BackupScheduler;
+use crate::config::{self, Config, ConfigWatcher, Shortcut};
+use crate::counters::ListCounters;
+use crate::duplicates::{self, DuplicateAction};
+use crate::encryption::{self, EncryptionError, EncryptionKey};
+use crate::events::TaskWatcher;
+use crate::git_store::GitStore;
+use crate::github::{GitHub, GitHubConfig};
+use crate::hooks::{HookEvent, HookRunner};
+use crate::jira::{Jira, JiraConfig};
+use crate::layouts::{self, LayoutStore};
+use crate::list_file::{self, FileSync, FileWatcher};
+use crate::notifications::Notifier;
+use crate::parser::{IcalImport, ParseFromFileError};
+use crate::platform;
+use crate::profiles;
+use crate::publish::Publisher;
+use crate::reminders::{MissedReminders, ReminderInbox};
+use crate::secrets;
+use crate::settings::{LayoutMode, Settings};
+use crate::share;
+use crate::sounds::{Sound, SoundPlayer};
+use crate::sync::{SyncBackend, SyncContext, SyncManager};
+use crate::sync_log::SyncLog;
+use crate::tags::TagRegistry;
 use crate::task::*;
 use crate::task_views::*;
+use crate::timezone::{self, TimeZoneChange, TimeZonePolicy, TimeZoneWatcher};
+use crate::transfer::{self, TransferMode};
 use crate::ui_elements;
+use crate::ui_elements::history::HistoryWindow;
+use crate::ui_elements::import_export::{ImportExportAction, ImportExportPanel};
+use crate::ui_elements::import_preview::{ImportAction, ImportPreview};
+use crate::ui_elements::merge_split::MergeSplitWindow;
+use crate::ui_elements::missed_reminders::MissedRemindersWindow;
+use crate::ui_elements::passphrase::{PassphraseResponse, PassphraseWindow};
+use crate::ui_elements::paste_list::PasteListWindow;
+use crate::ui_elements::restore::RestoreWindow;
+use crate::ui_elements::selection::Selection;
+use crate::ui_elements::statistics::StatisticsWindow;
+use crate::ui_elements::tag_manager::TagManagerWindow;
+use crate::ui_elements::task_input::{TaskInputAction, TaskInputPanel};
+use crate::ui_elements::task_list::TaskListPanel;
+use crate::ui_elements::time_zone::TimeZoneWindow;
+use crate::ui_elements::transfer::TransferWindow;
+use crate::webhooks::WebhookSender;
+use crate::window_title::WindowTitle;
+
+// Why the app is asking the user for a passphrase
+enum PassphraseRequest {
+    // To unlock the encrypted list that was saved last time the app was open
+    UnlockStore(Vec<u8>),
+    // To unlock an encrypted file that's being imported
+    UnlockImport(Vec<u8>),
+    // To choose the passphrase that lists are encrypted with
+    Choose,
+}
+
+// Windows narrower than this, in points, use the mobile layout
+// when the layout is picked automatically
+const NARROW_WIDTH: f32 = 500.;
+// How big everything is drawn, unless a different scale is asked for
+const DEFAULT_SCALE: f32 = 1.75;
 
 #[derive(Default)]
 pub struct App {
     // Stores application state
-    input_task: Task,
     input_task_list: TaskList,
-    show_completed_tasks: bool,
-    sort_by: TaskSort,
-    show_full_edit: bool,
-    // Stores the file dialog to choose which task list file to import
-    import_dialog: Option<FileDialog>,
-    // Stores the list parsed from the imported file
-    imported_list: Option<Result<TaskList, ParseFromFileError>>,
-    // Stores the file dialog to choose where to export the task list to a file
-    export_dialog: Option<FileDialog>,
+    // The panels the main window is made of, which each keep their own state
+    import_export_panel: ImportExportPanel,
+    task_input_panel: TaskInputPanel,
+    task_list_panel: TaskListPanel,
+    // Tasks picked in the list for the toolbar above it, kept by UUID so they
+    // stay picked when the list is sorted differently
+    selection: Selection,
+    // How each list was laid out in the task list panel, and which list the panel is laid out for
+    layouts: LayoutStore,
+    layout_key: Option<String>,
+    // Stores the list parsed from the imported file, ready for the user to pick tasks from
+    imported_list: Option<Result<ImportPreview, ParseFromFileError>>,
+    // Where the imported file is, so that the list can be bound to it if it replaces the current one
+    import_path: Option<PathBuf>,
+    // Watches the file the list is bound to for changes made by other apps
+    file_watcher: FileWatcher,
+    // Keeps the task list saved between runs on platforms that need it
+    list_store: platform::ListStore,
+    // Key that saved and exported lists are encrypted with, when encryption is turned on
+    encryption_key: Option<EncryptionKey>,
+    // How many UUIDs are shared by more than one task in the list that was just loaded
+    uuid_collisions: usize,
+    // A task the user tried to add which looks like one already in the list
+    pending_duplicate: Option<Task>,
+    // Makes backups of the task list every so often
+    backup_scheduler: BackupScheduler,
+    // Window listing backups that can be restored, if it's open
+    restore_window: Option<RestoreWindow>,
+    // Window showing the list's history in git, if it's open
+    history_window: Option<HistoryWindow>,
+    // Window for renaming and merging tags, if it's open
+    tag_manager: Option<TagManagerWindow>,
+    // Window for turning a pasted list into tasks
+    paste_list: Option<PasteListWindow>,
+    // Window for moving or copying a task to another list
+    transfer_window: Option<TransferWindow>,
+    // Window for merging lists and splitting this one, if it's open
+    merge_split: Option<MergeSplitWindow>,
+    // How many tasks are pending and overdue in each list, for the sidebar
+    list_counters: ListCounters,
+    // Window asking the user for a passphrase, and why it's being asked for
+    passphrase_request: Option<(PassphraseRequest, PassphraseWindow)>,
+    // Stores the user's settings, and how they were when they were last saved
+    settings: Settings,
+    saved_settings: Settings,
+    show_settings: bool,
+    // Window showing statistics about the list, if it's open
+    statistics: Option<StatisticsWindow>,
+    // Settings from the config file, and what watches it for changes
+    config: Config,
+    config_watcher: ConfigWatcher,
+    // Watches the task list for changes, and tells the parts of the app that react to them
+    task_watcher: TaskWatcher,
+    // Runs the hook commands for changes to the task list
+    hook_runner: HookRunner,
+    // Sends what happens to tasks to the webhook URLs
+    webhook_sender: WebhookSender,
+    // Shows notifications for tasks that are due soon, and does what's clicked on them
+    notifier: Notifier,
+    // Finds reminders that were missed while the app was closed or the computer was asleep
+    missed_reminders: MissedReminders,
+    // Window listing the missed reminders, if there were any
+    missed_reminders_window: Option<MissedRemindersWindow>,
+    // Reminders listed under the bell until they're dismissed
+    reminder_inbox: ReminderInbox,
+    // Notices when the time zone changes, and the window asking what to do about it
+    time_zone_watcher: TimeZoneWatcher,
+    time_zone_window: Option<TimeZoneWindow>,
+    // Plays sounds when tasks are completed or become due soon
+    sound_player: SoundPlayer,
+    // Makes the daily agenda
+    agenda_scheduler: AgendaScheduler,
+    // Syncs the list to where it's bound to, like its file, a WebDAV server or a git repository
+    sync: SyncManager,
+    // The settings the list is synced with GitHub with, which are kept up to date
+    github: GitHubConfig,
+    // The same for importing issues from Jira
+    jira: JiraConfig,
+    // How syncing the list to other places is going, and what happened each time
+    sync_log: SyncLog,
+    show_sync_log: bool,
+    // Celebrates when the last task that needed doing is completed
+    celebration: ui_elements::celebration::Celebration,
+    // Keeps the window's title showing how many tasks are due
+    window_title: WindowTitle,
+    // Shows how many tasks are due today on the app's icon
+    badge: platform::badge::Badge,
+    // Keeps the app launching at login if the user has asked it to
+    autostart: platform::autostart::Autostart,
+    // Whether the window should be minimized once it's shown
+    minimize: bool,
+    // How big everything is drawn, if it's been changed from the default
+    scale: Option<f32>,
+    // Stores the embedded HTTP server, if it started successfully
+    #[cfg(feature = "server")]
+    server: Option<crate::server::Server>,
+    // Serves the list as a calendar feed, if it's turned on
+    #[cfg(feature = "server")]
+    feed: crate::feed::CalendarFeed,
 }
 
 impl App {
     // Defines the default application state
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
-            show_completed_tasks: false,
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        // Use the profile that was used last time
+        profiles::restore();
+
+        let app = Self::start(&cc.egui_ctx);
+
+        // Start the HTTP server. If it can't start, the app still works without it
+        #[cfg(feature = "server")]
+        let app = Self {
+            server: crate::server::Server::start(&crate::server::address(), cc.egui_ctx.clone())
+                .map_err(|e| eprintln!("could not start server: {e}"))
+                .ok(),
+            ..app
+        };
+
+        app
+    }
+
+    // Sets up the app from the files of the profile being used
+    fn start(ctx: &egui::Context) -> Self {
+        let mut app = Self {
+            missed_reminders: MissedReminders::load(),
+            reminder_inbox: ReminderInbox::load(),
+            sync: SyncManager::load(),
+            sync_log: SyncLog::load(),
+            time_zone_watcher: TimeZoneWatcher::load(),
+            layouts: LayoutStore::load(),
+            config_watcher: ConfigWatcher::new(),
+            settings: Settings {
+                tags: TagRegistry::load(),
+                ..Settings::load()
+            },
             ..Default::default() // Everything else is default
+        };
+        app.apply_config(ctx, config::load());
+        let events = app.task_watcher.subscribe();
+        app.hook_runner.listen(events);
+        let events = app.task_watcher.subscribe();
+        app.webhook_sender.listen(events);
+        let mut git_store = GitStore::default();
+        git_store.listen(app.task_watcher.subscribe());
+        app.sync.add_syncer(Box::new(FileSync::default()));
+        app.sync.add_syncer(Box::new(Publisher::default()));
+        app.sync.add_syncer(Box::new(git_store));
+        let github = GitHub::default();
+        app.github = github.config();
+        app.sync.register(github);
+        let jira = Jira::default();
+        app.jira = jira.config();
+        app.sync.register(jira);
+        let events = app.task_watcher.subscribe();
+        app.list_counters.listen(events);
+        let events = app.task_watcher.subscribe();
+        app.celebration.listen(events);
+
+        // Passwords are kept in the keyring rather than with the other settings
+        app.settings.publish.password = secrets::get(secrets::PUBLISH_PASSWORD).unwrap_or_default();
+        app.settings.agenda.email.password =
+            secrets::get(secrets::EMAIL_PASSWORD).unwrap_or_default();
+        app.settings.github.token = secrets::get(secrets::GITHUB_TOKEN).unwrap_or_default();
+        app.settings.jira.token = secrets::get(secrets::JIRA_TOKEN).unwrap_or_default();
+        app.settings.feed.token = secrets::get(secrets::FEED_TOKEN).unwrap_or_default();
+        app.settings.api_token = secrets::get(secrets::API_TOKEN).unwrap_or_default();
+
+        // The settings for launching at login are kept by the platform, not the app
+        app.settings.startup = platform::autostart::current();
+        app.autostart = platform::autostart::Autostart::new(app.settings.startup);
+        app.saved_settings = app.settings.clone();
+
+        // Load the list that was open last time, if the platform keeps it
+        if let Some(saved) = platform::ListStore::load() {
+            if encryption::is_encrypted(&saved) {
+                // An encrypted list can't be shown until the user unlocks it
+                app.settings.encrypt = true;
+                let window = PassphraseWindow::unlock(
+                    "Unlock task list",
+                    "Your task list is encrypted. Enter its passphrase to open it.",
+                    false,
+                );
+                app.passphrase_request = Some((PassphraseRequest::UnlockStore(saved), window));
+            } else if let Ok(list) = TaskList::from_ical_reader(saved.as_slice()) {
+                app.replace_list(list);
+            }
+        } else if let Some(path) = list_file::bound_files()
+            .into_iter()
+            .next()
+            .or_else(|| app.config.default_list.clone())
+        {
+            // Otherwise open the file the list was bound to, or the one named in the config file
+            app.load_file(path);
         }
+
+        app
     }
-}
 
-// Define how the app behaves based on the app state
-#[allow(clippy::too_many_lines)]
-impl eframe::App for App {
-    // - Main render loop function ----------------------------------------
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // - Main UI panel ---------------------------
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // Set global ui scale
-            ctx.set_pixels_per_point(1.75);
+    // Switches to another profile, starting again from its files and settings.
+    // Views and sync backends that were added to the app, and the HTTP server and calendar feed, are kept.
+    fn switch_profile(&mut self, ctx: &egui::Context, name: &str) {
+        // Make sure nothing is lost from the profile being left
+        if !self.store_locked() {
+            self.list_store
+                .save(&self.input_task_list, self.encrypting());
+        }
+        self.settings.save();
+        if let Err(e) = profiles::switch(name) {
+            eprintln!("could not switch to profile {name}: {e}");
+            return;
+        }
 
-            // Set spacing between panels
-            ui.spacing_mut().item_spacing = egui::Vec2::splat(7.5);
+        let mut app = Self::start(ctx);
+        std::mem::swap(&mut app.task_list_panel, &mut self.task_list_panel);
+        app.sync.keep_registered(&mut self.sync);
+        #[cfg(feature = "server")]
+        {
+            app.server = self.server.take();
+            app.feed = std::mem::take(&mut self.feed);
+        }
+        app.scale = self.scale;
+        *self = app;
+    }
 
-            // Task list import/export panel
-            ui_elements::basic_frame().show(ui, |ui| {
-                ui.set_width(ui.available_width());
+    // Adds a task view to the view selector.
+    // This lets crates that run the app add their own views without changing this one.
+    #[must_use]
+    pub fn with_view(mut self, view: Box<dyn TaskView>) -> Self {
+        self.task_list_panel.register_view(view);
+        self
+    }
+
+    // Adds somewhere lists can be synced to, which they can be bound to next to the list's name.
+    // This lets crates that run the app sync with other services without changing this one.
+    #[must_use]
+    pub fn with_sync_backend(mut self, backend: impl SyncBackend + 'static) -> Self {
+        self.sync.register(backend);
+        self
+    }
+
+    // Starts the app minimized, like when it's launched at login
+    #[must_use]
+    pub const fn minimized(mut self) -> Self {
+        self.minimize = true;
+        self
+    }
+
+    // Uses the settings from the config file, such as when it's been edited
+    fn apply_config(&mut self, ctx: &egui::Context, config: Config) {
+        config.theme.apply(ctx);
+        if let Some(days) = config.due_soon_days {
+            self.settings.hooks.due_soon_days = days;
+        }
+        self.config = config;
+    }
+
+    // Does what a keyboard shortcut from the config file is for
+    fn handle_shortcut(&mut self, ctx: &egui::Context, shortcut: Shortcut) {
+        match shortcut {
+            Shortcut::OpenFile => self.import_export_panel.open_import_dialog(ctx),
+            Shortcut::Export => self.import_export_panel.open_export_dialog(
+                ctx,
+                &self.input_task_list,
+                self.encrypting().is_some(),
+                self.settings.export,
+            ),
+            Shortcut::Reload => self.handle_import_export(ctx, ImportExportAction::Reload),
+            Shortcut::ToggleCompleted => self.task_list_panel.toggle_completed(),
+            Shortcut::Settings => {
+                self.handle_import_export(ctx, ImportExportAction::ToggleSettings);
+            }
+        }
+    }
+
+    // Opens a list file instead of the one that was open last time
+    #[must_use]
+    pub fn with_list(mut self, path: PathBuf) -> Self {
+        self.load_file(path);
+        self
+    }
+
+    // Shows the list in the view with the given name, ignoring case.
+    // If there's no view with that name, the view isn't changed.
+    #[must_use]
+    pub fn in_view(mut self, name: &str) -> Self {
+        if !self.task_list_panel.select_view(name) {
+            eprintln!("there's no view called '{name}'");
+        }
+        // This view is used instead of the one the list was last shown in
+        self.layout_key = layouts::key(&self.input_task_list);
+        self
+    }
+
+    // Draws everything bigger or smaller. Scales that aren't above zero are ignored.
+    #[must_use]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        if scale > 0. {
+            self.scale = Some(scale);
+        } else {
+            eprintln!("the scale has to be more than zero");
+        }
+        self
+    }
+
+    // Adds a task the user made in the task input panel or add bar
+    fn handle_task_input(&mut self, action: TaskInputAction) {
+        match action {
+            TaskInputAction::Add(task) => {
+                // Enable showing completed tasks if the task
+                // that was just added is marked as complete.
+                if task.completed {
+                    self.task_list_panel.show_completed();
+                }
+                self.add_task(task);
+            }
+            TaskInputAction::FromClipboard(task) => self.add_task(task),
+            TaskInputAction::PasteList => self.paste_list = Some(PasteListWindow::default()),
+        }
+    }
+
+    // Does what the user asked for in the import and export panel
+    fn handle_import_export(&mut self, ctx: &egui::Context, action: ImportExportAction) {
+        match action {
+            // Encrypted files need a passphrase before they can be read
+            ImportExportAction::Import(file) if encryption::is_encrypted(&file.contents) => {
+                self.import_path = file.path;
+                let window = PassphraseWindow::unlock(
+                    "Unlock file",
+                    "This file is encrypted. Enter its passphrase to open it.",
+                    true,
+                );
+                self.passphrase_request =
+                    Some((PassphraseRequest::UnlockImport(file.contents), window));
+            }
+            // Start getting the calendar data from the file
+            ImportExportAction::Import(file) => {
+                self.import_path = file.path;
+                self.start_import(&file.contents);
+            }
+            ImportExportAction::ImportFailed => {
+                self.set_imported(Err(ParseFromFileError::InvalidFile));
+            }
+            ImportExportAction::Restore => {
+                // Backups aren't a file the list can be bound to
+                self.import_path = None;
+                self.restore_window = Some(RestoreWindow::open(
+                    &self.settings.backup,
+                    self.encrypting(),
+                ));
+            }
+            ImportExportAction::History => {
+                // Old versions aren't a file the list can be bound to either
+                self.import_path = None;
+                self.history_window = Some(HistoryWindow::open(
+                    &self.settings.git,
+                    &self.input_task_list.name,
+                ));
+            }
+            ImportExportAction::Reload => {
+                if let Some(path) = self.input_task_list.path.clone() {
+                    self.load_file(path);
+                }
+            }
+            // Exporting over the list's own file brings the file up to date with the list
+            ImportExportAction::Exported(path) => {
+                if path.is_some() && path == self.input_task_list.path {
+                    self.file_watcher.mark_current();
+                }
+            }
+            ImportExportAction::ToggleSettings => self.show_settings = !self.show_settings,
+            ImportExportAction::ToggleStatistics => {
+                self.statistics = match self.statistics {
+                    Some(_) => None,
+                    None => Some(StatisticsWindow::default()),
+                };
+            }
+            ImportExportAction::MergeSplit => {
+                self.merge_split = Some(MergeSplitWindow::default());
+            }
+            ImportExportAction::ManageTags => {
+                self.tag_manager = Some(TagManagerWindow::default());
+            }
+            ImportExportAction::ToggleSyncLog => self.show_sync_log = !self.show_sync_log,
+            ImportExportAction::BindSync(binding) => {
+                self.sync.bind(&self.input_task_list, binding);
+            }
+            ImportExportAction::SwitchProfile(name) => self.switch_profile(ctx, &name),
+        }
+    }
 
-                // Import a task list file
+    // Replaces the list with the one in a file, and binds the list to the file
+    fn load_file(&mut self, path: PathBuf) {
+        let data = match platform::read_file(&path) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("could not read {}: {e}", path.display());
+                self.set_imported(Err(ParseFromFileError::InvalidFile));
+                return;
+            }
+        };
+
+        // Encrypted files are opened with the key that's in use if they can be,
+        // and otherwise have to be unlocked like a saved list
+        let data = if encryption::is_encrypted(&data) {
+            let decrypted = self
+                .encryption_key
+                .as_ref()
+                .and_then(|key| key.decrypt(&data).ok());
+            let Some(decrypted) = decrypted else {
+                self.input_task_list.path = Some(path);
+                let window = PassphraseWindow::unlock(
+                    "Unlock task list",
+                    "Your task list is encrypted. Enter its passphrase to open it.",
+                    false,
+                );
+                self.passphrase_request = Some((PassphraseRequest::UnlockStore(data), window));
+                return;
+            };
+            decrypted
+        } else {
+            data
+        };
+
+        match TaskList::from_ical_reader(data.as_slice()) {
+            Ok(mut list) => {
+                list.path = Some(path);
+                self.replace_list(list);
+                self.file_watcher.mark_current();
+            }
+            Err(e) => self.set_imported(Err(e)),
+        }
+    }
+
+    // Replaces the whole task list, such as when a file is imported
+    fn replace_list(&mut self, list: TaskList) {
+        self.input_task_list = list;
+        // Files from other computers bring how the list was laid out, and the colors of its tags.
+        // Layouts kept on this computer are put back over the file's when the list is bound.
+        if let Some(layout) = &self.input_task_list.layout {
+            self.task_list_panel.apply_layout(layout);
+        }
+        if self
+            .settings
+            .tags
+            .add_colors(&self.input_task_list.tag_colors)
+        {
+            self.settings.tags.save();
+        }
+        // Replacing the list shouldn't count as adding every task in it
+        self.task_watcher.reset(&self.input_task_list);
+        // Hand-edited files can have tasks which share a UUID
+        self.uuid_collisions = self.input_task_list.uuid_collisions().len();
+    }
+
+    // Does what the user chose for the times on tasks when the time zone changed
+    fn apply_time_zone(&mut self, change: TimeZoneChange, policy: TimeZonePolicy) {
+        if policy == TimeZonePolicy::Anchored {
+            let shift = change.shift();
+            timezone::shift_times(&mut self.input_task_list, shift);
+            // Moving every time shouldn't count as changing every task
+            self.task_watcher.reset(&self.input_task_list);
+            self.missed_reminders.shift(shift);
+            self.notifier.shift(shift);
+        }
+        self.time_zone_watcher.accept(change.to);
+    }
+
+    // Asks what to do with the times on tasks when the time zone has changed
+    fn time_zone_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.time_zone_window else {
+            return;
+        };
+        let Some((policy, remember)) = window.show(ctx) else {
+            return;
+        };
+        let change = window.change();
+        self.time_zone_window = None;
+        if remember {
+            self.settings.time_zone = policy;
+        }
+        self.apply_time_zone(change, policy);
+    }
+
+    // Puts a list back the way it was laid out when it's switched to,
+    // and keeps how the open one is laid out, both here and in its file
+    fn keep_layout(&mut self) {
+        let key = layouts::key(&self.input_task_list);
+        if key != self.layout_key {
+            if let Some(layout) = key.as_deref().and_then(|key| self.layouts.get(key)) {
+                self.task_list_panel.apply_layout(layout);
+            }
+            self.layout_key = key;
+        } else if let Some(key) = &key {
+            if self.layouts.update(key, &self.task_list_panel.layout()) {
+                self.layouts.save();
+            }
+        }
+
+        // The list's file keeps its layout and the colors of its tags, for other computers
+        let layout = self.task_list_panel.layout();
+        if self.input_task_list.layout.as_ref() != Some(&layout) {
+            self.input_task_list.layout = Some(layout);
+        }
+        let tag_colors = self.settings.tags.colors_for(&self.input_task_list);
+        if tag_colors != self.input_task_list.tag_colors {
+            self.input_task_list.tag_colors = tag_colors;
+        }
+    }
+
+    // Offers to give new UUIDs to tasks which share one, since they can't be told apart
+    fn uuid_collision_window(&mut self, ctx: &egui::Context) {
+        if self.uuid_collisions == 0 {
+            return;
+        }
+
+        let mut close = false;
+        egui::Window::new("Tasks with the same ID")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} IDs in this list are used by more than one task, which stops \
+                    those tasks from being edited or synced properly.",
+                    self.uuid_collisions
+                ));
                 ui.horizontal(|ui| {
-                    // This stores the path of the imported file
-                    let mut opened_file: Option<PathBuf> = None;
-
-                    if ui.button("Open file").clicked() {
-                        // Create a file dialog, which reads from and writes to 
-                        // the file path variable created earlier
-                        let mut dialog = FileDialog::open_file(opened_file.clone());
-                        // Mark it as opened
-                        dialog.open();
-                        // This makes sure that the import and export dialogs 
-                        // aren't open at the same time
-                        self.export_dialog = None;
-                        // Stores the dialog in the app state
-                        // This means it can be used in later update loops
-                        self.import_dialog = Some(dialog);
+                    if ui.button("Give them new IDs").clicked() {
+                        self.input_task_list.regenerate_colliding_uuids();
+                        // Tasks with new IDs shouldn't count as having just been added
+                        self.task_watcher.reset(&self.input_task_list);
+                        close = true;
                     }
-
-                    // If there is an import dialog stored in the app's state
-                    if let Some(dialog) = &mut self.import_dialog {
-                        // If a file has been picked using the dialog
-                        if dialog.show(ctx).selected() {
-                            // If the file has a valid path
-                            if let Some(file) = dialog.path() {
-                                // Store the path
-                                opened_file = Some(file.to_path_buf());
-                            }
-                        }
+                    if ui.button("Ignore").clicked() {
+                        close = true;
                     }
+                });
+            });
 
-                    // If there is a file path being stored
-                    if let Some(file) = &mut opened_file {
-                        // Try to get the calendar data from the file at that path
-                        let parse_result = TaskList::from_ical_file(file);
-                        // Store the result in the app state
-                        self.imported_list = Some(parse_result);
+        if close {
+            self.uuid_collisions = 0;
+        }
+    }
+
+    // Adds a task the user has made to the list, unless it looks like one that's
+    // already there, in which case the user is asked what to do with it first
+    fn add_task(&mut self, task: Task) {
+        if duplicates::find(&self.input_task_list, &task).is_some() {
+            self.pending_duplicate = Some(task);
+        } else {
+            self.input_task_list.add(task);
+        }
+    }
+
+    // Lets the user pick another list to move or copy a task to, once a task view asks for it
+    fn transfer_window(&mut self, ctx: &egui::Context) {
+        if let Some((uuid, mode)) = ui_elements::transfer::take_request(ctx) {
+            if let Some(task) = self.input_task_list.tasks.iter().find(|t| t.uuid == uuid) {
+                let current = self.input_task_list.path.as_deref();
+                self.transfer_window = Some(TransferWindow::new(task, mode, current));
+            }
+        }
+        let Some(window) = &mut self.transfer_window else {
+            return;
+        };
+
+        let mut open = true;
+        if let Some(path) = window.show(ctx, &mut open) {
+            let index = self
+                .input_task_list
+                .tasks
+                .iter()
+                .position(|task| task.uuid == window.uuid);
+            // The task might have been removed while the window was open
+            let export = self.settings.export;
+            let result = index.map(|index| {
+                let task = &self.input_task_list.tasks[index];
+                transfer::send(task, &path, window.mode, export.line_ending, export.format)
+                    .map(|()| index)
+            });
+            match result {
+                Some(Ok(index)) => {
+                    if window.mode == TransferMode::Move {
+                        self.input_task_list.tasks.remove(index);
                     }
+                    open = false;
+                }
+                Some(Err(e)) => window.set_error(format!("Could not add the task: {e}")),
+                None => open = false,
+            }
+        }
+        if !open {
+            self.transfer_window = None;
+        }
+    }
+
+    // Posts a task to the chat channel once a task view asks for it, or opens the
+    // settings if there isn't a channel to post to yet
+    fn share_task(&mut self, ctx: &egui::Context) {
+        let Some(uuid) = share::take_request(ctx) else {
+            return;
+        };
+        if self.settings.share.webhook_url.trim().is_empty() {
+            self.show_settings = true;
+            return;
+        }
+        if let Some(task) = self.input_task_list.tasks.iter().find(|t| t.uuid == uuid) {
+            share::share(task, &self.settings.share, &self.settings.network);
+        }
+    }
 
-                    // If there is a task list parsing result stored 
-                    if let Some(list) = &self.imported_list {
-                        match list {
-                            // If the parse was successful
-                            Ok(list) => {
-                                // Give information about the task list in the file
-                                ui.label(format!("File contains list '{}'", &list.name));
-                                // Display a button to import the task list from the file into the app
-                                if ui.button("Import ( warning: overwrites current list )").clicked() {
-                                    self.input_task_list = list.clone();
-                                }
-                            }
-                            // If the parse was unsuccessful
-                            Err(e) => {
-                                // Create an appropriate string from all the possible errors
-                                let err_str = match e {
-                                    ParseFromFileError::InvalidFile => "Invalid task list file",
-                                    ParseFromFileError::NonTaskItem => "File contained items that were not todo items. Was it exported from calendar software?",
-                                    ParseFromFileError::InvalidField => "File contains invalid data",
-                                };
-                                // Display a label showing the error
-                                ui.label(err_str);
-                            }
+    // Asks the user what to do with a new task that looks like one already in the list
+    fn duplicate_window(&mut self, ctx: &egui::Context) {
+        let Some(task) = &self.pending_duplicate else {
+            return;
+        };
+        let Some(index) = duplicates::find(&self.input_task_list, task) else {
+            // The task it looked like has gone, so there's nothing to ask
+            if let Some(task) = self.pending_duplicate.take() {
+                self.input_task_list.add(task);
+            }
+            return;
+        };
+
+        let mut picked = None;
+        egui::Window::new("Possible duplicate")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "'{}' looks like '{}', which is already in the list.",
+                    task.summary, self.input_task_list.tasks[index].summary
+                ));
+                ui.horizontal(|ui| {
+                    for action in DuplicateAction::iterator() {
+                        if ui.button(action.name()).clicked() {
+                            picked = Some(*action);
                         }
                     }
                 });
+            });
 
-                // Export task list to a file
-                ui.horizontal(|ui| {
+        if let Some(action) = picked {
+            if let Some(task) = self.pending_duplicate.take() {
+                duplicates::add(&mut self.input_task_list, task, action);
+            }
+        }
+    }
 
-                    if ui.button("Export task list").clicked() {
-                        // Create a file export dialog
-                        let mut dialog = FileDialog::save_file(None);
-                        // Mark it as opened
-                        dialog.open();
-                        // This makes sure that the import and export dialogs 
-                        // aren't open at the same time
-                        self.import_dialog = None;
-                        // Stores the dialog in the app state
-                        // This means it can be used in later update loops
-                        self.export_dialog = Some(dialog);
-                    }
+    // The key to encrypt lists with, if encryption is turned on
+    fn encrypting(&self) -> Option<&EncryptionKey> {
+        self.encryption_key
+            .as_ref()
+            .filter(|_| self.settings.encrypt)
+    }
 
-                    // If there is an import dialog stored in the app's state
-                    if let Some(dialog) = &mut self.export_dialog {
-                        // If a file has been picked using the dialog
-                        if dialog.show(ctx).selected() {
-                            // If the path is valid
-                            if let Some(file) = dialog.path() {
-                                // Get contents of file which will be exported
-                                let list_str = self.input_task_list.to_ical_string();
-                                // Create the file which will have the data
-                                let mut f = File::create(file)
-                                    .expect("could not create file");
-                                // Write the data to the file
-                                f.write_all(list_str.as_bytes())
-                                    .expect("could not write data to file");
-                            }
-                        }
+    // Whether the saved list is still waiting to be unlocked.
+    // Until it is, nothing is saved, so that it isn't overwritten.
+    const fn store_locked(&self) -> bool {
+        matches!(
+            self.passphrase_request,
+            Some((PassphraseRequest::UnlockStore(_), _))
+        )
+    }
+
+    // Shows the passphrase window if there is one, and does whatever it was
+    // opened for once the user has entered a passphrase
+    fn passphrase_window(&mut self, ctx: &egui::Context) {
+        // Encryption has just been turned on, so a passphrase needs to be chosen
+        if self.settings.encrypt
+            && self.encryption_key.is_none()
+            && self.passphrase_request.is_none()
+        {
+            let window = PassphraseWindow::choose(
+                "Choose a passphrase",
+                "Task lists will be encrypted with this passphrase. \
+                If it's forgotten, they can't be recovered.",
+            );
+            self.passphrase_request = Some((PassphraseRequest::Choose, window));
+        }
+        // Forget the key once encryption is turned off, so a new passphrase
+        // is asked for if it's turned back on
+        if !self.settings.encrypt && !self.store_locked() {
+            self.encryption_key = None;
+        }
+
+        let Some((request, window)) = &mut self.passphrase_request else {
+            return;
+        };
+        let Some(response) = window.show(ctx) else {
+            return;
+        };
+
+        match (request, response) {
+            (PassphraseRequest::Choose, PassphraseResponse::Cancelled) => {
+                self.settings.encrypt = false;
+            }
+            (_, PassphraseResponse::Cancelled) => (),
+            (PassphraseRequest::Choose, PassphraseResponse::Submitted(passphrase)) => {
+                self.encryption_key = Some(EncryptionKey::new(&passphrase));
+            }
+            (PassphraseRequest::UnlockStore(data), PassphraseResponse::Submitted(passphrase)) => {
+                match unlock(data, &passphrase) {
+                    Ok((key, list)) => {
+                        // A list that can't be parsed is treated like there was no list saved.
+                        // Either way, it stays bound to the file it was read from.
+                        let path = self.input_task_list.path.take();
+                        self.input_task_list =
+                            TaskList::from_ical_reader(list.as_slice()).unwrap_or_default();
+                        self.input_task_list.path = path;
+                        self.task_watcher.reset(&self.input_task_list);
+                        self.uuid_collisions = self.input_task_list.uuid_collisions().len();
+                        // Keep saving the list with the same passphrase
+                        self.encryption_key = Some(key);
+                    }
+                    Err(e) => {
+                        window.set_error(encryption_error_str(e));
+                        return;
+                    }
+                }
+            }
+            (PassphraseRequest::UnlockImport(data), PassphraseResponse::Submitted(passphrase)) => {
+                match unlock(data, &passphrase) {
+                    Ok((_, list)) => {
+                        self.start_import(&list);
                     }
+                    Err(e) => {
+                        window.set_error(encryption_error_str(e));
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.passphrase_request = None;
+    }
+
+    // Starts reading an imported file, showing its tasks as they're read.
+    // Anything in the file that can't be read is skipped, and the user is told what.
+    fn start_import(&mut self, data: &[u8]) {
+        let import = IcalImport::new(data).lenient();
+        self.imported_list = Some(Ok(ImportPreview::loading(import)));
+    }
+
+    // Stores the result of parsing an imported file, so that its tasks can be picked from
+    fn set_imported(&mut self, result: Result<TaskList, ParseFromFileError>) {
+        self.imported_list = Some(result.map(ImportPreview::new));
+    }
+
+    // Replaces the list with one from a backup, backing up the current list first
+    fn restore(&mut self, data: Vec<u8>) {
+        let data = if encryption::is_encrypted(&data) {
+            let decrypted = self
+                .encryption_key
+                .as_ref()
+                .and_then(|key| key.decrypt(&data).ok());
+            // Backups made with a different passphrase are unlocked like an encrypted file
+            let Some(decrypted) = decrypted else {
+                let window = PassphraseWindow::unlock(
+                    "Unlock backup",
+                    "This backup was encrypted with a different passphrase. Enter it to open the backup.",
+                    true,
+                );
+                self.passphrase_request = Some((PassphraseRequest::UnlockImport(data), window));
+                return;
+            };
+            decrypted
+        } else {
+            data
+        };
+
+        match TaskList::from_ical_reader(data.as_slice()) {
+            Ok(mut list) => {
+                // The restored list takes the current list's place in its file
+                list.path.clone_from(&self.input_task_list.path);
+                let key = self
+                    .encryption_key
+                    .as_ref()
+                    .filter(|_| self.settings.encrypt);
+                self.backup_scheduler
+                    .backup_now(&self.input_task_list, &self.settings.backup, key);
+                self.replace_list(list);
+            }
+            // Show why the backup couldn't be restored, the same way as for an imported file
+            Err(e) => self.set_imported(Err(e)),
+        }
+    }
+}
+
+// Decrypts an encrypted list, giving back the key it was encrypted with and the decrypted list
+fn unlock(data: &[u8], passphrase: &str) -> Result<(EncryptionKey, Vec<u8>), EncryptionError> {
+    let key = EncryptionKey::for_list(passphrase, data)?;
+    let list = key.decrypt(data)?;
+    Ok((key, list))
+}
+
+const fn encryption_error_str(error: EncryptionError) -> &'static str {
+    match error {
+        EncryptionError::WrongPassphrase => "Wrong passphrase",
+        EncryptionError::InvalidData => "The file is damaged, or isn't an encrypted task list",
+    }
+}
+
+// Define how the app behaves based on the app state
+#[allow(clippy::too_many_lines)]
+impl eframe::App for App {
+    // - Main render loop function ----------------------------------------
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // The window can only be minimized once it exists, so this is done on the first frame
+        if self.minimize {
+            self.minimize = false;
+            frame.set_minimized(true);
+        }
 
-                    // This lets you change the list's name before exporting it
-                    ui.label("List name:");
-                    ui.text_edit_singleline(&mut self.input_task_list.name);
+        // Pick up changes to the config file, and handle its keyboard shortcuts
+        if let Some(config) = self.config_watcher.check(ctx) {
+            self.apply_config(ctx, config);
+        }
+        for shortcut in self.config.keybindings.pressed(ctx) {
+            self.handle_shortcut(ctx, shortcut);
+        }
+
+        // Answer any requests that the HTTP server has received
+        #[cfg(feature = "server")]
+        if let Some(server) = &self.server {
+            server.handle_requests(&mut self.input_task_list, &self.settings.api_token);
+        }
+        #[cfg(feature = "server")]
+        self.feed
+            .check(ctx, &self.input_task_list, &self.settings.feed);
+
+        // Decide whether to use the layout for narrow, touch screens
+        let narrow = match self.settings.layout {
+            LayoutMode::Automatic => ctx.screen_rect().width() < NARROW_WIDTH,
+            LayoutMode::Desktop => false,
+            LayoutMode::Mobile => true,
+        };
+
+        // Turn egui's animations, and the app's own, off if the user has asked for less motion
+        let motion_reduced = ctx.style().animation_time <= 0.;
+        if motion_reduced != self.settings.reduce_motion {
+            let mut style = (*ctx.style()).clone();
+            style.animation_time = if self.settings.reduce_motion {
+                0.
+            } else {
+                egui::Style::default().animation_time
+            };
+            ctx.set_style(style);
+        }
+
+        // - Add bar -----------------------------------
+        // On narrow screens, tasks are added from a bar at the bottom of the
+        // screen, where it's easy to reach with a thumb
+        if narrow {
+            let action = egui::TopBottomPanel::bottom("add_bar")
+                .show(ctx, |ui| {
+                    ui_elements::touch_spacing(ui);
+                    self.task_input_panel.show_add_bar(ui)
+                })
+                .inner;
+            if let Some(action) = action {
+                self.handle_task_input(action);
+            }
+        }
+
+        // - Sidebar ---------------------------------
+        // Lists, with how many of their tasks are pending and overdue.
+        // Narrow screens don't have room for it.
+        if !narrow {
+            egui::SidePanel::left("sidebar")
+                .resizable(true)
+                .default_width(150.)
+                .show(ctx, |ui| {
+                    ui_elements::sidebar::show(ui, &self.input_task_list, &self.list_counters);
                 });
-            });
+        }
 
-            //Task input panel
-            ui_elements::basic_frame().show(ui, |ui| {
-                ui.horizontal(|ui| {
-                    // Expand to fit window
-                    ui.set_width(ui.available_width());
+        // - Agenda sidebar --------------------------
+        // The tasks due over the next week, whichever view the list is in
+        if !narrow && self.settings.agenda_sidebar {
+            let clicked = egui::SidePanel::right("agenda_sidebar")
+                .resizable(true)
+                .default_width(180.)
+                .show(ctx, |ui| {
+                    ui_elements::agenda_sidebar::show(ui, &self.input_task_list)
+                })
+                .inner;
+            // Tasks are opened from the classic view, which is where their modals are shown
+            if let Some(uuid) = clicked {
+                self.task_list_panel.select_view("Classic");
+                ui_elements::task_modal::set_open(ctx, uuid, true);
+            }
+        }
 
-                    // Decides whether to show the simple task editing UI
-                    // or a simplified/minimal version
-                    if self.show_full_edit {
-                        // Full task editing UI
-                        ui.vertical(|ui| {
-                            ui_elements::task_edit::full(ui, &mut self.input_task);
-
-                            if ui.button("Add task").clicked() {
-                                // Add input task to the list of tasks
-                                self.input_task_list.tasks.push(self.input_task.clone());
-                                self.input_task.uuid = uuid::Uuid::new_v4();
-
-                                // Enable showing completed tasks if the task
-                                // that was just added is marked as complete.
-                                // This prevents confusion from a newly added
-                                // task not being shown if it's already marked
-                                // as complete when it's added to the list.
-                                if self.input_task.completed && !self.show_completed_tasks {
-                                    self.show_completed_tasks = true;
-                                }
-                            }
-                        });
-                    } else {
-                        // Simplified task editing UI
-                        ui.horizontal(|ui| {
-                            if ui.button("+").clicked() {
-                                // Add input task to the list of tasks
-                                self.input_task_list.add(Task {
-                                    summary: self.input_task.clone().summary,
-                                    completed: self.input_task.clone().completed,
-                                    ..Task::default()
-                                });
-
-                                if self.input_task.completed && !self.show_completed_tasks {
-                                    self.show_completed_tasks = true;
-                                }
-                            }
-
-                            ui_elements::task_edit::lite(ui, &mut self.input_task);
-                        });
-                    }
+        // - Main UI panel ---------------------------
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // Set global ui scale
+            ctx.set_pixels_per_point(self.scale.unwrap_or(DEFAULT_SCALE));
 
-                    // This is the button which switches full and simplified UIs
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Max), |ui| {
-                        let btn_str = if self.show_full_edit {
-                            "⏶"
-                        } else {
-                            "⏷"
-                        };
-                        if ui.button(btn_str).clicked() {
-                            self.show_full_edit = !self.show_full_edit;
-                        }
-                    });
+            // Set spacing between panels
+            ui.spacing_mut().item_spacing = egui::Vec2::splat(7.5);
+
+            // Make everything easier to tap on touch screens
+            if narrow {
+                ui_elements::touch_spacing(ui);
+            }
+
+            // Task list import/export panel
+            let action = ui_elements::basic_frame()
+                .show(ui, |ui| {
+                    ui.set_width(ui.available_width());
 
+                    // On narrow screens this panel can be collapsed, leaving more room for tasks
+                    let key = self
+                        .encryption_key
+                        .as_ref()
+                        .filter(|_| self.settings.encrypt);
+                    let import_error = match &self.imported_list {
+                        Some(Err(e)) => Some(e),
+                        _ => None,
+                    };
+                    ui_elements::collapsible(ui, narrow, "File", |ui| {
+                        self.import_export_panel.show(
+                            ui,
+                            &mut self.input_task_list,
+                            key,
+                            self.settings.export,
+                            &self.settings.network,
+                            import_error,
+                            self.file_watcher.changed_on_disk(),
+                            &mut self.reminder_inbox,
+                            &self.sync,
+                            &self.sync_log,
+                        )
+                    })
+                    .flatten()
                 })
-            });
+                .inner;
+            if let Some(action) = action {
+                self.handle_import_export(ctx, action);
+            }
+
+            //Task input panel
+            // On narrow screens, tasks are added from the add bar instead
+            if !narrow {
+                let action = ui_elements::basic_frame()
+                    .show(ui, |ui| self.task_input_panel.show(ui))
+                    .inner;
+                if let Some(action) = action {
+                    self.handle_task_input(action);
+                }
+            }
 
             //Task list panel
             ui_elements::basic_frame().show(ui, |ui| {
@@ -234,50 +1037,288 @@ impl eframe::App for App {
                 ui.set_width(ui.available_width());
                 ui.set_height(ui.available_height());
 
-                // Top bar, with sorting and other list options
-                top_bar(self, ui);
+                // Toolbar for the tasks that are picked, which the views can see and change
+                self.selection.retain(&self.input_task_list);
+                ui_elements::selection::toolbar(ui, &mut self.selection, &mut self.input_task_list);
+                ui_elements::selection::share(ctx, &self.selection);
 
-                // Scrollable area that shows all the tasks
-                egui::ScrollArea::vertical().show_rows(
+                self.task_list_panel.show(
                     ui,
-                    14.,
-                    self.input_task_list.tasks.len(),
-                    |ui, _| {
-                        // Display tasks in classic view
-                        ClassicView::display(
-                            ui,
-                            &mut self.input_task_list,
-                            self.show_completed_tasks,
-                        );
-                    },
+                    &mut self.input_task_list,
+                    narrow,
+                    self.settings.stale.days,
+                    &mut self.settings.rows,
                 );
+                if let Some(selection) = ui_elements::selection::take_changed(ctx) {
+                    self.selection = selection;
+                }
             });
+            // Remember how the list is laid out, or put it back when the list has changed
+            self.keep_layout();
         });
-    }
-}
 
-// Top bar, with sorting and other list options
-fn top_bar(app: &mut App, ui: &mut egui::Ui) {
-    ui.horizontal(|ui| {
-        // Checkbox to show tasks that have been completed
-        ui.checkbox(&mut app.show_completed_tasks, "Show completed tasks");
+        // Settings window
+        ui_elements::settings::window(ctx, &mut self.show_settings, &mut self.settings);
+
+        // Window with the log of syncs
+        ui_elements::sync_log::window(ctx, &mut self.show_sync_log, &self.sync_log);
+
+        // Statistics window
+        if let Some(window) = &mut self.statistics {
+            let mut open = true;
+            window.show(ctx, &mut open, &self.input_task_list);
+            if !open {
+                self.statistics = None;
+            }
+        }
+
+        // Window asking what to do with times on tasks when the time zone has changed
+        self.time_zone_window(ctx);
+
+        // Passphrase window, for unlocking encrypted lists and choosing a passphrase
+        self.passphrase_window(ctx);
 
-        ui.label("| ");
+        // Window offering to fix tasks which share a UUID
+        self.uuid_collision_window(ctx);
 
-        // Button to sort task list by chosen field
-        if ui.button("Sort").clicked() {
-            app.input_task_list.sort(app.sort_by);
+        // Window asking what to do with a duplicate task
+        self.duplicate_window(ctx);
+
+        // Window for picking which tasks to import from a file
+        if let Some(Ok(preview)) = &mut self.imported_list {
+            if let Some(action) = preview.show(ctx, &self.input_task_list) {
+                self.imported_list = None;
+                match action {
+                    ImportAction::Add(tasks, duplicate_action) => {
+                        for task in tasks {
+                            duplicates::add(&mut self.input_task_list, task, duplicate_action);
+                        }
+                    }
+                    ImportAction::Replace(mut list) => {
+                        // The list now comes from the imported file
+                        list.path = self.import_path.take();
+                        self.replace_list(list);
+                    }
+                    ImportAction::Cancel => (),
+                    ImportAction::Failed(e) => self.set_imported(Err(e)),
+                }
+                // Importing tasks shouldn't count as adding each of them
+                self.task_watcher.reset(&self.input_task_list);
+            }
         }
 
-        // Dropdown to choose which field to sort by
-        ui.label("by");
-        egui::ComboBox::from_label("")
-            .selected_text(format!("{:?}", &app.sort_by)) // Show selected sort field
-            .show_ui(ui, |ui| {
-                for sort_by in TaskSort::iterator() {
-                    // Iterate over sortable fields and display each as an option
-                    ui.selectable_value(&mut app.sort_by, *sort_by, format!("{sort_by:?}"));
+        // Window for restoring a backup
+        if let Some(window) = &self.restore_window {
+            let mut open = true;
+            let picked = window.show(ctx, &mut open);
+            if !open || picked.is_some() {
+                self.restore_window = None;
+            }
+            if let Some(data) = picked {
+                self.restore(data);
+            }
+        }
+
+        // Window for managing tags
+        if let Some(window) = &mut self.tag_manager {
+            let mut open = true;
+            if window.show(
+                ctx,
+                &mut open,
+                &mut self.settings.tags,
+                &mut self.input_task_list,
+            ) {
+                self.settings.tags.save();
+            }
+            if !open {
+                self.tag_manager = None;
+            }
+        }
+
+        // Window for turning a pasted list into tasks
+        if let Some(window) = &mut self.paste_list {
+            let mut open = true;
+            if let Some((tasks, action)) = window.show(ctx, &mut open, &self.input_task_list) {
+                for task in tasks {
+                    duplicates::add(&mut self.input_task_list, task, action);
                 }
-            });
-    });
+            }
+            if !open {
+                self.paste_list = None;
+            }
+        }
+
+        // Window for moving or copying a task to another list
+        self.transfer_window(ctx);
+
+        // Share a task to the chat channel, once a task view asks for it
+        self.share_task(ctx);
+
+        // Window for merging lists and splitting this one
+        if let Some(window) = &mut self.merge_split {
+            let mut open = true;
+            if window.show(ctx, &mut open, &mut self.input_task_list) {
+                // Like importing, merging shouldn't count as adding each task
+                self.task_watcher.reset(&self.input_task_list);
+            }
+            if !open {
+                self.merge_split = None;
+            }
+        }
+
+        // Window for restoring an old version from git
+        if let Some(window) = &mut self.history_window {
+            let mut open = true;
+            let picked = window.show(ctx, &mut open);
+            if !open || picked.is_some() {
+                self.history_window = None;
+            }
+            if let Some(data) = picked {
+                self.restore(data);
+            }
+        }
+
+        // Save and back up the task list, unless it's still waiting to be unlocked
+        if !self.store_locked() {
+            let key = self
+                .encryption_key
+                .as_ref()
+                .filter(|_| self.settings.encrypt);
+            self.list_store.update(ctx, &self.input_task_list, key);
+            self.backup_scheduler
+                .check(ctx, &self.input_task_list, &self.settings.backup, key);
+            // Sync the list to where it's bound to
+            self.github
+                .update(&self.settings.github, &self.settings.network);
+            self.jira
+                .update(&self.settings.jira, &self.settings.network);
+            self.sync.check(
+                ctx,
+                &self.input_task_list,
+                &mut SyncContext {
+                    settings: &self.settings,
+                    key,
+                    log: &self.sync_log,
+                    file_watcher: &mut self.file_watcher,
+                },
+            );
+            // Bring in what changed on the services the list syncs with
+            self.sync.take_incoming(&mut self.input_task_list);
+        }
+        self.sync_log.save_if_changed();
+
+        // Notice if the list's file has been changed by another app
+        self.file_watcher.check(ctx, &self.input_task_list);
+
+        // Notice if the time zone has changed, and do what the user chose for times on tasks
+        if let Some(change) = self.time_zone_watcher.check(ctx) {
+            match self.settings.time_zone {
+                TimeZonePolicy::Ask => {
+                    if self.time_zone_window.is_none() {
+                        self.time_zone_window = Some(TimeZoneWindow::new(change));
+                    }
+                }
+                policy => self.apply_time_zone(change, policy),
+            }
+        }
+
+        // Tell everything that's subscribed what happened to the task list this frame,
+        // then run hook commands for it
+        self.task_watcher.touch_changed(
+            &mut self.input_task_list,
+            chrono::Local::now().naive_local(),
+        );
+        self.task_watcher.check(&self.input_task_list);
+        self.list_counters.check(ctx, &self.input_task_list);
+
+        // Reminders are held back during quiet hours, so make sure the app is awake when they end
+        let now = chrono::Local::now().naive_local();
+        let quiet_hours = &self.settings.quiet_hours;
+        let quiet = quiet_hours.is_quiet(now, self.input_task_list.path.as_deref());
+        if let Some(wait) = quiet_hours
+            .quiet_until(now)
+            .and_then(|end| (end - now).to_std().ok())
+        {
+            ctx.request_repaint_after(wait);
+        }
+
+        let events = self
+            .hook_runner
+            .check(&self.input_task_list, &self.settings.hooks, quiet);
+        self.webhook_sender.check(
+            &self.input_task_list,
+            &self.settings.webhooks,
+            &self.settings.hooks,
+            &self.settings.network,
+            quiet,
+        );
+        self.notifier.check(
+            ctx,
+            &mut self.input_task_list,
+            &self.settings.notifications,
+            &self.settings.hooks,
+            quiet,
+        );
+
+        // List any reminders that were missed while the app was away.
+        // A list that's still locked can't be checked yet, so it's checked once it's unlocked.
+        if !self.store_locked() {
+            let missed = self.missed_reminders.check(
+                ctx,
+                &self.input_task_list,
+                &self.settings.hooks,
+                quiet,
+            );
+            self.reminder_inbox
+                .check(&self.input_task_list, &self.settings.hooks);
+            if !missed.is_empty() {
+                match &mut self.missed_reminders_window {
+                    Some(window) => window.extend(missed),
+                    None => self.missed_reminders_window = Some(MissedRemindersWindow::new(missed)),
+                }
+            }
+        }
+        if let Some(window) = &self.missed_reminders_window {
+            if !window.show(ctx) {
+                self.missed_reminders_window = None;
+            }
+        }
+
+        // Play a sound for what happened, only once even if it happened to several tasks
+        if events.contains(&HookEvent::Completed) {
+            self.sound_player
+                .play(Sound::Completed, &self.settings.sounds);
+        }
+        if events.contains(&HookEvent::DueSoon) {
+            self.sound_player
+                .play(Sound::Reminder, &self.settings.sounds);
+        }
+
+        // Make the daily agenda, if it's time to
+        self.agenda_scheduler.check(
+            ctx,
+            &self.input_task_list,
+            &self.settings.agenda,
+            &self.settings.network,
+        );
+
+        let view =
+            self.task_list_panel
+                .options(narrow, self.settings.stale.days, &self.settings.rows);
+        self.celebration
+            .update(ctx, &self.input_task_list, &view, self.settings.celebration);
+
+        self.window_title.update(ctx, frame, &self.input_task_list);
+        let today = chrono::Local::now().date_naive();
+        self.badge
+            .update(self.input_task_list.due_counts(today).due_today);
+
+        self.autostart.update(self.settings.startup);
+
+        // Keep the settings for next time whenever they've been changed
+        if self.settings != self.saved_settings {
+            self.settings.save();
+            self.saved_settings = self.settings.clone();
+        }
+    }
 }
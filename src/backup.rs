@@ -0,0 +1,156 @@
+// ----------------------------------------------------------------------------
+// Automatic backups of the task list. Every few minutes, if the list has
+// changed, a timestamped snapshot of it is saved, and the oldest snapshots
+// are deleted so that only a set number are kept. Native builds keep
+// snapshots in a folder the user picks, and web builds keep them in the
+// browser's local storage.
+// ----------------------------------------------------------------------------
+
+use std::cmp::Reverse;
+
+use chrono::{Local, NaiveDateTime};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::encryption::EncryptionKey;
+use crate::platform;
+use crate::task::TaskList;
+
+// Snapshot names are made of these and the time the snapshot was made, so
+// that sorting them by name sorts them by age
+const NAME_PREFIX: &str = "taskmaster-backup-";
+const NAME_SUFFIX: &str = ".ics";
+const TIME_FORMAT: &str = "%Y-%m-%d-%H%M%S";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    // How often to check whether the list needs backing up
+    pub interval_minutes: u32,
+    // How many snapshots to keep. Older ones are deleted.
+    pub keep: u32,
+    // Folder that snapshots are saved in. Not used by web builds.
+    pub directory: String,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 10,
+            keep: 10,
+            directory: String::new(),
+        }
+    }
+}
+
+// A saved copy of the task list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    // Name the snapshot is saved under
+    pub name: String,
+    // When the snapshot was made, in local time
+    pub time: NaiveDateTime,
+}
+
+impl Snapshot {
+    fn from_name(name: String) -> Option<Self> {
+        let time = name.strip_prefix(NAME_PREFIX)?.strip_suffix(NAME_SUFFIX)?;
+        let time = NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?;
+        Some(Self { name, time })
+    }
+
+    // Reads the contents of the snapshot, which might need decrypting
+    pub fn read(&self, settings: &BackupSettings) -> Option<Vec<u8>> {
+        platform::read_backup(&settings.directory, &self.name)
+    }
+}
+
+// Every snapshot that has been saved, newest first
+pub fn snapshots(settings: &BackupSettings) -> Vec<Snapshot> {
+    let mut snapshots: Vec<Snapshot> = platform::backup_names(&settings.directory)
+        .into_iter()
+        .filter_map(Snapshot::from_name)
+        .collect();
+    snapshots.sort_by_key(|snapshot| Reverse(snapshot.time));
+    snapshots
+}
+
+// Makes snapshots of the task list every so often
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct BackupScheduler {
+    // When the list was last checked, in seconds since the app started
+    last_check: Option<f64>,
+    // What was last backed up, so that a snapshot is only made when the list changes
+    last_backed_up: String,
+}
+
+impl BackupScheduler {
+    // Backs up the list if it's time to, encrypting it if a key is given.
+    // Should be called once every frame.
+    pub fn check(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        settings: &BackupSettings,
+        key: Option<&EncryptionKey>,
+    ) {
+        let now = ctx.input(|i| i.time);
+        let Some(last_check) = self.last_check else {
+            // The list the app opened with doesn't need backing up until it's changed
+            self.last_check = Some(now);
+            self.last_backed_up = task_list.to_ical_string();
+            return;
+        };
+        if !settings.enabled || now - last_check < f64::from(settings.interval_minutes) * 60. {
+            return;
+        }
+        self.last_check = Some(now);
+
+        let list = task_list.to_ical_string();
+        if list != self.last_backed_up && backup(&list, settings, key) {
+            self.last_backed_up = list;
+        }
+    }
+
+    // Makes a snapshot straight away, such as before the list is replaced with an old one
+    pub fn backup_now(
+        &mut self,
+        task_list: &TaskList,
+        settings: &BackupSettings,
+        key: Option<&EncryptionKey>,
+    ) {
+        let list = task_list.to_ical_string();
+        if settings.enabled && backup(&list, settings, key) {
+            self.last_backed_up = list;
+        }
+    }
+}
+
+// Saves a snapshot of a list and deletes the oldest ones.
+// Returns whether the snapshot was saved.
+fn backup(list: &str, settings: &BackupSettings, key: Option<&EncryptionKey>) -> bool {
+    let name = format!(
+        "{NAME_PREFIX}{}{NAME_SUFFIX}",
+        Local::now().format(TIME_FORMAT)
+    );
+    let data = key.map_or_else(
+        || list.as_bytes().to_vec(),
+        |key| key.encrypt(list.as_bytes()),
+    );
+    if let Err(e) = platform::write_backup(&settings.directory, &name, &data) {
+        eprintln!("could not back up task list: {e}");
+        return false;
+    }
+
+    let keep = usize::try_from(settings.keep).unwrap_or(usize::MAX);
+    for old in snapshots(settings).iter().skip(keep.max(1)) {
+        if let Err(e) = platform::remove_backup(&settings.directory, &old.name) {
+            eprintln!("could not delete old backup {}: {e}", old.name);
+        }
+    }
+    true
+}
@@ -0,0 +1,184 @@
+// ----------------------------------------------------------------------------
+// The order tasks are in on the kanban board, which has a column for each
+// status. Each task keeps where it is in its status's column, which is saved
+// in X-TASKMASTER-BOARD-POSITION, so the order tasks were put in during
+// triage stays the same however the rest of the list is sorted. Tasks that
+// haven't been put anywhere go after the ones that have, in the list's order.
+//
+// The board can also be split into swimlanes, which are rows across every
+// column for each of the list's tags, or for high, medium and low priority.
+// ----------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::slice::Iter;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::task::{Status, Task, TaskList};
+
+/// What the kanban board's swimlanes split its tasks by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Swimlanes {
+    // There's one lane with every task in it
+    #[default]
+    None,
+    // Tasks are in the lane for their first tag
+    Tag,
+    // Tasks are in lanes for high, medium or low priority, like in RFC 5545
+    Priority,
+}
+
+impl Swimlanes {
+    pub fn iterator() -> Iter<'static, Self> {
+        [Self::None, Self::Tag, Self::Priority].iter()
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::None => "No swimlanes",
+            Self::Tag => "Tag",
+            Self::Priority => "Priority",
+        }
+    }
+
+    // The lane a task goes in, as a number the lanes are ordered by, then the lane's name
+    fn lane(self, task: &Task) -> (u8, String) {
+        let (rank, name) = match self {
+            Self::None => (0, ""),
+            Self::Tag => match task.tags.first() {
+                Some(tag) => return (0, tag.clone()),
+                None => (1, "No tag"),
+            },
+            Self::Priority => match task.priority.get() {
+                0 => (3, "No priority"),
+                1..=4 => (0, "High priority"),
+                5 => (1, "Medium priority"),
+                _ => (2, "Low priority"),
+            },
+        };
+        (rank, name.to_string())
+    }
+}
+
+impl TaskList {
+    // The statuses the board has a column for, which are the standard
+    // ones and any others the list's tasks have, in order
+    pub fn board_statuses(&self) -> Vec<Status> {
+        let mut statuses: Vec<Status> = Status::iterator().cloned().collect();
+        for task in &self.tasks {
+            if !statuses.contains(&task.status) {
+                statuses.push(task.status.clone());
+            }
+        }
+        statuses.sort();
+        statuses
+    }
+
+    /// Splits tasks, given as indices in the order they're shown in, into
+    /// swimlanes, which are named and in order. There's always at least one lane.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::board::Swimlanes;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let task = |priority| Task { priority: Priority::new(priority), ..Task::default() };
+    /// let list = TaskList { tasks: vec![task(9), task(0), task(2), task(1)], ..Default::default() };
+    /// let order = list.display_order(TaskSort::None);
+    ///
+    /// assert_eq!(
+    ///     list.swimlanes(Swimlanes::Priority, &order),
+    ///     vec![
+    ///         (String::from("High priority"), vec![2, 3]),
+    ///         (String::from("Low priority"), vec![0]),
+    ///         (String::from("No priority"), vec![1]),
+    ///     ]
+    /// );
+    /// assert_eq!(list.swimlanes(Swimlanes::None, &order), vec![(String::new(), order.clone())]);
+    /// assert_eq!(list.swimlanes(Swimlanes::Tag, &[]), vec![(String::new(), vec![])]);
+    /// ```
+    pub fn swimlanes(&self, swimlanes: Swimlanes, order: &[usize]) -> Vec<(String, Vec<usize>)> {
+        let mut lanes: BTreeMap<(u8, String), Vec<usize>> = BTreeMap::new();
+        for &index in order {
+            let lane = swimlanes.lane(&self.tasks[index]);
+            lanes.entry(lane).or_default().push(index);
+        }
+        if lanes.is_empty() {
+            return vec![(String::new(), Vec::new())];
+        }
+        lanes
+            .into_iter()
+            .map(|((_, name), tasks)| (name, tasks))
+            .collect()
+    }
+
+    /// The tasks in a status's column, out of `order`, which are indices
+    /// of tasks in the order the list is shown in.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let task = |status| Task { status, ..Task::default() };
+    /// let mut list = TaskList {
+    ///     tasks: vec![task(Status::NeedsAction), task(Status::InProgress), task(Status::NeedsAction)],
+    ///     ..Default::default()
+    /// };
+    /// let order = list.display_order(TaskSort::None);
+    /// let uuids: Vec<_> = list.tasks.iter().map(|task| task.uuid).collect();
+    /// assert_eq!(list.board_column(&Status::NeedsAction, &order), vec![0, 2]);
+    ///
+    /// // The last task is moved to the top of its column
+    /// list.move_on_board(uuids[2], &Status::NeedsAction, Some(uuids[0]));
+    /// assert_eq!(list.board_column(&Status::NeedsAction, &order), vec![2, 0]);
+    ///
+    /// // Then to the bottom of the in progress column
+    /// list.move_on_board(uuids[2], &Status::InProgress, None);
+    /// assert_eq!(list.board_column(&Status::NeedsAction, &order), vec![0]);
+    /// assert_eq!(list.board_column(&Status::InProgress, &order), vec![1, 2]);
+    /// assert_eq!(list.tasks[2].status, Status::InProgress);
+    /// ```
+    pub fn board_column(&self, status: &Status, order: &[usize]) -> Vec<usize> {
+        let mut column: Vec<usize> = order
+            .iter()
+            .copied()
+            .filter(|&index| self.tasks[index].status == *status)
+            .collect();
+        // The sort is stable, so tasks without a position stay in the list's order
+        column.sort_by_key(|&index| {
+            let position = self.tasks[index].board_position;
+            (position.is_none(), position)
+        });
+        column
+    }
+
+    // Moves a task into a status's column, giving it that status, just above another
+    // task in the column, or at the bottom. Every task in the column, including ones
+    // that aren't shown, is numbered again so they keep their new order.
+    pub fn move_on_board(&mut self, uuid: Uuid, status: &Status, before: Option<Uuid>) {
+        let Some(moved) = self.tasks.iter().position(|task| task.uuid == uuid) else {
+            return;
+        };
+        let every_task: Vec<usize> = (0..self.tasks.len()).collect();
+        let mut column = self.board_column(status, &every_task);
+        column.retain(|&index| index != moved);
+        let place = before
+            .and_then(|before| {
+                column
+                    .iter()
+                    .position(|&index| self.tasks[index].uuid == before)
+            })
+            .unwrap_or(column.len());
+        column.insert(place, moved);
+
+        let task = &mut self.tasks[moved];
+        if task.status != *status {
+            task.status = status.clone();
+            task.completed = *status == Status::Completed;
+        }
+        for (position, index) in (0..).zip(column) {
+            self.tasks[index].board_position = Some(position);
+        }
+    }
+}
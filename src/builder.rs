@@ -0,0 +1,150 @@
+// ----------------------------------------------------------------------------
+// Builder for making tasks one field at a time, which checks that the
+// fields are valid. Code that makes tasks this way doesn't have to change
+// every time a field is added to Task.
+// ----------------------------------------------------------------------------
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::task::*;
+
+// Reasons a task couldn't be built
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum TaskBuildError {
+    // The progress was more than MAX_PROGRESS
+    ProgressOutOfRange(u8),
+    // The priority was more than MAX_PRIORITY
+    PriorityOutOfRange(u8),
+}
+
+/// Builds a task one field at a time. Any field that isn't set
+/// is the same as in `Task::default()`.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::builder::TaskBuildError;
+/// use taskmaster_rs::task::*;
+///
+/// let due = chrono::NaiveDate::from_ymd_opt(2023, 8, 25).unwrap();
+/// let task = Task::builder()
+///     .summary("Send the report")
+///     .due(due)
+///     .priority(3)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(task.summary, "Send the report");
+/// assert_eq!(task.due, Some(due));
+/// assert_eq!(task.priority.get(), 3);
+///
+/// assert_eq!(
+///     Task::builder().progress(150).build(),
+///     Err(TaskBuildError::ProgressOutOfRange(150))
+/// );
+/// ```
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TaskBuilder {
+    task: Task,
+    // Kept as they're given until the task is built, so that they can be checked
+    progress: u8,
+    priority: u8,
+}
+
+impl Task {
+    #[must_use]
+    pub fn builder() -> TaskBuilder {
+        TaskBuilder::default()
+    }
+}
+
+impl TaskBuilder {
+    #[must_use]
+    pub const fn uuid(mut self, uuid: uuid::Uuid) -> Self {
+        self.task.uuid = uuid;
+        self
+    }
+
+    #[must_use]
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.task.summary = summary.into();
+        self
+    }
+
+    #[must_use]
+    pub const fn completed(mut self, completed: bool) -> Self {
+        self.task.completed = completed;
+        self
+    }
+
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.task.description = description.into();
+        self
+    }
+
+    #[must_use]
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.task.url = Some(url.into());
+        self
+    }
+
+    // How far through the task is, as a percentage up to MAX_PROGRESS
+    #[must_use]
+    pub const fn progress(mut self, progress: u8) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    // From 0 for no priority, up to MAX_PRIORITY
+    #[must_use]
+    pub const fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    #[must_use]
+    pub fn status(mut self, status: Status) -> Self {
+        self.task.status = status;
+        self
+    }
+
+    // Adds a tag, unless the task already has it
+    #[must_use]
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.task.tags.contains(&tag) {
+            self.task.tags.push(tag);
+        }
+        self
+    }
+
+    #[must_use]
+    pub const fn due(mut self, due: NaiveDate) -> Self {
+        self.task.due = Some(due);
+        self
+    }
+
+    #[must_use]
+    pub const fn created(mut self, created: NaiveDateTime) -> Self {
+        self.task.created = created;
+        self
+    }
+
+    // Schedules the task for a block of time on the day planner
+    #[must_use]
+    pub const fn scheduled(mut self, start: NaiveDateTime, minutes: u32) -> Self {
+        self.task.scheduled = Some(TimeBlock { start, minutes });
+        self
+    }
+
+    // Makes the task, if all of its fields are valid
+    pub fn build(mut self) -> Result<Task, TaskBuildError> {
+        self.task.progress = Percent::checked(self.progress)
+            .ok_or(TaskBuildError::ProgressOutOfRange(self.progress))?;
+        self.task.priority = Priority::checked(self.priority)
+            .ok_or(TaskBuildError::PriorityOutOfRange(self.priority))?;
+        Ok(self.task)
+    }
+}
@@ -0,0 +1,132 @@
+// ----------------------------------------------------------------------------
+// The color of a task list. The crate has its own color type, rather than
+// using egui's, so that lists can be read, written and tested without a UI.
+// Colors are written as hexadecimal, like #5382A3, in iCal files and JSON.
+// ----------------------------------------------------------------------------
+
+use std::fmt;
+
+use eframe::egui::Color32;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Writes the color as hexadecimal, like #5382A3.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::color::Color;
+    ///
+    /// assert_eq!(Color::from_rgb(83, 130, 163).to_hex(), "#5382A3");
+    /// assert_eq!(Color::from_rgb(1, 0, 12).to_hex(), "#01000C");
+    /// ```
+    pub fn to_hex(self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    /// Reads a color written as hexadecimal, with or without the #. Colors can
+    /// have two digits for each channel, or one digit which is doubled.
+    ///
+    /// Colors can also have an alpha channel, which apps put at either end:
+    /// Apple Calendar writes #RRGGBBAA, while some Android apps write #AARRGGBB.
+    /// Colors are almost always opaque, so whichever end is FF is taken to be
+    /// the alpha, and #RRGGBBAA is assumed if neither is. Lists are always drawn
+    /// opaque, so the alpha is left out.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::color::Color;
+    ///
+    /// assert_eq!(Color::from_hex("#5382A3"), Some(Color::from_rgb(83, 130, 163)));
+    /// assert_eq!(Color::from_hex("5382a3"), Some(Color::from_rgb(83, 130, 163)));
+    /// assert_eq!(Color::from_hex("#F80"), Some(Color::from_rgb(255, 136, 0)));
+    /// assert_eq!(Color::from_hex("#5382A3FF"), Some(Color::from_rgb(83, 130, 163)));
+    /// assert_eq!(Color::from_hex("#FF5382A3"), Some(Color::from_rgb(83, 130, 163)));
+    /// assert_eq!(Color::from_hex("#5382A380"), Some(Color::from_rgb(83, 130, 163)));
+    /// assert_eq!(Color::from_hex("#5382"), None);
+    /// assert_eq!(Color::from_hex("blue"), None);
+    /// assert_eq!(Color::from_hex("#+F+F+F"), None);
+    /// assert_eq!(Color::from_hex("+12345"), None);
+    /// ```
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.is_ascii() {
+            return None;
+        }
+        // from_str_radix allows a sign before the digits, which colors can't have
+        let channel = |digits: &str| {
+            if digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                u8::from_str_radix(digits, 16).ok()
+            } else {
+                None
+            }
+        };
+        match hex.len() {
+            6 => Some(Self::from_rgb(
+                channel(&hex[0..2])?,
+                channel(&hex[2..4])?,
+                channel(&hex[4..6])?,
+            )),
+            8 => {
+                let (rgb, alpha) = if hex[..2].eq_ignore_ascii_case("FF")
+                    && !hex[6..].eq_ignore_ascii_case("FF")
+                {
+                    (&hex[2..], &hex[..2])
+                } else {
+                    (&hex[..6], &hex[6..])
+                };
+                channel(alpha)?;
+                Self::from_hex(rgb)
+            }
+            // #F80 is short for #FF8800
+            3 => {
+                let short = |start: usize| channel(&hex[start..=start]).map(|digit| digit * 17);
+                Some(Self::from_rgb(short(0)?, short(1)?, short(2)?))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl From<Color> for Color32 {
+    fn from(color: Color) -> Self {
+        Self::from_rgb(color.r, color.g, color.b)
+    }
+}
+
+// Colors don't have any transparency, so it's lost
+impl From<Color32> for Color {
+    fn from(color: Color32) -> Self {
+        Self::from_rgb(color.r(), color.g(), color.b())
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Self::from_hex(&hex).ok_or_else(|| D::Error::custom(format!("invalid color '{hex}'")))
+    }
+}
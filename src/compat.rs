@@ -0,0 +1,231 @@
+// ----------------------------------------------------------------------------
+// Getting along with the iCal files other task apps write. Each app has its
+// own habits, which the importer has to cope with:
+//
+//   Apple Reminders   Alarms in every task, upper case UIDs, and its own
+//                     X-APPLE- properties
+//   Thunderbird       Time zone definitions before the tasks, alarms, and,
+//                     in files from older versions, long lines folded in the
+//                     middle of a character
+//   Nextcloud Tasks   Subtasks of tasks with UIDs that aren't UUIDs, when
+//                     they were made in other apps, and tags split over
+//                     several CATEGORIES lines
+//   OpenTasks         Time zone definitions, and parameters in any order,
+//                     including quoted ones before ENCODING
+//
+// Files from each of them are in tests/fixtures, and are checked to import
+// and export again without anything being lost.
+//
+// Apple Reminders' own properties are kept, and written back, so its lists
+// look the same after being through the app:
+//
+//   X-APPLE-SORT-ORDER   Where the task goes in the list's manual order, as
+//                        an INTEGER, with lower numbers first
+//   X-APPLE-FLAGGED      TRUE if the task is flagged, which the app calls pinned
+//
+// So is whether Nextcloud Tasks hides a task's subtasks, which it keeps in
+// X-OC-HIDESUBTASKS, as 1 if they're hidden. Nextcloud Tasks also expects a
+// task's PERCENT-COMPLETE to go along with its STATUS, so exported files can
+// have them put in step for it.
+// ----------------------------------------------------------------------------
+
+use crate::encoding;
+use crate::task::{Percent, Status, Task, TaskList, MAX_PROGRESS};
+
+pub const APPLE_SORT_ORDER: &str = "X-APPLE-SORT-ORDER";
+pub const APPLE_FLAGGED: &str = "X-APPLE-FLAGGED";
+pub const NEXTCLOUD_HIDE_SUBTASKS: &str = "X-OC-HIDESUBTASKS";
+
+// What UIDs that aren't UUIDs are turned into UUIDs with
+const UID_NAMESPACE: uuid::Uuid = uuid::Uuid::NAMESPACE_OID;
+
+/// The app that wrote an iCal file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Producer {
+    AppleReminders,
+    Thunderbird,
+    NextcloudTasks,
+    OpenTasks,
+    Taskmaster,
+    // An app that isn't known, or a file that doesn't say
+    Unknown,
+}
+
+impl Producer {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::AppleReminders => "Apple Reminders",
+            Self::Thunderbird => "Thunderbird",
+            Self::NextcloudTasks => "Nextcloud Tasks",
+            Self::OpenTasks => "OpenTasks",
+            Self::Taskmaster => "Taskmaster",
+            Self::Unknown => "Unknown app",
+        }
+    }
+}
+
+/// Works out which app wrote an iCal file, going by its PRODID.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::compat::{self, Producer};
+///
+/// let ical = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+///     PRODID:-//Mozilla.org/NONSGML Mozilla Calendar V1.1//EN\r\nEND:VCALENDAR\r\n";
+/// assert_eq!(compat::detect_producer(ical), Producer::Thunderbird);
+///
+/// let ical = "BEGIN:VCALENDAR\nPRODID:-//Apple Inc.//iOS 17.4//EN\nEND:VCALENDAR\n";
+/// assert_eq!(compat::detect_producer(ical), Producer::AppleReminders);
+///
+/// assert_eq!(compat::detect_producer("BEGIN:VCALENDAR\nEND:VCALENDAR\n"), Producer::Unknown);
+/// ```
+pub fn detect_producer(ical: &str) -> Producer {
+    let Some(product) = ical.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("PRODID")
+            .then(|| value.to_lowercase())
+    }) else {
+        return Producer::Unknown;
+    };
+
+    let made_by = |names: &[&str]| names.iter().any(|name| product.contains(name));
+    if made_by(&["taskmaster-rs"]) {
+        Producer::Taskmaster
+    } else if made_by(&["apple inc."]) {
+        Producer::AppleReminders
+    } else if made_by(&["mozilla.org", "thunderbird"]) {
+        Producer::Thunderbird
+    } else if made_by(&["nextcloud", "sabredav"]) {
+        Producer::NextcloudTasks
+    // OpenTasks syncs through DAVx5, which writes files with ical4android
+    } else if made_by(&["opentasks", "dmfs.org", "bitfire.at"]) {
+        Producer::OpenTasks
+    } else {
+        Producer::Unknown
+    }
+}
+
+/// The UUID a task with a UID gets. UIDs are usually UUIDs already, but
+/// ones that aren't are turned into the same UUID every time, so a task
+/// keeps its UUID when it's imported again and its subtasks can still find it.
+/// Empty UIDs don't give a UUID.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::compat;
+///
+/// let uuid = "ae02186d-10ae-404f-a4c9-450e06ea77cf";
+/// assert_eq!(compat::uid_to_uuid(uuid).unwrap().to_string(), uuid);
+/// assert_eq!(compat::uid_to_uuid("AE02186D-10AE-404F-A4C9-450E06EA77CF").unwrap().to_string(), uuid);
+///
+/// let uid = "20240501T101500Z-4821@example.com";
+/// assert_eq!(compat::uid_to_uuid(uid), compat::uid_to_uuid(uid));
+/// assert_eq!(compat::uid_to_uuid(" "), None);
+/// ```
+pub fn uid_to_uuid(uid: &str) -> Option<uuid::Uuid> {
+    let uid = uid.trim();
+    if uid.is_empty() {
+        return None;
+    }
+    Some(
+        uuid::Uuid::parse_str(uid)
+            .unwrap_or_else(|_| uuid::Uuid::new_v5(&UID_NAMESPACE, uid.as_bytes())),
+    )
+}
+
+/// Decodes the text of an iCal file, like `encoding::decode`, but first
+/// unfolds lines that were folded in the middle of a character. RFC 5545
+/// says not to, but some apps count bytes rather than characters, which
+/// leaves lines that aren't valid UTF-8 on their own.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::compat;
+///
+/// // é is 0xC3 0xA9 in UTF-8, and was folded between them
+/// let bytes = b"SUMMARY:Caf\xc3\r\n \xa9 au lait\r\n";
+/// assert_eq!(compat::decode(bytes), "SUMMARY:Café au lait\r\n");
+/// // Latin-1 is still decoded as Latin-1
+/// assert_eq!(compat::decode(b"SUMMARY:Caf\xe9\r\n"), "SUMMARY:Café\r\n");
+/// ```
+pub fn decode(bytes: &[u8]) -> String {
+    let text = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    if std::str::from_utf8(text).is_err() {
+        if let Ok(unfolded) = String::from_utf8(unfold(text)) {
+            return unfolded;
+        }
+    }
+    encoding::decode(bytes)
+}
+
+// Joins folded lines, which start with a space or a tab, onto the line before them
+fn unfold(bytes: &[u8]) -> Vec<u8> {
+    let mut unfolded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let newline = match &bytes[i..] {
+            [b'\r', b'\n', b' ' | b'\t', ..] => 3,
+            [b'\n', b' ' | b'\t', ..] => 2,
+            _ => 0,
+        };
+        if newline == 0 {
+            unfolded.push(bytes[i]);
+            i += 1;
+        } else {
+            i += newline;
+        }
+    }
+    unfolded
+}
+
+/// Puts a task's progress and status in step, the way Nextcloud Tasks keeps
+/// them: completed tasks are 100% done, tasks that are 100% done are completed,
+/// and tasks are in progress once they've been started and not before.
+/// Cancelled tasks and ones with custom statuses are left as they are.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::compat;
+/// use taskmaster_rs::task::*;
+///
+/// let mut task = Task { completed: true, status: Status::Completed, ..Task::default() };
+/// compat::nextcloud_progress(&mut task);
+/// assert_eq!(task.progress.get(), 100);
+///
+/// let mut task = Task { progress: Percent::new(30), status: Status::NeedsAction, ..Task::default() };
+/// compat::nextcloud_progress(&mut task);
+/// assert_eq!(task.status, Status::InProgress);
+///
+/// let mut task = Task { progress: Percent::new(100), ..Task::default() };
+/// compat::nextcloud_progress(&mut task);
+/// assert!(task.completed);
+/// assert_eq!(task.status, Status::Completed);
+/// ```
+pub fn nextcloud_progress(task: &mut Task) {
+    if task.progress.get() == MAX_PROGRESS && !task.completed {
+        if !matches!(task.status, Status::NeedsAction | Status::InProgress) {
+            return;
+        }
+        task.completed = true;
+        task.completed_at = Some(task.last_touched);
+    }
+    if task.completed {
+        task.status = Status::Completed;
+        task.progress = Percent::new(MAX_PROGRESS);
+        return;
+    }
+    // A task that was completed and then unchecked goes back to how far along it was
+    task.status = match (&task.status, task.progress.get()) {
+        (Status::InProgress | Status::Completed, 0) => Status::NeedsAction,
+        (Status::NeedsAction, 1..) | (Status::Completed, _) => Status::InProgress,
+        (status, _) => status.clone(),
+    };
+}
+
+// A copy of a list with every task's progress and status put in step for Nextcloud Tasks
+pub fn for_nextcloud_tasks(task_list: &TaskList) -> TaskList {
+    let mut task_list = task_list.clone();
+    task_list.tasks.iter_mut().for_each(nextcloud_progress);
+    task_list
+}
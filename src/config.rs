@@ -0,0 +1,333 @@
+// ----------------------------------------------------------------------------
+// A config.toml file for settings that power users might want to edit by
+// hand, like keyboard shortcuts and theme colors. It's kept in the same
+// folder as the app's other files, and is checked every few seconds so that
+// changes to it take effect without restarting the app.
+// ----------------------------------------------------------------------------
+
+use std::path::PathBuf;
+
+use eframe::egui;
+use egui::{Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::json;
+use crate::platform;
+
+// Name the config file is saved under
+pub const CONFIG_FILE: &str = "config.toml";
+// How often, in seconds, the config file is checked for changes
+const CHECK_INTERVAL: f64 = 2.;
+
+// Written to the config file the first time the app runs, so that there's
+// something to start from when editing it
+const TEMPLATE: &str = r##"# Taskmaster config. Changes are picked up while the app is running.
+
+# File to open when the app starts, if no list was open last time
+# default_list = "/home/me/tasks.ics"
+
+# How many days before a task is due to remind you about it
+# due_soon_days = 1
+
+[keybindings]
+# Shortcuts are modifiers and a key joined with +, like "Ctrl+Shift+O".
+# Ctrl is Cmd on macOS. Leave a shortcut empty to turn it off.
+open_file = "Ctrl+O"
+export = "Ctrl+E"
+reload = "F5"
+toggle_completed = "Ctrl+H"
+settings = "F10"
+
+[theme]
+# Colors are written as hexadecimal, like "#5382A3"
+dark = true
+# accent = "#5382A3"
+# background = "#1B1B1B"
+# text = "#DDDDDD"
+"##;
+
+/// Settings which are read from the config file.
+/// Anything left out of the file keeps its default.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::config::Config;
+///
+/// let config = Config::parse(
+///     r#"
+///     due_soon_days = 3
+///
+///     [keybindings]
+///     reload = "Ctrl+Shift+R"
+///     "#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(config.due_soon_days, Some(3));
+/// assert_eq!(config.keybindings.reload, "Ctrl+Shift+R");
+/// assert_eq!(config.keybindings.open_file, "Ctrl+O");
+/// assert!(Config::parse("due_soon_days = \"soon\"").is_err());
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub keybindings: Keybindings,
+    pub theme: Theme,
+    // File to open at startup, if no list was open last time
+    pub default_list: Option<PathBuf>,
+    // How many days before a task is due to remind the user about it,
+    // which replaces the setting in the settings window
+    pub due_soon_days: Option<u32>,
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+}
+
+// Keyboard shortcuts for things the app does, written like "Ctrl+O"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybindings {
+    pub open_file: String,
+    pub export: String,
+    pub reload: String,
+    pub toggle_completed: String,
+    pub settings: String,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            open_file: String::from("Ctrl+O"),
+            export: String::from("Ctrl+E"),
+            reload: String::from("F5"),
+            toggle_completed: String::from("Ctrl+H"),
+            settings: String::from("F10"),
+        }
+    }
+}
+
+// Something a keyboard shortcut can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shortcut {
+    OpenFile,
+    Export,
+    Reload,
+    ToggleCompleted,
+    Settings,
+}
+
+impl Keybindings {
+    // Finds which shortcuts have been pressed this frame, so that they can be
+    // handled. Shortcuts that can't be read are ignored.
+    pub fn pressed(&self, ctx: &egui::Context) -> Vec<Shortcut> {
+        let bindings = [
+            (&self.open_file, Shortcut::OpenFile),
+            (&self.export, Shortcut::Export),
+            (&self.reload, Shortcut::Reload),
+            (&self.toggle_completed, Shortcut::ToggleCompleted),
+            (&self.settings, Shortcut::Settings),
+        ];
+        bindings
+            .into_iter()
+            .filter_map(|(text, shortcut)| Some((parse_shortcut(text)?, shortcut)))
+            .filter(|(keys, _)| ctx.input_mut(|i| i.consume_shortcut(keys)))
+            .map(|(_, shortcut)| shortcut)
+            .collect()
+    }
+}
+
+// Colors that replace the ones in the app's default theme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub dark: bool,
+    // Used for selected things and links
+    pub accent: Option<String>,
+    // Used behind panels and windows
+    pub background: Option<String>,
+    pub text: Option<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            dark: true,
+            accent: None,
+            background: None,
+            text: None,
+        }
+    }
+}
+
+impl Theme {
+    // Changes the app's colors to the theme's. Colors that can't be read are left as they are.
+    pub fn apply(&self, ctx: &egui::Context) {
+        let mut visuals = if self.dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+
+        let color = |hex: Option<&str>| {
+            let hex = hex?;
+            let color = json::color_from_hex(hex);
+            if color.is_none() {
+                eprintln!("invalid color '{hex}' in {CONFIG_FILE}");
+            }
+            color
+        };
+        if let Some(accent) = color(self.accent.as_deref()) {
+            visuals.selection.bg_fill = accent;
+            visuals.hyperlink_color = accent;
+        }
+        if let Some(background) = color(self.background.as_deref()) {
+            visuals.panel_fill = background;
+            visuals.window_fill = background;
+        }
+        if let Some(text) = color(self.text.as_deref()) {
+            visuals.override_text_color = Some(text);
+        }
+
+        ctx.set_visuals(visuals);
+    }
+}
+
+/// Reads a keyboard shortcut written like "Ctrl+Shift+O". Ctrl and Cmd both
+/// mean the Cmd key on macOS and the Ctrl key everywhere else.
+/// Returns `None` for an empty shortcut, or one that can't be read.
+///
+/// Example:
+/// ```
+/// use eframe::egui::{Key, Modifiers};
+/// use taskmaster_rs::config::parse_shortcut;
+///
+/// let shortcut = parse_shortcut("ctrl+shift+o").unwrap();
+/// assert_eq!(shortcut.key, Key::O);
+/// assert!(shortcut.modifiers.command && shortcut.modifiers.shift);
+///
+/// assert_eq!(parse_shortcut("F5").unwrap().modifiers, Modifiers::NONE);
+/// assert!(parse_shortcut("Ctrl+Banana").is_none());
+/// assert!(parse_shortcut("").is_none());
+/// ```
+pub fn parse_shortcut(text: &str) -> Option<KeyboardShortcut> {
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let key = key_from_name(parts.pop()?)?;
+
+    let mut modifiers = Modifiers::NONE;
+    for part in parts {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "cmd" | "command" => modifiers.command = true,
+            "shift" => modifiers.shift = true,
+            "alt" | "option" => modifiers.alt = true,
+            _ => return None,
+        }
+    }
+    Some(KeyboardShortcut::new(modifiers, key))
+}
+
+// Finds the key with a name like "O", "5", "F5" or "Escape"
+#[rustfmt::skip]
+fn key_from_name(name: &str) -> Option<Key> {
+    const LETTERS: [Key; 26] = [
+        Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I,
+        Key::J, Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R,
+        Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    ];
+    const DIGITS: [Key; 10] = [
+        Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4,
+        Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+    ];
+    const FUNCTION_KEYS: [Key; 12] = [
+        Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6,
+        Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+    ];
+
+    let name = name.to_ascii_uppercase();
+    let mut chars = name.chars();
+    match (chars.next(), chars.as_str()) {
+        (Some(c @ 'A'..='Z'), "") => ('A'..='Z').position(|letter| letter == c).map(|i| LETTERS[i]),
+        (Some(c @ '0'..='9'), "") => ('0'..='9').position(|digit| digit == c).map(|i| DIGITS[i]),
+        (Some('F'), number) => {
+            let number: usize = number.parse().ok()?;
+            FUNCTION_KEYS.get(number.checked_sub(1)?).copied()
+        }
+        _ => match name.as_str() {
+            "ESCAPE" | "ESC" => Some(Key::Escape),
+            "ENTER" | "RETURN" => Some(Key::Enter),
+            "SPACE" => Some(Key::Space),
+            "TAB" => Some(Key::Tab),
+            "DELETE" => Some(Key::Delete),
+            "BACKSPACE" => Some(Key::Backspace),
+            _ => None,
+        },
+    }
+}
+
+// Reads the config file, writing the template to it first if there isn't one yet.
+// If the file can't be read, the defaults are used.
+pub fn load() -> Config {
+    let Some(text) = platform::read_state(CONFIG_FILE) else {
+        // Web builds have nowhere the user could edit the file
+        if platform::state_path(CONFIG_FILE).is_some() {
+            if let Err(e) = platform::write_state(CONFIG_FILE, TEMPLATE) {
+                eprintln!("could not write {CONFIG_FILE}: {e}");
+            }
+        }
+        return Config::default();
+    };
+    Config::parse(&text).unwrap_or_else(|e| {
+        eprintln!("could not read {CONFIG_FILE}: {e}");
+        Config::default()
+    })
+}
+
+// Watches the config file for changes
+#[derive(Default)]
+pub struct ConfigWatcher {
+    // What was in the file when it was last read
+    text: Option<String>,
+    // When the file was last checked, in seconds since the app started
+    last_check: f64,
+}
+
+impl ConfigWatcher {
+    // Starts watching from what's in the file now, so that the config that was
+    // just loaded isn't loaded again
+    pub fn new() -> Self {
+        Self {
+            text: platform::read_state(CONFIG_FILE),
+            last_check: 0.,
+        }
+    }
+
+    // Reads the file again every few seconds, and gives back the new config if it has changed.
+    // A config that can't be read is ignored, so that a half-finished edit
+    // doesn't undo the user's settings.
+    // Should be called once every frame.
+    pub fn check(&mut self, ctx: &egui::Context) -> Option<Config> {
+        let now = ctx.input(|i| i.time);
+        if now - self.last_check < CHECK_INTERVAL {
+            return None;
+        }
+        self.last_check = now;
+        // Keep checking even if nothing else makes the app repaint
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(CHECK_INTERVAL));
+
+        let text = platform::read_state(CONFIG_FILE);
+        if text == self.text {
+            return None;
+        }
+        self.text = text;
+
+        match Config::parse(self.text.as_deref().unwrap_or_default()) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("could not read {CONFIG_FILE}: {e}");
+                None
+            }
+        }
+    }
+}
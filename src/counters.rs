@@ -0,0 +1,185 @@
+// ----------------------------------------------------------------------------
+// Counts of the tasks in each list that still need doing and that are
+// overdue, which are shown in the sidebar. The open list is counted again
+// whenever the task watcher says it's changed, and the other lists the app
+// knows about are counted again when their files change.
+// ----------------------------------------------------------------------------
+
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::SystemTime;
+
+use chrono::NaiveDate;
+use eframe::egui;
+
+use crate::encryption;
+use crate::events::TaskEvent;
+use crate::list_file;
+use crate::platform;
+use crate::task::{TaskList, MAX_PRIORITY};
+
+// How often, in seconds, the other lists' files are checked for changes.
+// The open list is counted again this often too, in case it was replaced
+// without any events, like when a file is imported.
+const CHECK_INTERVAL: f64 = 5.;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ListCounts {
+    // Tasks that haven't been completed
+    pub pending: usize,
+    // Pending tasks that were due before today
+    pub overdue: usize,
+    // How many pending tasks have each priority, from 0 for no priority up to MAX_PRIORITY
+    pub by_priority: [usize; MAX_PRIORITY as usize + 1],
+}
+
+impl ListCounts {
+    /// Counts the tasks in a list.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::counters::ListCounts;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let today = chrono::Local::now().date_naive();
+    /// let list = TaskList {
+    ///     tasks: vec![
+    ///         Task { due: today.pred_opt(), priority: Priority::new(1), ..Task::default() },
+    ///         Task { due: Some(today), ..Task::default() },
+    ///         Task { due: today.pred_opt(), completed: true, ..Task::default() },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let counts = ListCounts::of(&list, today);
+    /// assert_eq!((counts.pending, counts.overdue), (2, 1));
+    /// assert_eq!(counts.by_priority[0], 1);
+    /// assert_eq!(counts.by_priority[1], 1);
+    /// ```
+    pub fn of(task_list: &TaskList, today: NaiveDate) -> Self {
+        let mut counts = Self::default();
+        for task in task_list.tasks.iter().filter(|task| !task.completed) {
+            counts.pending += 1;
+            if task.due.is_some_and(|due| due < today) {
+                counts.overdue += 1;
+            }
+            let priority = usize::from(task.priority.get());
+            counts.by_priority[priority] += 1;
+        }
+        counts
+    }
+
+    // Adds another list's counts to these, for the total across every list
+    pub fn add(&mut self, other: &Self) {
+        self.pending += other.pending;
+        self.overdue += other.overdue;
+        for (count, other) in self.by_priority.iter_mut().zip(other.by_priority) {
+            *count += other;
+        }
+    }
+}
+
+// A list that isn't open, which is counted from its file
+pub struct OtherList {
+    pub path: PathBuf,
+    pub name: String,
+    // When the file was last changed, as of when it was last counted
+    modified: Option<SystemTime>,
+    // Nothing if the list couldn't be counted, like when it's encrypted
+    pub counts: Option<ListCounts>,
+}
+
+#[derive(Default)]
+pub struct ListCounters {
+    events: Option<mpsc::Receiver<TaskEvent>>,
+    current: ListCounts,
+    others: Vec<OtherList>,
+    // The day the lists were counted on, since tasks become overdue as days pass
+    counted_on: Option<NaiveDate>,
+    // When the lists were last counted, in seconds since the app started
+    last_check: Option<f64>,
+}
+
+impl ListCounters {
+    // Counts the open list again whenever these events say it's changed
+    pub fn listen(&mut self, events: mpsc::Receiver<TaskEvent>) {
+        self.events = Some(events);
+    }
+
+    pub const fn current(&self) -> &ListCounts {
+        &self.current
+    }
+
+    pub fn others(&self) -> &[OtherList] {
+        &self.others
+    }
+
+    // Counts the lists again if they've changed. Should be called once every frame.
+    pub fn check(&mut self, ctx: &egui::Context, task_list: &TaskList) {
+        let changed = self
+            .events
+            .iter()
+            .flat_map(mpsc::Receiver::try_iter)
+            .count()
+            > 0;
+        let today = chrono::Local::now().date_naive();
+        let now = ctx.input(|i| i.time);
+        let new_day = self.counted_on != Some(today);
+        let due = self
+            .last_check
+            .map_or(true, |last| now - last >= CHECK_INTERVAL);
+
+        if changed || new_day || due {
+            self.current = ListCounts::of(task_list, today);
+        }
+        if new_day || due {
+            self.last_check = Some(now);
+            self.check_others(task_list, today, new_day);
+            self.counted_on = Some(today);
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(CHECK_INTERVAL));
+    }
+
+    // Counts the lists that aren't open whose files have changed, or all of them on a new day
+    fn check_others(&mut self, task_list: &TaskList, today: NaiveDate, recount: bool) {
+        let paths = list_file::known_lists()
+            .into_iter()
+            .filter(|path| Some(path) != task_list.path.as_ref());
+        let mut others = Vec::new();
+        for path in paths {
+            let modified = platform::file_modified(&path);
+            let previous = self
+                .others
+                .iter()
+                .position(|other| other.path == path)
+                .map(|index| self.others.swap_remove(index));
+            match previous {
+                Some(other) if !recount && other.modified == modified => others.push(other),
+                _ => others.push(count_file(path, modified, today)),
+            }
+        }
+        self.others = others;
+    }
+}
+
+// Counts the tasks in the list in a file
+fn count_file(path: PathBuf, modified: Option<SystemTime>, today: NaiveDate) -> OtherList {
+    let list = platform::read_file(&path)
+        .ok()
+        .filter(|data| !encryption::is_encrypted(data))
+        .and_then(|data| TaskList::from_ical_reader(data.as_slice()).ok());
+    let name = match &list {
+        Some(list) if !list.name.is_empty() => list.name.clone(),
+        _ => path
+            .file_stem()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned(),
+    };
+    OtherList {
+        counts: list.map(|list| ListCounts::of(&list, today)),
+        path,
+        name,
+        modified,
+    }
+}
@@ -0,0 +1,111 @@
+// ----------------------------------------------------------------------------
+// Detects tasks which look like ones that are already in a list, so that
+// adding the same task twice, or importing the same file again, doesn't
+// fill the list with copies. A task is a duplicate of another if they have
+// the same UUID, or the same summary and due date.
+// ----------------------------------------------------------------------------
+
+use std::slice::Iter;
+
+use crate::task::*;
+
+// What to do with a task that looks like one already in the list
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum DuplicateAction {
+    // Don't add the task
+    #[default]
+    Skip,
+    // Fill in anything the existing task is missing from the new one
+    Merge,
+    // Add the task anyway
+    KeepBoth,
+}
+
+impl DuplicateAction {
+    pub fn iterator() -> Iter<'static, Self> {
+        [Self::Skip, Self::Merge, Self::KeepBoth].iter()
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Skip => "Skip",
+            Self::Merge => "Merge",
+            Self::KeepBoth => "Add anyway",
+        }
+    }
+}
+
+/// Checks whether two tasks look like the same task.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::duplicates;
+/// use taskmaster_rs::task::Task;
+///
+/// let task = Task {
+///     summary: String::from("Call the bank"),
+///     ..Default::default()
+/// };
+/// let copy = Task {
+///     summary: String::from("call the bank "),
+///     ..Default::default()
+/// };
+/// let later = Task {
+///     due: chrono::NaiveDate::from_ymd_opt(2023, 8, 7),
+///     ..copy.clone()
+/// };
+///
+/// assert!(duplicates::is_duplicate(&task, &copy));
+/// assert!(!duplicates::is_duplicate(&task, &later));
+/// ```
+pub fn is_duplicate(a: &Task, b: &Task) -> bool {
+    a.uuid == b.uuid
+        || (a.due == b.due && a.summary.trim().to_lowercase() == b.summary.trim().to_lowercase())
+}
+
+// Finds the position of a task in the list which looks like the given one
+pub fn find(task_list: &TaskList, task: &Task) -> Option<usize> {
+    task_list
+        .tasks
+        .iter()
+        .position(|existing| is_duplicate(existing, task))
+}
+
+// Fills in anything the existing task is missing from another copy of it,
+// and keeps whichever copy is further along
+pub fn merge(existing: &mut Task, other: Task) {
+    if existing.description.trim().is_empty() {
+        existing.description = other.description;
+    }
+    if existing.url.is_none() {
+        existing.url = other.url;
+    }
+    if existing.due.is_none() {
+        existing.due = other.due;
+    }
+    if !existing.priority.is_set() {
+        existing.priority = other.priority;
+    }
+    if other.completed && !existing.completed {
+        existing.completed = true;
+        existing.status = other.status.clone();
+    }
+    existing.progress = existing.progress.max(other.progress);
+    existing.created = existing.created.min(other.created);
+}
+
+// Adds a task to a list, doing what the user picked if it's a duplicate of one already there
+pub fn add(task_list: &mut TaskList, task: Task, action: DuplicateAction) {
+    let Some(index) = find(task_list, &task) else {
+        task_list.add(task);
+        return;
+    };
+
+    match action {
+        DuplicateAction::Skip => (),
+        DuplicateAction::Merge => merge(&mut task_list.tasks[index], task),
+        // The list gives the task a new UUID if it has the same one as the existing task
+        DuplicateAction::KeepBoth => task_list.add(task),
+    }
+}
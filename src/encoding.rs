@@ -0,0 +1,141 @@
+// ----------------------------------------------------------------------------
+// Decoding of text in imported files which isn't plain UTF-8. Files from
+// older apps can be Latin-1 or UTF-16, and can have values which are
+// quoted-printable encoded, like `SUMMARY;ENCODING=QUOTED-PRINTABLE:Caf=C3=A9`.
+// ----------------------------------------------------------------------------
+
+/// Decodes the text of a file, working out which encoding it's in.
+/// UTF-8 and UTF-16 are recognised, and anything else is treated as Latin-1,
+/// which every sequence of bytes is valid in.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::encoding;
+///
+/// assert_eq!(encoding::decode("Café".as_bytes()), "Café");
+/// // Latin-1
+/// assert_eq!(encoding::decode(b"Caf\xe9"), "Café");
+/// // UTF-16, little endian
+/// assert_eq!(encoding::decode(b"\xff\xfeC\0a\0f\0\xe9\0"), "Café");
+/// ```
+pub fn decode(bytes: &[u8]) -> String {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => decode(rest),
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        _ => std::str::from_utf8(bytes).map_or_else(|_| decode_latin1(bytes), str::to_string),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+// Every Latin-1 byte is the Unicode code point with the same number
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&byte| char::from(byte)).collect()
+}
+
+/// Decodes a quoted-printable value, then decodes the bytes it contains
+/// in the given character set, if there is one.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::encoding;
+///
+/// assert_eq!(encoding::decode_quoted_printable("Caf=C3=A9", None), "Café");
+/// assert_eq!(encoding::decode_quoted_printable("Caf=E9", Some("ISO-8859-1")), "Café");
+/// ```
+pub fn decode_quoted_printable(value: &str, charset: Option<&str>) -> String {
+    let input = value.as_bytes();
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        let hex = input
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (input[i], hex) {
+            (b'=', Some(byte)) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            // A soft line break, which is left over at the end of a value
+            (b'=', None) if i + 1 == input.len() => i += 1,
+            (byte, _) => {
+                bytes.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    match charset {
+        Some(charset) if !charset.eq_ignore_ascii_case("UTF-8") => decode_latin1(&bytes),
+        _ => decode(&bytes),
+    }
+}
+
+/// Joins lines that were split with a quoted-printable soft line break.
+///
+/// A soft line break is an `=` at the end of a line. Unlike folded lines, the next
+/// line doesn't start with a space, so it has to be joined before the file is parsed.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::encoding;
+///
+/// let text = "DESCRIPTION;ENCODING=QUOTED-PRINTABLE:One=\r\n two=0D=0A=\r\nthree\r\nEND:VTODO\r\n";
+/// assert_eq!(
+///     encoding::join_soft_line_breaks(text),
+///     "DESCRIPTION;ENCODING=QUOTED-PRINTABLE:One two=0D=0Athree\nEND:VTODO\n",
+/// );
+///
+/// // Quoted parameters can have colons in them, before the one the value starts after
+/// let text = "DESCRIPTION;ALTREP=\"cid:note\";ENCODING=QUOTED-PRINTABLE:One=\r\ntwo\r\n";
+/// assert_eq!(
+///     encoding::join_soft_line_breaks(text),
+///     "DESCRIPTION;ALTREP=\"cid:note\";ENCODING=QUOTED-PRINTABLE:Onetwo\n",
+/// );
+/// ```
+pub fn join_soft_line_breaks(text: &str) -> String {
+    let mut joined = String::with_capacity(text.len());
+    // Whether the line being joined is a quoted-printable value which continues on the next line
+    let mut continues = false;
+    for line in text.lines() {
+        let quoted_printable = continues
+            || name_and_params(line)
+                .is_some_and(|name| name.to_uppercase().contains("QUOTED-PRINTABLE"));
+
+        match line.strip_suffix('=') {
+            Some(start) if quoted_printable => {
+                joined.push_str(start);
+                continues = true;
+            }
+            _ => {
+                joined.push_str(line);
+                joined.push('\n');
+                continues = false;
+            }
+        }
+    }
+    joined
+}
+
+// The start of a line, before the colon its value starts after, which is the
+// first one that isn't in a quoted parameter
+fn name_and_params(line: &str) -> Option<&str> {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some(&line[..index]),
+            _ => (),
+        }
+    }
+    None
+}
@@ -0,0 +1,156 @@
+// ----------------------------------------------------------------------------
+// Optional encryption of saved and exported task lists, using a passphrase.
+// A key is derived from the passphrase with Argon2, and lists are encrypted
+// with ChaCha20-Poly1305. The key is only derived once, when the passphrase
+// is entered, so that saving an encrypted list doesn't slow the app down.
+//
+// Encrypted lists are stored as text, so they can go anywhere a list can:
+//   -----BEGIN TASKMASTER ENCRYPTED LIST-----
+//   <hex encoded salt, nonce and encrypted list>
+//   -----END TASKMASTER ENCRYPTED LIST-----
+// ----------------------------------------------------------------------------
+
+use std::fmt::Write;
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+const HEADER: &str = "-----BEGIN TASKMASTER ENCRYPTED LIST-----";
+const FOOTER: &str = "-----END TASKMASTER ENCRYPTED LIST-----";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum EncryptionError {
+    // The passphrase isn't the one the list was encrypted with
+    WrongPassphrase,
+    // The data isn't an encrypted list
+    InvalidData,
+}
+
+/// Checks whether some data is an encrypted list, which needs a passphrase to read.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::encryption;
+///
+/// assert!(encryption::is_encrypted(b"-----BEGIN TASKMASTER ENCRYPTED LIST-----\n..."));
+/// assert!(!encryption::is_encrypted(b"BEGIN:VCALENDAR\n..."));
+/// ```
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.trim_ascii_start().starts_with(HEADER.as_bytes())
+}
+
+// A key derived from a passphrase, which lists are encrypted and decrypted with
+#[derive(Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct EncryptionKey {
+    // Random data mixed into the passphrase, so that the same passphrase
+    // doesn't always give the same key
+    salt: [u8; SALT_LENGTH],
+    key: Key,
+}
+
+impl EncryptionKey {
+    // Derives a new key from a passphrase, for encrypting lists
+    pub fn new(passphrase: &str) -> Self {
+        let mut salt = [0; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, salt)
+    }
+
+    // Derives the key that an encrypted list was encrypted with from a passphrase.
+    // Whether the passphrase is the right one is only known once the list is decrypted.
+    pub fn for_list(passphrase: &str, data: &[u8]) -> Result<Self, EncryptionError> {
+        let (salt, _, _) = split(&unarmor(data)?)?;
+        Ok(Self::derive(passphrase, salt))
+    }
+
+    // Uses a key that's already random, like one kept in a file, as it is. Only
+    // passphrases need deriving, so keys like this don't have a salt, which is left as zeros.
+    pub fn from_bytes(key: [u8; 32]) -> Self {
+        Self {
+            salt: [0; SALT_LENGTH],
+            key: *Key::from_slice(&key),
+        }
+    }
+
+    fn derive(passphrase: &str, salt: [u8; SALT_LENGTH]) -> Self {
+        let mut key = Key::default();
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .expect("salt and key lengths should be valid for Argon2");
+        Self { salt, key }
+    }
+
+    // Encrypts a list
+    pub fn encrypt(&self, data: &[u8]) -> Vec<u8> {
+        let cipher = ChaCha20Poly1305::new(&self.key);
+        // A new nonce is used every time, so that the same key can be reused safely
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let encrypted = cipher
+            .encrypt(&nonce, data)
+            .expect("list should not be too long to encrypt");
+
+        let mut text = format!("{HEADER}\n");
+        for byte in self.salt.iter().chain(nonce.as_slice()).chain(&encrypted) {
+            let _ = write!(text, "{byte:02x}");
+        }
+        let _ = writeln!(text, "\n{FOOTER}");
+        text.into_bytes()
+    }
+
+    // Decrypts a list that was encrypted with this key
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let bytes = unarmor(data)?;
+        let (salt, nonce, encrypted) = split(&bytes)?;
+        // A different salt means the list was encrypted with a different key
+        if salt != self.salt {
+            return Err(EncryptionError::WrongPassphrase);
+        }
+
+        ChaCha20Poly1305::new(&self.key)
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| EncryptionError::WrongPassphrase)
+    }
+}
+
+// Gets the bytes from between the header and footer of an encrypted list
+fn unarmor(data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    let text = std::str::from_utf8(data).map_err(|_| EncryptionError::InvalidData)?;
+    let hex: String = text
+        .trim()
+        .strip_prefix(HEADER)
+        .and_then(|text| text.strip_suffix(FOOTER))
+        .ok_or(EncryptionError::InvalidData)?
+        .split_whitespace()
+        .collect();
+
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or(EncryptionError::InvalidData)
+        })
+        .collect()
+}
+
+// The salt, nonce and encrypted data that make up an encrypted list
+type Parts<'a> = ([u8; SALT_LENGTH], &'a [u8], &'a [u8]);
+
+// Splits an encrypted list into its salt, nonce and encrypted data
+fn split(bytes: &[u8]) -> Result<Parts<'_>, EncryptionError> {
+    if bytes.len() < SALT_LENGTH + NONCE_LENGTH {
+        return Err(EncryptionError::InvalidData);
+    }
+    let (salt, rest) = bytes.split_at(SALT_LENGTH);
+    let (nonce, encrypted) = rest.split_at(NONCE_LENGTH);
+    let salt = salt.try_into().map_err(|_| EncryptionError::InvalidData)?;
+    Ok((salt, nonce, encrypted))
+}
@@ -0,0 +1,189 @@
+// ----------------------------------------------------------------------------
+// Events which are sent whenever a task in the list is added, removed,
+// changed or completed. Parts of the app that react to changes, like hooks,
+// subscribe to the events, rather than the UI code telling each of them
+// about every change it makes.
+// ----------------------------------------------------------------------------
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc;
+
+use chrono::NaiveDateTime;
+
+use crate::task::*;
+
+// Something that happened to a task, with the task as it is now,
+// or as it was before it was removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum TaskEvent {
+    Added(Task),
+    Removed(Task),
+    // Anything about the task changed, other than it being completed
+    Updated(Task),
+    Completed(Task),
+}
+
+impl TaskEvent {
+    pub const fn task(&self) -> &Task {
+        match self {
+            Self::Added(task)
+            | Self::Removed(task)
+            | Self::Updated(task)
+            | Self::Completed(task) => task,
+        }
+    }
+}
+
+/// Watches a task list for changes, and sends an event for each one
+/// to everything that has subscribed.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::events::{TaskEvent, TaskWatcher};
+/// use taskmaster_rs::task::*;
+///
+/// let mut list = TaskList::default();
+/// let mut watcher = TaskWatcher::default();
+/// let events = watcher.subscribe();
+///
+/// list.add(Task::default());
+/// watcher.check(&list);
+/// assert!(matches!(events.try_recv(), Ok(TaskEvent::Added(_))));
+///
+/// list.tasks[0].completed = true;
+/// watcher.check(&list);
+/// assert!(matches!(events.try_recv(), Ok(TaskEvent::Completed(_))));
+///
+/// list.tasks.clear();
+/// watcher.check(&list);
+/// assert!(matches!(events.try_recv(), Ok(TaskEvent::Removed(_))));
+/// assert!(events.try_recv().is_err());
+/// ```
+#[derive(Default)]
+pub struct TaskWatcher {
+    // Every task as it was the last time the list was checked
+    tasks: HashMap<uuid::Uuid, Task>,
+    subscribers: Vec<mpsc::Sender<TaskEvent>>,
+}
+
+impl TaskWatcher {
+    // Gives back a receiver which every event from now on is sent to
+    pub fn subscribe(&mut self) -> mpsc::Receiver<TaskEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    // Treats the given list as the new starting point without sending any events.
+    // This is used when a whole list is replaced, so that importing a file
+    // doesn't count as adding every task in it.
+    pub fn reset(&mut self, task_list: &TaskList) {
+        self.tasks = task_list
+            .tasks
+            .iter()
+            .map(|task| (task.uuid, task.clone()))
+            .collect();
+    }
+
+    /// Marks every task that has changed since the list was last checked as
+    /// touched at the given time, so that tasks which haven't been touched
+    /// for a long time can be found. Tasks that were completed are stamped
+    /// with the time too, and so is the list if any task was added, changed
+    /// or removed. Should be called just before `check`.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use taskmaster_rs::events::TaskWatcher;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let mut list = TaskList::default();
+    /// list.add(Task::default());
+    /// list.add(Task::default());
+    /// let mut watcher = TaskWatcher::default();
+    /// watcher.check(&list);
+    ///
+    /// let now = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap().and_hms_opt(9, 0, 0).unwrap();
+    /// list.tasks[0].summary = String::from("Changed");
+    /// list.tasks[1].completed = true;
+    /// watcher.touch_changed(&mut list, now);
+    ///
+    /// assert_eq!(list.tasks[0].last_touched, now);
+    /// assert_eq!(list.tasks[0].completed_at, None);
+    /// assert_eq!(list.tasks[1].completed_at, Some(now));
+    /// assert_eq!(list.modified, now);
+    /// ```
+    pub fn touch_changed(&self, task_list: &mut TaskList, now: NaiveDateTime) {
+        let mut changed = false;
+        let mut seen = HashSet::with_capacity(task_list.tasks.len());
+        for task in &mut task_list.tasks {
+            seen.insert(task.uuid);
+            // Completed tasks are stamped with when they were completed, which
+            // is taken away again if they're marked as not completed
+            match (task.completed, task.completed_at) {
+                (true, None) => task.completed_at = Some(now),
+                (false, Some(_)) => task.completed_at = None,
+                _ => (),
+            }
+
+            let Some(previous) = self.tasks.get(&task.uuid) else {
+                changed = true;
+                continue;
+            };
+            // Being touched isn't a change in itself, so it's left out of the comparison
+            let touched = std::mem::replace(&mut task.last_touched, previous.last_touched);
+            if task == previous {
+                task.last_touched = touched;
+            } else {
+                task.last_touched = now;
+                changed = true;
+            }
+        }
+        // Tasks were removed if fewer of them are left than were in the list last time
+        if changed || seen.len() != self.tasks.len() {
+            task_list.modified = now;
+        }
+    }
+
+    // Finds what has changed in the list since it was last checked, and sends an event for each change.
+    // Should be called once every frame, after anything that changes the list.
+    pub fn check(&mut self, task_list: &TaskList) {
+        let mut seen = HashSet::with_capacity(task_list.tasks.len());
+        for task in &task_list.tasks {
+            // Tasks which share a UUID can't be told apart, so only the first one is watched
+            if !seen.insert(task.uuid) {
+                continue;
+            }
+
+            let event = match self.tasks.get(&task.uuid) {
+                None => TaskEvent::Added(task.clone()),
+                Some(previous) if task.completed && !previous.completed => {
+                    TaskEvent::Completed(task.clone())
+                }
+                Some(previous) if previous != task => TaskEvent::Updated(task.clone()),
+                Some(_) => continue,
+            };
+            self.tasks.insert(task.uuid, task.clone());
+            self.send(&event);
+        }
+
+        let removed: Vec<uuid::Uuid> = self
+            .tasks
+            .keys()
+            .filter(|uuid| !seen.contains(uuid))
+            .copied()
+            .collect();
+        for uuid in removed {
+            if let Some(task) = self.tasks.remove(&uuid) {
+                self.send(&TaskEvent::Removed(task));
+            }
+        }
+    }
+
+    // Sends an event to every subscriber, forgetting any that have stopped listening
+    fn send(&mut self, event: &TaskEvent) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
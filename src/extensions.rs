@@ -0,0 +1,131 @@
+// ----------------------------------------------------------------------------
+// The properties the app adds to the iCal files it writes, beyond the ones in
+// RFC 5545. Other apps ignore them, but they let the app put a list back the
+// way it was when the same file is opened on another computer.
+//
+// Properties of the list, in the VCALENDAR:
+//
+//   X-TASKMASTER-LIST-UID        The list's UID, which stays the same when it's
+//                                renamed or moved
+//   X-TASKMASTER-LIST-CREATED    When the list was made, as a local DATE-TIME
+//   X-TASKMASTER-LIST-MODIFIED   When a task in the list was last added, changed
+//                                or removed, as a local DATE-TIME
+//   X-TASKMASTER-VIEW            The name of the view the list is shown in, as TEXT
+//   X-TASKMASTER-SORT            What the list is sorted by, like DUE. It has the
+//                                parameter ORDER=DESCENDING if it's sorted backwards.
+//   X-TASKMASTER-FILTER          Which filter the list is shown with, like STALE
+//   X-TASKMASTER-SHOW-COMPLETED  TRUE if completed tasks are shown, or FALSE
+//   X-TASKMASTER-TAG-COLOR       A tag's color as hexadecimal, then a semicolon
+//                                and the tag's name as TEXT, like #5382A3;work.
+//                                There's one for each tag used in the list.
+//   X-TASKMASTER-WIP-LIMIT       A kanban column's work in progress limit as an
+//                                INTEGER, then a semicolon and the column's status,
+//                                like 3;IN-PROCESS. There's one for each column
+//                                that has a limit.
+//   X-TASKMASTER-BLOCK-FULL      TRUE if cards can't be dropped into kanban columns
+//                                that are at their limit, or FALSE
+//   X-TASKMASTER-SWIMLANES       What the kanban board is split into swimlanes by,
+//                                like PRIORITY
+//   X-TASKMASTER-EXPANDED-CARDS  The UIDs of the tasks whose cards on the kanban
+//                                board are expanded, separated by commas. It's
+//                                left out if none are.
+//
+// Properties of a task, in a VTODO:
+//
+//   X-TASKMASTER-DURATION        How long the task is scheduled for, as a DURATION
+//   X-TASKMASTER-ESTIMATE        How long the task is expected to take, as a DURATION
+//   X-TASKMASTER-MY-DAY          The DATE the task was picked for My Day
+//   X-TASKMASTER-BOARD-POSITION  Where the task is in its status's column on the
+//                                kanban board, as an INTEGER, with lower numbers first
+//
+// The view, sort, filter, whether completed tasks are shown and the kanban
+// board's limits, swimlanes and expanded cards make up the list's layout, and are written together. Values the app doesn't know, like
+// ones written by a newer version, are ignored.
+// ----------------------------------------------------------------------------
+
+use crate::board::Swimlanes;
+use crate::filters::TaskFilter;
+use crate::task::TaskSort;
+
+pub const LIST_UID: &str = "X-TASKMASTER-LIST-UID";
+pub const LIST_CREATED: &str = "X-TASKMASTER-LIST-CREATED";
+pub const LIST_MODIFIED: &str = "X-TASKMASTER-LIST-MODIFIED";
+pub const VIEW: &str = "X-TASKMASTER-VIEW";
+pub const SORT: &str = "X-TASKMASTER-SORT";
+pub const FILTER: &str = "X-TASKMASTER-FILTER";
+pub const SHOW_COMPLETED: &str = "X-TASKMASTER-SHOW-COMPLETED";
+pub const TAG_COLOR: &str = "X-TASKMASTER-TAG-COLOR";
+pub const WIP_LIMIT: &str = "X-TASKMASTER-WIP-LIMIT";
+pub const BLOCK_FULL: &str = "X-TASKMASTER-BLOCK-FULL";
+pub const SWIMLANES: &str = "X-TASKMASTER-SWIMLANES";
+pub const EXPANDED_CARDS: &str = "X-TASKMASTER-EXPANDED-CARDS";
+
+pub const DURATION: &str = "X-TASKMASTER-DURATION";
+pub const ESTIMATE: &str = "X-TASKMASTER-ESTIMATE";
+pub const MY_DAY: &str = "X-TASKMASTER-MY-DAY";
+pub const BOARD_POSITION: &str = "X-TASKMASTER-BOARD-POSITION";
+
+// The parameter of X-TASKMASTER-SORT saying which way the list is sorted,
+// and its value when the list is sorted backwards
+pub const ORDER: &str = "ORDER";
+pub const DESCENDING: &str = "DESCENDING";
+
+/// What a sort is written as in X-TASKMASTER-SORT.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::extensions;
+/// use taskmaster_rs::task::TaskSort;
+///
+/// assert_eq!(extensions::sort_name(TaskSort::Due), "DUE");
+/// assert_eq!(extensions::sort_from_name("due"), Some(TaskSort::Due));
+/// assert_eq!(extensions::sort_from_name("COLOR"), None);
+/// ```
+pub const fn sort_name(sort: TaskSort) -> &'static str {
+    match sort {
+        TaskSort::None => "NONE",
+        TaskSort::Summary => "SUMMARY",
+        TaskSort::Completed => "COMPLETED",
+        TaskSort::Description => "DESCRIPTION",
+        TaskSort::Progress => "PROGRESS",
+        TaskSort::Priority => "PRIORITY",
+        TaskSort::Status => "STATUS",
+        TaskSort::Due => "DUE",
+        TaskSort::Manual => "MANUAL",
+    }
+}
+
+pub fn sort_from_name(name: &str) -> Option<TaskSort> {
+    TaskSort::iterator()
+        .copied()
+        .find(|sort| sort_name(*sort).eq_ignore_ascii_case(name.trim()))
+}
+
+// What a filter is written as in X-TASKMASTER-FILTER
+pub const fn filter_name(filter: TaskFilter) -> &'static str {
+    match filter {
+        TaskFilter::All => "ALL",
+        TaskFilter::Stale => "STALE",
+    }
+}
+
+pub fn filter_from_name(name: &str) -> Option<TaskFilter> {
+    TaskFilter::iterator()
+        .copied()
+        .find(|filter| filter_name(*filter).eq_ignore_ascii_case(name.trim()))
+}
+
+// What swimlanes are written as in X-TASKMASTER-SWIMLANES
+pub const fn swimlanes_name(swimlanes: Swimlanes) -> &'static str {
+    match swimlanes {
+        Swimlanes::None => "NONE",
+        Swimlanes::Tag => "TAG",
+        Swimlanes::Priority => "PRIORITY",
+    }
+}
+
+pub fn swimlanes_from_name(name: &str) -> Option<Swimlanes> {
+    Swimlanes::iterator()
+        .copied()
+        .find(|swimlanes| swimlanes_name(*swimlanes).eq_ignore_ascii_case(name.trim()))
+}
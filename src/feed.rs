@@ -0,0 +1,247 @@
+// ----------------------------------------------------------------------------
+// Serves the list as a calendar feed, at http://localhost:PORT/taskmaster.ics,
+// so that calendar apps on the same computer, or on the same network if the
+// user allows it, can subscribe to it and see changes as they're made. The
+// feed can only be read, and only by someone who knows its token, which is
+// part of the feed's URL since calendar apps can't be given a password for
+// a subscription. The token is kept with the app's other secrets.
+//
+// Serving the feed needs the app to be built with the `server` feature.
+// ----------------------------------------------------------------------------
+
+#[cfg(feature = "server")]
+use std::{
+    error::Error,
+    io::Cursor,
+    sync::{
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+#[cfg(feature = "server")]
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use tiny_http::{Header, Method, Request, Response};
+
+#[cfg(feature = "server")]
+use crate::task::TaskList;
+
+// Where the feed is served from
+pub const FEED_PATH: &str = "/taskmaster.ics";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct FeedSettings {
+    pub enabled: bool,
+    pub port: u16,
+    // Whether other devices on the network can subscribe, rather than only this computer
+    pub on_network: bool,
+    // Has to be given in the feed's URL for the feed to be served
+    #[serde(skip)]
+    pub token: String,
+}
+
+impl Default for FeedSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7879,
+            on_network: false,
+            token: String::new(),
+        }
+    }
+}
+
+impl FeedSettings {
+    // The address the feed is served on
+    pub fn address(&self) -> String {
+        let host = if self.on_network {
+            "0.0.0.0"
+        } else {
+            "127.0.0.1"
+        };
+        format!("{host}:{}", self.port)
+    }
+
+    /// The URL calendar apps on this computer subscribe to.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::feed::FeedSettings;
+    ///
+    /// let settings = FeedSettings { token: String::from("abc123"), ..Default::default() };
+    /// assert_eq!(settings.url(), "http://localhost:7879/taskmaster.ics?token=abc123");
+    /// ```
+    pub fn url(&self) -> String {
+        format!(
+            "http://localhost:{}{FEED_PATH}?token={}",
+            self.port, self.token
+        )
+    }
+}
+
+// Makes a token that's too long to guess
+pub fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Whether a request's URL asks for the feed with the right token.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::feed;
+///
+/// assert!(feed::authorized("/taskmaster.ics?token=abc123", "abc123"));
+/// assert!(feed::authorized("/taskmaster.ics?refresh=1&token=abc123", "abc123"));
+/// assert!(!feed::authorized("/taskmaster.ics?token=abc12", "abc123"));
+/// assert!(!feed::authorized("/taskmaster.ics", "abc123"));
+/// // Without a token, nobody can read the feed
+/// assert!(!feed::authorized("/taskmaster.ics?token=", ""));
+/// ```
+pub fn authorized(url: &str, token: &str) -> bool {
+    let Some((_, query)) = url.split_once('?') else {
+        return false;
+    };
+    let given = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .unwrap_or_default();
+    tokens_match(given, token)
+}
+
+// Whether a token that was given is the right one. An empty token is never right.
+pub fn tokens_match(given: &str, token: &str) -> bool {
+    // Compare every byte, so the time it takes doesn't give away how much of the token was right
+    !token.is_empty()
+        && given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |differences, (a, b)| differences | (a ^ b))
+            == 0
+}
+
+#[cfg(feature = "server")]
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+// Handle to a server that's serving the feed. Like the app's HTTP server, requests
+// are received on a background thread and answered on the UI thread, which has the list.
+#[cfg(feature = "server")]
+struct FeedServer {
+    address: String,
+    server: Arc<tiny_http::Server>,
+    requests: Receiver<Request>,
+}
+
+#[cfg(feature = "server")]
+impl FeedServer {
+    fn start(address: &str, ctx: egui::Context) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let server = Arc::new(tiny_http::Server::http(address)?);
+        let (sender, requests) = mpsc::channel();
+
+        let listener = Arc::clone(&server);
+        thread::spawn(move || {
+            for request in listener.incoming_requests() {
+                if sender.send(request).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+            }
+        });
+
+        Ok(Self {
+            address: address.to_string(),
+            server,
+            requests,
+        })
+    }
+}
+
+#[cfg(feature = "server")]
+impl Drop for FeedServer {
+    // Stops listening, so that the port can be used again
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+// Serves the feed while it's turned on in the settings
+#[cfg(feature = "server")]
+#[derive(Default)]
+pub struct CalendarFeed {
+    server: Option<FeedServer>,
+    // The address the feed last couldn't be served on, so it isn't tried again every frame
+    failed: Option<String>,
+}
+
+#[cfg(feature = "server")]
+impl CalendarFeed {
+    // Starts or stops serving the feed as the settings change, and answers
+    // any requests for it. Should be called once every frame.
+    pub fn check(&mut self, ctx: &egui::Context, task_list: &TaskList, settings: &FeedSettings) {
+        if !settings.enabled {
+            self.server = None;
+            self.failed = None;
+            return;
+        }
+
+        let address = settings.address();
+        let serving = self.server.as_ref().map(|server| &server.address);
+        if serving != Some(&address) && self.failed.as_ref() != Some(&address) {
+            // Stop serving on the old address before starting on the new one,
+            // in case only the host changed
+            self.server = None;
+            match FeedServer::start(&address, ctx.clone()) {
+                Ok(server) => {
+                    self.server = Some(server);
+                    self.failed = None;
+                }
+                Err(e) => {
+                    eprintln!("could not serve the calendar feed on {address}: {e}");
+                    self.failed = Some(address);
+                }
+            }
+        }
+
+        if let Some(server) = &self.server {
+            while let Ok(request) = server.requests.try_recv() {
+                let response = respond(&request, task_list, &settings.token);
+                // If the calendar app has already disconnected, there's nobody to tell
+                let _ = request.respond(response);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+fn respond(request: &Request, task_list: &TaskList, token: &str) -> HttpResponse {
+    let url = request.url();
+    let path = url.split('?').next().unwrap_or_default();
+    if path != FEED_PATH {
+        return text(404, "not found");
+    }
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        return text(405, "the feed can only be read");
+    }
+    if !authorized(url, token) {
+        return text(401, "the feed's URL needs the right token");
+    }
+    Response::from_string(task_list.to_ical_string()).with_header(content_type("text/calendar"))
+}
+
+#[cfg(feature = "server")]
+fn text(status: u16, message: &str) -> HttpResponse {
+    Response::from_string(message)
+        .with_status_code(status)
+        .with_header(content_type("text/plain"))
+}
+
+#[cfg(feature = "server")]
+fn content_type(value: &str) -> Header {
+    Header::from_bytes("Content-Type", format!("{value}; charset=utf-8"))
+        .expect("content type header should be valid")
+}
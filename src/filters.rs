@@ -0,0 +1,200 @@
+// ----------------------------------------------------------------------------
+// Built-in filters, which narrow a task view down to the tasks that need
+// a particular kind of attention, like ones that have been forgotten about.
+//
+// Quick filters are chips above the task list, which narrow it down further
+// and can be turned on together. Chips about when tasks are due, like Overdue
+// and Today, show tasks that match any of them, as do the chips for tags,
+// while tasks have to match each kind of chip that's on.
+// ----------------------------------------------------------------------------
+
+use std::collections::BTreeSet;
+use std::slice::Iter;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::task::Task;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub enum TaskFilter {
+    #[default]
+    All,
+    // Open tasks that haven't been touched for a while
+    Stale,
+}
+
+impl TaskFilter {
+    pub fn iterator() -> Iter<'static, Self> {
+        [Self::All, Self::Stale].iter()
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::All => "All tasks",
+            Self::Stale => "Stale tasks",
+        }
+    }
+
+    /// Whether a task is shown when the filter is being used.
+    /// Tasks are stale after `stale_days` days without being touched.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::{Duration, Local};
+    /// use taskmaster_rs::filters::TaskFilter;
+    /// use taskmaster_rs::task::Task;
+    ///
+    /// let now = Local::now().naive_local();
+    /// let old = Task { last_touched: now - Duration::days(30), ..Task::default() };
+    /// let new = Task { last_touched: now, ..Task::default() };
+    ///
+    /// assert!(TaskFilter::Stale.matches(&old, now, 14));
+    /// assert!(!TaskFilter::Stale.matches(&new, now, 14));
+    /// assert!(TaskFilter::All.matches(&new, now, 14));
+    /// ```
+    pub fn matches(self, task: &Task, now: NaiveDateTime, stale_days: u32) -> bool {
+        match self {
+            Self::All => true,
+            Self::Stale => task.is_stale(now, stale_days),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaleSettings {
+    // How many days a task can go untouched before it's stale
+    pub days: u32,
+}
+
+impl Default for StaleSettings {
+    fn default() -> Self {
+        Self { days: 14 }
+    }
+}
+
+/// A chip above the task list, which only shows the tasks that match it when it's on
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuickFilter {
+    // Open tasks that were due before today
+    Overdue,
+    Today,
+    // Tasks with a priority from 1 to 4, which RFC 5545 calls high
+    HighPriority,
+    NoDueDate,
+    Tag(String),
+}
+
+impl QuickFilter {
+    // The chips that are always there, before the ones for each tag
+    pub const BUILT_IN: [Self; 4] = [
+        Self::Overdue,
+        Self::Today,
+        Self::HighPriority,
+        Self::NoDueDate,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Overdue => String::from("Overdue"),
+            Self::Today => String::from("Today"),
+            Self::HighPriority => String::from("High priority"),
+            Self::NoDueDate => String::from("No due date"),
+            Self::Tag(tag) => format!("#{tag}"),
+        }
+    }
+
+    // Which kind of chip this is. Tasks only have to match one chip of each kind.
+    const fn kind(&self) -> usize {
+        match self {
+            Self::Overdue | Self::Today | Self::NoDueDate => 0,
+            Self::HighPriority => 1,
+            Self::Tag(_) => 2,
+        }
+    }
+
+    pub fn matches(&self, task: &Task, today: NaiveDate) -> bool {
+        match self {
+            Self::Overdue => !task.completed && task.due.is_some_and(|due| due < today),
+            Self::Today => task.due == Some(today),
+            Self::HighPriority => (1..=4).contains(&task.priority.get()),
+            Self::NoDueDate => task.due.is_none(),
+            Self::Tag(tag) => task.tags.contains(tag),
+        }
+    }
+}
+
+/// The quick filters that are turned on.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::filters::{QuickFilter, QuickFilters};
+/// use taskmaster_rs::task::{Priority, Task};
+///
+/// let today = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+/// let urgent = Task { due: Some(today), priority: Priority::new(1), ..Task::default() };
+/// let someday = Task { tags: vec![String::from("home")], ..Task::default() };
+///
+/// let mut filters = QuickFilters::default();
+/// assert!(filters.matches(&someday, today));
+///
+/// // Chips about when tasks are due show tasks that match either of them
+/// filters.toggle(QuickFilter::Today);
+/// filters.toggle(QuickFilter::NoDueDate);
+/// assert!(filters.matches(&urgent, today));
+/// assert!(filters.matches(&someday, today));
+///
+/// // But tasks have to match a chip of each kind
+/// filters.toggle(QuickFilter::HighPriority);
+/// assert!(filters.matches(&urgent, today));
+/// assert!(!filters.matches(&someday, today));
+///
+/// filters.toggle(QuickFilter::HighPriority);
+/// filters.toggle(QuickFilter::Tag(String::from("home")));
+/// assert!(!filters.matches(&urgent, today));
+/// assert!(filters.matches(&someday, today));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuickFilters(BTreeSet<QuickFilter>);
+
+impl QuickFilters {
+    pub fn contains(&self, filter: &QuickFilter) -> bool {
+        self.0.contains(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    // Turns a chip on, or off if it was on
+    pub fn toggle(&mut self, filter: QuickFilter) {
+        if !self.0.remove(&filter) {
+            self.0.insert(filter);
+        }
+    }
+
+    // The tags that have a chip turned on
+    pub fn tags(&self) -> impl Iterator<Item = &String> {
+        self.0.iter().filter_map(|filter| match filter {
+            QuickFilter::Tag(tag) => Some(tag),
+            _ => None,
+        })
+    }
+
+    pub fn matches(&self, task: &Task, today: NaiveDate) -> bool {
+        // Whether the task matches a chip of each kind, for kinds that have any chips on
+        let mut kinds = [None; 3];
+        for filter in &self.0 {
+            let kind = &mut kinds[filter.kind()];
+            *kind = Some(kind.unwrap_or(false) || filter.matches(task, today));
+        }
+        kinds.iter().all(|kind| kind.unwrap_or(true))
+    }
+}
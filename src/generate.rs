@@ -0,0 +1,161 @@
+// ----------------------------------------------------------------------------
+// Generates task lists full of made-up tasks, for benchmarking and for
+// stress-testing task views with many thousands of tasks. The same size
+// always gives the same list, so that runs can be compared with each other.
+// ----------------------------------------------------------------------------
+
+use chrono::{Duration, NaiveDate, NaiveTime};
+
+use crate::task::*;
+
+// Used when no seed is given, so that every run generates the same tasks
+const DEFAULT_SEED: u64 = 0x5EED_7A5C;
+
+// Words that the summaries and descriptions of generated tasks are made from
+const VERBS: [&str; 10] = [
+    "Write", "Review", "Call", "Fix", "Plan", "Buy", "Clean", "Email", "Book", "Finish",
+];
+const NOUNS: [&str; 10] = [
+    "the report",
+    "the kitchen",
+    "groceries",
+    "the dentist",
+    "the budget",
+    "a birthday card",
+    "the release notes",
+    "the garden",
+    "flights",
+    "the presentation",
+];
+// Tags that some generated tasks are given
+const TAGS: [&str; 4] = ["work", "home", "errands", "someday"];
+
+// A small, fast random number generator (SplitMix64). The tasks don't need
+// good randomness, just variety, and this avoids depending on a crate for it.
+struct Rng(u64);
+
+impl Rng {
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // A number from 0 up to, but not including, the given bound
+    #[allow(clippy::cast_possible_truncation)]
+    const fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    // True the given percentage of the time
+    const fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+}
+
+impl TaskList {
+    /// Makes a list of `n` made-up tasks, with a mix of summaries, due dates,
+    /// priorities and statuses. The same `n` always gives the same tasks.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::TaskList;
+    ///
+    /// let list = TaskList::generate_random(10_000);
+    /// assert_eq!(list.tasks.len(), 10_000);
+    /// assert!(list.uuid_collisions().is_empty());
+    /// assert_eq!(list, TaskList::generate_random(10_000));
+    /// ```
+    pub fn generate_random(n: usize) -> Self {
+        Self::generate_random_with_seed(n, DEFAULT_SEED)
+    }
+
+    /// Makes a list of `n` made-up tasks like [`TaskList::generate_random`],
+    /// using the seed to pick which tasks are made.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::TaskList;
+    ///
+    /// let list = TaskList::generate_random_with_seed(100, 1);
+    /// assert_ne!(list, TaskList::generate_random_with_seed(100, 2));
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn generate_random_with_seed(n: usize, seed: u64) -> Self {
+        let mut rng = Rng(seed);
+        let start = NaiveDate::from_ymd_opt(2023, 1, 1)
+            .and_then(|date| date.and_hms_opt(9, 0, 0))
+            .expect("could not create the start date for generated tasks");
+
+        let tasks = (0..n)
+            .map(|_| {
+                let verb = VERBS[rng.below(VERBS.len())];
+                let noun = NOUNS[rng.below(NOUNS.len())];
+                let created = start + Duration::minutes(rng.below(365 * 24 * 60) as i64);
+                let completed = rng.chance(25);
+                let status = match rng.below(10) {
+                    _ if completed => Status::Completed,
+                    0 => Status::Cancelled,
+                    1..=4 => Status::NeedsAction,
+                    _ => Status::InProgress,
+                };
+
+                Task {
+                    uuid: uuid::Uuid::from_u64_pair(rng.next(), rng.next()),
+                    summary: format!("{verb} {noun}"),
+                    completed,
+                    description: if rng.chance(40) {
+                        format!("{verb} {noun} before {}", NOUNS[rng.below(NOUNS.len())])
+                    } else {
+                        String::new()
+                    },
+                    url: rng
+                        .chance(10)
+                        .then(|| format!("https://example.com/tasks/{}", rng.below(1000))),
+                    progress: Percent::new(if completed {
+                        100
+                    } else {
+                        (rng.below(11) * 10) as u8
+                    }),
+                    priority: Priority::new(rng.below(10) as u8),
+                    status,
+                    due: rng
+                        .chance(70)
+                        .then(|| created.date() + Duration::days(rng.below(60) as i64)),
+                    created,
+                    // Some tasks are planned for half an hour or more, starting on the half hour
+                    scheduled: rng.chance(20).then(|| TimeBlock {
+                        start: created.date().and_time(NaiveTime::MIN)
+                            + Duration::minutes((rng.below(48) * 30) as i64),
+                        minutes: (rng.below(4) as u32 + 1) * 30,
+                    }),
+                    tags: rng
+                        .chance(30)
+                        .then(|| TAGS[rng.below(TAGS.len())].to_string())
+                        .into_iter()
+                        .collect(),
+                    last_touched: created,
+                    // Estimates are in quarter hours, up to two hours
+                    estimate: rng.chance(50).then(|| (rng.below(8) as u32 + 1) * 15),
+                    my_day: None,
+                    recurrence: None,
+                    exception_dates: Vec::new(),
+                    parent: None,
+                    completed_at: None,
+                    sort_order: None,
+                    pinned: false,
+                    hide_subtasks: false,
+                    board_position: None,
+                }
+            })
+            .collect();
+
+        Self {
+            name: format!("{n} generated tasks"),
+            tasks,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,306 @@
+// ----------------------------------------------------------------------------
+// Keeps the task list in a git repository, committing it whenever it
+// changes with a message saying what happened to which tasks. This gives a
+// history of the list which can be browsed and restored from, and lets it
+// be synced through any git remote by pushing after each commit.
+// The `git` command has to be installed, and web builds can't use this.
+// ----------------------------------------------------------------------------
+
+use std::{io, path::Path, sync::mpsc, thread};
+
+use chrono::NaiveDateTime;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::encryption::EncryptionKey;
+use crate::events::TaskEvent;
+use crate::platform;
+use crate::settings::Settings;
+use crate::sync::{ListSyncer, SyncContext};
+use crate::sync_log::SyncLog;
+use crate::task::TaskList;
+
+// How often, in seconds, the list is committed if it has changed, so that
+// typing a task's summary doesn't make a commit for every letter
+const COMMIT_INTERVAL: f64 = 10.;
+// Used to sign commits if the user hasn't told git who they are
+const FALLBACK_NAME: &str = "Taskmaster";
+const FALLBACK_EMAIL: &str = "taskmaster@localhost";
+// How git is asked to write the times of commits, in local time
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct GitSettings {
+    pub enabled: bool,
+    // Folder of the repository. It's made into one if it isn't already.
+    pub directory: String,
+    // Pushes to the repository's remote after every commit
+    pub push: bool,
+}
+
+/// The name of the file a list is kept in, which is made from its name.
+/// Anything that can't be in a file name is left out.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::git_store;
+///
+/// assert_eq!(git_store::file_name("Work: Q3/Q4"), "Work Q3Q4.ics");
+/// assert_eq!(git_store::file_name("..."), "tasks.ics");
+/// ```
+pub fn file_name(list_name: &str) -> String {
+    let name: String = list_name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .collect();
+    let name = name.trim();
+    if name.is_empty() {
+        String::from("tasks.ics")
+    } else {
+        format!("{name}.ics")
+    }
+}
+
+/// Describes what happened to a list for a commit message,
+/// like "Add 'Buy milk', complete 2 tasks".
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::events::TaskEvent;
+/// use taskmaster_rs::git_store;
+/// use taskmaster_rs::task::Task;
+///
+/// let task = |summary: &str| Task { summary: summary.into(), ..Task::default() };
+/// let events = vec![
+///     TaskEvent::Added(task("Buy milk")),
+///     TaskEvent::Completed(task("Call Sam")),
+///     TaskEvent::Completed(task("Book flights")),
+/// ];
+///
+/// assert_eq!(git_store::commit_message(&events), "Add 'Buy milk', complete 2 tasks");
+/// assert_eq!(git_store::commit_message(&[]), "Update task list");
+/// ```
+pub fn commit_message(events: &[TaskEvent]) -> String {
+    let kinds: [(&str, fn(&TaskEvent) -> bool); 4] = [
+        ("add", |event| matches!(event, TaskEvent::Added(_))),
+        ("complete", |event| matches!(event, TaskEvent::Completed(_))),
+        ("update", |event| matches!(event, TaskEvent::Updated(_))),
+        ("remove", |event| matches!(event, TaskEvent::Removed(_))),
+    ];
+
+    let parts: Vec<String> = kinds
+        .iter()
+        .filter_map(|(verb, is_kind)| {
+            let mut tasks = events.iter().filter(|event| is_kind(event));
+            let first = tasks.next()?;
+            Some(match tasks.count() {
+                0 => format!("{verb} '{}'", first.task().summary),
+                others => format!("{verb} {} tasks", others + 1),
+            })
+        })
+        .collect();
+
+    let message = parts.join(", ");
+    let mut chars = message.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::from("Update task list"),
+    }
+}
+
+// Runs git in the repository, giving back what it printed
+fn git(settings: &GitSettings, args: &[&str]) -> io::Result<Vec<u8>> {
+    platform::run_program("git", args, Path::new(&settings.directory))
+}
+
+// Writes the list to its file in the repository, and commits it
+fn commit(settings: &GitSettings, file: &str, data: &[u8], message: &str) -> io::Result<()> {
+    let directory = Path::new(&settings.directory);
+    if !directory.join(".git").exists() {
+        std::fs::create_dir_all(directory)?;
+        git(settings, &["init"])?;
+    }
+    std::fs::write(directory.join(file), data)?;
+    git(settings, &["add", "--", file])?;
+
+    // Commits can't be made without a name and email, so the app signs them
+    // itself if git hasn't been told who the user is
+    let has_identity =
+        git(settings, &["config", "user.email"]).is_ok_and(|email| !email.is_empty());
+    let name = format!("user.name={FALLBACK_NAME}");
+    let email = format!("user.email={FALLBACK_EMAIL}");
+    let mut args = Vec::new();
+    if !has_identity {
+        args.extend(["-c", &name, "-c", &email]);
+    }
+    args.extend(["commit", "--quiet", "--message", message, "--", file]);
+    git(settings, &args)?;
+
+    if settings.push {
+        git(settings, &["push", "--quiet"])?;
+    }
+    Ok(())
+}
+
+// A commit in the history of a list's file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    pub time: NaiveDateTime,
+    pub message: String,
+}
+
+// Every commit of a list's file, newest first
+pub fn history(settings: &GitSettings, file: &str) -> io::Result<Vec<Commit>> {
+    let date = format!("--date=format-local:{TIME_FORMAT}");
+    let log = git(
+        settings,
+        &["log", "--format=%H%x09%ad%x09%s", &date, "--", file],
+    )?;
+    Ok(String::from_utf8_lossy(&log)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let hash = parts.next()?.to_string();
+            let time = NaiveDateTime::parse_from_str(parts.next()?, TIME_FORMAT).ok()?;
+            let message = parts.next().unwrap_or_default().to_string();
+            Some(Commit {
+                hash,
+                time,
+                message,
+            })
+        })
+        .collect())
+}
+
+// What a commit changed in a list's file
+pub fn diff(settings: &GitSettings, hash: &str, file: &str) -> io::Result<String> {
+    let diff = git(
+        settings,
+        &["show", "--format=", "--patch", hash, "--", file],
+    )?;
+    Ok(String::from_utf8_lossy(&diff).into_owned())
+}
+
+// The list's file as it was in a commit
+pub fn read_version(settings: &GitSettings, hash: &str, file: &str) -> io::Result<Vec<u8>> {
+    git(settings, &["show", &format!("{hash}:{file}")])
+}
+
+// Commits the list to the repository when it changes
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct GitStore {
+    // Events for changes to the list, once it has subscribed to them
+    events: Option<mpsc::Receiver<TaskEvent>>,
+    // What has happened to the list since it was last committed
+    pending: Vec<TaskEvent>,
+    // When the list was last checked, in seconds since the app started
+    last_check: f64,
+    // What was last committed, so that the list is only committed when it changes
+    last_committed: String,
+}
+
+impl GitStore {
+    // Starts keeping track of the changes that are sent to the given receiver,
+    // so that commit messages can say what changed
+    pub fn listen(&mut self, events: mpsc::Receiver<TaskEvent>) {
+        self.events = Some(events);
+    }
+
+    // Forgets what has happened to the list, so that changes don't pile up
+    // while the list isn't kept in git
+    pub fn skip_changes(&mut self) {
+        self.events
+            .iter()
+            .flat_map(mpsc::Receiver::try_iter)
+            .for_each(drop);
+        self.pending.clear();
+    }
+
+    // Commits the list in the background if it has changed, encrypting it if a key is given.
+    // Should be called once every frame while the list is kept in git.
+    pub fn check(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        settings: &GitSettings,
+        key: Option<&EncryptionKey>,
+        sync_log: &SyncLog,
+    ) {
+        if settings.directory.trim().is_empty() {
+            self.skip_changes();
+            return;
+        }
+        self.pending
+            .extend(self.events.iter().flat_map(mpsc::Receiver::try_iter));
+
+        let now = ctx.input(|i| i.time);
+        if now - self.last_check < COMMIT_INTERVAL {
+            return;
+        }
+        self.last_check = now;
+        // Make sure changes are committed even if nothing else makes the app repaint
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(COMMIT_INTERVAL));
+
+        let list = task_list.to_ical_string();
+        if list == self.last_committed {
+            self.pending.clear();
+            return;
+        }
+        let message = commit_message(&self.pending);
+        let data = key.map_or_else(
+            || list.as_bytes().to_vec(),
+            |key| key.encrypt(list.as_bytes()),
+        );
+        self.last_committed = list;
+        self.pending.clear();
+
+        // Pushing can take a while, so git is run on another thread
+        let settings = settings.clone();
+        let file = file_name(&task_list.name);
+        let sync = sync_log.start("Git", task_list);
+        thread::spawn(move || match commit(&settings, &file, &data, &message) {
+            Ok(()) if settings.push => {
+                sync.finish(Ok(format!("Committed and pushed \"{message}\"")))
+            }
+            Ok(()) => sync.finish(Ok(format!("Committed \"{message}\""))),
+            Err(e) => {
+                eprintln!("could not commit task list to {}: {e}", settings.directory);
+                sync.finish(Err(format!("Could not commit \"{message}\": {e}")));
+            }
+        });
+    }
+}
+
+// Lists bound to git are committed to the repository in the git settings,
+// even if keeping lists in git hasn't been turned on for every list
+impl ListSyncer for GitStore {
+    fn id(&self) -> &'static str {
+        "git"
+    }
+
+    fn name(&self) -> &'static str {
+        "Git repository"
+    }
+
+    fn enabled(&self, settings: &Settings) -> bool {
+        settings.git.enabled
+    }
+
+    fn sync(&mut self, ctx: &egui::Context, task_list: &TaskList, context: &mut SyncContext<'_>) {
+        self.check(
+            ctx,
+            task_list,
+            &context.settings.git,
+            context.key,
+            context.log,
+        );
+    }
+
+    fn skip(&mut self) {
+        self.skip_changes();
+    }
+}
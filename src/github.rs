@@ -0,0 +1,383 @@
+// ----------------------------------------------------------------------------
+// Syncs a list with the GitHub issues assigned to the user, so they can be
+// worked through alongside everything else. Each issue is a task: its title
+// is the summary, its body the description, its labels the tags, and its
+// milestone's due date the task's. Closing an issue completes its task, and
+// completing or reopening a task closes or reopens its issue.
+//
+// A list bound to GitHub is kept as the issues, so it's meant to be a list of
+// its own. Issues are looked for in the repositories picked in the settings,
+// or in every repository the user can see if none are picked. GitHub is
+// reached with a personal access token, which is kept in the keyring. Syncing
+// needs the app to be built with the `http` feature.
+// ----------------------------------------------------------------------------
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::network::NetworkSettings;
+use crate::sync::{SyncBackend, SyncError};
+use crate::task::{Task, TaskList};
+
+#[cfg(feature = "http")]
+const API_URL: &str = "https://api.github.com";
+// How many issues are asked for at once, which is as many as GitHub gives
+#[cfg(feature = "http")]
+const PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct GitHubSettings {
+    // Personal access token, which needs to be able to read and close issues
+    #[serde(skip)]
+    pub token: String,
+    // Repositories to look for issues in, like rust-lang/rust, one on each line
+    pub repos: String,
+}
+
+impl GitHubSettings {
+    /// The repositories issues are looked for in, leaving out anything that isn't owner/name.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::github::GitHubSettings;
+    ///
+    /// let settings = GitHubSettings {
+    ///     repos: String::from(" rust-lang/rust \nnot a repo\n\nhttps://github.com/emilk/egui\n"),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(settings.repo_list(), vec!["rust-lang/rust", "emilk/egui"]);
+    /// ```
+    pub fn repo_list(&self) -> Vec<String> {
+        self.repos
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .trim_start_matches("https://")
+                    .trim_start_matches("github.com/")
+                    .trim_end_matches('/')
+            })
+            .filter(|repo| {
+                let mut parts = repo.split('/');
+                let valid = |part: Option<&str>| {
+                    part.is_some_and(|part| !part.is_empty() && !part.contains(char::is_whitespace))
+                };
+                valid(parts.next()) && valid(parts.next()) && parts.next().is_none()
+            })
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+// An issue, as GitHub describes it
+#[derive(Debug, Clone, Deserialize)]
+pub struct Issue {
+    // Where the issue is changed through the API
+    pub url: String,
+    pub html_url: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: String,
+    pub labels: Vec<Label>,
+    pub milestone: Option<Milestone>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub closed_at: Option<String>,
+    // Pull requests are issues too, as far as GitHub is concerned
+    pub pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Label {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Milestone {
+    pub due_on: Option<String>,
+}
+
+// GitHub's times are in UTC, like 2024-05-01T07:00:00Z, which tasks keep in local time
+fn local_time(time: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&chrono::Local).naive_local())
+}
+
+// Milestones are due on a day, but GitHub gives them a time in UTC too, which is left off
+fn due_date(time: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.date_naive())
+}
+
+/// The task an issue is synced as. Each issue always has the same UUID, going by its URL.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::github::{self, Issue};
+///
+/// let issue: Issue = serde_json::from_str(r#"{
+///     "url": "https://api.github.com/repos/me/app/issues/7",
+///     "html_url": "https://github.com/me/app/issues/7",
+///     "title": "Crash on start",
+///     "body": "It crashes",
+///     "state": "closed",
+///     "labels": [{ "name": "bug" }],
+///     "milestone": { "due_on": "2024-05-01T07:00:00Z" },
+///     "created_at": "2024-04-01T10:00:00Z",
+///     "updated_at": "2024-04-20T10:00:00Z",
+///     "closed_at": "2024-04-20T10:00:00Z"
+/// }"#).unwrap();
+///
+/// let task = github::issue_task(&issue);
+/// assert_eq!(task.summary, "Crash on start");
+/// assert_eq!(task.tags, vec!["bug"]);
+/// assert_eq!(task.due.unwrap().to_string(), "2024-05-01");
+/// assert!(task.completed);
+/// assert_eq!(task.uuid, github::issue_task(&issue).uuid);
+/// ```
+pub fn issue_task(issue: &Issue) -> Task {
+    let created = local_time(&issue.created_at).unwrap_or_default();
+    Task {
+        uuid: uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_URL, issue.html_url.as_bytes()),
+        summary: issue.title.clone(),
+        description: issue.body.clone().unwrap_or_default(),
+        url: Some(issue.html_url.clone()),
+        completed: issue.state == "closed",
+        completed_at: issue.closed_at.as_deref().and_then(local_time),
+        due: issue
+            .milestone
+            .as_ref()
+            .and_then(|milestone| milestone.due_on.as_deref())
+            .and_then(due_date),
+        tags: issue
+            .labels
+            .iter()
+            .map(|label| label.name.clone())
+            .collect(),
+        created,
+        last_touched: local_time(&issue.updated_at).unwrap_or(created),
+        ..Task::default()
+    }
+}
+
+// The settings GitHub is synced with, which the app keeps up to date as they're changed
+#[derive(Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct GitHubConfig(Arc<Mutex<(GitHubSettings, NetworkSettings)>>);
+
+impl GitHubConfig {
+    pub fn update(&self, settings: &GitHubSettings, network: &NetworkSettings) {
+        let mut config = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if config.0 != *settings || config.1 != *network {
+            *config = (settings.clone(), network.clone());
+        }
+    }
+
+    fn get(&self) -> (GitHubSettings, NetworkSettings) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+// Syncs lists with GitHub issues
+#[derive(Default)]
+pub struct GitHub {
+    config: GitHubConfig,
+    // The API URL of every issue that was last fetched, by its web URL, and whether it was closed,
+    // so that pushing only changes the issues whose tasks have been completed or reopened
+    issues: Mutex<HashMap<String, (String, bool)>>,
+}
+
+impl GitHub {
+    // Where the app keeps the settings GitHub is synced with
+    pub fn config(&self) -> GitHubConfig {
+        self.config.clone()
+    }
+
+    fn issues(&self) -> MutexGuard<'_, HashMap<String, (String, bool)>> {
+        self.issues.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl SyncBackend for GitHub {
+    fn id(&self) -> &'static str {
+        "github"
+    }
+
+    fn name(&self) -> &'static str {
+        "GitHub issues"
+    }
+
+    fn fetch(&self, _collection: Option<&str>) -> Result<Option<TaskList>, SyncError> {
+        let (settings, network) = self.config.get();
+        if settings.token.trim().is_empty() {
+            return Err("there's no GitHub token, it can be set in the settings".into());
+        }
+        let issues: Vec<Issue> = assigned_issues(&settings, &network)?
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .collect();
+
+        *self.issues() = issues
+            .iter()
+            .map(|issue| {
+                let closed = issue.state == "closed";
+                (issue.html_url.clone(), (issue.url.clone(), closed))
+            })
+            .collect();
+        Ok(Some(TaskList {
+            tasks: issues.iter().map(issue_task).collect(),
+            ..TaskList::default()
+        }))
+    }
+
+    fn push(&self, _collection: Option<&str>, task_list: &TaskList) -> Result<String, SyncError> {
+        let (settings, network) = self.config.get();
+        let changes: Vec<(String, bool)> = {
+            let issues = self.issues();
+            task_list
+                .tasks
+                .iter()
+                .filter_map(|task| {
+                    let (api_url, closed) = issues.get(task.url.as_deref()?)?;
+                    (*closed != task.completed).then(|| (api_url.clone(), task.completed))
+                })
+                .collect()
+        };
+
+        let (mut closed, mut reopened) = (0, 0);
+        for (api_url, close) in &changes {
+            set_closed(&settings, &network, api_url, *close)?;
+            if *close {
+                closed += 1;
+            } else {
+                reopened += 1;
+            }
+        }
+        Ok(match (closed, reopened) {
+            (0, 0) => String::from("No issues needed changing"),
+            (closed, 0) => format!("Closed {closed} issues"),
+            (0, reopened) => format!("Reopened {reopened} issues"),
+            (closed, reopened) => format!("Closed {closed} issues and reopened {reopened}"),
+        })
+    }
+}
+
+// Every issue assigned to the user in the picked repositories, or in every repository
+#[cfg(feature = "http")]
+fn assigned_issues(
+    settings: &GitHubSettings,
+    network: &NetworkSettings,
+) -> Result<Vec<Issue>, SyncError> {
+    #[derive(Deserialize)]
+    struct User {
+        login: String,
+    }
+
+    let repos = settings.repo_list();
+    if repos.is_empty() {
+        return all_pages(
+            settings,
+            network,
+            &format!("{API_URL}/issues?filter=assigned&state=all"),
+        );
+    }
+    let user: User = serde_json::from_reader(
+        request(settings, network, "GET", &format!("{API_URL}/user"))?
+            .call()?
+            .into_reader(),
+    )?;
+    let mut issues = Vec::new();
+    for repo in repos {
+        let url = format!(
+            "{API_URL}/repos/{repo}/issues?assignee={}&state=all",
+            user.login
+        );
+        issues.extend(all_pages(settings, network, &url)?);
+    }
+    Ok(issues)
+}
+
+// Gets every page of issues from a URL that lists them
+#[cfg(feature = "http")]
+fn all_pages(
+    settings: &GitHubSettings,
+    network: &NetworkSettings,
+    url: &str,
+) -> Result<Vec<Issue>, SyncError> {
+    let mut issues = Vec::new();
+    for page in 1.. {
+        let url = format!("{url}&per_page={PAGE_SIZE}&page={page}");
+        let reader = request(settings, network, "GET", &url)?
+            .call()?
+            .into_reader();
+        let found: Vec<Issue> = serde_json::from_reader(reader)?;
+        let last = found.len() < PAGE_SIZE;
+        issues.extend(found);
+        if last {
+            break;
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(feature = "http")]
+fn set_closed(
+    settings: &GitHubSettings,
+    network: &NetworkSettings,
+    api_url: &str,
+    closed: bool,
+) -> Result<(), SyncError> {
+    let state = if closed { "closed" } else { "open" };
+    request(settings, network, "PATCH", api_url)?
+        .set("Content-Type", "application/json")
+        .send_string(&serde_json::json!({ "state": state }).to_string())?;
+    Ok(())
+}
+
+// A request to GitHub's API, signed with the user's token
+#[cfg(feature = "http")]
+fn request(
+    settings: &GitHubSettings,
+    network: &NetworkSettings,
+    method: &str,
+    url: &str,
+) -> Result<ureq::Request, SyncError> {
+    let agent = network.agent().map_err(|e| e.to_string())?;
+    Ok(agent
+        .request(method, url)
+        .set("Accept", "application/vnd.github+json")
+        .set("X-GitHub-Api-Version", "2022-11-28")
+        .set(
+            "Authorization",
+            &format!("Bearer {}", settings.token.trim()),
+        ))
+}
+
+#[cfg(not(feature = "http"))]
+fn assigned_issues(
+    _settings: &GitHubSettings,
+    _network: &NetworkSettings,
+) -> Result<Vec<Issue>, SyncError> {
+    Err("this build of the app can't reach GitHub, it needs the `http` feature".into())
+}
+
+#[cfg(not(feature = "http"))]
+fn set_closed(
+    _settings: &GitHubSettings,
+    _network: &NetworkSettings,
+    _api_url: &str,
+    _closed: bool,
+) -> Result<(), SyncError> {
+    Err("this build of the app can't reach GitHub, it needs the `http` feature".into())
+}
@@ -0,0 +1,276 @@
+// ----------------------------------------------------------------------------
+// Heuristics for turning loosely structured text, like a copied email, a
+// mailto: link, or a list of notes from a meeting, into tasks. None of this is exact, it just tries to make
+// a sensible guess that the user can then fix up in the task's modal.
+// ----------------------------------------------------------------------------
+
+use chrono::{Duration, NaiveDate};
+
+use crate::task::{Task, MAX_PRIORITY};
+
+// Date formats that are tried against every group of words in the text
+const DATE_FORMATS: [&str; 6] = [
+    "%Y-%m-%d", "%d/%m/%Y", "%d %B %Y", "%d %b %Y", "%B %d %Y", "%b %d %Y",
+];
+
+/// Creates a task from the text of an email or a mailto: link.
+///
+/// The subject becomes the task's summary, the body becomes its description,
+/// and the first date found in the subject or body becomes its due date.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::heuristics;
+///
+/// let task = heuristics::task_from_email(
+/// "From: Alex <alex@example.com>
+/// Date: Mon, 7 Aug 2023 09:16:58 +1000
+/// Subject: Quarterly report
+///
+/// Hi, could you send me the report by 25 August 2023?",
+/// );
+///
+/// assert_eq!(task.summary, "Quarterly report");
+/// assert_eq!(task.description, "Hi, could you send me the report by 25 August 2023?");
+/// assert_eq!(task.due, chrono::NaiveDate::from_ymd_opt(2023, 8, 25));
+/// ```
+pub fn task_from_email(text: &str) -> Task {
+    let (subject, body) = text
+        .trim()
+        .strip_prefix("mailto:")
+        .map_or_else(|| split_email(text), split_mailto);
+
+    let mut builder = Task::builder();
+    if let Some(due) = find_date(&subject).or_else(|| find_date(&body)) {
+        builder = builder.due(due);
+    }
+    if !subject.is_empty() {
+        builder = builder.summary(subject);
+    }
+    builder
+        .description(body)
+        .build()
+        .expect("a task guessed from an email should always be valid")
+}
+
+/// Makes a task from each line of a pasted list, like notes from a meeting.
+/// Bullets and checkboxes at the start of a line are left out, and each
+/// line can have tags (`@errands`), a priority (`!3`) and a due date
+/// (`due:2023-08-25`, `due:today` or `due:tomorrow`) anywhere in it.
+/// Blank lines are skipped.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::heuristics;
+///
+/// let today = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap();
+/// let tasks = heuristics::tasks_from_lines(
+/// "- Book the venue @events !2 due:tomorrow
+///
+/// * [x] Send the invites
+/// 3. Order catering due:25/08/2023 @events @food",
+///     today,
+/// );
+///
+/// assert_eq!(tasks.len(), 3);
+/// assert_eq!(tasks[0].summary, "Book the venue");
+/// assert_eq!(tasks[0].tags, ["events"]);
+/// assert_eq!(tasks[0].priority.get(), 2);
+/// assert_eq!(tasks[0].due, today.succ_opt());
+/// assert!(tasks[1].completed);
+/// assert_eq!(tasks[2].summary, "Order catering");
+/// assert_eq!(tasks[2].tags, ["events", "food"]);
+/// assert_eq!(tasks[2].due, NaiveDate::from_ymd_opt(2023, 8, 25));
+/// ```
+pub fn tasks_from_lines(text: &str, today: NaiveDate) -> Vec<Task> {
+    text.lines()
+        .filter_map(|line| task_from_line(line, today))
+        .collect()
+}
+
+// Makes a task from one line of a pasted list, or nothing if the line has no summary
+fn task_from_line(line: &str, today: NaiveDate) -> Option<Task> {
+    let (line, completed) = strip_bullet(line);
+
+    let mut builder = Task::builder().completed(completed);
+    let mut summary = Vec::new();
+    for word in line.split_whitespace() {
+        // Tags can't have commas in them, since they're kept in a list separated by commas
+        if let Some(tag) = word
+            .strip_prefix('@')
+            .map(|tag| tag.replace(',', ""))
+            .filter(|tag| !tag.is_empty())
+        {
+            builder = builder.tag(tag);
+        } else if let Some(priority) = word
+            .strip_prefix('!')
+            .and_then(|n| n.parse().ok())
+            .filter(|n| (1..=MAX_PRIORITY).contains(n))
+        {
+            builder = builder.priority(priority);
+        } else if let Some(due) = word
+            .strip_prefix("due:")
+            .and_then(|date| parse_due(date, today))
+        {
+            builder = builder.due(due);
+        } else {
+            summary.push(word);
+        }
+    }
+
+    if summary.is_empty() {
+        return None;
+    }
+    builder.summary(summary.join(" ")).build().ok()
+}
+
+// Takes a bullet, number or checkbox off the start of a line.
+// Gives back the rest of the line, and whether it had a ticked checkbox.
+fn strip_bullet(line: &str) -> (&str, bool) {
+    let mut line = line.trim_start();
+    for bullet in ["- ", "* ", "+ ", "• "] {
+        if let Some(rest) = line.strip_prefix(bullet) {
+            line = rest.trim_start();
+            break;
+        }
+    }
+
+    // Numbered lists, like "1." or "2)"
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        if let Some(rest) = line[digits..]
+            .strip_prefix(['.', ')'])
+            .filter(|rest| rest.starts_with(' '))
+        {
+            line = rest.trim_start();
+        }
+    }
+
+    for (checkbox, completed) in [("[ ]", false), ("[x]", true), ("[X]", true)] {
+        if let Some(rest) = line.strip_prefix(checkbox) {
+            return (rest.trim_start(), completed);
+        }
+    }
+    (line, false)
+}
+
+// Reads the date written after "due:"
+fn parse_due(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match text.to_ascii_lowercase().as_str() {
+        "today" => Some(today),
+        "tomorrow" => today.checked_add_signed(Duration::days(1)),
+        _ => find_date(text),
+    }
+}
+
+// Splits an email into its subject and body.
+// Headers are the lines at the start of the email that look like "Name: value",
+// and the body is everything after them.
+fn split_email(text: &str) -> (String, String) {
+    let mut subject = None;
+    let mut lines = text.trim().lines().peekable();
+
+    // Read headers until a line that isn't a header is found
+    while let Some(line) = lines.peek() {
+        let Some((name, value)) = line.split_once(':') else {
+            break;
+        };
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            break;
+        }
+        if name.eq_ignore_ascii_case("subject") {
+            subject = Some(value.trim().to_string());
+        }
+        lines.next();
+    }
+
+    let body = lines.collect::<Vec<&str>>().join("\n").trim().to_string();
+
+    // If there was no subject header, the first line of the body is the best guess
+    match subject {
+        Some(subject) => (subject, body),
+        None => match body.split_once('\n') {
+            Some((first, rest)) => (first.trim().to_string(), rest.trim().to_string()),
+            None => (body, String::new()),
+        },
+    }
+}
+
+// Splits a mailto: link into its subject and body query parameters
+fn split_mailto(mailto: &str) -> (String, String) {
+    let mut subject = String::new();
+    let mut body = String::new();
+
+    if let Some((_, query)) = mailto.split_once('?') {
+        for param in query.split('&') {
+            match param.split_once('=') {
+                Some((name, value)) if name.eq_ignore_ascii_case("subject") => {
+                    subject = percent_decode(value);
+                }
+                Some((name, value)) if name.eq_ignore_ascii_case("body") => {
+                    body = percent_decode(value);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    (subject, body)
+}
+
+// Decodes %XX escapes in a URL component, leaving any invalid escapes as they are
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Finds the first date written in a piece of text, if there is one.
+/// Understands ISO dates (2023-08-25), day-first numeric dates (25/08/2023),
+/// and dates with month names (25 August 2023, Aug 25th, 2023).
+pub fn find_date(text: &str) -> Option<NaiveDate> {
+    // Strip punctuation and ordinal suffixes so that "25th," can be read as "25"
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| {
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '/' && c != '-');
+            let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+            let is_ordinal = !digits.is_empty()
+                && digits.chars().all(|c| c.is_ascii_digit())
+                && ["st", "nd", "rd", "th"].contains(&&word[digits.len()..]);
+            if is_ordinal { digits } else { word }.to_string()
+        })
+        .collect();
+
+    // Try every run of one to three words against every known format
+    for start in 0..words.len() {
+        for len in 1..=3 {
+            let Some(group) = words.get(start..start + len) else {
+                break;
+            };
+            let group = group.join(" ");
+            for format in DATE_FORMATS {
+                if let Ok(date) = NaiveDate::parse_from_str(&group, format) {
+                    return Some(date);
+                }
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,142 @@
+// ----------------------------------------------------------------------------
+// Hooks are user-defined shell commands which are run when something happens
+// to a task, such as it being added, completed, or becoming due soon.
+// Each command is given the task as JSON, both on its standard input
+// and in the TASKMASTER_TASK environment variable.
+// ----------------------------------------------------------------------------
+
+use std::collections::HashSet;
+use std::sync::mpsc;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::events::TaskEvent;
+use crate::platform;
+use crate::task::*;
+
+// The commands to run for each event. An empty command means nothing is run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct HookSettings {
+    pub on_added: String,
+    pub on_completed: String,
+    pub on_due_soon: String,
+    // How many days before its due date a task counts as due soon
+    pub due_soon_days: u32,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self {
+            on_added: String::new(),
+            on_completed: String::new(),
+            on_due_soon: String::new(),
+            due_soon_days: 1,
+        }
+    }
+}
+
+// Things that can happen to a task which can trigger a hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum HookEvent {
+    Added,
+    Completed,
+    DueSoon,
+}
+
+impl HookEvent {
+    // Name of the event, given to hook commands in the TASKMASTER_EVENT environment variable
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Added => "task-added",
+            Self::Completed => "task-completed",
+            Self::DueSoon => "task-due-soon",
+        }
+    }
+}
+
+// Runs the matching hooks for changes to a task list, which it hears
+// about from the list's events, and for tasks that become due soon
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct HookRunner {
+    // Events for changes to the list, once it has subscribed to them
+    events: Option<mpsc::Receiver<TaskEvent>>,
+    // Tasks which have already had their due soon hook run
+    due_soon: HashSet<uuid::Uuid>,
+}
+
+impl HookRunner {
+    // Starts running hooks for the events that are sent to the given receiver
+    pub fn listen(&mut self, events: mpsc::Receiver<TaskEvent>) {
+        self.events = Some(events);
+    }
+
+    // Runs the hooks for every event since the list was last checked, and for tasks that are due soon.
+    // While it's quiet, tasks that are due soon are held back until it isn't.
+    // Returns every event that happened, so that other parts of the app can react to them.
+    pub fn check(
+        &mut self,
+        task_list: &TaskList,
+        settings: &HookSettings,
+        quiet: bool,
+    ) -> Vec<HookEvent> {
+        let mut events = Vec::new();
+        for event in self.events.iter().flat_map(mpsc::Receiver::try_iter) {
+            match event {
+                TaskEvent::Added(task) => {
+                    run(&settings.on_added, HookEvent::Added, &task);
+                    events.push(HookEvent::Added);
+                }
+                TaskEvent::Completed(task) => {
+                    run(&settings.on_completed, HookEvent::Completed, &task);
+                    events.push(HookEvent::Completed);
+                }
+                TaskEvent::Removed(_) | TaskEvent::Updated(_) => (),
+            }
+        }
+
+        if quiet {
+            return events;
+        }
+
+        // Only run the due soon hook once for each task
+        let today = chrono::Local::now().date_naive();
+        for task in &task_list.tasks {
+            if is_due_soon(task, settings, today) && self.due_soon.insert(task.uuid) {
+                run(&settings.on_due_soon, HookEvent::DueSoon, task);
+                events.push(HookEvent::DueSoon);
+            }
+        }
+
+        events
+    }
+}
+
+// Whether a task still needs doing and is due within the days that count as soon
+pub fn is_due_soon(task: &Task, settings: &HookSettings, today: NaiveDate) -> bool {
+    let due_soon_date = today + Days::new(settings.due_soon_days.into());
+    !task.completed && task.due.is_some_and(|due| due <= due_soon_date)
+}
+
+// Runs a hook command in the background for an event that happened to a task
+fn run(command: &str, event: HookEvent, task: &Task) {
+    if command.trim().is_empty() {
+        return;
+    }
+
+    let Ok(json) = serde_json::to_string(task) else {
+        return;
+    };
+
+    let env = [
+        ("TASKMASTER_EVENT", event.name()),
+        ("TASKMASTER_TASK", &json),
+    ];
+    if let Err(e) = platform::run_command(command, &env, json.clone()) {
+        eprintln!("could not run {} hook: {e}", event.name());
+    }
+}
@@ -0,0 +1,363 @@
+// ----------------------------------------------------------------------------
+// Imports the open Jira Cloud issues assigned to the user into a list of
+// their own, so work tracked in Jira shows up next to everything else. Each
+// issue is a task: its summary and description are the task's, its labels
+// are the tags, and its priority and due date are mapped onto the task's.
+//
+// The list is read-only for now: it's refreshed from Jira every so often,
+// and anything changed in the app is replaced by what's in Jira the next time
+// it's refreshed. Issues which are done drop out of the list. Jira is reached
+// with the user's email address and an API token, which is kept in the
+// keyring. Importing needs the app to be built with the `http` feature.
+// ----------------------------------------------------------------------------
+
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::network::NetworkSettings;
+use crate::sync::{SyncBackend, SyncError};
+use crate::task::{Priority, Task, TaskList};
+
+// How many issues are asked for at once, which is as many as Jira gives
+#[cfg(feature = "http")]
+const PAGE_SIZE: usize = 100;
+// The fields of each issue that tasks are made from
+#[cfg(feature = "http")]
+const FIELDS: &str = "summary,description,priority,duedate,labels,created,updated";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct JiraSettings {
+    // The Jira Cloud site, like example.atlassian.net
+    pub site: String,
+    // The email address the user signs in to Jira with
+    pub email: String,
+    // API token, made in the user's Atlassian account settings
+    #[serde(skip)]
+    pub token: String,
+    // Keys of the projects to import issues from, like APP, or every project if it's empty
+    pub projects: String,
+    // How often the list is refreshed from Jira, in minutes
+    pub refresh_minutes: u32,
+}
+
+impl Default for JiraSettings {
+    fn default() -> Self {
+        Self {
+            site: String::new(),
+            email: String::new(),
+            token: String::new(),
+            projects: String::new(),
+            refresh_minutes: 15,
+        }
+    }
+}
+
+impl JiraSettings {
+    /// The site's address, however it was written in the settings.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::jira::JiraSettings;
+    ///
+    /// let mut settings = JiraSettings { site: String::from(" example.atlassian.net "), ..Default::default() };
+    /// assert_eq!(settings.site_url().as_deref(), Some("https://example.atlassian.net"));
+    ///
+    /// settings.site = String::from("https://example.atlassian.net/jira/your-work");
+    /// assert_eq!(settings.site_url().as_deref(), Some("https://example.atlassian.net"));
+    ///
+    /// settings.site = String::new();
+    /// assert_eq!(settings.site_url(), None);
+    /// ```
+    pub fn site_url(&self) -> Option<String> {
+        let site = self.site.trim();
+        let host = site
+            .split_once("://")
+            .map_or(site, |(_, rest)| rest)
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        (!host.is_empty()).then(|| format!("https://{host}"))
+    }
+
+    /// The search for the issues that are imported.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::jira::JiraSettings;
+    ///
+    /// let mut settings = JiraSettings::default();
+    /// assert_eq!(
+    ///     settings.jql(),
+    ///     "assignee = currentUser() AND statusCategory != Done ORDER BY updated DESC"
+    /// );
+    ///
+    /// settings.projects = String::from("app, web");
+    /// assert_eq!(
+    ///     settings.jql(),
+    ///     "assignee = currentUser() AND statusCategory != Done \
+    ///     AND project IN (\"APP\", \"WEB\") ORDER BY updated DESC"
+    /// );
+    /// ```
+    pub fn jql(&self) -> String {
+        let projects: Vec<String> = self
+            .projects
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|key| !key.is_empty())
+            .map(|key| format!("\"{}\"", key.to_uppercase().replace('"', "")))
+            .collect();
+        let mut jql = String::from("assignee = currentUser() AND statusCategory != Done");
+        if !projects.is_empty() {
+            jql.push_str(&format!(" AND project IN ({})", projects.join(", ")));
+        }
+        jql.push_str(" ORDER BY updated DESC");
+        jql
+    }
+}
+
+// An issue, as Jira describes it
+#[derive(Debug, Clone, Deserialize)]
+pub struct Issue {
+    pub key: String,
+    pub fields: IssueFields,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueFields {
+    pub summary: String,
+    pub description: Option<String>,
+    pub priority: Option<IssuePriority>,
+    pub duedate: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub created: String,
+    pub updated: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssuePriority {
+    pub name: String,
+}
+
+/// The task priority for one of Jira's priorities. Priorities a site has
+/// added itself aren't known, so they're left without one.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::jira;
+/// use taskmaster_rs::task::Priority;
+///
+/// assert_eq!(jira::priority("Highest"), Priority::new(1));
+/// assert_eq!(jira::priority("medium"), Priority::new(5));
+/// assert_eq!(jira::priority("Whenever"), Priority::NONE);
+/// ```
+pub fn priority(name: &str) -> Priority {
+    match name.trim().to_lowercase().as_str() {
+        "highest" | "blocker" => Priority::new(1),
+        "high" | "critical" => Priority::new(3),
+        "medium" | "major" => Priority::new(5),
+        "low" | "minor" => Priority::new(7),
+        "lowest" | "trivial" => Priority::new(9),
+        _ => Priority::NONE,
+    }
+}
+
+// Jira's times are like 2024-04-01T10:00:00.000+0000, which tasks keep in local time
+fn local_time(time: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S%.f%z")
+        .ok()
+        .map(|time| time.with_timezone(&chrono::Local).naive_local())
+}
+
+/// The task an issue is imported as. Each issue always has the same UUID, going by its link.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::jira::{self, Issue};
+/// use taskmaster_rs::task::Priority;
+///
+/// let issue: Issue = serde_json::from_str(r#"{
+///     "key": "APP-7",
+///     "fields": {
+///         "summary": "Crash on start",
+///         "description": "It crashes",
+///         "priority": { "name": "High" },
+///         "duedate": "2024-05-01",
+///         "labels": ["bug"],
+///         "created": "2024-04-01T10:00:00.000+0000",
+///         "updated": "2024-04-20T10:00:00.000+0000"
+///     }
+/// }"#).unwrap();
+///
+/// let task = jira::issue_task("https://example.atlassian.net", &issue);
+/// assert_eq!(task.summary, "Crash on start");
+/// assert_eq!(task.url.as_deref(), Some("https://example.atlassian.net/browse/APP-7"));
+/// assert_eq!(task.priority, Priority::new(3));
+/// assert_eq!(task.due.unwrap().to_string(), "2024-05-01");
+/// assert_eq!(task.tags, vec!["bug"]);
+/// ```
+pub fn issue_task(site_url: &str, issue: &Issue) -> Task {
+    let url = format!("{site_url}/browse/{}", issue.key);
+    let fields = &issue.fields;
+    let created = local_time(&fields.created).unwrap_or_default();
+    Task {
+        uuid: uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_URL, url.as_bytes()),
+        summary: fields.summary.clone(),
+        description: fields.description.clone().unwrap_or_default(),
+        priority: fields
+            .priority
+            .as_ref()
+            .map_or(Priority::NONE, |p| priority(&p.name)),
+        due: fields
+            .duedate
+            .as_deref()
+            .and_then(|due| NaiveDate::parse_from_str(due, "%Y-%m-%d").ok()),
+        tags: fields.labels.clone(),
+        url: Some(url),
+        created,
+        last_touched: local_time(&fields.updated).unwrap_or(created),
+        ..Task::default()
+    }
+}
+
+// The settings Jira is imported with, which the app keeps up to date as they're changed
+#[derive(Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct JiraConfig(Arc<Mutex<(JiraSettings, NetworkSettings)>>);
+
+impl JiraConfig {
+    pub fn update(&self, settings: &JiraSettings, network: &NetworkSettings) {
+        let mut config = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if config.0 != *settings || config.1 != *network {
+            *config = (settings.clone(), network.clone());
+        }
+    }
+
+    fn get(&self) -> (JiraSettings, NetworkSettings) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+// Imports Jira issues into lists
+#[derive(Default)]
+pub struct Jira {
+    config: JiraConfig,
+}
+
+impl Jira {
+    // Where the app keeps the settings Jira is imported with
+    pub fn config(&self) -> JiraConfig {
+        self.config.clone()
+    }
+}
+
+impl SyncBackend for Jira {
+    fn id(&self) -> &'static str {
+        "jira"
+    }
+
+    fn name(&self) -> &'static str {
+        "Jira issues"
+    }
+
+    fn interval(&self) -> Duration {
+        let minutes = self.config.get().0.refresh_minutes.max(1);
+        Duration::from_secs(u64::from(minutes) * 60)
+    }
+
+    fn fetch(&self, _collection: Option<&str>) -> Result<Option<TaskList>, SyncError> {
+        let (settings, network) = self.config.get();
+        let Some(site_url) = settings.site_url() else {
+            return Err("there's no Jira site, it can be set in the settings".into());
+        };
+        if settings.email.trim().is_empty() || settings.token.trim().is_empty() {
+            return Err(
+                "there's no Jira email or API token, they can be set in the settings".into(),
+            );
+        }
+        let issues = assigned_issues(&settings, &network, &site_url)?;
+        Ok(Some(TaskList {
+            tasks: issues
+                .iter()
+                .map(|issue| issue_task(&site_url, issue))
+                .collect(),
+            ..TaskList::default()
+        }))
+    }
+
+    // Nothing is sent back to Jira yet
+    fn push(&self, _collection: Option<&str>, _task_list: &TaskList) -> Result<String, SyncError> {
+        Ok(String::from(
+            "Jira lists are read-only, so nothing was sent",
+        ))
+    }
+
+    // What's in Jira always wins, since changes can't be sent back
+    fn resolve_conflict(&self, _local: &TaskList, remote: &TaskList) -> TaskList {
+        remote.clone()
+    }
+}
+
+// Every open issue assigned to the user, going through each page of the search
+#[cfg(feature = "http")]
+fn assigned_issues(
+    settings: &JiraSettings,
+    network: &NetworkSettings,
+    site_url: &str,
+) -> Result<Vec<Issue>, SyncError> {
+    use base64::Engine;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Page {
+        issues: Vec<Issue>,
+        next_page_token: Option<String>,
+    }
+
+    let agent = network.agent().map_err(|e| e.to_string())?;
+    let credentials = base64::engine::general_purpose::STANDARD.encode(format!(
+        "{}:{}",
+        settings.email.trim(),
+        settings.token.trim()
+    ));
+    let jql = settings.jql();
+    let mut issues = Vec::new();
+    let mut page_token = None;
+    loop {
+        let mut request = agent
+            .get(&format!("{site_url}/rest/api/2/search/jql"))
+            .set("Accept", "application/json")
+            .set("Authorization", &format!("Basic {credentials}"))
+            .query("jql", &jql)
+            .query("fields", FIELDS)
+            .query("maxResults", &PAGE_SIZE.to_string());
+        if let Some(token) = &page_token {
+            request = request.query("nextPageToken", token);
+        }
+        let page: Page = serde_json::from_reader(request.call()?.into_reader())?;
+        issues.extend(page.issues);
+        match page.next_page_token {
+            Some(token) => page_token = Some(token),
+            None => break,
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(not(feature = "http"))]
+fn assigned_issues(
+    _settings: &JiraSettings,
+    _network: &NetworkSettings,
+    _site_url: &str,
+) -> Result<Vec<Issue>, SyncError> {
+    Err("this build of the app can't reach Jira, it needs the `http` feature".into())
+}
@@ -0,0 +1,64 @@
+// ----------------------------------------------------------------------------
+// How tasks and task lists are written as JSON, shared by everything that
+// hands tasks to other programs, like the HTTP server and hook commands.
+// The types themselves derive Serialize and Deserialize, using the helpers
+// here for the fields that need a particular format.
+// ----------------------------------------------------------------------------
+
+use eframe::egui::Color32;
+
+use crate::color::Color;
+
+// Format used for dates in JSON
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Format used for date-times in JSON
+pub const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+// Writes a color as hexadecimal, like #5382A3
+pub fn color_hex(color: Color32) -> String {
+    Color::from(color).to_hex()
+}
+
+// Reads a color written as hexadecimal, with or without the #
+pub fn color_from_hex(hex: &str) -> Option<Color32> {
+    Color::from_hex(hex).map(Color32::from)
+}
+
+// Serializes colors as hexadecimal strings, for use with #[serde(with = "crate::json::color")]
+pub mod color {
+    use eframe::egui::Color32;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::color_hex(*color))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        super::color_from_hex(&hex)
+            .ok_or_else(|| D::Error::custom(format!("invalid color '{hex}'")))
+    }
+}
+
+// Serializes date-times in DATE_TIME_FORMAT, for use with #[serde(with = "crate::json::date_time")].
+// Fractions of a second are left out, but are still accepted when reading.
+pub mod date_time {
+    use chrono::NaiveDateTime;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        date_time: &NaiveDateTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&date_time.format(super::DATE_TIME_FORMAT).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<NaiveDateTime, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        NaiveDateTime::parse_from_str(&text, &format!("{}%.f", super::DATE_TIME_FORMAT))
+            .map_err(|_| D::Error::custom(format!("invalid date and time '{text}'")))
+    }
+}
@@ -0,0 +1,106 @@
+// ----------------------------------------------------------------------------
+// How each list was last laid out, like which view it was shown in and how
+// it was sorted and filtered, so that switching back to a list puts it back
+// the way it was. Layouts are saved with the app's other files, keyed by
+// each list's UID, so they follow a list when it's renamed or moved. A list's
+// file keeps its layout as well, for when it's opened on another computer.
+// ----------------------------------------------------------------------------
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Swimlanes;
+use crate::filters::TaskFilter;
+use crate::platform;
+use crate::task::{Status, TaskList, TaskSort};
+
+// Name the layouts are saved under
+const LAYOUTS_STATE: &str = "layouts.json";
+
+// How a list is shown
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ListLayout {
+    // Name of the view the list is shown in
+    pub view: String,
+    pub show_completed_tasks: bool,
+    pub sort_by: TaskSort,
+    pub sort_descending: bool,
+    pub filter: TaskFilter,
+    // The most cards each status's column on the kanban board should have,
+    // for columns that have a limit
+    pub column_limits: BTreeMap<Status, usize>,
+    // Whether cards can't be dropped into a kanban column that's already at its limit
+    pub block_full_columns: bool,
+    // What the kanban board is split into swimlanes by
+    pub swimlanes: Swimlanes,
+    // The tasks whose cards on the kanban board are expanded
+    pub expanded_cards: BTreeSet<uuid::Uuid>,
+}
+
+// What a list's layout is kept under. Lists that aren't bound to a file
+// get a new UID each time they're opened, so their layouts aren't kept.
+pub fn key(task_list: &TaskList) -> Option<String> {
+    task_list.path.is_some().then(|| task_list.uuid.to_string())
+}
+
+/// The layout of every list that's been shown.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::layouts::{LayoutStore, ListLayout};
+/// use taskmaster_rs::task::TaskSort;
+///
+/// let mut layouts = LayoutStore::default();
+/// let layout = ListLayout {
+///     view: String::from("Week"),
+///     sort_by: TaskSort::Due,
+///     ..Default::default()
+/// };
+///
+/// assert!(layouts.update("work.ics", &layout));
+/// // Nothing changes if the layout is the same as before
+/// assert!(!layouts.update("work.ics", &layout));
+/// assert_eq!(layouts.get("work.ics"), Some(&layout));
+/// assert_eq!(layouts.get("home.ics"), None);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LayoutStore(BTreeMap<String, ListLayout>);
+
+impl LayoutStore {
+    // Reads the layouts that were saved last time
+    pub fn load() -> Self {
+        platform::read_state(LAYOUTS_STATE)
+            .and_then(|text| {
+                serde_json::from_str(&text)
+                    .map_err(|e| eprintln!("could not read {LAYOUTS_STATE}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(std::io::Error::other)
+            .and_then(|text| platform::write_state(LAYOUTS_STATE, &text));
+        if let Err(e) = result {
+            eprintln!("could not save list layouts: {e}");
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ListLayout> {
+        self.0.get(key)
+    }
+
+    // Keeps a list's layout. Returns whether it's different to the one that was kept.
+    pub fn update(&mut self, key: &str, layout: &ListLayout) -> bool {
+        if self.0.get(key) == Some(layout) {
+            return false;
+        }
+        self.0.insert(key.to_string(), layout.clone());
+        true
+    }
+}
@@ -1,5 +1,66 @@
+pub mod agenda;
 pub mod app;
+pub mod backup;
+pub mod board;
+pub mod builder;
+pub mod color;
+pub mod compat;
+pub mod config;
+pub mod counters;
+pub mod duplicates;
+pub mod encoding;
+pub mod encryption;
+pub mod events;
+pub mod extensions;
+pub mod feed;
+pub mod filters;
+pub mod generate;
+pub mod git_store;
+pub mod github;
+pub mod heuristics;
+pub mod hooks;
+pub mod jira;
+pub mod json;
+pub mod layouts;
+pub mod list_file;
+pub mod merge;
+pub mod network;
+pub mod notifications;
 pub mod parser;
+pub mod platform;
+pub mod profiles;
+pub mod publish;
+pub mod query;
+pub mod quiet_hours;
+pub mod recurrence;
+pub mod reminders;
+pub mod report;
+pub mod secrets;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod settings;
+pub mod share;
+pub mod sounds;
+pub mod stats;
+pub mod subtasks;
+pub mod sync;
+pub mod sync_log;
+pub mod tags;
 pub mod task;
 pub mod task_views;
+pub mod timezone;
+pub mod transfer;
 pub mod ui_elements;
+pub mod url_import;
+pub mod validator;
+pub mod webhooks;
+pub mod window_title;
+
+// The types most programs using the crate need, so they don't have to know
+// which module each one is in
+pub use app::App;
+pub use builder::{TaskBuildError, TaskBuilder};
+pub use events::{TaskEvent, TaskWatcher};
+pub use parser::{ExportFormat, IcalImport, ImportWarning, LineEnding, ParseFromFileError};
+pub use task::{Status, Task, TaskList, TaskSort, TimeBlock};
+pub use task_views::{ClassicView, DayPlannerView, TaskView, TaskViewRegistry, ViewOptions};
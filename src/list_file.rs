@@ -0,0 +1,199 @@
+// ----------------------------------------------------------------------------
+// Binds task lists to the files they were opened from. The files lists are
+// bound to are remembered between runs so that they can be opened again when
+// the app starts, and each file is watched so that the user can be told when
+// something else, like a sync client or another calendar app, changes it.
+// Web builds can't read files by their path, so lists are never bound there.
+// ----------------------------------------------------------------------------
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use eframe::egui;
+
+use crate::platform;
+use crate::settings::Settings;
+use crate::sync::{ListSyncer, SyncContext};
+use crate::task::TaskList;
+use crate::ui_elements::import_export;
+
+// Name that the paths of bound files are saved under, one on each line
+const BOUND_FILES_STATE: &str = "bound-files";
+// Name that every file a list has been bound to is saved under, one on each line
+const KNOWN_LISTS_STATE: &str = "known-lists";
+// How often, in seconds, bound files are checked for changes
+const CHECK_INTERVAL: f64 = 2.;
+// How often, in seconds, lists that sync to their file are written to it if they've changed
+const SAVE_INTERVAL: f64 = 5.;
+
+// The files that lists were bound to last time the app was open, which
+// should be opened again
+pub fn bound_files() -> Vec<PathBuf> {
+    platform::read_state(BOUND_FILES_STATE)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+fn save_bound_files(paths: &[&Path]) {
+    let text: Vec<String> = paths
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    if let Err(e) = platform::write_state(BOUND_FILES_STATE, &text.join("\n")) {
+        eprintln!("could not save which files lists are opened from: {e}");
+    }
+}
+
+// Every file a list has been bound to, which tasks can be moved or copied to
+pub fn known_lists() -> Vec<PathBuf> {
+    platform::read_state(KNOWN_LISTS_STATE)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+// Adds a file to the known lists, unless it's already there
+fn remember_list(path: &Path) {
+    let mut known = known_lists();
+    if known.iter().any(|known| known == path) {
+        return;
+    }
+    known.push(path.to_path_buf());
+    let text: Vec<String> = known
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    if let Err(e) = platform::write_state(KNOWN_LISTS_STATE, &text.join("\n")) {
+        eprintln!("could not save which files lists have been opened from: {e}");
+    }
+}
+
+// Watches the file a list is bound to, to notice when it's changed on disk
+#[derive(Default)]
+pub struct FileWatcher {
+    // The file being watched
+    path: Option<PathBuf>,
+    // When the file was last changed, as of when the list was last read from or written to it
+    known_modified: Option<SystemTime>,
+    // Whether the file has changed since the list was last read from or written to it
+    changed: bool,
+    // When the file was last checked, in seconds since the app started
+    last_check: f64,
+}
+
+impl FileWatcher {
+    // Whether the file the list is bound to has changed since it was read
+    pub const fn changed_on_disk(&self) -> bool {
+        self.changed
+    }
+
+    // Marks the list as matching its file, such as after it's been reloaded
+    // from it, or exported over it
+    pub fn mark_current(&mut self) {
+        self.known_modified = self.path.as_deref().and_then(platform::file_modified);
+        self.changed = false;
+    }
+
+    // Starts watching a list's file if the list has been bound to a different one,
+    // and checks whether the file has changed every few seconds.
+    // Should be called once every frame.
+    pub fn check(&mut self, ctx: &egui::Context, task_list: &TaskList) {
+        if task_list.path != self.path {
+            self.path.clone_from(&task_list.path);
+            self.mark_current();
+            save_bound_files(self.path.as_deref().as_slice());
+            if let Some(path) = &self.path {
+                remember_list(path);
+            }
+        }
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let now = ctx.input(|i| i.time);
+        if now - self.last_check >= CHECK_INTERVAL {
+            self.last_check = now;
+            let modified = platform::file_modified(path);
+            self.changed = modified.is_some() && modified != self.known_modified;
+        }
+        // Keep checking even if nothing else makes the app repaint
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(CHECK_INTERVAL));
+    }
+}
+
+// Writes lists that sync to their own file back to it when they change, the
+// same way they're exported. Lists aren't written over a file that something
+// else has changed since it was read, so that those changes aren't lost.
+#[derive(Default)]
+pub struct FileSync {
+    // The file the list was last written to, and what was written before
+    // it was encrypted, so that it's only written when it changes
+    path: Option<PathBuf>,
+    last_saved: String,
+    // When the list was last checked, in seconds since the app started
+    last_check: f64,
+}
+
+impl ListSyncer for FileSync {
+    fn id(&self) -> &'static str {
+        "file"
+    }
+
+    fn name(&self) -> &'static str {
+        "Its file"
+    }
+
+    // Lists have only ever been written to their file when they're exported
+    fn enabled(&self, _settings: &Settings) -> bool {
+        false
+    }
+
+    fn available(&self, task_list: &TaskList) -> bool {
+        task_list.path.is_some()
+    }
+
+    fn sync(&mut self, ctx: &egui::Context, task_list: &TaskList, context: &mut SyncContext<'_>) {
+        let Some(path) = &task_list.path else {
+            return;
+        };
+        let now = ctx.input(|i| i.time);
+        if now - self.last_check < SAVE_INTERVAL {
+            return;
+        }
+        self.last_check = now;
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(SAVE_INTERVAL));
+
+        let export = context.settings.export;
+        let list = export.ical_text(task_list);
+        // A list that was just opened matches its file, so it's only written once it changes
+        if self.path.as_ref() != Some(path) {
+            self.path = Some(path.clone());
+            self.last_saved = list;
+            return;
+        }
+        if list == self.last_saved || context.file_watcher.changed_on_disk() {
+            return;
+        }
+
+        let sync = context.log.start("File", task_list);
+        // Completed tasks are added to the archive that's already there, rather than
+        // written over it, and it's written before they're taken out of the list's file
+        let target = platform::ExportTarget::file(path);
+        match import_export::export(target, task_list, &list, context.key, export) {
+            Ok(()) => {
+                context.file_watcher.mark_current();
+                self.last_saved = list;
+                sync.finish(Ok(format!("Saved to {}", path.display())));
+            }
+            Err(e) => {
+                eprintln!("could not save task list to {}: {e}", path.display());
+                sync.finish(Err(format!("Could not save to {}: {e}", path.display())));
+            }
+        }
+    }
+}
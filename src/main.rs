@@ -1,14 +1,88 @@
 use taskmaster_rs::app;
 
-const WINDOW_TITLE: &str = "Taskmaster";
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
 
+#[cfg(not(target_arch = "wasm32"))]
+use clap::Parser;
+
+// Options for how the app starts, so that launchers and scripts can control it
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Parser)]
+#[command(version, about = "A task manager which keeps its lists in iCal files")]
+struct Args {
+    /// Folder to keep settings and the app's other files in
+    #[arg(long, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
+    /// Profile to use, instead of the one that was used last time.
+    /// A new profile is made if there isn't one with this name.
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+    /// Task list file to open, instead of the one that was open last time
+    #[arg(long, value_name = "FILE")]
+    list: Option<PathBuf>,
+    /// View to show the list in, like "classic" or "day planner"
+    #[arg(long)]
+    view: Option<String>,
+    /// How big to draw everything
+    #[arg(long)]
+    scale: Option<f32>,
+    /// Start with the window minimized
+    #[arg(long)]
+    minimized: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 fn main() {
+    let args = Args::parse();
+    // The app's files are read as soon as it starts, so they have to be moved first
+    if let Some(directory) = args.data_dir {
+        taskmaster_rs::platform::set_data_directory(directory);
+    }
+    if let Some(profile) = &args.profile {
+        if let Err(e) = taskmaster_rs::profiles::switch(profile) {
+            eprintln!("could not use profile {profile}: {e}");
+        }
+    }
+
     // Initialize the window with a default option set and run the app defined in app.rs
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
-        WINDOW_TITLE,
+        taskmaster_rs::window_title::WINDOW_TITLE,
         native_options,
-        Box::new(|cc| Box::new(app::App::new(cc))),
+        Box::new(move |cc| {
+            let mut app = app::App::new(cc);
+            if let Some(path) = args.list {
+                app = app.with_list(path);
+            }
+            if let Some(view) = &args.view {
+                app = app.in_view(view);
+            }
+            if let Some(scale) = args.scale {
+                app = app.with_scale(scale);
+            }
+            // The app is launched minimized at login, if the user asked for that
+            if args.minimized {
+                app = app.minimized();
+            }
+            Box::new(app)
+        }),
     )
     .expect("failed to start egui");
 }
+
+// When compiled for the web, run the app in the canvas on the page (see index.html)
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    let web_options = eframe::WebOptions::default();
+    wasm_bindgen_futures::spawn_local(async {
+        eframe::WebRunner::new()
+            .start(
+                "taskmaster_canvas",
+                web_options,
+                Box::new(|cc| Box::new(app::App::new(cc))),
+            )
+            .await
+            .expect("failed to start egui");
+    });
+}
@@ -0,0 +1,203 @@
+// ----------------------------------------------------------------------------
+// Merging two lists into one, and splitting some of a list's tasks out into
+// a list of their own. Lists are merged by UUID, so that merging two copies
+// of the same list that have both been changed doesn't double up its tasks.
+// ----------------------------------------------------------------------------
+
+use std::collections::HashSet;
+
+use crate::task::{Task, TaskList};
+
+// What merging one list into another does, or would do
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergeSummary {
+    // Tasks that weren't in the list yet
+    pub added: usize,
+    // Tasks that were in both lists, but were changed more recently in the other one
+    pub updated: usize,
+    // Tasks that were in both lists, and are kept as they are
+    pub unchanged: usize,
+}
+
+// Which tasks are taken out when a list is split
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplitBy {
+    // Tasks with a tag
+    Tag(String),
+    // Tasks that have been completed
+    Completed,
+}
+
+impl SplitBy {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Tag(tag) => format!("Tagged {tag}"),
+            Self::Completed => String::from("Completed"),
+        }
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Self::Tag(tag) => task.tags.contains(tag),
+            Self::Completed => task.completed,
+        }
+    }
+}
+
+impl TaskList {
+    // What merging another list into this one would do, without doing it
+    pub fn merge_summary(&self, other: &Self) -> MergeSummary {
+        let mut summary = MergeSummary::default();
+        for task in &other.tasks {
+            match self
+                .tasks
+                .iter()
+                .find(|existing| existing.uuid == task.uuid)
+            {
+                None => summary.added += 1,
+                Some(existing) if task.last_touched > existing.last_touched => summary.updated += 1,
+                Some(_) => summary.unchanged += 1,
+            }
+        }
+        summary
+    }
+
+    /// Merges another list into this one. Tasks that are in both lists, going
+    /// by their UUID, are only kept once, as whichever was changed last.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::Duration;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let shared = Task::builder().summary("Old").build().unwrap();
+    /// let mut newer = shared.clone();
+    /// newer.summary = String::from("New");
+    /// newer.last_touched += Duration::hours(1);
+    ///
+    /// let mut list = TaskList { tasks: vec![shared], ..Default::default() };
+    /// let other = TaskList { tasks: vec![newer, Task::default()], ..Default::default() };
+    ///
+    /// let summary = list.merge(other);
+    /// assert_eq!((summary.added, summary.updated, summary.unchanged), (1, 1, 0));
+    /// assert_eq!(list.tasks.len(), 2);
+    /// assert_eq!(list.tasks[0].summary, "New");
+    /// ```
+    pub fn merge(&mut self, other: Self) -> MergeSummary {
+        let summary = self.merge_summary(&other);
+        for task in other.tasks {
+            match self
+                .tasks
+                .iter_mut()
+                .find(|existing| existing.uuid == task.uuid)
+            {
+                None => self.tasks.push(task),
+                Some(existing) if task.last_touched > existing.last_touched => *existing = task,
+                Some(_) => (),
+            }
+        }
+        summary
+    }
+
+    /// The archive of the list's completed tasks, which are merged by UUID into
+    /// the archive that was written before, if there was one, so tasks archived
+    /// earlier are kept. Tasks that have been reopened since are taken out of
+    /// it, as they're back in the list's own file.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let done = |summary: &str| Task { summary: summary.into(), completed: true, ..Task::default() };
+    /// let (old, reopened) = (done("Old"), done("Reopened"));
+    /// let archived = TaskList { tasks: vec![old.clone(), reopened.clone()], ..Default::default() };
+    /// let list = TaskList {
+    ///     tasks: vec![Task { completed: false, ..reopened }, done("New")],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let archive = list.archive(Some(archived));
+    /// let summaries: Vec<&str> = archive.tasks.iter().map(|task| task.summary.as_str()).collect();
+    /// assert_eq!(summaries, ["Old", "New"]);
+    /// assert_eq!(list.archive(None).tasks.len(), 1);
+    /// ```
+    pub fn archive(&self, archived: Option<Self>) -> Self {
+        let (open, mut completed) = self.split_completed();
+        if let Some(mut archived) = archived {
+            let reopened: HashSet<uuid::Uuid> = open.tasks.iter().map(|task| task.uuid).collect();
+            archived.tasks.retain(|task| !reopened.contains(&task.uuid));
+            archived.merge(completed.clone());
+            completed.tasks = archived.tasks;
+        }
+        completed
+    }
+
+    // Where the tasks that splitting the list would take out are. Subtasks
+    // go with the tasks they belong to, so they aren't left behind without them.
+    pub fn split_indices(&self, by: &SplitBy) -> Vec<usize> {
+        let mut taken: HashSet<uuid::Uuid> = self
+            .tasks
+            .iter()
+            .filter(|task| by.matches(task))
+            .map(|task| task.uuid)
+            .collect();
+        // Each pass takes the subtasks of the tasks taken so far, until there are none left
+        loop {
+            let before = taken.len();
+            for task in &self.tasks {
+                if task.parent.is_some_and(|parent| taken.contains(&parent)) {
+                    taken.insert(task.uuid);
+                }
+            }
+            if taken.len() == before {
+                break;
+            }
+        }
+        (0..self.tasks.len())
+            .filter(|&index| taken.contains(&self.tasks[index].uuid))
+            .collect()
+    }
+
+    /// Takes some of the list's tasks out into a new list with the given name,
+    /// which has the same color as this one.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::merge::SplitBy;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let work = Task::builder().summary("Report").tag("work").build().unwrap();
+    /// let step = Task { parent: Some(work.uuid), ..Task::default() };
+    /// let mut list = TaskList {
+    ///     tasks: vec![work, step, Task::builder().summary("Groceries").build().unwrap()],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let work_list = list.split_off("Work", &SplitBy::Tag(String::from("work")));
+    /// assert_eq!(work_list.name, "Work");
+    /// // The report's subtask goes with it
+    /// assert_eq!(work_list.tasks.len(), 2);
+    /// assert_eq!(list.tasks.len(), 1);
+    /// assert_eq!(list.tasks[0].summary, "Groceries");
+    /// ```
+    pub fn split_off(&mut self, name: &str, by: &SplitBy) -> Self {
+        let indices = self.split_indices(by);
+        let mut index = 0;
+        let mut taken = Vec::new();
+        self.tasks.retain(|task| {
+            let take = indices.contains(&index);
+            index += 1;
+            if take {
+                taken.push(task.clone());
+            }
+            !take
+        });
+        Self {
+            name: name.to_string(),
+            tasks: taken,
+            color: self.color,
+            // The new list is a list of its own, with its own UID, and isn't saved anywhere yet
+            ..Self::default()
+        }
+    }
+}
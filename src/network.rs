@@ -0,0 +1,103 @@
+// ----------------------------------------------------------------------------
+// Settings for how the app connects to other servers, which everything that
+// talks to one goes through, like publishing lists and importing them from
+// URLs. Offices often only let requests out through a proxy, and look inside
+// HTTPS with a certificate authority of their own, which has to be trusted.
+// Requests can only be made when the app is built with the `http` feature.
+// ----------------------------------------------------------------------------
+
+use std::time::Duration;
+
+#[cfg(feature = "http")]
+use std::{error::Error, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct NetworkSettings {
+    // Proxy that requests go through, like http://proxy.example.com:8080.
+    // When it's empty, the proxy in the HTTPS_PROXY or ALL_PROXY environment variable is used.
+    pub proxy: String,
+    // PEM file of certificate authorities to trust, as well as the usual ones
+    pub ca_bundle: String,
+    // How long to wait for a server before giving up, in seconds
+    pub timeout_seconds: u32,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy: String::new(),
+            ca_bundle: String::new(),
+            timeout_seconds: 30,
+        }
+    }
+}
+
+impl NetworkSettings {
+    /// The proxy requests go through, if one is set.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::network::NetworkSettings;
+    ///
+    /// let mut settings = NetworkSettings::default();
+    /// assert_eq!(settings.proxy_url(), None);
+    ///
+    /// settings.proxy = String::from(" http://proxy.example.com:8080 ");
+    /// assert_eq!(settings.proxy_url(), Some("http://proxy.example.com:8080"));
+    /// ```
+    pub fn proxy_url(&self) -> Option<&str> {
+        Some(self.proxy.trim()).filter(|proxy| !proxy.is_empty())
+    }
+
+    pub fn ca_bundle_path(&self) -> Option<&str> {
+        Some(self.ca_bundle.trim()).filter(|path| !path.is_empty())
+    }
+
+    // How long to wait for a server, which is at least a second
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(u64::from(self.timeout_seconds.max(1)))
+    }
+
+    // Makes something to send HTTP requests with, which uses these settings
+    #[cfg(feature = "http")]
+    pub fn agent(&self) -> Result<ureq::Agent, Box<dyn Error>> {
+        let mut builder = ureq::AgentBuilder::new().timeout(self.timeout());
+        builder = match self.proxy_url() {
+            Some(proxy) => builder.proxy(ureq::Proxy::new(proxy)?),
+            None => builder.try_proxy_from_env(true),
+        };
+        if let Some(path) = self.ca_bundle_path() {
+            builder = builder.tls_config(tls_config(path)?);
+        }
+        Ok(builder.build())
+    }
+}
+
+// TLS settings which trust the certificate authorities in a PEM file as well as the usual ones
+#[cfg(feature = "http")]
+fn tls_config(path: &str) -> Result<Arc<rustls::ClientConfig>, Box<dyn Error>> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("could not open the certificate bundle {path}: {e}"))?;
+    let mut added = 0;
+    for certificate in rustls_pemfile::certs(&mut std::io::BufReader::new(file)) {
+        roots.add(certificate?)?;
+        added += 1;
+    }
+    if added == 0 {
+        return Err(format!("there are no certificates in {path}").into());
+    }
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(Arc::new(config))
+}
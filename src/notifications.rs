@@ -0,0 +1,174 @@
+// ----------------------------------------------------------------------------
+// Desktop notifications for tasks that become due soon. Where the desktop
+// supports it, each notification has buttons to complete the task or snooze
+// it for an hour, which are sent back to the running app to be done there.
+// Notifications are held back during quiet hours, like every other reminder.
+// ----------------------------------------------------------------------------
+
+use std::collections::HashSet;
+use std::sync::mpsc;
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::hooks::{self, HookSettings};
+use crate::platform::notification;
+use crate::task::{Status, TaskList};
+
+// How long a snoozed notification waits before it's shown again, in minutes
+const SNOOZE_MINUTES: i64 = 60;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+}
+
+// Buttons that can be clicked on a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    Complete,
+    Snooze,
+}
+
+impl NotificationAction {
+    fn iterator() -> std::slice::Iter<'static, Self> {
+        [Self::Complete, Self::Snooze].iter()
+    }
+
+    // What the notification is told the button is, and tells the app was clicked
+    const fn key(self) -> &'static str {
+        match self {
+            Self::Complete => "done",
+            Self::Snooze => "snooze",
+        }
+    }
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Complete => "Done",
+            Self::Snooze => "Snooze 1h",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::iterator().copied().find(|action| action.key() == key)
+    }
+}
+
+// Shows notifications for tasks that are due soon, and does what's
+// clicked on them
+#[allow(clippy::module_name_repetitions)]
+pub struct Notifier {
+    // Clicked buttons are sent here from the threads waiting on notifications
+    sender: mpsc::Sender<(Uuid, NotificationAction)>,
+    receiver: mpsc::Receiver<(Uuid, NotificationAction)>,
+    // Tasks which have already had a notification shown
+    notified: HashSet<Uuid>,
+    // Tasks which were snoozed, and when to show their notifications again
+    snoozed: Vec<(Uuid, NaiveDateTime)>,
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            notified: HashSet::new(),
+            snoozed: Vec::new(),
+        }
+    }
+}
+
+impl Notifier {
+    // Does what was clicked on notifications, and shows notifications for tasks
+    // that have become due soon or whose snooze is over, unless it's quiet.
+    // Should be called once every frame.
+    pub fn check(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &mut TaskList,
+        settings: &NotificationSettings,
+        hooks: &HookSettings,
+        quiet: bool,
+    ) {
+        let now = Local::now().naive_local();
+        for (uuid, action) in self.receiver.try_iter() {
+            match action {
+                NotificationAction::Complete => {
+                    if let Some(task) = task_list.tasks.iter_mut().find(|t| t.uuid == uuid) {
+                        task.completed = true;
+                        task.status = Status::Completed;
+                    }
+                }
+                NotificationAction::Snooze => {
+                    self.snoozed.retain(|(snoozed, _)| *snoozed != uuid);
+                    self.snoozed
+                        .push((uuid, now + Duration::minutes(SNOOZE_MINUTES)));
+                }
+            }
+        }
+
+        if !settings.enabled || quiet {
+            return;
+        }
+
+        let today = now.date();
+        let (over, snoozed): (Vec<_>, Vec<_>) =
+            self.snoozed.drain(..).partition(|(_, until)| *until <= now);
+        self.snoozed = snoozed;
+        for task in &task_list.tasks {
+            let snoozing = self.snoozed.iter().any(|(uuid, _)| *uuid == task.uuid);
+            if !hooks::is_due_soon(task, hooks, today) || snoozing {
+                continue;
+            }
+            // Only notify once for each task, and again each time its snooze is over
+            let snooze_over = over.iter().any(|(uuid, _)| *uuid == task.uuid);
+            if self.notified.insert(task.uuid) || snooze_over {
+                self.notify(ctx, task.uuid, &task.summary, task.due);
+            }
+        }
+
+        // Wake up when the next snooze is over
+        if let Some(wait) = self
+            .snoozed
+            .iter()
+            .map(|(_, until)| *until)
+            .min()
+            .and_then(|until| (until - now).to_std().ok())
+        {
+            ctx.request_repaint_after(wait);
+        }
+    }
+
+    // Moves when snoozed notifications are shown again along with the clocks,
+    // when the time zone changes and times are kept at the same moment
+    pub fn shift(&mut self, by: Duration) {
+        for (_, until) in &mut self.snoozed {
+            *until += by;
+        }
+    }
+
+    // Shows a notification for a task, with buttons if the desktop supports them
+    fn notify(&self, ctx: &egui::Context, uuid: Uuid, summary: &str, due: Option<NaiveDate>) {
+        let body = due.map_or_else(String::new, |due| {
+            format!("Due {}", due.format("%A %-d %B"))
+        });
+        let actions: Vec<(&str, &str)> = NotificationAction::iterator()
+            .map(|action| (action.key(), action.label()))
+            .collect();
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+        notification::show(summary, &body, &actions, move |key| {
+            if let Some(action) = NotificationAction::from_key(key) {
+                let _ = sender.send((uuid, action));
+                // The app might be asleep, waiting for something to happen
+                ctx.request_repaint();
+            }
+        });
+    }
+}
@@ -2,16 +2,32 @@
 // Functionality for parsing a TaskList to or from an iCal file with VTODOs.
 // ----------------------------------------------------------------------------
 
-use std::{fs::File, io, path::Path};
+use std::{fs::File, io, ops::ControlFlow, path::Path};
 
-use eframe::egui;
+use chrono::TimeZone;
+use ical::property::Property;
+use serde::{Deserialize, Serialize};
 
+use crate::color::Color;
+use crate::compat;
+use crate::encoding;
+use crate::extensions;
+use crate::layouts::ListLayout;
+use crate::platform;
 use crate::task::*;
 
 const FORMAT: &str = "%Y%m%dT%H%M%S";
+// Format of dates without a time
+const DATE_FORMAT: &str = "%Y%m%d";
+
+// How many tasks are read between each call of the progress function when importing
+const PROGRESS_INTERVAL: usize = 500;
+
+// Lines longer than this many bytes are folded onto the next line, as RFC 5545 requires
+const MAX_LINE_LENGTH: usize = 75;
 
 impl TaskList {
-    /// Converts an iCal file to a TaskList.
+    /// Converts an iCal file to a TaskList, which remembers the file it came from.
     ///
     /// Example:
     /// ```
@@ -19,176 +35,150 @@ impl TaskList {
     /// use std::path::Path;
     /// use std::fs;
     ///
-    /// use std::{cell::RefCell, rc::Rc};
-    ///
-    /// use eframe::egui::Color32;
+    /// use taskmaster_rs::color::Color;
     ///
     /// let list = TaskList::from_ical_file(Path::new("test.ics")).unwrap();
     ///
     /// assert_eq!(
     ///     list,
     ///     TaskList {
+    ///         uuid: uuid::Uuid::parse_str("6a0f2b61-3c55-4a0e-9d7c-2f4b8e1d5a93").unwrap(),
     ///         name: String::from("test"),
+    ///         description: Some(String::from("Tasks for testing")),
     ///         tasks: vec![Task {
     ///             uuid: uuid::Uuid::parse_str("ae02186d-10ae-404f-a4c9-450e06ea77cf").unwrap(),
     ///             summary: String::from("Task 1"),
     ///             completed: false,
     ///             description: String::from("description\n"),
-    ///             progress: 47,
-    ///             priority: 9,
-    ///             status: TaskStatus::NeedsAction,
+    ///             url: None,
+    ///             progress: Percent::new(47),
+    ///             priority: Priority::new(9),
+    ///             status: Status::NeedsAction,
     ///             due: Some(chrono::NaiveDate::parse_from_str("20230825", "%Y%m%d").unwrap()),
-    ///             show_modal: Rc::new(RefCell::new(false)),
-    ///             created: chrono::NaiveDateTime::parse_from_str("20230801T151208", "%Y%m%dT%H%M%S").unwrap()
+    ///             created: chrono::NaiveDateTime::parse_from_str("20230801T151208", "%Y%m%dT%H%M%S").unwrap(),
+    ///             scheduled: None,
+    ///             tags: Vec::new(),
+    ///             last_touched: chrono::NaiveDateTime::parse_from_str("20230807T091658", "%Y%m%dT%H%M%S").unwrap(),
+    ///             estimate: None,
+    ///             my_day: None,
+    ///             recurrence: None,
+    ///             exception_dates: Vec::new(),
+    ///             parent: None,
+    ///             completed_at: None,
+    ///             sort_order: None,
+    ///             pinned: false,
+    ///             hide_subtasks: false,
+    ///             board_position: None,
     ///         }],
-    ///         color: Color32::from_rgb(83, 130, 163)
+    ///         color: Color::from_rgb(83, 130, 163),
+    ///         created: chrono::NaiveDateTime::parse_from_str("20230801T150000", "%Y%m%dT%H%M%S").unwrap(),
+    ///         modified: chrono::NaiveDateTime::parse_from_str("20230807T091658", "%Y%m%dT%H%M%S").unwrap(),
+    ///         layout: None,
+    ///         tag_colors: Default::default(),
+    ///         path: Some(Path::new("test.ics").to_path_buf()),
     ///     }
     /// );
     /// ```
-    #[allow(
-        clippy::cast_possible_truncation,
-        clippy::cast_sign_loss,
-        clippy::too_many_lines
-    )]
     pub fn from_ical_file(path: &Path) -> Result<Self, ParseFromFileError> {
-        let Ok(file) = File::open(path)
-        else {
-            return Err(ParseFromFileError::InvalidFile)
+        let Ok(file) = File::open(path) else {
+            return Err(ParseFromFileError::InvalidFile);
         };
 
-        let reader = io::BufReader::new(file);
+        let mut list = Self::from_ical_reader(io::BufReader::new(file))?;
+        list.path = Some(path.to_path_buf());
+        Ok(list)
+    }
 
-        let lines = ical::PropertyParser::from_reader(reader);
+    /// Converts the contents of an iCal file to a TaskList, wherever they're read from,
+    /// like a network response or a file picked in a web browser. The list isn't
+    /// bound to a file. Lines can end with either CRLF or LF, and text in other
+    /// encodings than UTF-8 is decoded.
+    ///
+    /// Example:
+    /// ```
+    /// use std::io::BufReader;
+    /// use taskmaster_rs::task::TaskList;
+    ///
+    /// let ical = "BEGIN:VCALENDAR\nX-WR-CALNAME:Chores\nBEGIN:VTODO\nSUMMARY:Wash up\nEND:VTODO\nEND:VCALENDAR\n";
+    ///
+    /// let list = TaskList::from_ical_reader(BufReader::new(ical.as_bytes())).unwrap();
+    /// assert_eq!(list.name, "Chores");
+    /// assert_eq!(list.tasks[0].summary, "Wash up");
+    /// assert_eq!(list.path, None);
+    /// ```
+    pub fn from_ical_reader<R: io::BufRead>(reader: R) -> Result<Self, ParseFromFileError> {
+        Self::from_ical_reader_with_progress(reader, |_| ControlFlow::Continue(()))
+    }
 
-        let mut list: Self = Self::default();
-        let mut tasks: Vec<Task> = vec![];
-        let mut task_counter = 0usize;
+    /// Converts iCal text to a TaskList, such as text pasted from the clipboard.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::TaskList;
+    ///
+    /// let list = TaskList::from_ical_str(
+    ///     "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nSUMMARY:Hoover\r\nEND:VTODO\r\nEND:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(list.tasks[0].summary, "Hoover");
+    /// assert!(TaskList::from_ical_str("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n").is_err());
+    /// ```
+    pub fn from_ical_str(ical: &str) -> Result<Self, ParseFromFileError> {
+        Self::from_ical_reader(ical.as_bytes())
+    }
 
-        // Iterate through each line parsed from the file
-        for line in lines {
-            // If the line is valid
-            if let Ok(property) = line {
-                // Makes sure the line is saying something
-                let value;
-                if let Some(val) = property.value {
-                    value = val.to_string();
-                } else {
-                    return Err(ParseFromFileError::InvalidField);
-                }
-                // Checks what the line is saying
-                match property.name.as_str() {
-                    // Set calendar name
-                    "X-WR-CALNAME" => list.name = value,
-                    // Set calendar color
-                    "X-APPLE-CALENDAR-COLOR" => {
-                        // Conver ical hex color to rgb color that can be stored in a Task
-                        if let Ok(rgb) = colorsys::Rgb::from_hex_str(&value) {
-                            list.color = egui::Color32::from_rgb(
-                                rgb.red().round() as u8,
-                                rgb.green().round() as u8,
-                                rgb.blue().round() as u8,
-                            );
-                        }
-                    }
-                    // Checks for a BEGIN statement
-                    "BEGIN" => {
-                        match value.as_str() {
-                            // If it's starting a new task, add a task to the vector of tasks
-                            "VTODO" => tasks.push(Task::default()),
-                            // If it's just starting the file, do nothing
-                            "VCALENDAR" => (),
-                            // If it's starting anything else, return an error
-                            _ => return Err(ParseFromFileError::NonTaskItem),
-                        }
-                    }
-                    // If the task has a valid UUID in the file, then use that as the generated task's UUID.
-                    // iCal allows IDs that don't follow the UUID format, so if the ID isn't a uuid, just
-                    // use the UUID that is generated when a Task is created.
-                    "UID" => {
-                        if let Ok(parsed_uuid) = uuid::Uuid::parse_str(value.as_str()) {
-                            tasks[task_counter].uuid = parsed_uuid;
-                        }
-                    }
-                    // Set the currently addressed task's summary
-                    "SUMMARY" => {
-                        tasks[task_counter].summary = value;
-                    }
-                    // Set the currently addressed task's due date
-                    "DUE" => {
-                        let datestr = value;
-                        if let Ok(date) = chrono::NaiveDateTime::parse_from_str(&datestr, FORMAT) {
-                            tasks[task_counter].due = Some(date.date());
-                        } else {
-                            return Err(ParseFromFileError::InvalidField);
-                        }
-                    }
-                    // Set the currently addressed task's priority
-                    "PRIORITY" => {
-                        if let Ok(priority) = value.parse() {
-                            tasks[task_counter].priority = priority;
-                        } else {
-                            return Err(ParseFromFileError::InvalidField);
-                        }
-                    }
-                    // Set the currently addressed task's completion percent
-                    "PERCENT-COMPLETE" => {
-                        if let Ok(progress) = value.parse() {
-                            tasks[task_counter].progress = progress;
-                        } else {
-                            return Err(ParseFromFileError::InvalidField);
-                        }
-                    }
-                    // Set the currently addressed task's status
-                    "STATUS" => {
-                        let task = &mut tasks[task_counter];
-                        task.status = match value.as_str() {
-                            "IN-PROGRESS" => Status::InProgress,
-                            "NEEDS-ACTION" => Status::NeedsAction,
-                            "COMPLETED" => {
-                                task.completed = true;
-                                Status::Completed
-                            }
-                            "CANCELLED" => Status::Cancelled,
-                            _ => return Err(ParseFromFileError::InvalidField),
-                        }
-                    }
-                    // Set the currently addressed task's description
-                    "DESCRIPTION" => {
-                        tasks[task_counter].description = value.replace("\\n", "\n");
-                    }
-                    // If the file says that the task description is complete,
-                    // iterate the task counter so that it can be used to address
-                    // the task that is added next (if any).
-                    "END" => {
-                        if value == *"VTODO" {
-                            task_counter += 1;
-                        }
-                    }
-                    // Store the task's creation date
-                    "CREATED" => {
-                        let datestr = value;
-                        if let Ok(date) = chrono::NaiveDateTime::parse_from_str(&datestr, FORMAT) {
-                            tasks[task_counter].created = date;
-                        } else {
-                            return Err(ParseFromFileError::InvalidField);
-                        }
-                    }
-                    // If the line isn't any of the above, just do nothing
-                    _ => (),
-                }
-            } else {
-                // If the line is invalid, return an error
-                return Err(ParseFromFileError::InvalidFile);
+    /// Converts the contents of an iCal file to a TaskList, calling a function with
+    /// how far through the file it is, from 0 to 1, every few hundred tasks.
+    /// If the function returns `ControlFlow::Break`, the import is cancelled.
+    ///
+    /// Example:
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use taskmaster_rs::parser::ParseFromFileError;
+    /// use taskmaster_rs::task::TaskList;
+    ///
+    /// let ical = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nSUMMARY:Wash up\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+    ///
+    /// let mut progress = Vec::new();
+    /// let list = TaskList::from_ical_reader_with_progress(ical.as_bytes(), |done| {
+    ///     progress.push(done);
+    ///     ControlFlow::Continue(())
+    /// });
+    /// assert_eq!(list.unwrap().tasks[0].summary, "Wash up");
+    /// assert_eq!(progress.last(), Some(&1.));
+    ///
+    /// let cancelled = TaskList::from_ical_reader_with_progress(ical.as_bytes(), |_| ControlFlow::Break(()));
+    /// assert!(matches!(cancelled, Err(ParseFromFileError::Cancelled)));
+    /// ```
+    pub fn from_ical_reader_with_progress<R: io::BufRead>(
+        mut reader: R,
+        mut on_progress: impl FnMut(f32) -> ControlFlow<()>,
+    ) -> Result<Self, ParseFromFileError> {
+        let mut bytes = Vec::new();
+        if reader.read_to_end(&mut bytes).is_err() {
+            return Err(ParseFromFileError::InvalidFile);
+        }
+
+        let mut import = IcalImport::new(&bytes);
+        let mut tasks = Vec::new();
+        while !import.is_finished() {
+            tasks.extend(import.next_chunk(PROGRESS_INTERVAL)?);
+            if on_progress(import.progress()).is_break() {
+                return Err(ParseFromFileError::Cancelled);
             }
         }
-        list.tasks = tasks;
+
         // Everything is all good, so return the list
-        Ok(list)
+        Ok(Self {
+            tasks,
+            ..import.list().clone()
+        })
     }
 
     /// Converts a TaskList to a string containing the contents of a potential iCal file.
     /// This lets whatever is implementing the function handle writing it to a file,
     /// or using the string for any other purpose.
+    /// Lines end with CRLF, as RFC 5545 requires.
     ///
     /// Example:
     /// ```
@@ -196,16 +186,26 @@ impl TaskList {
     /// use std::path::Path;
     /// use std::fs;
     ///
-    /// use eframe::egui::Color32;
+    /// use chrono::TimeZone;
     ///
     /// let liststr = TaskList::from_ical_file(Path::new("test.ics")).unwrap()
     ///     .to_ical_string();
     ///
-    /// println!("{}", liststr.trim());
-    /// assert!(
-    /// // WildMatch lets you check if two strings are matching non-exactly using wildcards,
-    /// // which is useful here because the creation and modification time of a task is always different
-    ///     wildmatch::WildMatch::new(
+    /// assert!(liststr.ends_with("END:VCALENDAR\r\n"));
+    /// let liststr = liststr.replace("\r\n", "\n");
+    ///
+    /// // Tasks' times are written in UTC, so they depend on the computer's time zone
+    /// let utc = |time: &str| {
+    ///     let local = chrono::NaiveDateTime::parse_from_str(time, "%Y%m%dT%H%M%S").unwrap();
+    ///     let utc = chrono::Local.from_local_datetime(&local).unwrap().naive_utc();
+    ///     utc.format("%Y%m%dT%H%M%SZ").to_string()
+    /// };
+    /// let created = utc("20230801T151208");
+    /// let touched = utc("20230807T091658");
+    ///
+    /// assert_eq!(
+    ///     liststr.trim(),
+    ///     format!(
     ///"BEGIN:VCALENDAR
     ///VERSION:2.0
     ///CALSCALE:GREGORIAN
@@ -214,11 +214,15 @@ impl TaskList {
     ///X-APPLE-CALENDAR-COLOR:#5382A3
     ///REFRESH-INTERVAL;VALUE=DURATION:PT4H
     ///X-PUBLISHED-TTL:PT4H
+    ///X-TASKMASTER-LIST-UID:6a0f2b61-3c55-4a0e-9d7c-2f4b8e1d5a93
+    ///X-TASKMASTER-LIST-CREATED:20230801T150000
+    ///X-TASKMASTER-LIST-MODIFIED:20230807T091658
+    ///X-WR-CALDESC:Tasks for testing
     ///BEGIN:VTODO
     ///UID:ae02186d-10ae-404f-a4c9-450e06ea77cf
-    ///CREATED:20230801T151208
-    ///LAST-MODIFIED:????????T??????
-    ///DTSTAMP:????????T??????
+    ///CREATED:{created}
+    ///LAST-MODIFIED:{touched}
+    ///DTSTAMP:{touched}
     ///SUMMARY:Task 1
     ///DUE:20230825T000000
     ///PRIORITY:9
@@ -226,14 +230,171 @@ impl TaskList {
     ///STATUS:NEEDS-ACTION
     ///DESCRIPTION:description\\n
     ///END:VTODO
-    ///END:VCALENDAR").matches(liststr.trim())
+    ///END:VCALENDAR"
+    ///     )
     /// );
     /// ```
     pub fn to_ical_string(&self) -> String {
+        self.to_ical_string_with(LineEnding::default())
+    }
+
+    /// Writes a TaskList as iCal text, like `to_ical_string_as`, to anything that can be
+    /// written to.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::parser::{ExportFormat, LineEnding};
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let mut list = TaskList::default();
+    /// list.add(Task::builder().summary("Wash up").build().unwrap());
+    ///
+    /// let mut written = Vec::new();
+    /// list.to_ical_writer(&mut written, LineEnding::Lf, ExportFormat::Todos)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(written).unwrap(),
+    ///     list.to_ical_string_with(LineEnding::Lf)
+    /// );
+    /// ```
+    pub fn to_ical_writer<W: io::Write>(
+        &self,
+        mut writer: W,
+        line_ending: LineEnding,
+        format: ExportFormat,
+    ) -> io::Result<()> {
+        writer.write_all(self.to_ical_string_as(line_ending, format).as_bytes())?;
+        writer.flush()
+    }
+
+    /// Writes a TaskList as iCal text, like `to_ical_string_as`, to a file. The file is
+    /// replaced all at once, so it's never left half written if something goes wrong.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::parser::{ExportFormat, LineEnding};
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let mut list = TaskList::default();
+    /// list.add(Task::builder().summary("Wash up").build().unwrap());
+    ///
+    /// let path = std::env::temp_dir().join("taskmaster-to-ical-file.ics");
+    /// list.to_ical_file(&path, LineEnding::default(), ExportFormat::Todos)
+    ///     .unwrap();
+    /// let read = TaskList::from_ical_file(&path).unwrap();
+    /// assert_eq!(read.tasks[0].summary, "Wash up");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn to_ical_file(
+        &self,
+        path: &Path,
+        line_ending: LineEnding,
+        format: ExportFormat,
+    ) -> io::Result<()> {
+        platform::write_file(path, self.to_ical_string_as(line_ending, format).as_bytes())
+    }
+
+    // Converts a TaskList to iCal text like to_ical_string, with lines ending in the given way.
+    // Some older programs can only read files with LF line endings.
+    pub fn to_ical_string_with(&self, line_ending: LineEnding) -> String {
+        self.to_ical_string_as(line_ending, ExportFormat::Todos)
+    }
+
+    /// Converts a TaskList to iCal text like `to_ical_string_with`, with tasks that
+    /// have due dates written as all day events as well as, or instead of, todo items.
+    /// Some calendar apps, like Google Calendar, ignore todo items.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use taskmaster_rs::parser::{ExportFormat, LineEnding};
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let due = NaiveDate::from_ymd_opt(2023, 8, 25).unwrap();
+    /// let list = TaskList {
+    ///     tasks: vec![
+    ///         Task::builder().summary("Send report").due(due).build().unwrap(),
+    ///         Task::builder().summary("Someday").build().unwrap(),
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let ical = list.to_ical_string_as(LineEnding::Lf, ExportFormat::Events);
+    /// assert_eq!(ical.matches("BEGIN:VEVENT").count(), 1);
+    /// assert!(!ical.contains("BEGIN:VTODO"));
+    /// assert!(ical.contains("DTSTART;VALUE=DATE:20230825\n"));
+    /// assert!(ical.contains("DTEND;VALUE=DATE:20230826\n"));
+    ///
+    /// let ical = list.to_ical_string_as(LineEnding::Lf, ExportFormat::TodosAndEvents);
+    /// assert_eq!(ical.matches("BEGIN:VTODO").count(), 2);
+    /// assert_eq!(ical.matches("BEGIN:VEVENT").count(), 1);
+    /// ```
+    pub fn to_ical_string_as(&self, line_ending: LineEnding, format: ExportFormat) -> String {
+        let mut ical_text = self.calendar_header();
+
+        for task in &self.tasks {
+            // Add data for every todo item
+            if format != ExportFormat::Events {
+                push_todo(&mut ical_text, task);
+            }
+            // Tasks with due dates are shown on the day they're due in calendar apps
+            if format != ExportFormat::Todos {
+                if let Some(due) = task.due {
+                    push_event(&mut ical_text, task, "due", EventTime::AllDay(due));
+                }
+            }
+        }
+        // Ends the file
+        ical_text.push_str("END:VCALENDAR\n");
+
+        // We're all good, return a reference to the file
+        finish(&ical_text, line_ending)
+    }
+
+    /// Converts the blocks of time that tasks are scheduled for on the day planner
+    /// to iCal text with a VEVENT for each, so that they show up in calendar apps.
+    /// Tasks that aren't scheduled are left out.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use taskmaster_rs::parser::LineEnding;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let start = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap().and_hms_opt(9, 30, 0).unwrap();
+    /// let list = TaskList {
+    ///     tasks: vec![
+    ///         Task::builder().summary("Write report").scheduled(start, 90).build().unwrap(),
+    ///         Task::builder().summary("Not planned").build().unwrap(),
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let ical = list.schedule_to_ical_string_with(LineEnding::Lf);
+    /// assert_eq!(ical.matches("BEGIN:VEVENT").count(), 1);
+    /// assert!(ical.contains("SUMMARY:Write report\n"));
+    /// assert!(ical.contains("DTSTART:20230807T093000\n"));
+    /// assert!(ical.contains("DTEND:20230807T110000\n"));
+    /// ```
+    pub fn schedule_to_ical_string_with(&self, line_ending: LineEnding) -> String {
+        let mut ical_text = self.calendar_header();
+
+        for task in &self.tasks {
+            if let Some(block) = task.scheduled {
+                push_event(&mut ical_text, task, "scheduled", EventTime::Timed(block));
+            }
+        }
+        ical_text.push_str("END:VCALENDAR\n");
+
+        finish(&ical_text, line_ending)
+    }
+
+    // Starts the iCal text for the list, with the details of the list itself
+    #[allow(clippy::uninlined_format_args)]
+    fn calendar_header(&self) -> String {
         // Initiate text that will eventually be added to the calendar file
         // As well as adding some initial variables via a format string
-        #[allow(clippy::uninlined_format_args)]
-        let mut ical_text = format!(
+        let mut header = format!(
             "BEGIN:VCALENDAR
 VERSION:2.0
 CALSCALE:GREGORIAN
@@ -241,87 +402,1363 @@ PRODID:-//taskmaster-rs//github.com//
 X-WR-CALNAME:{}
 X-APPLE-CALENDAR-COLOR:{}
 REFRESH-INTERVAL;VALUE=DURATION:PT4H
-X-PUBLISHED-TTL:PT4H",
+X-PUBLISHED-TTL:PT4H
+{}:{}
+{}:{}
+{}:{}
+",
             // Now the variables that are substituted into the {}s are specified
-            self.name,
+            escape_text(&self.name),
             // Convert the TaskList's color to hexadecimal and insert it into the string
-            // {:X} in a format string changes decimal numbers to hexadecimal
-            format_args!(
-                "#{:X}{:X}{:X}",
-                self.color.r(),
-                self.color.g(),
-                self.color.b()
-            )
+            self.color.to_hex(),
+            extensions::LIST_UID,
+            self.uuid,
+            extensions::LIST_CREATED,
+            self.created.format(FORMAT),
+            extensions::LIST_MODIFIED,
+            self.modified.format(FORMAT)
         );
+        if let Some(description) = &self.description {
+            header.push_str(format!("X-WR-CALDESC:{}\n", escape_text(description)).as_str());
+        }
 
-        // Add data for every todo item
-        for task in &self.tasks {
-            // Begins the task data
-            ical_text.push_str("\nBEGIN:VTODO\n");
-            // Generates a unique UID for the task
-            ical_text.push_str(format!("UID:{}\n", task.uuid).as_str());
-
-            // Gets the current date and converts it to be compatible with the ical format
-            let nowstr = chrono::Utc::now().naive_utc().format(FORMAT);
-            // Gets the date the task was created and converts it as well
-            let createdstr = task.created.format(FORMAT);
-
-            // Add metadata dates for the task
-            ical_text.push_str(format!("CREATED:{createdstr}\n").as_str());
-            ical_text.push_str(format!("LAST-MODIFIED:{nowstr}\n").as_str());
-            ical_text.push_str(format!("DTSTAMP:{nowstr}\n").as_str());
-
-            // Adds task summary
-            ical_text.push_str(format!("SUMMARY:{}\n", &task.summary).as_str());
-
-            // Adds task due date
-            if let Some(due) = task.due {
-                ical_text.push_str(
+        // How the list was laid out in the app, so that it's laid out the same wherever it's opened
+        if let Some(layout) = &self.layout {
+            header
+                .push_str(format!("{}:{}\n", extensions::VIEW, escape_text(&layout.view)).as_str());
+            let order = if layout.sort_descending {
+                format!(";{}={}", extensions::ORDER, extensions::DESCENDING)
+            } else {
+                String::new()
+            };
+            header.push_str(
+                format!(
+                    "{}{}:{}\n",
+                    extensions::SORT,
+                    order,
+                    extensions::sort_name(layout.sort_by)
+                )
+                .as_str(),
+            );
+            header.push_str(
+                format!(
+                    "{}:{}\n",
+                    extensions::FILTER,
+                    extensions::filter_name(layout.filter)
+                )
+                .as_str(),
+            );
+            let show_completed = if layout.show_completed_tasks {
+                "TRUE"
+            } else {
+                "FALSE"
+            };
+            header
+                .push_str(format!("{}:{}\n", extensions::SHOW_COMPLETED, show_completed).as_str());
+            for (status, limit) in &layout.column_limits {
+                header.push_str(
                     format!(
-                        "DUE:{}\n",
-                        due.and_time(chrono::NaiveTime::default()).format(FORMAT)
+                        "{}:{};{}\n",
+                        extensions::WIP_LIMIT,
+                        limit,
+                        escape_text(status.to_ical())
                     )
-                    .to_string()
                     .as_str(),
                 );
             }
+            let block_full = if layout.block_full_columns {
+                "TRUE"
+            } else {
+                "FALSE"
+            };
+            header.push_str(format!("{}:{}\n", extensions::BLOCK_FULL, block_full).as_str());
+            header.push_str(
+                format!(
+                    "{}:{}\n",
+                    extensions::SWIMLANES,
+                    extensions::swimlanes_name(layout.swimlanes)
+                )
+                .as_str(),
+            );
+            if !layout.expanded_cards.is_empty() {
+                let uuids: Vec<String> = layout
+                    .expanded_cards
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                header.push_str(
+                    format!("{}:{}\n", extensions::EXPANDED_CARDS, uuids.join(",")).as_str(),
+                );
+            }
+        }
+        for (tag, color) in &self.tag_colors {
+            header.push_str(
+                format!("{}:{};{}\n", extensions::TAG_COLOR, color, escape_text(tag)).as_str(),
+            );
+        }
+        header
+    }
+}
+
+// Adds a VTODO for a task to the text
+fn push_todo(ical_text: &mut String, task: &Task) {
+    // Begins the task data
+    ical_text.push_str("BEGIN:VTODO\n");
+    // Generates a unique UID for the task
+    ical_text.push_str(format!("UID:{}\n", task.uuid).as_str());
+
+    // Gets the date the task was created and converts it to be compatible with the ical format
+    let createdstr = format_utc(task.created);
+    // The task is stamped with when it was last changed, rather than when it was exported,
+    // so that exporting a list again only changes the tasks that were changed
+    let touchedstr = format_utc(task.last_touched);
+
+    // Add metadata dates for the task
+    ical_text.push_str(format!("CREATED:{createdstr}\n").as_str());
+    ical_text.push_str(format!("LAST-MODIFIED:{touchedstr}\n").as_str());
+    ical_text.push_str(format!("DTSTAMP:{touchedstr}\n").as_str());
+
+    // Adds task summary
+    ical_text.push_str(format!("SUMMARY:{}\n", escape_text(&task.summary)).as_str());
+
+    // Adds task due date
+    if let Some(due) = task.due {
+        ical_text.push_str(
+            format!(
+                "DUE:{}\n",
+                due.and_time(chrono::NaiveTime::default()).format(FORMAT)
+            )
+            .as_str(),
+        );
+    }
+
+    // Adds when the task is scheduled on the day planner. VTODOs can't have a DURATION
+    // as well as a DUE date, so how long it's scheduled for has its own property.
+    if let Some(block) = task.scheduled {
+        ical_text.push_str(format!("DTSTART:{}\n", block.start.format(FORMAT)).as_str());
+        ical_text.push_str(
+            format!(
+                "{}:{}\n",
+                extensions::DURATION,
+                format_duration(block.minutes)
+            )
+            .as_str(),
+        );
+    }
+
+    // Adds how long the task is expected to take, which day it was picked for My Day,
+    // and where it is on the kanban board
+    if let Some(estimate) = task.estimate {
+        ical_text
+            .push_str(format!("{}:{}\n", extensions::ESTIMATE, format_duration(estimate)).as_str());
+    }
+    if let Some(day) = task.my_day {
+        ical_text
+            .push_str(format!("{}:{}\n", extensions::MY_DAY, day.format(DATE_FORMAT)).as_str());
+    }
+    if let Some(position) = task.board_position {
+        ical_text.push_str(format!("{}:{position}\n", extensions::BOARD_POSITION).as_str());
+    }
+
+    // Adds how the task repeats, and the occurrences that were skipped
+    if let Some(rule) = &task.recurrence {
+        ical_text.push_str(format!("RRULE:{rule}\n").as_str());
+    }
+    if !task.exception_dates.is_empty() {
+        let dates: Vec<String> = task
+            .exception_dates
+            .iter()
+            .map(|date| date.format(DATE_FORMAT).to_string())
+            .collect();
+        ical_text.push_str(format!("EXDATE;VALUE=DATE:{}\n", dates.join(",")).as_str());
+    }
+
+    // Adds when the task was completed
+    if let Some(completed_at) = task.completed_at.filter(|_| task.completed) {
+        ical_text.push_str(format!("COMPLETED:{}\n", format_utc(completed_at)).as_str());
+    }
+
+    // Adds the task this is a subtask of
+    if let Some(parent) = task.parent {
+        ical_text.push_str(format!("RELATED-TO;RELTYPE=PARENT:{parent}\n").as_str());
+    }
+
+    // Adds task priority if it's not 0
+    if task.priority.is_set() {
+        ical_text.push_str(format!("PRIORITY:{}\n", task.priority).as_str());
+    }
+    // Adds task progress if it's not 0
+    if task.progress.get() != 0 {
+        ical_text.push_str(format!("PERCENT-COMPLETE:{}\n", task.progress).as_str());
+    }
+    // Adds task's status. The completion checkbox takes precedence,
+    // but if it's not checked then the task's status field is used.
+    let status = if task.completed {
+        Status::Completed.to_ical()
+    } else {
+        task.status.to_ical()
+    };
+    ical_text.push_str(format!("STATUS:{status}\n").as_str());
+    // Adds task description if it's not empty
+    if !task.description.is_empty() {
+        ical_text.push_str(format!("DESCRIPTION:{}\n", escape_text(&task.description)).as_str());
+    }
+    // Adds task URL if it has one
+    if let Some(url) = &task.url {
+        ical_text.push_str(format!("URL:{url}\n").as_str());
+    }
+    // Adds task's tags, separated by commas, if it has any
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|tag| escape_text(tag)).collect();
+        ical_text.push_str(format!("CATEGORIES:{}\n", tags.join(",")).as_str());
+    }
+    // Adds where the task goes in its list's manual order, and whether it's pinned,
+    // the way Apple Reminders writes them
+    if let Some(order) = task.sort_order {
+        ical_text.push_str(format!("{}:{order}\n", compat::APPLE_SORT_ORDER).as_str());
+    }
+    if task.pinned {
+        ical_text.push_str(format!("{}:TRUE\n", compat::APPLE_FLAGGED).as_str());
+    }
+    // Adds whether the task's subtasks are hidden, the way Nextcloud Tasks writes it
+    if task.hide_subtasks {
+        ical_text.push_str(format!("{}:1\n", compat::NEXTCLOUD_HIDE_SUBTASKS).as_str());
+    }
+    // Ends the task data
+    ical_text.push_str("END:VTODO\n");
+}
+
+// When an event for a task happens
+#[derive(Clone, Copy)]
+enum EventTime {
+    // All day on a date, like the day a task is due
+    AllDay(chrono::NaiveDate),
+    // A block of time, like when a task is scheduled on the day planner
+    Timed(TimeBlock),
+}
+
+// Adds a VEVENT for a task to the text, so that it shows up in calendar apps.
+// Each task can have several events, so their UIDs are the task's UUID and what they're for.
+fn push_event(ical_text: &mut String, task: &Task, kind: &str, time: EventTime) {
+    ical_text.push_str("BEGIN:VEVENT\n");
+    ical_text.push_str(format!("UID:{}-{kind}\n", task.uuid).as_str());
+    // Events change when their task does, so they're stamped the same way
+    ical_text.push_str(format!("DTSTAMP:{}\n", format_utc(task.last_touched)).as_str());
+    match time {
+        EventTime::AllDay(date) => {
+            // All day events end at the start of the next day
+            let end = date.succ_opt().unwrap_or(date);
+            ical_text.push_str(format!("DTSTART;VALUE=DATE:{}\n", date.format("%Y%m%d")).as_str());
+            ical_text.push_str(format!("DTEND;VALUE=DATE:{}\n", end.format("%Y%m%d")).as_str());
+            // Due dates shouldn't make the user look busy all day
+            ical_text.push_str("TRANSP:TRANSPARENT\n");
+        }
+        EventTime::Timed(block) => {
+            ical_text.push_str(format!("DTSTART:{}\n", block.start.format(FORMAT)).as_str());
+            ical_text.push_str(format!("DTEND:{}\n", block.end().format(FORMAT)).as_str());
+        }
+    }
+    ical_text.push_str(format!("SUMMARY:{}\n", escape_text(&task.summary)).as_str());
+    if !task.description.is_empty() {
+        ical_text.push_str(format!("DESCRIPTION:{}\n", escape_text(&task.description)).as_str());
+    }
+    // Links the event back to the task it's for
+    ical_text.push_str(format!("RELATED-TO:{}\n", task.uuid).as_str());
+    ical_text.push_str("END:VEVENT\n");
+}
+
+// The text is built with LF line endings and without folding long lines,
+// so both are done once it's finished
+fn finish(ical_text: &str, line_ending: LineEnding) -> String {
+    let newline = match line_ending {
+        LineEnding::Crlf => "\r\n",
+        LineEnding::Lf => "\n",
+    };
+    let mut folded = String::with_capacity(ical_text.len());
+    for line in ical_text.lines() {
+        fold_line(line, newline, &mut folded);
+    }
+    folded
+}
+
+/// Reads the tasks in an iCal file a few at a time, so that a big file can be
+/// imported without freezing the app, and the import can be stopped part way through.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::parser::IcalImport;
+///
+/// let ical = "BEGIN:VCALENDAR\r\nX-WR-CALNAME:Chores\r\n\
+///     BEGIN:VTODO\r\nSUMMARY:Wash up\r\nEND:VTODO\r\n\
+///     BEGIN:VTODO\r\nSUMMARY:Hoover\r\nEND:VTODO\r\n\
+///     END:VCALENDAR\r\n";
+/// let mut import = IcalImport::new(ical.as_bytes());
+///
+/// assert_eq!(import.next_chunk(1).unwrap()[0].summary, "Wash up");
+/// assert_eq!(import.list().name, "Chores");
+///
+/// assert_eq!(import.next_chunk(10).unwrap()[0].summary, "Hoover");
+/// assert!(import.is_finished());
+/// assert_eq!(import.progress(), 1.);
+/// ```
+///
+/// A lenient import skips anything it can't read, rather than failing,
+/// and keeps a warning for each thing it skipped:
+/// ```
+/// use taskmaster_rs::parser::{IcalImport, ImportWarning};
+/// use taskmaster_rs::task::Priority;
+///
+/// let ical = "BEGIN:VCALENDAR\r\n\
+///     BEGIN:VTODO\r\nSUMMARY:Wash up\r\nPRIORITY:high\r\nEND:VTODO\r\n\
+///     BEGIN:VEVENT\r\nSUMMARY:Party\r\nEND:VEVENT\r\n\
+///     END:VCALENDAR\r\n";
+/// let mut import = IcalImport::new(ical.as_bytes()).lenient();
+///
+/// let tasks = import.next_chunk(10).unwrap();
+/// assert_eq!(tasks.len(), 1);
+/// assert_eq!(tasks[0].summary, "Wash up");
+/// assert_eq!(tasks[0].priority, Priority::NONE);
+/// assert_eq!(
+///     import.warnings(),
+///     [
+///         ImportWarning::InvalidProperty {
+///             task: 1,
+///             name: String::from("PRIORITY"),
+///             value: String::from("high"),
+///         },
+///         ImportWarning::SkippedComponent { name: String::from("VEVENT") },
+///     ]
+/// );
+/// assert!(IcalImport::new(ical.as_bytes()).next_chunk(10).is_err());
+/// ```
+///
+/// Numbers that are out of range, like a task that's 250% complete, are
+/// brought into range whether or not the import is lenient, with a warning:
+/// ```
+/// use taskmaster_rs::parser::{IcalImport, ImportWarning};
+///
+/// let ical = "BEGIN:VCALENDAR\r\n\
+///     BEGIN:VTODO\r\nSUMMARY:Wash up\r\nPERCENT-COMPLETE:250\r\nEND:VTODO\r\n\
+///     END:VCALENDAR\r\n";
+/// let mut import = IcalImport::new(ical.as_bytes());
+///
+/// assert_eq!(import.next_chunk(10).unwrap()[0].progress.get(), 100);
+/// assert_eq!(
+///     import.warnings(),
+///     [ImportWarning::OutOfRange {
+///         task: 1,
+///         name: String::from("PERCENT-COMPLETE"),
+///         value: String::from("250"),
+///         used: 100,
+///     }]
+/// );
+/// ```
+///
+/// Times in a time zone are moved into local time. Ones in a zone that isn't
+/// known are read as they're written, with a warning:
+/// ```
+/// use taskmaster_rs::parser::{IcalImport, ImportWarning};
+///
+/// let ical = "BEGIN:VCALENDAR\r\n\
+///     BEGIN:VTODO\r\nSUMMARY:Wash up\r\nDUE;TZID=Mars/Olympus_Mons:20230825T090000\r\nEND:VTODO\r\n\
+///     END:VCALENDAR\r\n";
+/// let mut import = IcalImport::new(ical.as_bytes());
+///
+/// assert_eq!(import.next_chunk(10).unwrap()[0].due, chrono::NaiveDate::from_ymd_opt(2023, 8, 25));
+/// assert_eq!(
+///     import.warnings(),
+///     [ImportWarning::UnknownTimeZone {
+///         task: 1,
+///         name: String::from("DUE"),
+///         zone: String::from("Mars/Olympus_Mons"),
+///     }]
+/// );
+/// ```
+pub struct IcalImport {
+    text: String,
+    // How many bytes of the text have been read so far
+    read: usize,
+    // The list's details, like its name, without any tasks
+    list: TaskList,
+    // The task whose properties are being read
+    task: Option<Task>,
+    // How long the task being read is scheduled for, if that was read before when it starts
+    scheduled_minutes: Option<u32>,
+    // When the task being read was last changed, if the file says
+    last_modified: Option<chrono::NaiveDateTime>,
+    // When the task being read was stamped, which is when it was last changed
+    // in files that don't say that on its own
+    stamped: Option<chrono::NaiveDateTime>,
+    // How many tasks have been started so far, which warnings say which task they're about with
+    tasks_started: usize,
+    // Whether anything that can't be read is skipped with a warning, rather than failing
+    lenient: bool,
+    warnings: Vec<ImportWarning>,
+    // A component that isn't a task, which everything is skipped in until it ends
+    skipping: Option<String>,
+    finished: bool,
+}
+
+impl IcalImport {
+    // Starts importing the contents of an iCal file.
+    // Text in other encodings than UTF-8 is decoded first.
+    pub fn new(bytes: &[u8]) -> Self {
+        Self {
+            text: encoding::join_soft_line_breaks(&compat::decode(bytes)),
+            read: 0,
+            list: TaskList::default(),
+            task: None,
+            scheduled_minutes: None,
+            last_modified: None,
+            stamped: None,
+            tasks_started: 0,
+            lenient: false,
+            warnings: Vec::new(),
+            skipping: None,
+            finished: false,
+        }
+    }
+
+    // Skips anything in the file that can't be read, keeping a warning for each
+    // thing that's skipped, rather than failing
+    #[must_use]
+    pub const fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    // Everything that was skipped so far, when the import is lenient, any numbers
+    // that were out of range and had to be changed, and any time zones that aren't known
+    pub fn warnings(&self) -> &[ImportWarning] {
+        &self.warnings
+    }
+
+    // Reads up to the given number of tasks from the file.
+    // Once the file has been read to the end, no more tasks are returned.
+    pub fn next_chunk(&mut self, max_tasks: usize) -> Result<Vec<Task>, ParseFromFileError> {
+        let mut tasks = Vec::new();
+        while !self.finished && tasks.len() < max_tasks {
+            let Some(line) = self.next_line() else {
+                self.finished = true;
+                // A task that was never ended is still kept
+                tasks.extend(self.take_task());
+                break;
+            };
+
+            // If the line is invalid, return an error
+            let result = parse_property(&line)
+                .ok_or(ParseFromFileError::InvalidFile)
+                .and_then(|property| self.read_property(&property));
+            match result {
+                Ok(task) => tasks.extend(task),
+                // A lenient import carries on with the next line
+                Err(ParseFromFileError::InvalidFile) if self.lenient => {
+                    self.warnings.push(ImportWarning::InvalidLine);
+                }
+                Err(e) => {
+                    self.finished = true;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(tasks)
+    }
+
+    // Whether the whole file has been read, or reading it failed
+    pub const fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // How far through the file the import is, from 0 to 1
+    #[allow(clippy::cast_precision_loss)]
+    pub fn progress(&self) -> f32 {
+        if self.finished || self.text.is_empty() {
+            return 1.;
+        }
+        (self.read as f32 / self.text.len() as f32).min(1.)
+    }
+
+    // The list's details read so far, like its name and color, without any tasks
+    pub const fn list(&self) -> &TaskList {
+        &self.list
+    }
 
-            // Adds task priority if it's not 0
-            if task.priority != 0 {
-                ical_text.push_str(format!("PRIORITY:{}\n", task.priority).as_str());
+    // Reads the next line of the text, with any lines it was folded onto joined back up.
+    // Only the line endings are taken off, so that whitespace at the end of a value is kept.
+    fn next_line(&mut self) -> Option<String> {
+        let mut line: Option<String> = None;
+        while self.read < self.text.len() {
+            let rest = &self.text[self.read..];
+            let length = rest.find('\n').map_or(rest.len(), |index| index + 1);
+            let physical = rest[..length].trim_end_matches(['\r', '\n']);
+            match (physical.strip_prefix([' ', '\t']), &mut line) {
+                (Some(folded), Some(line)) => line.push_str(folded),
+                _ if physical.is_empty() => (),
+                // The next property starts here, so it's left for next time
+                (_, Some(_)) => break,
+                (_, None) => line = Some(physical.to_string()),
             }
-            // Adds task progress if it's not 0
-            if task.progress != 0 {
-                ical_text.push_str(format!("PERCENT-COMPLETE:{}\n", task.progress).as_str());
+            self.read += length;
+        }
+        line
+    }
+
+    // Finishes reading the current task, if there is one.
+    // Tasks which don't say when they were last changed or stamped haven't been changed
+    // since they were made.
+    fn take_task(&mut self) -> Option<Task> {
+        let mut task = self.task.take()?;
+        let stamped = self.stamped.take();
+        task.last_touched = self
+            .last_modified
+            .take()
+            .or(stamped)
+            .unwrap_or(task.created);
+        Some(task)
+    }
+
+    // Reads one line of the file. Returns a task if the line was the end of one.
+    fn read_property(&mut self, property: &Property) -> Result<Option<Task>, ParseFromFileError> {
+        // A line with nothing after the colon, like a task with no summary, has an empty value.
+        // Lines where that isn't allowed still fail to parse below.
+        let value = property.value.as_deref().unwrap_or_default();
+        // Old files can have quoted-printable values, which need decoding
+        let value = if param(property, "ENCODING")
+            .is_some_and(|encoding| encoding.eq_ignore_ascii_case("QUOTED-PRINTABLE"))
+        {
+            encoding::decode_quoted_printable(value, param(property, "CHARSET"))
+        } else {
+            value.to_string()
+        };
+        // Whitespace at the end of a line is usually left there by whatever wrote the file,
+        // so it's only kept in text, where it can be part of what was written
+        let value = match property.name.as_str() {
+            "SUMMARY"
+            | "DESCRIPTION"
+            | "X-WR-CALNAME"
+            | "X-WR-CALDESC"
+            | extensions::VIEW
+            | extensions::TAG_COLOR => value,
+            _ => value.trim_end().to_string(),
+        };
+
+        // Everything in a component that's being skipped is ignored, until it ends
+        if let Some(component) = &self.skipping {
+            if property.name == "END" && value == *component {
+                self.skipping = None;
             }
-            // Adds task's status. The completion checkbox takes precedence,
-            // but if it's not checked then the task's status field is used.
-            if task.completed {
-                ical_text.push_str("STATUS:COMPLETED\n");
-            } else {
-                let mut statstr = String::from("STATUS:");
-                match task.status {
-                    Status::InProgress => statstr.push_str("IN-PROGRESS\n"),
-                    Status::NeedsAction => statstr.push_str("NEEDS-ACTION\n"),
-                    Status::Completed => statstr.push_str("COMPLETED\n"),
-                    Status::Cancelled => statstr.push_str("CANCELLED\n"),
+            return Ok(None);
+        }
+
+        // Checks whether the line is about the list, or starts or ends a task
+        match property.name.as_str() {
+            // Set calendar name
+            "X-WR-CALNAME" => {
+                self.list.name = unescape_text(&value);
+                return Ok(None);
+            }
+            "X-WR-CALDESC" => {
+                self.list.description = Some(unescape_text(&value));
+                return Ok(None);
+            }
+            // Lists from files without a UID keep the new one they were given
+            extensions::LIST_UID => {
+                if let Ok(uuid) = uuid::Uuid::parse_str(&value) {
+                    self.list.uuid = uuid;
                 }
-                ical_text.push_str(&statstr);
+                return Ok(None);
             }
-            // Adds task description if it's not empty
-            if !task.description.is_empty() {
-                ical_text.push_str(
-                    format!("DESCRIPTION:{}\n", &task.description.replace('\n', "\\n")).as_str(),
-                );
+            extensions::LIST_CREATED => {
+                if let Some(created) = parse_date_time(property, &value) {
+                    self.list.created = created;
+                }
+                return Ok(None);
+            }
+            extensions::LIST_MODIFIED => {
+                if let Some(modified) = parse_date_time(property, &value) {
+                    self.list.modified = modified;
+                }
+                return Ok(None);
+            }
+            // How the list was laid out. Anything that can't be read is left as it was by default.
+            extensions::VIEW => {
+                self.layout().view = unescape_text(&value);
+                return Ok(None);
+            }
+            extensions::SORT => {
+                let descending = param(property, extensions::ORDER)
+                    .is_some_and(|order| order.eq_ignore_ascii_case(extensions::DESCENDING));
+                let layout = self.layout();
+                if let Some(sort_by) = extensions::sort_from_name(&value) {
+                    layout.sort_by = sort_by;
+                }
+                layout.sort_descending = descending;
+                return Ok(None);
+            }
+            extensions::FILTER => {
+                if let Some(filter) = extensions::filter_from_name(&value) {
+                    self.layout().filter = filter;
+                }
+                return Ok(None);
+            }
+            extensions::SHOW_COMPLETED => {
+                self.layout().show_completed_tasks = value.trim().eq_ignore_ascii_case("TRUE");
+                return Ok(None);
+            }
+            // Limits that can't be read are left out, and columns without one have no limit
+            extensions::WIP_LIMIT => {
+                if let Some((limit, status)) = value.split_once(';') {
+                    if let Ok(limit @ 1..) = limit.trim().parse::<usize>() {
+                        let status = Status::from_ical_or_custom(&unescape_text(status));
+                        self.layout().column_limits.insert(status, limit);
+                    }
+                }
+                return Ok(None);
+            }
+            extensions::BLOCK_FULL => {
+                self.layout().block_full_columns = value.trim().eq_ignore_ascii_case("TRUE");
+                return Ok(None);
+            }
+            extensions::SWIMLANES => {
+                if let Some(swimlanes) = extensions::swimlanes_from_name(&value) {
+                    self.layout().swimlanes = swimlanes;
+                }
+                return Ok(None);
+            }
+            // UIDs that can't be read are left out
+            extensions::EXPANDED_CARDS => {
+                let uuids = value
+                    .split(',')
+                    .filter_map(|uuid| uuid::Uuid::parse_str(uuid.trim()).ok());
+                self.layout().expanded_cards.extend(uuids);
+                return Ok(None);
+            }
+            // Tags whose color can't be read are left out
+            extensions::TAG_COLOR => {
+                if let Some((color, tag)) = value.split_once(';') {
+                    if let Some(color) = Color::from_hex(color) {
+                        self.list.tag_colors.insert(unescape_text(tag), color);
+                    }
+                }
+                return Ok(None);
+            }
+            // Set calendar color, which some apps write with an alpha channel
+            "X-APPLE-CALENDAR-COLOR" => {
+                // Colors that can't be read are left as they are
+                if let Some(color) = Color::from_hex(&value) {
+                    self.list.color = color;
+                }
+                return Ok(None);
+            }
+            // Checks for a BEGIN statement
+            "BEGIN" => {
+                return match value.as_str() {
+                    // If it's starting a new task, start reading it.
+                    // If the last task was never ended, it's finished here instead.
+                    "VTODO" => {
+                        let previous = self.take_task();
+                        self.scheduled_minutes = None;
+                        self.task = Some(Task::default());
+                        self.tasks_started += 1;
+                        Ok(previous)
+                    }
+                    // If it's just starting the file, do nothing
+                    "VCALENDAR" => Ok(None),
+                    // Time zones and alarms are in most other apps' files, and don't
+                    // change the tasks, so they're skipped without a warning
+                    "VTIMEZONE" | "VALARM" => {
+                        self.skipping = Some(value);
+                        Ok(None)
+                    }
+                    // A lenient import skips anything else, with everything in it
+                    _ if self.lenient => {
+                        self.warnings.push(ImportWarning::SkippedComponent {
+                            name: value.clone(),
+                        });
+                        self.skipping = Some(value);
+                        Ok(None)
+                    }
+                    // If it's starting anything else, return an error
+                    _ => Err(ParseFromFileError::NonTaskItem),
+                };
+            }
+            // If the file says that the task is complete, it's ready to be added to the list
+            "END" => {
+                return Ok(if value == "VTODO" {
+                    self.take_task()
+                } else {
+                    None
+                });
             }
-            // Ends the task data
-            ical_text.push_str("END:VTODO\n");
+            _ => (),
         }
-        // Ends the file
-        ical_text.push_str("END:VCALENDAR\n");
 
-        // We're all good, return a reference to the file
-        ical_text
+        // Everything else is about the task being read, and is ignored outside of a task.
+        // A lenient import skips values that can't be read, leaving the field as it was.
+        match self.read_task_property(property, &value) {
+            Err(ParseFromFileError::InvalidField) if self.lenient => {
+                self.warnings.push(ImportWarning::InvalidProperty {
+                    task: self.tasks_started,
+                    name: property.name.clone(),
+                    value,
+                });
+                Ok(None)
+            }
+            result => result.map(|()| None),
+        }
+    }
+
+    // The layout the file gives the list, which starts as the default once any of it is read
+    fn layout(&mut self) -> &mut ListLayout {
+        self.list.layout.get_or_insert_with(ListLayout::default)
+    }
+
+    // Reads one line of the file which is about the task being read
+    #[allow(clippy::too_many_lines)]
+    fn read_task_property(
+        &mut self,
+        property: &Property,
+        value: &str,
+    ) -> Result<(), ParseFromFileError> {
+        let Some(task) = &mut self.task else {
+            return Ok(());
+        };
+
+        // Times in a time zone that isn't known are read as they are, with a warning
+        if let Some(zone) = param(property, "TZID").filter(|zone| time_zone(zone).is_none()) {
+            self.warnings.push(ImportWarning::UnknownTimeZone {
+                task: self.tasks_started,
+                name: property.name.clone(),
+                zone: zone.to_string(),
+            });
+        }
+
+        // Checks what the line is saying
+        match property.name.as_str() {
+            // If the task has a UID in the file, then use it for the task's UUID.
+            // iCal allows IDs that don't follow the UUID format, which are turned into one.
+            // Tasks with an empty UID keep the UUID that is generated when a Task is created.
+            "UID" => {
+                if let Some(uuid) = compat::uid_to_uuid(value) {
+                    task.uuid = uuid;
+                }
+            }
+            // Set the currently addressed task's summary
+            "SUMMARY" => {
+                task.summary = unescape_text(value);
+            }
+            // Set the currently addressed task's due date
+            "DUE" => {
+                let Some(date) = parse_date_time(property, value) else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                task.due = Some(date.date());
+            }
+            // A start with a time is when the task is scheduled on the day planner.
+            // Tasks don't have a start date on its own, but an invalid one still means the file is invalid.
+            "DTSTART" => {
+                let Some(start) = parse_date_time(property, value) else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                if !is_date_only(property, value) {
+                    task.scheduled = Some(TimeBlock {
+                        start,
+                        minutes: self.scheduled_minutes.take().unwrap_or_default(),
+                    });
+                }
+            }
+            // How long the task is scheduled for
+            extensions::DURATION => {
+                let Some(minutes) = parse_duration(value) else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                match &mut task.scheduled {
+                    Some(block) => block.minutes = minutes,
+                    None => self.scheduled_minutes = Some(minutes),
+                }
+            }
+            // How long the task is expected to take
+            extensions::ESTIMATE => {
+                let Some(minutes) = parse_duration(value) else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                task.estimate = Some(minutes);
+            }
+            // The day the task was picked for My Day
+            extensions::MY_DAY => {
+                let Ok(day) = chrono::NaiveDate::parse_from_str(value, DATE_FORMAT) else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                task.my_day = Some(day);
+            }
+            // Where the task is in its status's column on the kanban board
+            extensions::BOARD_POSITION => {
+                let Ok(position) = value.trim().parse() else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                task.board_position = Some(position);
+            }
+            // How the task repeats, which is kept as it's written
+            "RRULE" => {
+                task.recurrence = Some(value.to_string());
+            }
+            // Occurrences of the task that were skipped. Tasks can have several EXDATE lines.
+            "EXDATE" => {
+                for date in value.split(',') {
+                    let Some(date) = parse_date_time(property, date.trim()) else {
+                        return Err(ParseFromFileError::InvalidField);
+                    };
+                    if !task.exception_dates.contains(&date.date()) {
+                        task.exception_dates.push(date.date());
+                    }
+                }
+            }
+            // The task this is a subtask of. Other kinds of relationships, like
+            // a task that has to be done first, are left out.
+            "RELATED-TO" => {
+                let is_parent = param(property, "RELTYPE")
+                    .map_or(true, |kind| kind.eq_ignore_ascii_case("PARENT"));
+                if is_parent {
+                    if let Some(parent) = compat::uid_to_uuid(value) {
+                        task.parent = Some(parent);
+                    }
+                }
+            }
+            // A task with a completion date has been completed
+            "COMPLETED" => {
+                let Some(completed_at) = parse_date_time(property, value) else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                task.completed = true;
+                task.completed_at = Some(completed_at);
+            }
+            // Set the currently addressed task's priority
+            // Priorities that are out of range are brought into it, with a warning
+            "PRIORITY" => {
+                let Ok(priority) = value.trim().parse() else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                let used = clamp_to(priority, MAX_PRIORITY);
+                task.priority = Priority::new(used);
+                if i64::from(used) != priority {
+                    self.warnings.push(ImportWarning::OutOfRange {
+                        task: self.tasks_started,
+                        name: property.name.clone(),
+                        value: value.to_string(),
+                        used,
+                    });
+                }
+            }
+            // Set the currently addressed task's completion percent, brought into range like the priority
+            "PERCENT-COMPLETE" => {
+                let Ok(progress) = value.trim().parse() else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                let used = clamp_to(progress, MAX_PROGRESS);
+                task.progress = Percent::new(used);
+                if i64::from(used) != progress {
+                    self.warnings.push(ImportWarning::OutOfRange {
+                        task: self.tasks_started,
+                        name: property.name.clone(),
+                        value: value.to_string(),
+                        used,
+                    });
+                }
+            }
+            // Set the currently addressed task's status
+            "STATUS" => {
+                if value.is_empty() {
+                    return Err(ParseFromFileError::InvalidField);
+                }
+                let status = Status::from_ical_or_custom(value);
+                task.completed = status == Status::Completed;
+                task.status = status;
+            }
+            // Set the currently addressed task's description
+            "DESCRIPTION" => {
+                task.description = unescape_text(value);
+            }
+            // Set the currently addressed task's URL
+            "URL" => {
+                task.url = Some(value.to_string());
+            }
+            // Add to the currently addressed task's tags. Tasks can have several
+            // CATEGORIES lines, and a tag that's already been read isn't added twice.
+            "CATEGORIES" => {
+                for tag in split_text_list(value) {
+                    if !tag.is_empty() && !task.tags.contains(&tag) {
+                        task.tags.push(tag);
+                    }
+                }
+            }
+            // Where the task goes in the list's manual order
+            compat::APPLE_SORT_ORDER => {
+                let Ok(order) = value.trim().parse() else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                task.sort_order = Some(order);
+            }
+            compat::APPLE_FLAGGED => {
+                task.pinned = value.trim().eq_ignore_ascii_case("TRUE");
+            }
+            compat::NEXTCLOUD_HIDE_SUBTASKS => {
+                task.hide_subtasks = value.trim() == "1";
+            }
+            // Store the task's creation date
+            "CREATED" => {
+                let Some(date) = parse_date_time(property, value) else {
+                    return Err(ParseFromFileError::InvalidField);
+                };
+                task.created = date;
+            }
+            // Store when the task was last changed. Files with an invalid one were
+            // read fine before it was used, so it's ignored rather than rejected.
+            "LAST-MODIFIED" => {
+                self.last_modified = parse_date_time(property, value);
+            }
+            // Read the same way as LAST-MODIFIED, for files that only have this
+            "DTSTAMP" => {
+                self.stamped = parse_date_time(property, value);
+            }
+            // If the line isn't any of the above, just do nothing
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl Status {
+    // Converts a status to the value used for it in an iCal STATUS field
+    pub fn to_ical(&self) -> &str {
+        match self {
+            Self::InProgress => "IN-PROCESS",
+            Self::NeedsAction => "NEEDS-ACTION",
+            Self::Completed => "COMPLETED",
+            Self::Cancelled => "CANCELLED",
+            Self::Custom(value) => value,
+        }
+    }
+
+    // Converts the value of an iCal STATUS field to a status, if it's a standard one.
+    // Values in iCal files aren't case sensitive.
+    pub fn from_ical(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            // Older versions of the app wrote IN-PROGRESS, which isn't valid iCal
+            "IN-PROCESS" | "IN-PROGRESS" => Some(Self::InProgress),
+            "NEEDS-ACTION" => Some(Self::NeedsAction),
+            "COMPLETED" => Some(Self::Completed),
+            "CANCELLED" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    /// Converts the value of an iCal STATUS field to a status, keeping values
+    /// that aren't standard as custom statuses.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::Status;
+    ///
+    /// assert_eq!(Status::from_ical_or_custom("NEEDS-ACTION"), Status::NeedsAction);
+    /// assert_eq!(Status::from_ical_or_custom("completed"), Status::Completed);
+    /// assert_eq!(
+    ///     Status::from_ical_or_custom("X-WAITING"),
+    ///     Status::Custom(String::from("X-WAITING"))
+    /// );
+    /// assert_eq!(Status::Custom(String::from("X-WAITING")).to_ical(), "X-WAITING");
+    /// ```
+    pub fn from_ical_or_custom(value: &str) -> Self {
+        Self::from_ical(value).unwrap_or_else(|| Self::Custom(value.to_string()))
+    }
+}
+
+// Escapes the characters that have a special meaning in iCal text values,
+// so that a summary like "Buy eggs, milk" isn't read as a list of values.
+// Line breaks are all written as \n, including ones that were CRLF or CR.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                escaped.push_str("\\n");
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Turns escaped characters in an iCal text value back into the characters they stand for.
+// A backslash before anything else is kept, since older versions of the app didn't escape them.
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(c @ ('\\' | ';' | ',')) => unescaped.push(c),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+// Splits an iCal list of text values, like CATEGORIES, at the commas which
+// aren't escaped, and unescapes each value
+fn split_text_list(text: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                values.push(unescape_text(text[start..index].trim()));
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    values.push(unescape_text(text[start..].trim()));
+    values
+}
+
+// Adds a line to the text, folding it onto several lines if it's longer than MAX_LINE_LENGTH bytes.
+// Lines are only broken after something other than whitespace, since whitespace
+// at the end of a line is removed by some apps when they read the file.
+fn fold_line(line: &str, newline: &str, text: &mut String) {
+    let break_after =
+        |(index, c): (usize, char)| (!c.is_whitespace()).then_some(index + c.len_utf8());
+
+    let mut rest = line;
+    // Every line after the first starts with a space, which counts towards its length
+    let mut max_length = MAX_LINE_LENGTH;
+    while rest.len() > max_length {
+        let end = rest
+            .char_indices()
+            .take_while(|(index, c)| index + c.len_utf8() <= max_length)
+            .filter_map(break_after)
+            .last()
+            // If there's nowhere to break the line in time, it's broken at the first place it can be
+            .or_else(|| rest.char_indices().find_map(break_after));
+        match end {
+            Some(end) if end < rest.len() => {
+                text.push_str(&rest[..end]);
+                text.push_str(newline);
+                text.push(' ');
+                rest = &rest[end..];
+                max_length = MAX_LINE_LENGTH - 1;
+            }
+            _ => break,
+        }
+    }
+    text.push_str(rest);
+    text.push_str(newline);
+}
+
+// Splits a line into its property's name, parameters and value, like
+// DUE;TZID="Europe/Berlin":20240517T170000. Parameter values can be quoted, so
+// that they can have colons and semicolons in them. The value is everything after
+// the first colon that isn't quoted, as it's written, even if it starts with a colon.
+fn parse_property(line: &str) -> Option<Property> {
+    let mut name = None;
+    let mut params = Vec::new();
+    let mut quoted = false;
+    // Where the name or parameter being read starts
+    let mut start = 0;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' | ':' if !quoted => {
+                let part = &line[start..index];
+                if name.is_none() {
+                    name = Some(part.trim().to_uppercase());
+                } else {
+                    params.push(parse_param(part)?);
+                }
+                if c == ':' {
+                    let name = name.filter(|name| !name.is_empty())?;
+                    return Some(Property {
+                        name,
+                        params: (!params.is_empty()).then_some(params),
+                        value: Some(line[index + 1..].to_string()),
+                    });
+                }
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    // A line without a value isn't a property
+    None
+}
+
+// Splits a parameter, like MEMBER="mailto:a@example.com","mailto:b@example.com",
+// into its name and values, without the quotes around them
+fn parse_param(param: &str) -> Option<(String, Vec<String>)> {
+    let (name, values) = param.split_once('=')?;
+    let mut parsed = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (index, c) in values.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                parsed.push(values[start..index].trim_matches('"').to_string());
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    parsed.push(values[start..].trim_matches('"').to_string());
+    Some((name.trim().to_uppercase(), parsed))
+}
+
+// Gets the value of one of a property's parameters, like the CHARSET in
+// SUMMARY;CHARSET=ISO-8859-1:..., if it has that parameter
+fn param<'a>(property: &'a Property, name: &str) -> Option<&'a str> {
+    property
+        .params
+        .as_ref()?
+        .iter()
+        .find(|(param, _)| param.eq_ignore_ascii_case(name))?
+        .1
+        .first()
+        .map(String::as_str)
+}
+
+// Parses the value of a DATE or DATE-TIME property, like DUE or CREATED, into local time.
+// Values can be dates on their own (DUE;VALUE=DATE:20230825), times in UTC
+// (DUE:20230825T090000Z), or times in a time zone (DUE;TZID=Europe/London:20230825T090000).
+// Times in a time zone are moved into local time like ones in UTC are. Ones in a zone
+// that isn't known, and floating times without a zone, are kept as they are.
+fn parse_date_time(property: &Property, value: &str) -> Option<chrono::NaiveDateTime> {
+    if is_date_only(property, value) {
+        let date = chrono::NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some(date.and_time(chrono::NaiveTime::default()));
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let date = chrono::NaiveDateTime::parse_from_str(utc, FORMAT).ok()?;
+        return Some(
+            chrono::Utc
+                .from_utc_datetime(&date)
+                .with_timezone(&chrono::Local)
+                .naive_local(),
+        );
+    }
+
+    let date = chrono::NaiveDateTime::parse_from_str(value, FORMAT).ok()?;
+    Some(match param(property, "TZID").and_then(time_zone) {
+        // Times skipped when the zone's clocks go forward are kept as they are
+        Some(zone) => zone
+            .from_local_datetime(&date)
+            .earliest()
+            .map_or(date, |time| {
+                time.with_timezone(&chrono::Local).naive_local()
+            }),
+        None => date,
+    })
+}
+
+// The time zone a TZID is for, if it's one in the time zone database.
+// Some apps put their own prefix before the zone's name, like
+// /mozilla.org/20070129_1/Europe/Berlin, so every part after a slash is tried too.
+fn time_zone(tzid: &str) -> Option<chrono_tz::Tz> {
+    let tzid = tzid.trim();
+    std::iter::once(tzid)
+        .chain(tzid.match_indices('/').map(|(index, _)| &tzid[index + 1..]))
+        .find_map(|name| name.parse().ok())
+}
+
+// Writes a local time as a time in UTC, like 20230807T081658Z, which is what RFC 5545
+// requires for DTSTAMP, CREATED, LAST-MODIFIED and COMPLETED
+fn format_utc(local: chrono::NaiveDateTime) -> String {
+    let utc = match chrono::Local.from_local_datetime(&local).earliest() {
+        Some(time) => time.naive_utc(),
+        // Times skipped when the clocks go forward are taken to be in the offset around then
+        None => {
+            let offset = chrono::Local
+                .offset_from_utc_datetime(&local)
+                .local_minus_utc();
+            local - chrono::Duration::seconds(offset.into())
+        }
+    };
+    format!("{}Z", utc.format(FORMAT))
+}
+
+// Whether the value of a DATE or DATE-TIME property is a date without a time
+fn is_date_only(property: &Property, value: &str) -> bool {
+    param(property, "VALUE").is_some_and(|value_type| value_type.eq_ignore_ascii_case("DATE"))
+        // Some apps leave out VALUE=DATE, but a date on its own is still obvious
+        || !value.contains('T')
+}
+
+// Brings a number read from a file into the range from 0 up to the highest that's allowed
+fn clamp_to(number: i64, max: u8) -> u8 {
+    u8::try_from(number.clamp(0, i64::from(max))).unwrap_or(max)
+}
+
+// Writes a number of minutes as an iCal duration, like PT1H30M
+fn format_duration(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("PT{minutes}M"),
+        (hours, 0) => format!("PT{hours}H"),
+        (hours, minutes) => format!("PT{hours}H{minutes}M"),
+    }
+}
+
+// Reads an iCal duration, like PT1H30M or P1DT2H, as a number of minutes.
+// Seconds are rounded down, and negative durations aren't accepted.
+fn parse_duration(value: &str) -> Option<u32> {
+    let value = value.strip_prefix('+').unwrap_or(value);
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+    let mut time = false;
+    let mut read_any = false;
+    for c in value.strip_prefix('P')?.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if number.is_empty() && !time => time = true,
+            _ => {
+                let unit = match (c, time) {
+                    ('W', false) => 7 * 24 * 60 * 60,
+                    ('D', false) => 24 * 60 * 60,
+                    ('H', true) => 60 * 60,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+                let amount: u64 = number.parse().ok()?;
+                seconds = seconds.checked_add(amount.checked_mul(unit)?)?;
+                number.clear();
+                read_any = true;
+            }
+        }
+    }
+    if !number.is_empty() || !read_any {
+        return None;
+    }
+    u32::try_from(seconds / 60).ok()
+}
+
+// How lines end in iCal text written by the app.
+// Files with either are accepted when importing.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineEnding {
+    // What RFC 5545 requires
+    #[default]
+    Crlf,
+    Lf,
+}
+
+impl LineEnding {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        [Self::Crlf, Self::Lf].iter()
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Crlf => "CRLF (standard)",
+            Self::Lf => "LF",
+        }
+    }
+}
+
+// Which kinds of iCal components tasks are exported as
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    // Todo items, which the app can import again
+    #[default]
+    Todos,
+    // Todo items, and an all day event on the day each task is due
+    TodosAndEvents,
+    // Only the events, for calendar apps that don't understand todo items
+    Events,
+}
+
+impl ExportFormat {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        [Self::Todos, Self::TodosAndEvents, Self::Events].iter()
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Todos => "Tasks",
+            Self::TodosAndEvents => "Tasks and due date events",
+            Self::Events => "Due date events only",
+        }
+    }
+}
+
+// Something in a file that was skipped by a lenient import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportWarning {
+    // A line that isn't a property at all
+    InvalidLine,
+    // A property of a task with a value that couldn't be read, so the field was left as it was.
+    // Tasks are numbered from 1, in the order they're in the file.
+    InvalidProperty {
+        task: usize,
+        name: String,
+        value: String,
+    },
+    // A component that isn't a task, like an event, which was skipped with everything in it
+    SkippedComponent {
+        name: String,
+    },
+    // A property of a task with a number that was too high or too low, so the nearest
+    // number that's allowed was used instead
+    OutOfRange {
+        task: usize,
+        name: String,
+        value: String,
+        used: u8,
+    },
+    // A property of a task with a time in a time zone that isn't known, so the time
+    // was read as it's written, as if it were local
+    UnknownTimeZone {
+        task: usize,
+        name: String,
+        zone: String,
+    },
+}
+
+impl std::fmt::Display for ImportWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLine => write!(f, "Skipped a line that couldn't be read"),
+            Self::InvalidProperty { task, name, value } => {
+                write!(f, "Task {task}: skipped {name} '{value}', which couldn't be read")
+            }
+            Self::SkippedComponent { name } => write!(f, "Skipped a {name}, which isn't a task"),
+            Self::OutOfRange {
+                task,
+                name,
+                value,
+                used,
+            } => write!(f, "Task {task}: {name} '{value}' is out of range, so {used} was used"),
+            Self::UnknownTimeZone { task, name, zone } => write!(
+                f,
+                "Task {task}: {name} is in the time zone '{zone}', which isn't known, so it was read as local time"
+            ),
+        }
     }
 }
 
@@ -331,4 +1768,6 @@ pub enum ParseFromFileError {
     InvalidFile,
     NonTaskItem,
     InvalidField,
+    // The import was stopped before it finished
+    Cancelled,
 }
@@ -0,0 +1,279 @@
+//-----------------------------------------------------------------------------
+// Launches the app when the user logs in, so that reminders work without
+// having to remember to open it. Each desktop keeps its list of apps to
+// launch somewhere different: Linux desktops have a folder of .desktop
+// files, macOS has launch agents, and Windows has a key in the registry.
+// Web builds can't be launched at login.
+//-----------------------------------------------------------------------------
+
+use std::io;
+
+// Passed to the app when it's launched at login, to start it minimized
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+// Whether the app can be launched at login on this platform
+pub const SUPPORTED: bool = !cfg!(target_arch = "wasm32");
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartupSettings {
+    pub launch_at_login: bool,
+    // Starts the app minimized when it's launched at login
+    pub start_minimized: bool,
+}
+
+// Reads the settings from the entry that launches the app at login, if there is one.
+// The entry is the only place they're kept, so that they're never out of step with it.
+pub fn current() -> StartupSettings {
+    read_entry().map_or_else(StartupSettings::default, |entry| StartupSettings {
+        launch_at_login: true,
+        start_minimized: entry.contains(MINIMIZED_ARG),
+    })
+}
+
+// Keeps the entry that launches the app at login matching the settings
+#[derive(Default)]
+pub struct Autostart {
+    // The settings the entry was last made for
+    applied: StartupSettings,
+}
+
+impl Autostart {
+    // Starts from the settings the entry was made for, so that it's only
+    // changed once the user changes them
+    pub const fn new(applied: StartupSettings) -> Self {
+        Self { applied }
+    }
+
+    // Adds, changes or removes the entry if the settings have changed.
+    // Should be called once every frame.
+    pub fn update(&mut self, settings: StartupSettings) {
+        if settings == self.applied {
+            return;
+        }
+        self.applied = settings;
+
+        let result = if settings.launch_at_login {
+            write_entry(settings.start_minimized)
+        } else {
+            remove_entry()
+        };
+        if let Err(e) = result {
+            eprintln!("could not change whether the app launches at login: {e}");
+        }
+    }
+}
+
+// The command that launches this copy of the app, as the program and its arguments
+#[cfg(not(target_arch = "wasm32"))]
+fn command(minimized: bool) -> io::Result<Vec<String>> {
+    let program = std::env::current_exe()?.to_string_lossy().into_owned();
+    let mut command = vec![program];
+    if minimized {
+        command.push(MINIMIZED_ARG.to_string());
+    }
+    Ok(command)
+}
+
+#[cfg(all(unix, not(target_arch = "wasm32")))]
+fn home() -> io::Result<std::path::PathBuf> {
+    std::env::var_os("HOME")
+        .map(std::path::PathBuf::from)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home folder"))
+}
+
+// - Linux -------------------------------------------------------------------
+// Desktops that follow the XDG autostart spec launch every .desktop file in
+// the autostart folder when the user logs in
+
+#[cfg(all(unix, not(target_os = "macos"), not(target_arch = "wasm32")))]
+fn entry_path() -> io::Result<std::path::PathBuf> {
+    let config = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => home()?.join(".config"),
+    };
+    Ok(config.join("autostart").join("taskmaster.desktop"))
+}
+
+#[cfg(all(unix, not(target_os = "macos"), not(target_arch = "wasm32")))]
+fn read_entry() -> Option<String> {
+    std::fs::read_to_string(entry_path().ok()?).ok()
+}
+
+#[cfg(all(unix, not(target_os = "macos"), not(target_arch = "wasm32")))]
+fn write_entry(minimized: bool) -> io::Result<()> {
+    // Arguments in a .desktop file's Exec line are quoted, with
+    // any characters that are special inside quotes escaped
+    let exec: Vec<String> = command(minimized)?
+        .iter()
+        .map(|arg| {
+            let mut quoted = String::from('"');
+            for c in arg.chars() {
+                if matches!(c, '"' | '`' | '$' | '\\') {
+                    quoted.push('\\');
+                }
+                quoted.push(c);
+            }
+            quoted.push('"');
+            quoted
+        })
+        .collect();
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Taskmaster\nExec={}\nX-GNOME-Autostart-enabled=true\n",
+        exec.join(" ")
+    );
+
+    let path = entry_path()?;
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)?;
+    }
+    std::fs::write(path, entry)
+}
+
+#[cfg(all(unix, not(target_os = "macos"), not(target_arch = "wasm32")))]
+fn remove_entry() -> io::Result<()> {
+    match std::fs::remove_file(entry_path()?) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+// - macOS -------------------------------------------------------------------
+// launchd runs the launch agents in the user's LaunchAgents folder
+// which have RunAtLoad set when the user logs in
+
+#[cfg(target_os = "macos")]
+const LAUNCH_AGENT: &str = "com.taskmaster.app";
+
+#[cfg(target_os = "macos")]
+fn entry_path() -> io::Result<std::path::PathBuf> {
+    Ok(home()?
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{LAUNCH_AGENT}.plist")))
+}
+
+#[cfg(target_os = "macos")]
+fn read_entry() -> Option<String> {
+    std::fs::read_to_string(entry_path().ok()?).ok()
+}
+
+#[cfg(target_os = "macos")]
+fn write_entry(minimized: bool) -> io::Result<()> {
+    use std::fmt::Write;
+
+    let mut arguments = String::new();
+    for arg in command(minimized)? {
+        let arg = arg
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        let _ = writeln!(arguments, "        <string>{arg}</string>");
+    }
+    let entry = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">
+<plist version=\"1.0\">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCH_AGENT}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"
+    );
+
+    let path = entry_path()?;
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)?;
+    }
+    std::fs::write(path, entry)
+}
+
+#[cfg(target_os = "macos")]
+fn remove_entry() -> io::Result<()> {
+    match std::fs::remove_file(entry_path()?) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+// - Windows -----------------------------------------------------------------
+// Windows launches every program listed in the current user's Run key.
+// It's changed with the reg command, which comes with Windows.
+
+#[cfg(windows)]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(windows)]
+const RUN_VALUE: &str = "Taskmaster";
+
+#[cfg(windows)]
+fn reg(args: &[&str]) -> io::Result<std::process::Output> {
+    std::process::Command::new("reg").args(args).output()
+}
+
+#[cfg(windows)]
+fn read_entry() -> Option<String> {
+    let output = reg(&["query", RUN_KEY, "/v", RUN_VALUE]).ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(windows)]
+fn write_entry(minimized: bool) -> io::Result<()> {
+    let mut command = command(minimized)?;
+    // The program is quoted, since it's usually somewhere with spaces in its path
+    command[0] = format!("\"{}\"", command[0]);
+    let output = reg(&[
+        "add",
+        RUN_KEY,
+        "/v",
+        RUN_VALUE,
+        "/t",
+        "REG_SZ",
+        "/d",
+        &command.join(" "),
+        "/f",
+    ])?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ))
+    }
+}
+
+#[cfg(windows)]
+fn remove_entry() -> io::Result<()> {
+    // If there's no entry, reg fails, but there's nothing to remove anyway
+    if read_entry().is_some() {
+        reg(&["delete", RUN_KEY, "/v", RUN_VALUE, "/f"])?;
+    }
+    Ok(())
+}
+
+// - Web ---------------------------------------------------------------------
+
+#[cfg(target_arch = "wasm32")]
+const fn read_entry() -> Option<String> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_entry(_minimized: bool) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "web builds can't be launched at login",
+    ))
+}
+
+#[cfg(target_arch = "wasm32")]
+const fn remove_entry() -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//-----------------------------------------------------------------------------
+// Shows a count on the app's icon in the taskbar or dock, on desktops which
+// support it. Linux desktops with the Unity launcher API (like Ubuntu's dock,
+// KDE Plasma and Dash to Dock) are told over D-Bus, and on macOS the count is
+// put on the dock tile. Everywhere else, nothing is shown.
+//-----------------------------------------------------------------------------
+
+// The name of the app's .desktop file, which Linux docks find the icon by
+#[cfg(all(unix, not(target_os = "macos"), not(target_arch = "wasm32")))]
+const DESKTOP_FILE: &str = "taskmaster.desktop";
+
+// Keeps the badge showing a count, only telling the platform when the count changes
+#[derive(Default)]
+pub struct Badge {
+    // The count the badge was last given
+    shown: Option<usize>,
+}
+
+impl Badge {
+    // Shows a count on the badge, or hides it if the count is zero.
+    // Should be called once every frame.
+    pub fn update(&mut self, count: usize) {
+        if self.shown != Some(count) {
+            self.shown = Some(count);
+            set_count(count);
+        }
+    }
+}
+
+// Sends the Unity launcher API's update signal with gdbus, which comes with
+// GLib and so is on pretty much every Linux desktop. It's run in the background,
+// and if it's missing or no dock is listening, nothing happens.
+#[cfg(all(unix, not(target_os = "macos"), not(target_arch = "wasm32")))]
+fn set_count(count: usize) {
+    use std::{process::Command, thread};
+
+    let properties = format!(
+        "{{'count': <int64 {count}>, 'count-visible': <{}>}}",
+        count > 0
+    );
+    let child = Command::new("gdbus")
+        .args([
+            "emit",
+            "--session",
+            "--object-path",
+            "/com/canonical/unity/launcherentry/taskmaster",
+            "--signal",
+            "com.canonical.Unity.LauncherEntry.Update",
+            &format!("application://{DESKTOP_FILE}"),
+            &properties,
+        ])
+        .spawn();
+    if let Ok(mut child) = child {
+        thread::spawn(move || child.wait());
+    }
+}
+
+// Sets the dock tile's badge label. Has to be called from the main thread,
+// which the app's update loop runs on.
+#[cfg(target_os = "macos")]
+fn set_count(count: usize) {
+    use std::ffi::CString;
+
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let Ok(label) = CString::new(count.to_string()) else {
+        return;
+    };
+    // SAFETY: these are AppKit's own methods for the dock tile, called with the
+    // types they take. A nil label removes the badge.
+    unsafe {
+        let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+        let tile: *mut Object = msg_send![app, dockTile];
+        let label: *mut Object = if count == 0 {
+            std::ptr::null_mut()
+        } else {
+            msg_send![class!(NSString), stringWithUTF8String: label.as_ptr()]
+        };
+        let _: () = msg_send![tile, setBadgeLabel: label];
+    }
+}
+
+// Windows and web builds don't show a badge
+#[cfg(any(windows, target_arch = "wasm32"))]
+const fn set_count(_count: usize) {}
@@ -0,0 +1,39 @@
+//-----------------------------------------------------------------------------
+// Abstracts over the things that work differently when the app runs natively
+// and when it runs in a web browser, like picking files and the clipboard.
+// Each platform has its own module which provides the same set of items,
+// and the one for the platform being compiled for is re-exported from here.
+//-----------------------------------------------------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
+
+// Launching the app at login, the count on its icon and notifications work differently on
+// each desktop, rather than just natively and in browsers, so they have their own modules
+pub mod autostart;
+pub mod badge;
+pub mod notification;
+
+use std::{path::PathBuf, time::Duration};
+
+// A file that the user has picked to import
+pub struct PickedFile {
+    // Where the file is, if the platform has a filesystem
+    pub path: Option<PathBuf>,
+    pub contents: Vec<u8>,
+}
+
+// A note that makes up part of a sound
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    // Pitch of the note in hertz, or zero for a gap of silence
+    pub frequency: f32,
+    pub duration: Duration,
+}
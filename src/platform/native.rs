@@ -0,0 +1,421 @@
+//-----------------------------------------------------------------------------
+// Platform functionality for native builds, which have a filesystem and a
+// system clipboard.
+//-----------------------------------------------------------------------------
+
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{OnceLock, PoisonError, RwLock},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use eframe::egui;
+use egui_file::FileDialog;
+
+use crate::encryption::EncryptionKey;
+use crate::task::TaskList;
+
+use rodio::Source;
+
+use super::{PickedFile, Tone};
+
+// Gets the text that is currently in the system clipboard, if there is any
+pub fn clipboard_text() -> Option<String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .ok()
+}
+
+// Runs a command with the system's shell in the background, so that users can
+// write commands the same way they would type them into a terminal.
+// The command is given some environment variables, and some text on its standard input.
+pub fn run_command(command: &str, env: &[(&str, &str)], input: String) -> io::Result<()> {
+    #[cfg(windows)]
+    let mut shell = Command::new("cmd");
+    #[cfg(windows)]
+    shell.args(["/C", command]);
+    #[cfg(not(windows))]
+    let mut shell = Command::new("sh");
+    #[cfg(not(windows))]
+    shell.args(["-c", command]);
+
+    let mut child = shell
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    // Give the command its input and wait for it on another thread,
+    // so that a slow command doesn't freeze the app
+    thread::spawn(move || {
+        if let Some(mut stdin) = child.stdin.take() {
+            // The command doesn't have to read its input
+            let _ = stdin.write_all(input.as_bytes());
+        }
+        let _ = child.wait();
+    });
+
+    Ok(())
+}
+
+// Runs a program in a folder and waits for it to finish, giving back what it
+// printed. The program failing counts as an error, with what it printed to
+// standard error as the message.
+pub fn run_program(program: &str, args: &[&str], directory: &Path) -> io::Result<Vec<u8>> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(directory)
+        .stdin(Stdio::null())
+        .output()?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+// Dialog which lets the user pick a file to import
+pub struct ImportDialog(FileDialog);
+
+impl ImportDialog {
+    // Creates the dialog and marks it as opened
+    pub fn open(_ctx: &egui::Context) -> Self {
+        let mut dialog = FileDialog::open_file(None);
+        dialog.open();
+        Self(dialog)
+    }
+
+    // Shows the dialog, and returns the picked file once the user has chosen one
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<io::Result<PickedFile>> {
+        if !self.0.show(ctx).selected() {
+            return None;
+        }
+        // The dialog stays selected after it closes, so it's replaced with a
+        // closed one to make sure the file is only read once
+        let path = self.0.path().map(PathBuf::from);
+        self.0 = FileDialog::open_file(path.clone());
+
+        path.map(|path| {
+            fs::read(&path).map(|contents| PickedFile {
+                path: Some(path),
+                contents,
+            })
+        })
+    }
+}
+
+// Dialog which lets the user choose where to export a file to
+pub struct ExportDialog(FileDialog);
+
+impl ExportDialog {
+    // Creates the dialog and marks it as opened
+    pub fn open(_ctx: &egui::Context, _file_name: &str) -> Self {
+        let mut dialog = FileDialog::save_file(None);
+        dialog.open();
+        Self(dialog)
+    }
+
+    // Shows the dialog, and returns where to export to once the user has chosen
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<ExportTarget> {
+        if !self.0.show(ctx).selected() {
+            return None;
+        }
+        // Replaced with a closed dialog so that the file is only written once
+        let path = self.0.path().map(PathBuf::from);
+        self.0 = FileDialog::save_file(path.clone());
+
+        path.map(ExportTarget)
+    }
+}
+
+// Somewhere that an exported file can be written to
+pub struct ExportTarget(PathBuf);
+
+impl ExportTarget {
+    // A file that's already known, like the one a list is bound to
+    pub fn file(path: &Path) -> Self {
+        Self(path.to_path_buf())
+    }
+
+    // Files are written like lists' own files, so they're never left half written
+    pub fn write(self, contents: &[u8]) -> io::Result<()> {
+        write_file(&self.0, contents)
+    }
+
+    // Where the file is
+    pub fn path(&self) -> Option<PathBuf> {
+        Some(self.0.clone())
+    }
+
+    // Another file with the given name, in the same folder as this one
+    #[must_use]
+    pub fn sibling(&self, file_name: &str) -> Self {
+        Self(self.0.with_file_name(file_name))
+    }
+}
+
+// Plays sounds through the system's default audio output
+#[derive(Default)]
+pub struct AudioOutput {
+    // The output stream has to be kept alive for as long as sounds are playing.
+    // It's only opened the first time a sound is played, so that nothing
+    // happens to the system's audio if sounds are turned off.
+    stream: Option<(rodio::OutputStream, rodio::OutputStreamHandle)>,
+}
+
+impl AudioOutput {
+    // Plays some tones one after another in the background, at a volume from 0 to 1
+    pub fn play(&mut self, tones: &[Tone], volume: f32) -> Result<(), Box<dyn Error>> {
+        let (_, handle) = match &mut self.stream {
+            Some(stream) => stream,
+            None => self.stream.insert(rodio::OutputStream::try_default()?),
+        };
+
+        let sink = rodio::Sink::try_new(handle)?;
+        sink.set_volume(volume);
+        for tone in tones {
+            if tone.frequency > 0. {
+                // Fade each note in a little, so that it doesn't start with a click
+                sink.append(
+                    rodio::source::SineWave::new(tone.frequency)
+                        .take_duration(tone.duration)
+                        .fade_in(Duration::from_millis(5)),
+                );
+            } else {
+                sink.append(
+                    rodio::source::Zero::<f32>::new(1, 48_000).take_duration(tone.duration),
+                );
+            }
+        }
+        // Let the sound finish playing on its own
+        sink.detach();
+
+        Ok(())
+    }
+}
+
+// Changes the title of the app's window
+pub fn set_window_title(frame: &mut eframe::Frame, title: &str) {
+    frame.set_window_title(title);
+}
+
+// Keeps the task list saved between runs on platforms that need it.
+// Native builds keep their lists in files that the user exports,
+// so there's nothing to do here.
+#[derive(Default)]
+pub struct ListStore;
+
+impl ListStore {
+    pub fn load() -> Option<Vec<u8>> {
+        None
+    }
+
+    pub fn update(
+        &mut self,
+        _ctx: &egui::Context,
+        _task_list: &TaskList,
+        _key: Option<&EncryptionKey>,
+    ) {
+    }
+
+    pub fn save(&mut self, _task_list: &TaskList, _key: Option<&EncryptionKey>) {}
+}
+
+// Reads the file a task list is bound to
+pub fn read_file(path: &Path) -> io::Result<Vec<u8>> {
+    fs::read(path)
+}
+
+// Writes over a list's file, such as when a task is moved to another list.
+// The contents are written to a file next to it first, which is then moved
+// over it, so that the file is never left half written.
+pub fn write_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the path isn't a file"))?;
+    let temporary = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+
+    let result = fs::File::create(&temporary).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    // Nothing is left behind if the file couldn't be written
+    if let Err(e) = result.and_then(|()| fs::rename(&temporary, path)) {
+        let _ = fs::remove_file(&temporary);
+        return Err(e);
+    }
+    Ok(())
+}
+
+// When a file was last changed, if it can be found out
+pub fn file_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// Backups are kept as files in a folder the user picks
+pub fn write_backup(directory: &str, name: &str, data: &[u8]) -> io::Result<()> {
+    let directory = backup_directory(directory)?;
+    fs::create_dir_all(&directory)?;
+    fs::write(directory.join(name), data)
+}
+
+pub fn backup_names(directory: &str) -> Vec<String> {
+    let Ok(entries) = backup_directory(directory).and_then(fs::read_dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect()
+}
+
+pub fn read_backup(directory: &str, name: &str) -> Option<Vec<u8>> {
+    fs::read(backup_directory(directory).ok()?.join(name)).ok()
+}
+
+pub fn remove_backup(directory: &str, name: &str) -> io::Result<()> {
+    fs::remove_file(backup_directory(directory)?.join(name))
+}
+
+fn backup_directory(directory: &str) -> io::Result<PathBuf> {
+    if directory.trim().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no folder has been picked to keep backups in",
+        ));
+    }
+    Ok(PathBuf::from(directory))
+}
+
+// Folder to keep the app's own files in instead of the usual one, if one was
+// given on the command line
+static DATA_DIRECTORY: OnceLock<PathBuf> = OnceLock::new();
+
+// Keeps the app's own files in a different folder. Has to be called before
+// anything is read from the folder, and only the first call has any effect.
+pub fn set_data_directory(directory: PathBuf) {
+    let _ = DATA_DIRECTORY.set(directory);
+}
+
+// The profile whose files are being used, or None for the default profile,
+// which uses the folder itself
+static PROFILE: RwLock<Option<String>> = RwLock::new(None);
+// Each profile other than the default has a folder in here
+const PROFILES_DIRECTORY: &str = "profiles";
+// Name that the last profile used is saved under, outside of any profile
+const PROFILE_STATE: &str = "profile";
+
+// Uses another profile's files from now on, and remembers it for next time
+pub fn set_profile(profile: Option<&str>) -> io::Result<()> {
+    *PROFILE.write().unwrap_or_else(PoisonError::into_inner) = profile.map(String::from);
+    fs::create_dir_all(state_directory()?)?;
+    fs::write(
+        base_directory()?.join(PROFILE_STATE),
+        profile.unwrap_or_default(),
+    )
+}
+
+// The profile that was used last time, if it wasn't the default one
+pub fn saved_profile() -> Option<String> {
+    let profile = fs::read_to_string(base_directory().ok()?.join(PROFILE_STATE)).ok()?;
+    let profile = profile.trim();
+    (!profile.is_empty()).then(|| profile.to_string())
+}
+
+// The profile being used, if it isn't the default one
+pub fn profile() -> Option<String> {
+    PROFILE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+// Every profile other than the default one
+pub fn profile_names() -> Vec<String> {
+    let Ok(entries) = base_directory().and_then(|base| fs::read_dir(base.join(PROFILES_DIRECTORY)))
+    else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect()
+}
+
+// The app keeps a few small files of its own, like when it was last open, in
+// the folder for the profile being used
+fn state_directory() -> io::Result<PathBuf> {
+    let base = base_directory()?;
+    Ok(match profile() {
+        Some(profile) => base.join(PROFILES_DIRECTORY).join(profile),
+        None => base,
+    })
+}
+
+// The folder each platform has for apps' settings, unless another one was picked
+fn base_directory() -> io::Result<PathBuf> {
+    if let Some(directory) = DATA_DIRECTORY.get() {
+        return Ok(directory.clone());
+    }
+
+    #[cfg(windows)]
+    let base = std::env::var_os("APPDATA").map(PathBuf::from);
+    #[cfg(target_os = "macos")]
+    let base = std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+    });
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|directory| !directory.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+    base.map(|base| base.join("taskmaster"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no folder for settings"))
+}
+
+// Where one of the app's own files is kept, so that the user can be told where to find it
+pub fn state_path(name: &str) -> Option<PathBuf> {
+    Some(state_directory().ok()?.join(name))
+}
+
+pub fn read_state(name: &str) -> Option<String> {
+    fs::read_to_string(state_directory().ok()?.join(name)).ok()
+}
+
+pub fn write_state(name: &str, contents: &str) -> io::Result<()> {
+    let directory = state_directory()?;
+    fs::create_dir_all(&directory)?;
+    fs::write(directory.join(name), contents)
+}
+
+// Writes one of the app's own files that only the user should be able to read,
+// like the key secrets are encrypted with
+pub fn write_private_state(name: &str, contents: &str) -> io::Result<()> {
+    let directory = state_directory()?;
+    fs::create_dir_all(&directory)?;
+    let path = directory.join(name);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode is only used when the file is made, so a file from before is fixed too
+        if path.exists() {
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
@@ -0,0 +1,94 @@
+//-----------------------------------------------------------------------------
+// Shows desktop notifications, on desktops which support them. Linux
+// desktops are sent them with notify-send, which can also put buttons on a
+// notification and say which one was clicked. On macOS they're shown with
+// osascript, which can't have buttons. Everywhere else, nothing is shown.
+//-----------------------------------------------------------------------------
+
+// Whether notifications can have buttons on this platform
+pub const ACTIONS_SUPPORTED: bool = cfg!(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_arch = "wasm32")
+));
+
+// Shows a notification with some buttons, each given as a key and a label.
+// When a button is clicked, its key is given to `on_action` on another thread.
+// notify-send waits for the notification to be closed when it has buttons,
+// so it's waited for in the background. If it's missing, nothing happens.
+#[cfg(all(unix, not(target_os = "macos"), not(target_arch = "wasm32")))]
+pub fn show(
+    summary: &str,
+    body: &str,
+    actions: &[(&str, &str)],
+    on_action: impl FnOnce(&str) + Send + 'static,
+) {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+    use std::thread;
+
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name", "Taskmaster"]);
+    for (key, label) in actions {
+        command.arg(format!("--action={key}={label}"));
+    }
+    let child = command.args([summary, body]).stdout(Stdio::piped()).spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("could not show notification: {e}");
+            return;
+        }
+    };
+
+    thread::spawn(move || {
+        // The key of the button that was clicked is printed, if one was
+        let mut clicked = String::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            let _ = stdout.read_to_string(&mut clicked);
+        }
+        let _ = child.wait();
+        let clicked = clicked.trim();
+        if !clicked.is_empty() {
+            on_action(clicked);
+        }
+    });
+}
+
+// Shows a notification with AppleScript, which doesn't support buttons
+#[cfg(target_os = "macos")]
+#[allow(clippy::needless_pass_by_value)]
+pub fn show(
+    summary: &str,
+    body: &str,
+    _actions: &[(&str, &str)],
+    _on_action: impl FnOnce(&str) + Send + 'static,
+) {
+    use std::process::Command;
+    use std::thread;
+
+    // AppleScript strings are quoted with double quotes, escaped with backslashes
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!(
+        "display notification {} with title \"Taskmaster\" subtitle {}",
+        quote(body),
+        quote(summary)
+    );
+    match Command::new("osascript").args(["-e", &script]).spawn() {
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(e) => eprintln!("could not show notification: {e}"),
+    }
+}
+
+// Windows and web builds don't show notifications
+#[cfg(any(windows, target_arch = "wasm32"))]
+#[allow(clippy::needless_pass_by_value)]
+pub fn show(
+    _summary: &str,
+    _body: &str,
+    _actions: &[(&str, &str)],
+    _on_action: impl FnOnce(&str) + Send + 'static,
+) {
+}
@@ -0,0 +1,384 @@
+//-----------------------------------------------------------------------------
+// Platform functionality for web builds, which have no filesystem.
+// Files are picked and saved with the browser, and the task list is kept
+// in the browser's local storage so that it survives reloading the page.
+//-----------------------------------------------------------------------------
+
+use std::{
+    cell::RefCell,
+    error::Error,
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::SystemTime,
+};
+
+use eframe::egui;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::encryption::EncryptionKey;
+use crate::task::TaskList;
+
+use super::{PickedFile, Tone};
+
+// Local storage key that the task list is saved under
+const STORAGE_KEY: &str = "taskmaster-list";
+
+// How many seconds to wait between saves of the task list
+const SAVE_INTERVAL: f64 = 5.;
+
+// Browsers only allow reading the clipboard from a paste event, so this isn't supported
+pub fn clipboard_text() -> Option<String> {
+    None
+}
+
+// Browsers can't run commands
+pub fn run_command(_command: &str, _env: &[(&str, &str)], _input: String) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "commands can't be run in a web browser",
+    ))
+}
+
+// Browsers can't run programs
+pub fn run_program(_program: &str, _args: &[&str], _directory: &Path) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "programs can't be run in a web browser",
+    ))
+}
+
+// Sounds aren't supported in web builds yet
+#[derive(Default)]
+pub struct AudioOutput;
+
+impl AudioOutput {
+    pub fn play(&mut self, _tones: &[Tone], _volume: f32) -> Result<(), Box<dyn Error>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "sounds can't be played in a web browser",
+        )
+        .into())
+    }
+}
+
+// The browser's file picker, which lets the user pick a file to import
+pub struct ImportDialog {
+    // The picker runs asynchronously, so it puts the file here once it has been read
+    picked: Rc<RefCell<Option<PickedFile>>>,
+}
+
+impl ImportDialog {
+    // Opens the browser's file picker
+    pub fn open(ctx: &egui::Context) -> Self {
+        let picked = Rc::new(RefCell::new(None));
+
+        let picked_file = picked.clone();
+        let ctx = ctx.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let file = rfd::AsyncFileDialog::new()
+                .add_filter("iCal", &["ics"])
+                .pick_file()
+                .await;
+            if let Some(file) = file {
+                *picked_file.borrow_mut() = Some(PickedFile {
+                    path: None,
+                    contents: file.read().await,
+                });
+                // Make sure the app notices the file straight away
+                ctx.request_repaint();
+            }
+        });
+
+        Self { picked }
+    }
+
+    // Returns the picked file once the browser has read it
+    pub fn show(&mut self, _ctx: &egui::Context) -> Option<io::Result<PickedFile>> {
+        self.picked.borrow_mut().take().map(Ok)
+    }
+}
+
+// Browsers choose where downloads go, so exporting doesn't need a dialog
+pub struct ExportDialog(Option<String>);
+
+impl ExportDialog {
+    pub fn open(_ctx: &egui::Context, file_name: &str) -> Self {
+        Self(Some(file_name.to_string()))
+    }
+
+    // Returns the download straight away, but only once
+    pub fn show(&mut self, _ctx: &egui::Context) -> Option<ExportTarget> {
+        self.0.take().map(ExportTarget)
+    }
+}
+
+// A file download which an exported file is written to
+pub struct ExportTarget(String);
+
+impl ExportTarget {
+    // Web builds can't write files by their path, so it's downloaded with the file's name
+    pub fn file(path: &Path) -> Self {
+        Self(
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        )
+    }
+
+    pub fn write(self, contents: &[u8]) -> io::Result<()> {
+        download(&self.0, contents).map_err(|e| io::Error::other(format!("{e:?}")))
+    }
+
+    // Downloads don't have a path the app can see
+    pub const fn path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    // Another download with the given name, which goes wherever the browser puts downloads
+    #[must_use]
+    pub fn sibling(&self, file_name: &str) -> Self {
+        Self(file_name.to_string())
+    }
+}
+
+// Makes the browser download some data as a file, by linking to it and clicking the link
+fn download(file_name: &str, contents: &[u8]) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("text/calendar");
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+    let link: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+
+    web_sys::Url::revoke_object_url(&url)
+}
+
+// The app runs in a page, so the page's title is what shows up in the browser's tab
+pub fn set_window_title(_frame: &mut eframe::Frame, title: &str) {
+    if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+        document.set_title(title);
+    }
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+// Keeps the task list saved in the browser's local storage
+#[derive(Default)]
+pub struct ListStore {
+    // When the list was last saved, in seconds since the app started
+    last_save: f64,
+    // What was last saved, so that the list is only saved when it changes
+    last_saved_list: String,
+    last_saved_encrypted: bool,
+}
+
+impl ListStore {
+    // Loads the list that was saved the last time the app was open,
+    // which might need to be decrypted before it can be read
+    pub fn load() -> Option<Vec<u8>> {
+        let list = local_storage()?
+            .get_item(&profile_key(STORAGE_KEY))
+            .ok()??;
+        Some(list.into_bytes())
+    }
+
+    // Saves the list if it has changed, at most once every few seconds.
+    // If a key is given, the list is encrypted with it.
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        key: Option<&EncryptionKey>,
+    ) {
+        let now = ctx.input(|i| i.time);
+        if now - self.last_save < SAVE_INTERVAL {
+            return;
+        }
+        self.last_save = now;
+        self.save(task_list, key);
+    }
+
+    // Saves the list straight away if it has changed, such as before switching profiles
+    pub fn save(&mut self, task_list: &TaskList, key: Option<&EncryptionKey>) {
+        let list = task_list.to_ical_string();
+        let encrypted = key.is_some();
+        if list != self.last_saved_list || encrypted != self.last_saved_encrypted {
+            let contents = match key {
+                // Encrypted lists are stored as text, so this can't fail
+                Some(key) => String::from_utf8(key.encrypt(list.as_bytes()))
+                    .expect("encrypted list should be text"),
+                None => list.clone(),
+            };
+            if let Some(storage) = local_storage() {
+                // If the browser refuses to store the list, it'll be tried again later
+                if storage
+                    .set_item(&profile_key(STORAGE_KEY), &contents)
+                    .is_ok()
+                {
+                    self.last_saved_list = list;
+                    self.last_saved_encrypted = encrypted;
+                }
+            }
+        }
+    }
+}
+
+// Browsers don't let pages read files without the user picking them, so lists
+// can't be bound to files
+pub fn read_file(_path: &Path) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "web builds can't read files by their path",
+    ))
+}
+
+pub fn write_file(_path: &Path, _contents: &[u8]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "web builds can't write files by their path",
+    ))
+}
+
+pub const fn file_modified(_path: &Path) -> Option<SystemTime> {
+    None
+}
+
+// Backups are kept in local storage, under keys starting with this
+const BACKUP_KEY_PREFIX: &str = "taskmaster-backup/";
+
+// Web builds have no folders, so the directory backups are kept in is ignored
+pub fn write_backup(_directory: &str, name: &str, data: &[u8]) -> io::Result<()> {
+    let data =
+        std::str::from_utf8(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    local_storage()
+        .ok_or_else(|| io::Error::other("local storage isn't available"))?
+        .set_item(&profile_key(&format!("{BACKUP_KEY_PREFIX}{name}")), data)
+        .map_err(|e| io::Error::other(format!("{e:?}")))
+}
+
+pub fn backup_names(_directory: &str) -> Vec<String> {
+    let Some(storage) = local_storage() else {
+        return Vec::new();
+    };
+    let prefix = profile_key(BACKUP_KEY_PREFIX);
+    let length = storage.length().unwrap_or(0);
+    (0..length)
+        .filter_map(|i| storage.key(i).ok()?)
+        .filter_map(|key| Some(key.strip_prefix(&prefix)?.to_string()))
+        .collect()
+}
+
+pub fn read_backup(_directory: &str, name: &str) -> Option<Vec<u8>> {
+    let data = local_storage()?
+        .get_item(&profile_key(&format!("{BACKUP_KEY_PREFIX}{name}")))
+        .ok()??;
+    Some(data.into_bytes())
+}
+
+pub fn remove_backup(_directory: &str, name: &str) -> io::Result<()> {
+    local_storage()
+        .ok_or_else(|| io::Error::other("local storage isn't available"))?
+        .remove_item(&profile_key(&format!("{BACKUP_KEY_PREFIX}{name}")))
+        .map_err(|e| io::Error::other(format!("{e:?}")))
+}
+
+// The app's own state, like when it was last open, is kept in local storage
+// under keys starting with this
+const STATE_KEY_PREFIX: &str = "taskmaster-state/";
+
+// Web builds keep their files in local storage, so there's no folder to change
+pub fn set_data_directory(_directory: PathBuf) {}
+
+// Local storage isn't somewhere the user can open files from
+pub const fn state_path(_name: &str) -> Option<PathBuf> {
+    None
+}
+
+pub fn read_state(name: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&profile_key(&format!("{STATE_KEY_PREFIX}{name}")))
+        .ok()?
+}
+
+pub fn write_state(name: &str, contents: &str) -> io::Result<()> {
+    local_storage()
+        .ok_or_else(|| io::Error::other("local storage isn't available"))?
+        .set_item(&profile_key(&format!("{STATE_KEY_PREFIX}{name}")), contents)
+        .map_err(|e| io::Error::other(format!("{e:?}")))
+}
+
+// Local storage can only be read by the app's own site anyway
+pub fn write_private_state(name: &str, contents: &str) -> io::Result<()> {
+    write_state(name, contents)
+}
+
+thread_local! {
+    // The profile whose data is being used, or None for the default profile
+    static PROFILE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+// Every profile other than the default keeps its data under keys starting with this and its name
+const PROFILE_KEY_PREFIX: &str = "taskmaster-profile/";
+// Key that the last profile used is saved under, outside of any profile
+const PROFILE_KEY: &str = "taskmaster-profile";
+
+// The key something is kept under in the profile being used
+fn profile_key(key: &str) -> String {
+    match profile() {
+        Some(profile) => format!("{PROFILE_KEY_PREFIX}{profile}/{key}"),
+        None => key.to_string(),
+    }
+}
+
+// Uses another profile's data from now on, and remembers it for next time
+pub fn set_profile(profile: Option<&str>) -> io::Result<()> {
+    PROFILE.with(|current| *current.borrow_mut() = profile.map(String::from));
+    let storage =
+        local_storage().ok_or_else(|| io::Error::other("local storage isn't available"))?;
+    // Profiles are found by their keys, so a new one needs a key straight away
+    if let Some(profile) = profile {
+        storage
+            .set_item(&format!("{PROFILE_KEY_PREFIX}{profile}/"), "")
+            .map_err(|e| io::Error::other(format!("{e:?}")))?;
+    }
+    storage
+        .set_item(PROFILE_KEY, profile.unwrap_or_default())
+        .map_err(|e| io::Error::other(format!("{e:?}")))
+}
+
+// The profile that was used last time, if it wasn't the default one
+pub fn saved_profile() -> Option<String> {
+    let profile = local_storage()?.get_item(PROFILE_KEY).ok()??;
+    (!profile.is_empty()).then_some(profile)
+}
+
+// The profile being used, if it isn't the default one
+pub fn profile() -> Option<String> {
+    PROFILE.with(|current| current.borrow().clone())
+}
+
+// Every profile other than the default one
+pub fn profile_names() -> Vec<String> {
+    let Some(storage) = local_storage() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = (0..storage.length().unwrap_or(0))
+        .filter_map(|i| storage.key(i).ok()?)
+        .filter_map(|key| {
+            let (name, _) = key.strip_prefix(PROFILE_KEY_PREFIX)?.split_once('/')?;
+            Some(name.to_string())
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
@@ -0,0 +1,67 @@
+// ----------------------------------------------------------------------------
+// Profiles keep separate sets of the app's files, like the settings, the
+// lists that are opened at startup, the config file and when the app was last
+// open, so that work and personal tasks, or different people sharing a
+// computer, don't mix. The profile that was used last is used again when the app starts.
+// ----------------------------------------------------------------------------
+
+use std::io;
+
+use crate::platform;
+
+// The profile which uses the app's files from before there were profiles
+pub const DEFAULT_PROFILE: &str = "Default";
+
+/// Whether a name can be used for a profile. Names are used for folders, so
+/// they can only have letters, numbers, spaces, dashes and underscores.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::profiles;
+///
+/// assert!(profiles::is_valid_name("Work"));
+/// assert!(profiles::is_valid_name("side-project 2"));
+/// assert!(!profiles::is_valid_name("../secrets"));
+/// assert!(!profiles::is_valid_name("  "));
+/// ```
+pub fn is_valid_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && name.trim() == name
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+}
+
+// The name of the profile being used
+pub fn current() -> String {
+    platform::profile().unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+// Every profile, with the default one first
+pub fn names() -> Vec<String> {
+    let mut names = platform::profile_names();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+// Uses a profile's files from now on, making it if it doesn't exist yet.
+// The profile is remembered, so that it's used again next time the app starts.
+pub fn switch(name: &str) -> io::Result<()> {
+    if !is_valid_name(name) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("'{name}' can't be used as a profile's name"),
+        ));
+    }
+    platform::set_profile((name != DEFAULT_PROFILE).then_some(name))
+}
+
+// Uses the profile that was used last time, if it wasn't the default one
+pub fn restore() {
+    if let Some(name) = platform::saved_profile().filter(|name| is_valid_name(name)) {
+        if let Err(e) = switch(&name) {
+            eprintln!("could not open profile {name}: {e}");
+        }
+    }
+}
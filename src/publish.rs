@@ -0,0 +1,214 @@
+// ----------------------------------------------------------------------------
+// Publishes a read-only copy of the task list, so that other people can
+// subscribe to it from their calendar apps, like a family subscribing to a
+// shopping list. Every so often, if the list has changed, it's uploaded to
+// a WebDAV server (such as Nextcloud) with an HTTP PUT. Uploading needs the
+// app to be built with the `http` feature.
+// ----------------------------------------------------------------------------
+
+use std::{
+    error::Error,
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+};
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::network::NetworkSettings;
+use crate::parser::{ExportFormat, LineEnding};
+use crate::settings::Settings;
+use crate::sync::{ListSyncer, SyncContext};
+use crate::sync_log::SyncLog;
+use crate::task::TaskList;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct PublishSettings {
+    pub enabled: bool,
+    // Where the list is uploaded to, like https://cloud.example.com/remote.php/dav/files/me/shopping.ics
+    pub url: String,
+    pub username: String,
+    // Kept in the keyring, not with the other settings
+    #[serde(skip)]
+    pub password: String,
+    // How often to check whether the list needs uploading again
+    pub interval_minutes: u32,
+    // Completed tasks are usually only clutter for people following the list
+    pub include_completed: bool,
+    // Some calendar apps only show events, so tasks can be published as those too
+    pub format: ExportFormat,
+}
+
+impl Default for PublishSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            username: String::new(),
+            password: String::new(),
+            interval_minutes: 15,
+            include_completed: false,
+            format: ExportFormat::TodosAndEvents,
+        }
+    }
+}
+
+/// Creates the iCal text that's published for a list, leaving out
+/// completed tasks unless the settings say to include them.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::publish::{self, PublishSettings};
+/// use taskmaster_rs::task::*;
+///
+/// let list = TaskList {
+///     tasks: vec![
+///         Task { summary: "Milk".into(), ..Task::default() },
+///         Task { summary: "Eggs".into(), completed: true, ..Task::default() },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let ical = publish::ical_text(&list, &PublishSettings::default());
+/// assert!(ical.contains("SUMMARY:Milk\r\n"));
+/// assert!(!ical.contains("SUMMARY:Eggs"));
+///
+/// let settings = PublishSettings { include_completed: true, ..Default::default() };
+/// assert!(publish::ical_text(&list, &settings).contains("SUMMARY:Eggs\r\n"));
+/// ```
+pub fn ical_text(task_list: &TaskList, settings: &PublishSettings) -> String {
+    if settings.include_completed {
+        task_list.to_ical_string_as(LineEnding::Crlf, settings.format)
+    } else {
+        let (open, _) = task_list.split_completed();
+        open.to_ical_string_as(LineEnding::Crlf, settings.format)
+    }
+}
+
+// Publishes the list every so often, when it has changed
+#[derive(Default)]
+pub struct Publisher {
+    // When the list was last checked, in seconds since the app started
+    last_check: Option<f64>,
+    // What was last published, and where to, so that the list is only uploaded when it changes.
+    // It's set once the upload has finished, so that failed uploads are tried again.
+    last_published: Arc<Mutex<Option<(String, String)>>>,
+}
+
+impl Publisher {
+    // Uploads the list in the background if it's time to and it has changed.
+    // Should be called once every frame while the list is published.
+    pub fn check(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        settings: &PublishSettings,
+        network: &NetworkSettings,
+        sync_log: &SyncLog,
+    ) {
+        if settings.url.trim().is_empty() {
+            return;
+        }
+        let now = ctx.input(|i| i.time);
+        let interval = f64::from(settings.interval_minutes.max(1)) * 60.;
+        if self
+            .last_check
+            .is_some_and(|last_check| now - last_check < interval)
+        {
+            return;
+        }
+        self.last_check = Some(now);
+        // Wake the app up in time for the next check, even if nothing else is happening
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(interval));
+
+        let text = ical_text(task_list, settings);
+        let published = (settings.url.clone(), text);
+        let last_published = Arc::clone(&self.last_published);
+        if last_published
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            == Some(&published)
+        {
+            return;
+        }
+
+        // Talking to the server can take a while, so it's done on another thread
+        let settings = settings.clone();
+        let network = network.clone();
+        let sync = sync_log.start("Publish", task_list);
+        thread::spawn(move || match upload(&settings, &network, &published.1) {
+            Ok(()) => {
+                sync.finish(Ok(format!("Published to {}", settings.url.trim())));
+                *last_published
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = Some(published);
+            }
+            Err(e) => {
+                eprintln!("could not publish task list to {}: {e}", settings.url);
+                sync.finish(Err(format!(
+                    "Could not publish to {}: {e}",
+                    settings.url.trim()
+                )));
+            }
+        });
+    }
+}
+
+// Lists bound to WebDAV are published with the publishing settings, even if
+// publishing hasn't been turned on for every list
+impl ListSyncer for Publisher {
+    fn id(&self) -> &'static str {
+        "webdav"
+    }
+
+    fn name(&self) -> &'static str {
+        "WebDAV server"
+    }
+
+    fn enabled(&self, settings: &Settings) -> bool {
+        settings.publish.enabled
+    }
+
+    fn sync(&mut self, ctx: &egui::Context, task_list: &TaskList, context: &mut SyncContext<'_>) {
+        self.check(
+            ctx,
+            task_list,
+            &context.settings.publish,
+            &context.settings.network,
+            context.log,
+        );
+    }
+}
+
+#[cfg(feature = "http")]
+fn upload(
+    settings: &PublishSettings,
+    network: &NetworkSettings,
+    text: &str,
+) -> Result<(), Box<dyn Error>> {
+    use base64::Engine;
+
+    let mut request = network
+        .agent()?
+        .put(settings.url.trim())
+        .set("Content-Type", "text/calendar; charset=utf-8");
+    if !settings.username.is_empty() {
+        let credentials = base64::engine::general_purpose::STANDARD
+            .encode(format!("{}:{}", settings.username, settings.password));
+        request = request.set("Authorization", &format!("Basic {credentials}"));
+    }
+    request.send_string(text)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "http"))]
+fn upload(
+    _settings: &PublishSettings,
+    _network: &NetworkSettings,
+    _text: &str,
+) -> Result<(), Box<dyn Error>> {
+    Err("this build of the app can't publish lists, it needs the `http` feature".into())
+}
@@ -0,0 +1,537 @@
+// ----------------------------------------------------------------------------
+// Filter expressions typed into the filter box above the task list, like
+//
+//   tag:home AND NOT status:cancelled OR priority>=8
+//
+// Each condition is a field, how it's compared, and a value, with no spaces
+// between them. Values with spaces in them go in double quotes. Conditions
+// are combined with NOT, AND and OR, which bind in that order, so the example
+// shows tasks tagged home that aren't cancelled, and any task with a low
+// priority. Brackets group conditions the other way.
+//
+//   tag        Tasks with the tag, with : or =, or without it, with !=
+//   status     Tasks with the status, going by its name or its iCal name
+//   priority   Compared as a number, with :, =, !=, <, <=, > or >=
+//   progress   Compared as a percentage, like priority
+//   due        Compared with a date, like 2024-05-06, or today, or none
+//              for tasks that aren't due, which can only be : or !=
+//   text       Tasks with the text in their summary or description
+//   completed  true or false
+//
+// Expressions are parsed into a tree, which is what tasks are matched with,
+// and errors say what was wrong and where.
+// ----------------------------------------------------------------------------
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::json::DATE_FORMAT;
+use crate::task::Task;
+
+// The fields conditions can look at, in the order they're suggested in
+pub const FIELDS: [&str; 7] = [
+    "tag",
+    "status",
+    "priority",
+    "progress",
+    "due",
+    "text",
+    "completed",
+];
+
+// Why an expression couldn't be parsed. Positions are in characters from the start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum QueryError {
+    // The expression has nothing in it
+    Empty,
+    // A field that isn't one of FIELDS
+    UnknownField {
+        field: String,
+        at: usize,
+    },
+    // A word without a comparison, like `home` rather than `tag:home`
+    MissingComparison {
+        word: String,
+        at: usize,
+    },
+    // A comparison that doesn't work with the field, like `tag<home`
+    BadComparison {
+        field: String,
+        at: usize,
+    },
+    // A value that doesn't make sense for the field, like `priority:high`
+    BadValue {
+        field: String,
+        value: String,
+        at: usize,
+    },
+    // The expression ends where a condition should be, like after AND
+    ExpectedCondition {
+        at: usize,
+    },
+    // Something that can't go where it is, like two conditions without AND between them
+    Unexpected {
+        found: String,
+        at: usize,
+    },
+    // A bracket that's never closed
+    UnclosedBracket {
+        at: usize,
+    },
+    // Double quotes that are never closed
+    UnclosedQuote {
+        at: usize,
+    },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The filter is empty"),
+            Self::UnknownField { field, at } => write!(
+                f,
+                "There's no field called {field} at {at}, try one of {}",
+                FIELDS.join(", ")
+            ),
+            Self::MissingComparison { word, at } => write!(
+                f,
+                "{word} at {at} needs a field and a comparison, like tag:{word}"
+            ),
+            Self::BadComparison { field, at } => {
+                write!(f, "{field} at {at} can't be compared that way")
+            }
+            Self::BadValue { field, value, at } => {
+                write!(f, "{value} isn't a value {field} can have, at {at}")
+            }
+            Self::ExpectedCondition { at } => write!(f, "Expected a condition at {at}"),
+            Self::Unexpected { found, at } => {
+                write!(f, "Didn't expect {found} at {at}, is AND or OR missing?")
+            }
+            Self::UnclosedBracket { at } => write!(f, "The bracket at {at} is never closed"),
+            Self::UnclosedQuote { at } => write!(f, "The quote at {at} is never closed"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+// How a field is compared with a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    // The comparisons, with the longer ones first so they're found before the shorter ones in them
+    const SYMBOLS: [(&'static str, Self); 7] = [
+        ("!=", Self::NotEqual),
+        ("<=", Self::LessOrEqual),
+        (">=", Self::GreaterOrEqual),
+        (":", Self::Equal),
+        ("=", Self::Equal),
+        ("<", Self::Less),
+        (">", Self::Greater),
+    ];
+
+    fn holds<T: Ord>(self, a: T, b: T) -> bool {
+        match self {
+            Self::Equal => a == b,
+            Self::NotEqual => a != b,
+            Self::Less => a < b,
+            Self::LessOrEqual => a <= b,
+            Self::Greater => a > b,
+            Self::GreaterOrEqual => a >= b,
+        }
+    }
+}
+
+// A date a task's due date is compared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueDate {
+    Today,
+    Date(NaiveDate),
+    // Tasks that aren't due
+    None,
+}
+
+// Something about a task that's checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Tag(String),
+    // The status's name, in lower case without spaces or dashes
+    Status(String),
+    Priority(u8),
+    Progress(u8),
+    Due(DueDate),
+    Text(String),
+    Completed(bool),
+}
+
+/// A parsed filter expression.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::query::Query;
+/// use taskmaster_rs::task::*;
+///
+/// let query = Query::parse("tag:home AND NOT status:cancelled OR priority>=8").unwrap();
+/// let today = chrono::Local::now().date_naive();
+/// let home = |status| Task { tags: vec![String::from("home")], status, ..Task::default() };
+///
+/// assert!(query.matches(&home(Status::NeedsAction), today));
+/// assert!(!query.matches(&home(Status::Cancelled), today));
+/// assert!(query.matches(&Task { priority: Priority::new(9), ..Task::default() }, today));
+/// assert!(!query.matches(&Task::default(), today));
+///
+/// // Brackets make OR come first
+/// let query = Query::parse("NOT (due:none OR completed:true)").unwrap();
+/// assert!(query.matches(&Task { due: Some(today), ..Task::default() }, today));
+/// assert!(!query.matches(&Task::default(), today));
+///
+/// assert_eq!(
+///     Query::parse("tag:home AND").unwrap_err().to_string(),
+///     "Expected a condition at 12"
+/// );
+/// assert!(Query::parse("priority:high").is_err());
+/// assert!(Query::parse("colour:red").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Condition(Comparison, Condition),
+    Not(Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Self, QueryError> {
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            return Err(QueryError::Empty);
+        }
+        let mut parser = Parser {
+            tokens,
+            next: 0,
+            end: text.chars().count(),
+        };
+        let query = parser.or()?;
+        match parser.tokens.get(parser.next) {
+            Some(token) => Err(QueryError::Unexpected {
+                found: token.text(),
+                at: token.at,
+            }),
+            None => Ok(query),
+        }
+    }
+
+    pub fn matches(&self, task: &Task, today: NaiveDate) -> bool {
+        match self {
+            Self::Condition(comparison, condition) => {
+                condition_matches(*comparison, condition, task, today)
+            }
+            Self::Not(query) => !query.matches(task, today),
+            Self::And(a, b) => a.matches(task, today) && b.matches(task, today),
+            Self::Or(a, b) => a.matches(task, today) || b.matches(task, today),
+        }
+    }
+}
+
+fn condition_matches(
+    comparison: Comparison,
+    condition: &Condition,
+    task: &Task,
+    today: NaiveDate,
+) -> bool {
+    match condition {
+        Condition::Tag(tag) => {
+            let tagged = task.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+            comparison.holds(tagged, true)
+        }
+        Condition::Status(status) => {
+            let matches = status_key(task.status.name()) == *status
+                || status_key(task.status.to_ical()) == *status;
+            comparison.holds(matches, true)
+        }
+        Condition::Priority(priority) => comparison.holds(task.priority.get(), *priority),
+        Condition::Progress(progress) => comparison.holds(task.progress.get(), *progress),
+        Condition::Due(DueDate::None) => comparison.holds(task.due.is_none(), true),
+        Condition::Due(DueDate::Today) => task.due.is_some_and(|due| comparison.holds(due, today)),
+        Condition::Due(DueDate::Date(date)) => {
+            task.due.is_some_and(|due| comparison.holds(due, *date))
+        }
+        Condition::Text(text) => {
+            let text = text.to_lowercase();
+            let has_text = task.summary.to_lowercase().contains(&text)
+                || task.description.to_lowercase().contains(&text);
+            comparison.holds(has_text, true)
+        }
+        Condition::Completed(completed) => comparison.holds(task.completed, *completed),
+    }
+}
+
+// A status's name as it's compared, so `needs-action`, `needsaction` and `Needs Action` are the same
+fn status_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The fields that the word being typed at the end of an expression could be,
+/// for suggesting while it's typed. Nothing is suggested once the word has a
+/// comparison in it, or is already a whole field.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::query;
+///
+/// assert_eq!(query::completions("tag:home AND pr"), vec!["priority", "progress"]);
+/// assert_eq!(query::completions("(St"), vec!["status"]);
+/// assert!(query::completions("tag:h").is_empty());
+/// assert!(query::completions("tag:home ").is_empty());
+/// ```
+pub fn completions(text: &str) -> Vec<&'static str> {
+    let word = text
+        .rsplit(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    if word.is_empty() || !word.chars().all(char::is_alphabetic) {
+        return Vec::new();
+    }
+    FIELDS
+        .into_iter()
+        .filter(|field| field.starts_with(&word) && *field != word)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    Open,
+    Close,
+    // A word, which is AND, OR, NOT or a condition, with any quotes taken out
+    Word(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    kind: TokenKind,
+    // Where the token starts, in characters
+    at: usize,
+}
+
+impl Token {
+    fn text(&self) -> String {
+        match &self.kind {
+            TokenKind::Open => String::from("("),
+            TokenKind::Close => String::from(")"),
+            TokenKind::Word(word) => word.clone(),
+        }
+    }
+
+    // Whether the token is AND, OR or NOT, in any case
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.kind, TokenKind::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+// Splits an expression into brackets and words. Spaces and brackets in
+// double quotes are part of the word they're in.
+fn tokenize(text: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut word: Option<(String, usize)> = None;
+    let mut quote = None;
+
+    for (at, c) in text.chars().enumerate() {
+        if quote.is_some() {
+            if c == '"' {
+                quote = None;
+            } else if let Some((word, _)) = &mut word {
+                word.push(c);
+            }
+            continue;
+        }
+        let kind = match c {
+            '(' => Some(TokenKind::Open),
+            ')' => Some(TokenKind::Close),
+            c if c.is_whitespace() => None,
+            _ => {
+                let (word, _) = word.get_or_insert_with(|| (String::new(), at));
+                if c == '"' {
+                    quote = Some(at);
+                } else {
+                    word.push(c);
+                }
+                continue;
+            }
+        };
+        if let Some((word, start)) = word.take() {
+            tokens.push(Token {
+                kind: TokenKind::Word(word),
+                at: start,
+            });
+        }
+        if let Some(kind) = kind {
+            tokens.push(Token { kind, at });
+        }
+    }
+
+    if let Some(at) = quote {
+        return Err(QueryError::UnclosedQuote { at });
+    }
+    if let Some((word, start)) = word {
+        tokens.push(Token {
+            kind: TokenKind::Word(word),
+            at: start,
+        });
+    }
+    Ok(tokens)
+}
+
+// Parses tokens into a query, with NOT binding tighter than AND, and AND tighter than OR
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+    // How long the expression is, which is where errors at the end of it are
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn take_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek().is_some_and(|token| token.is_keyword(keyword));
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Query, QueryError> {
+        let mut query = self.and()?;
+        while self.take_keyword("OR") {
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Query, QueryError> {
+        let mut query = self.not()?;
+        while self.take_keyword("AND") {
+            query = Query::And(Box::new(query), Box::new(self.not()?));
+        }
+        Ok(query)
+    }
+
+    fn not(&mut self) -> Result<Query, QueryError> {
+        if self.take_keyword("NOT") {
+            return Ok(Query::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Query, QueryError> {
+        let Some(token) = self.peek().cloned() else {
+            return Err(QueryError::ExpectedCondition { at: self.end });
+        };
+        self.next += 1;
+        match &token.kind {
+            TokenKind::Open => {
+                let query = self.or()?;
+                match self.peek() {
+                    Some(Token {
+                        kind: TokenKind::Close,
+                        ..
+                    }) => {
+                        self.next += 1;
+                        Ok(query)
+                    }
+                    Some(token) => Err(QueryError::Unexpected {
+                        found: token.text(),
+                        at: token.at,
+                    }),
+                    None => Err(QueryError::UnclosedBracket { at: token.at }),
+                }
+            }
+            TokenKind::Close => Err(QueryError::Unexpected {
+                found: token.text(),
+                at: token.at,
+            }),
+            TokenKind::Word(_) if ["AND", "OR", "NOT"].iter().any(|k| token.is_keyword(k)) => {
+                Err(QueryError::ExpectedCondition { at: token.at })
+            }
+            TokenKind::Word(word) => condition(word, token.at),
+        }
+    }
+}
+
+// Parses a condition, like `priority>=8`, which starts at `at`
+fn condition(word: &str, at: usize) -> Result<Query, QueryError> {
+    let field_end = word
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(word.len());
+    let (field, rest) = word.split_at(field_end);
+    let Some((symbol, comparison)) = Comparison::SYMBOLS
+        .into_iter()
+        .find(|(symbol, _)| rest.starts_with(symbol))
+    else {
+        return Err(QueryError::MissingComparison {
+            word: word.to_string(),
+            at,
+        });
+    };
+    let value = &rest[symbol.len()..];
+    let field = field.to_lowercase();
+
+    let bad_value = || QueryError::BadValue {
+        field: field.clone(),
+        value: value.to_string(),
+        at,
+    };
+    if value.is_empty() {
+        return Err(bad_value());
+    }
+    let condition = match field.as_str() {
+        "tag" => Condition::Tag(value.trim_start_matches('#').to_string()),
+        "status" => Condition::Status(status_key(value)),
+        "priority" => Condition::Priority(value.parse().map_err(|_| bad_value())?),
+        "progress" => Condition::Progress(
+            value
+                .trim_end_matches('%')
+                .parse()
+                .map_err(|_| bad_value())?,
+        ),
+        "due" if value.eq_ignore_ascii_case("today") => Condition::Due(DueDate::Today),
+        "due" if value.eq_ignore_ascii_case("none") => Condition::Due(DueDate::None),
+        "due" => Condition::Due(DueDate::Date(
+            NaiveDate::parse_from_str(value, DATE_FORMAT).map_err(|_| bad_value())?,
+        )),
+        "text" => Condition::Text(value.to_string()),
+        "completed" => Condition::Completed(match value.to_lowercase().as_str() {
+            "true" | "yes" => true,
+            "false" | "no" => false,
+            _ => return Err(bad_value()),
+        }),
+        _ => return Err(QueryError::UnknownField { field, at }),
+    };
+    // Only numbers and dates have an order
+    let ordered = matches!(
+        condition,
+        Condition::Priority(_)
+            | Condition::Progress(_)
+            | Condition::Due(DueDate::Today | DueDate::Date(_))
+    );
+    if !ordered && !matches!(comparison, Comparison::Equal | Comparison::NotEqual) {
+        return Err(QueryError::BadComparison { field, at });
+    }
+    Ok(Query::Condition(comparison, condition))
+}
@@ -0,0 +1,133 @@
+// ----------------------------------------------------------------------------
+// Quiet hours, when reminders are held back instead of going off. Reminders
+// can be kept quiet every night, all weekend, and always for lists the user
+// has muted. Reminders that are held back go off once the quiet hours end.
+// ----------------------------------------------------------------------------
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct QuietHoursSettings {
+    // Keeps reminders quiet every night, between the start and end times
+    pub enabled: bool,
+    // Local times of day that the quiet hours start and end at.
+    // If the end is before the start, the quiet hours go over midnight.
+    pub start_hour: u32,
+    pub start_minute: u32,
+    pub end_hour: u32,
+    pub end_minute: u32,
+    // Keeps reminders quiet all through Saturday and Sunday
+    pub weekends: bool,
+    // Files of lists whose reminders are always kept quiet
+    pub muted_lists: Vec<PathBuf>,
+}
+
+impl Default for QuietHoursSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_hour: 22,
+            start_minute: 0,
+            end_hour: 8,
+            end_minute: 0,
+            weekends: false,
+            muted_lists: Vec::new(),
+        }
+    }
+}
+
+impl QuietHoursSettings {
+    fn start(&self) -> NaiveTime {
+        NaiveTime::from_hms_opt(self.start_hour, self.start_minute, 0).unwrap_or(NaiveTime::MIN)
+    }
+
+    fn end(&self) -> NaiveTime {
+        NaiveTime::from_hms_opt(self.end_hour, self.end_minute, 0).unwrap_or(NaiveTime::MIN)
+    }
+
+    /// Whether reminders are kept quiet at a time, going by the nightly quiet
+    /// hours and weekends. Muted lists are left to [`Self::is_quiet`].
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use taskmaster_rs::quiet_hours::QuietHoursSettings;
+    ///
+    /// let settings = QuietHoursSettings { enabled: true, ..Default::default() };
+    /// // A Tuesday
+    /// let day = NaiveDate::from_ymd_opt(2023, 8, 8).unwrap();
+    ///
+    /// assert!(settings.in_quiet_hours(day.and_hms_opt(23, 30, 0).unwrap()));
+    /// assert!(settings.in_quiet_hours(day.and_hms_opt(7, 59, 0).unwrap()));
+    /// assert!(!settings.in_quiet_hours(day.and_hms_opt(8, 0, 0).unwrap()));
+    /// assert!(!settings.in_quiet_hours(day.and_hms_opt(12, 0, 0).unwrap()));
+    /// ```
+    pub fn in_quiet_hours(&self, time: NaiveDateTime) -> bool {
+        let weekend = self.weekends && matches!(time.weekday(), Weekday::Sat | Weekday::Sun);
+        let (start, end, now) = (self.start(), self.end(), time.time());
+        let nightly = self.enabled
+            && if start <= end {
+                start <= now && now < end
+            } else {
+                // The quiet hours go over midnight
+                now >= start || now < end
+            };
+        weekend || nightly
+    }
+
+    // Whether a list's reminders are always kept quiet
+    pub fn is_muted(&self, path: Option<&Path>) -> bool {
+        path.is_some_and(|path| self.muted_lists.iter().any(|muted| muted == path))
+    }
+
+    // Mutes or unmutes a list's reminders
+    pub fn set_muted(&mut self, path: &Path, muted: bool) {
+        self.muted_lists.retain(|list| list != path);
+        if muted {
+            self.muted_lists.push(path.to_path_buf());
+        }
+    }
+
+    // Whether reminders for the list in a file are kept quiet at a time
+    pub fn is_quiet(&self, time: NaiveDateTime, path: Option<&Path>) -> bool {
+        self.is_muted(path) || self.in_quiet_hours(time)
+    }
+
+    /// When the quiet hours that a time is in end, so that the reminders held
+    /// back can go off. Gives back nothing if the time isn't in quiet hours.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use taskmaster_rs::quiet_hours::QuietHoursSettings;
+    ///
+    /// let settings = QuietHoursSettings { enabled: true, weekends: true, ..Default::default() };
+    /// let day = |d| NaiveDate::from_ymd_opt(2023, 8, d).unwrap();
+    ///
+    /// // Friday night is quiet until Monday morning
+    /// let friday_night = day(11).and_hms_opt(23, 0, 0).unwrap();
+    /// assert_eq!(settings.quiet_until(friday_night), day(14).and_hms_opt(8, 0, 0));
+    /// assert_eq!(settings.quiet_until(day(14).and_hms_opt(12, 0, 0).unwrap()), None);
+    /// ```
+    pub fn quiet_until(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        if !self.in_quiet_hours(time) {
+            return None;
+        }
+        // Quiet hours can only end at midnight, when a weekend ends, or at the end time.
+        // A weekend and the nights either side of it are the longest they can last.
+        let end = self.end();
+        (0..4)
+            .filter_map(|days| time.date().checked_add_days(Days::new(days)))
+            .flat_map(|day| {
+                let mut times = [day.and_time(NaiveTime::MIN), day.and_time(end)];
+                times.sort();
+                times
+            })
+            .find(|candidate| *candidate > time && !self.in_quiet_hours(*candidate))
+    }
+}
@@ -0,0 +1,184 @@
+// ----------------------------------------------------------------------------
+// Repeating tasks, like chores that are due every week. A task's repeat rule
+// is kept as the text of its RRULE, so that rules from other apps survive
+// being opened and saved, but only simple rules, which repeat every so many
+// days, weeks, months or years, can be edited or followed by the app.
+// Occurrences can be skipped, which adds them to the task's EXDATEs.
+// ----------------------------------------------------------------------------
+
+use std::slice::Iter;
+
+use chrono::{Duration, Months, NaiveDate};
+
+use crate::task::Task;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub fn iterator() -> Iter<'static, Self> {
+        [Self::Daily, Self::Weekly, Self::Monthly, Self::Yearly].iter()
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Daily => "Daily",
+            Self::Weekly => "Weekly",
+            Self::Monthly => "Monthly",
+            Self::Yearly => "Yearly",
+        }
+    }
+
+    // What the interval is counted in, like "every 2 weeks"
+    pub const fn unit(self) -> &'static str {
+        match self {
+            Self::Daily => "days",
+            Self::Weekly => "weeks",
+            Self::Monthly => "months",
+            Self::Yearly => "years",
+        }
+    }
+
+    pub const fn to_ical(self) -> &'static str {
+        match self {
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+            Self::Yearly => "YEARLY",
+        }
+    }
+
+    pub fn from_ical(text: &str) -> Option<Self> {
+        Self::iterator()
+            .copied()
+            .find(|frequency| frequency.to_ical().eq_ignore_ascii_case(text))
+    }
+}
+
+/// A simple repeat rule, which repeats every `interval` days, weeks, months or years.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::recurrence::{Frequency, Repeat};
+///
+/// let repeat = Repeat::parse("FREQ=WEEKLY;INTERVAL=2").unwrap();
+/// assert_eq!(repeat, Repeat { frequency: Frequency::Weekly, interval: 2 });
+/// assert_eq!(repeat.to_rrule(), "FREQ=WEEKLY;INTERVAL=2");
+/// assert_eq!(Repeat::parse("FREQ=DAILY").unwrap().interval, 1);
+///
+/// // Rules the app can't follow aren't read
+/// assert!(Repeat::parse("FREQ=MONTHLY;BYDAY=1MO").is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat {
+    pub frequency: Frequency,
+    pub interval: u32,
+}
+
+impl Repeat {
+    // Reads an RRULE, if it only has parts the app knows how to follow
+    pub fn parse(rule: &str) -> Option<Self> {
+        let mut frequency = None;
+        let mut interval = 1;
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part.split_once('=')?;
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(Frequency::from_ical(value)?),
+                "INTERVAL" => interval = value.parse().ok().filter(|&n| n > 0)?,
+                // Which day weeks start on doesn't change anything for simple rules
+                "WKST" => (),
+                _ => return None,
+            }
+        }
+        Some(Self {
+            frequency: frequency?,
+            interval,
+        })
+    }
+
+    pub fn to_rrule(self) -> String {
+        if self.interval == 1 {
+            format!("FREQ={}", self.frequency.to_ical())
+        } else {
+            format!(
+                "FREQ={};INTERVAL={}",
+                self.frequency.to_ical(),
+                self.interval
+            )
+        }
+    }
+
+    // The date the rule comes round again after the given one
+    pub fn after(self, date: NaiveDate) -> Option<NaiveDate> {
+        let interval = self.interval.max(1);
+        match self.frequency {
+            Frequency::Daily => date.checked_add_signed(Duration::days(interval.into())),
+            Frequency::Weekly => date.checked_add_signed(Duration::weeks(interval.into())),
+            Frequency::Monthly => date.checked_add_months(Months::new(interval)),
+            Frequency::Yearly => date.checked_add_months(Months::new(interval.checked_mul(12)?)),
+        }
+    }
+}
+
+// Occurrences that are skipped in a row are only searched this far, in case
+// a rule and its exception dates never leave an occurrence to land on
+const MAX_SKIPPED: usize = 1000;
+
+impl Task {
+    // The task's repeat rule, if it repeats in a way the app can follow
+    pub fn repeat(&self) -> Option<Repeat> {
+        self.recurrence.as_deref().and_then(Repeat::parse)
+    }
+
+    // When the task is due next after its current due date, leaving out skipped occurrences
+    pub fn next_occurrence(&self) -> Option<NaiveDate> {
+        let repeat = self.repeat()?;
+        let mut date = self.due?;
+        for _ in 0..MAX_SKIPPED {
+            date = repeat.after(date)?;
+            if !self.exception_dates.contains(&date) {
+                return Some(date);
+            }
+        }
+        None
+    }
+
+    /// Skips the occurrence the task is due on, without completing it, so that
+    /// it's due on the next occurrence instead. The skipped date is kept as an
+    /// exception date. Returns whether the task repeats and could be moved on.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use taskmaster_rs::task::Task;
+    ///
+    /// let date = |day| NaiveDate::from_ymd_opt(2023, 12, day).unwrap();
+    /// let mut chore = Task {
+    ///     due: Some(date(18)),
+    ///     recurrence: Some(String::from("FREQ=WEEKLY")),
+    ///     exception_dates: vec![date(25)],
+    ///     ..Task::default()
+    /// };
+    ///
+    /// assert!(chore.skip_occurrence());
+    /// // Christmas was already skipped, so the week after it is next
+    /// assert_eq!(chore.due, NaiveDate::from_ymd_opt(2024, 1, 1));
+    /// assert_eq!(chore.exception_dates, [date(25), date(18)]);
+    /// assert!(!chore.completed);
+    /// ```
+    pub fn skip_occurrence(&mut self) -> bool {
+        let (Some(due), Some(next)) = (self.due, self.next_occurrence()) else {
+            return false;
+        };
+        if !self.exception_dates.contains(&due) {
+            self.exception_dates.push(due);
+        }
+        self.due = Some(next);
+        true
+    }
+}
@@ -0,0 +1,286 @@
+// ----------------------------------------------------------------------------
+// Finds reminders that were missed because the computer was asleep, or the
+// app wasn't open, when they should have gone off, so that they can be
+// listed when the user comes back.
+// A task's reminder goes off at the start of the day it becomes due soon,
+// which is when its due soon hook is run. Missed reminders that are found
+// during quiet hours are held back until they're over.
+// Reminders are also listed under a bell in the app until they're dismissed,
+// for when notifications aren't shown or have been missed.
+// ----------------------------------------------------------------------------
+
+use chrono::{Days, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use eframe::egui;
+use uuid::Uuid;
+
+use crate::hooks::{self, HookSettings};
+use crate::json::{DATE_FORMAT, DATE_TIME_FORMAT};
+use crate::platform;
+use crate::task::*;
+
+// Name that the time the app was last open is saved under
+const LAST_OPEN_STATE: &str = "last-open";
+// Name that the reminders dismissed from the bell are saved under
+const DISMISSED_STATE: &str = "dismissed-reminders";
+// How late, in minutes, a reminder has to be to count as missed.
+// The app wakes up when reminders are due, so a reminder is only late if the
+// computer was asleep or the app was closed.
+const GRACE_MINUTES: i64 = 5;
+// How often, in seconds, the time is saved while the app is open
+const SAVE_INTERVAL: i64 = 60;
+
+/// Finds the incomplete tasks whose reminders were missed between two times.
+///
+/// A reminder is missed if it should have gone off after `since` and more
+/// than a few minutes before `now`. Reminders go off at the start of the day
+/// that's `due_soon_days` before a task is due.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::reminders;
+/// use taskmaster_rs::task::*;
+///
+/// let day = |d| NaiveDate::from_ymd_opt(2023, 8, d).unwrap();
+/// let list = TaskList {
+///     tasks: vec![
+///         Task { summary: "Reminded before".into(), due: Some(day(7)), ..Task::default() },
+///         Task { summary: "Missed".into(), due: Some(day(9)), ..Task::default() },
+///         Task { summary: "Not yet".into(), due: Some(day(12)), ..Task::default() },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// // The app was closed on the evening of the 7th, and opened again on the morning of the 9th
+/// let since = day(7).and_hms_opt(21, 0, 0).unwrap();
+/// let now = day(9).and_hms_opt(8, 30, 0).unwrap();
+/// let missed = reminders::missed(&list, 1, since, now);
+///
+/// assert_eq!(missed.len(), 1);
+/// assert_eq!(missed[0].summary, "Missed");
+/// ```
+pub fn missed(
+    task_list: &TaskList,
+    due_soon_days: u32,
+    since: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Vec<&Task> {
+    let late = now - Duration::minutes(GRACE_MINUTES);
+    task_list
+        .tasks
+        .iter()
+        .filter(|task| !task.completed)
+        .filter(|task| {
+            task.due
+                .and_then(|due| due.checked_sub_days(Days::new(due_soon_days.into())))
+                .map(|day| day.and_time(NaiveTime::MIN))
+                .is_some_and(|reminder| reminder > since && reminder <= late)
+        })
+        .collect()
+}
+
+// Keeps track of when the app was last open, including between runs,
+// and finds the reminders that were missed while it wasn't
+#[derive(Default)]
+pub struct MissedReminders {
+    // The last time the app was known to be open and awake
+    last_open: Option<NaiveDateTime>,
+    // When the time was last saved
+    last_saved: Option<NaiveDateTime>,
+    // Missed reminders that were found while it was quiet
+    held_back: Vec<Task>,
+}
+
+impl MissedReminders {
+    // Starts from when the app was open last time it was run
+    pub fn load() -> Self {
+        let last_open = platform::read_state(LAST_OPEN_STATE)
+            .and_then(|text| NaiveDateTime::parse_from_str(text.trim(), DATE_TIME_FORMAT).ok());
+        Self {
+            last_open,
+            last_saved: None,
+            held_back: Vec::new(),
+        }
+    }
+
+    // Moves when the app was last open along with the clocks, when the time zone
+    // changes and times are kept at the same moment
+    pub fn shift(&mut self, by: Duration) {
+        self.last_open = self.last_open.map(|last_open| last_open + by);
+    }
+
+    // Returns the tasks whose reminders were missed since this was last called,
+    // or since the app was last open. While it's quiet, they're held back and
+    // returned once it isn't. Should be called once every frame.
+    pub fn check(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        settings: &HookSettings,
+        quiet: bool,
+    ) -> Vec<Task> {
+        let now = Local::now().naive_local();
+        if let Some(since) = self.last_open {
+            for task in missed(task_list, settings.due_soon_days, since, now) {
+                if !self.held_back.iter().any(|held| held.uuid == task.uuid) {
+                    self.held_back.push(task.clone());
+                }
+            }
+        }
+        self.last_open = Some(now);
+        let missed = if quiet {
+            Vec::new()
+        } else {
+            std::mem::take(&mut self.held_back)
+        };
+
+        let saved_recently = self
+            .last_saved
+            .is_some_and(|saved| now - saved < Duration::seconds(SAVE_INTERVAL));
+        if !saved_recently {
+            self.last_saved = Some(now);
+            let text = now.format(DATE_TIME_FORMAT).to_string();
+            if let Err(e) = platform::write_state(LAST_OPEN_STATE, &text) {
+                eprintln!("could not save when the app was last open: {e}");
+            }
+        }
+
+        // Reminders go off at midnight, so make sure the app is awake then.
+        // If it isn't woken up until a while later, the computer must have been asleep.
+        let midnight = now
+            .date()
+            .succ_opt()
+            .map(|tomorrow| tomorrow.and_time(NaiveTime::MIN));
+        if let Some(wait) = midnight.and_then(|midnight| (midnight - now).to_std().ok()) {
+            ctx.request_repaint_after(wait);
+        }
+
+        missed
+    }
+}
+
+/// Finds the incomplete tasks that are overdue or due soon, which have
+/// reminders, with the ones due first listed first.
+///
+/// Example:
+/// ```
+/// use chrono::Datelike;
+/// use taskmaster_rs::hooks::HookSettings;
+/// use taskmaster_rs::reminders;
+/// use taskmaster_rs::task::*;
+///
+/// let today = chrono::Local::now().date_naive();
+/// let list = TaskList {
+///     tasks: vec![
+///         Task { summary: "Tomorrow".into(), due: today.succ_opt(), ..Task::default() },
+///         Task { summary: "Yesterday".into(), due: today.pred_opt(), ..Task::default() },
+///         Task { summary: "Next year".into(), due: today.with_year(2100), ..Task::default() },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let due = reminders::due(&list, &HookSettings::default(), today);
+/// let summaries: Vec<&str> = due.iter().map(|task| task.summary.as_str()).collect();
+/// assert_eq!(summaries, ["Yesterday", "Tomorrow"]);
+/// ```
+pub fn due<'a>(
+    task_list: &'a TaskList,
+    settings: &HookSettings,
+    today: NaiveDate,
+) -> Vec<&'a Task> {
+    let mut due: Vec<&Task> = task_list
+        .tasks
+        .iter()
+        .filter(|task| hooks::is_due_soon(task, settings, today))
+        .collect();
+    due.sort_by_key(|task| task.due);
+    due
+}
+
+// A reminder listed under the bell
+pub struct Reminder {
+    pub uuid: Uuid,
+    pub summary: String,
+    pub due: NaiveDate,
+}
+
+// Keeps the reminders that are listed under the bell, leaving out the ones
+// that have been dismissed. A dismissed reminder comes back if its task's
+// due date changes.
+#[derive(Default)]
+pub struct ReminderInbox {
+    // Tasks whose reminders have been dismissed, with the due dates they had then
+    dismissed: Vec<(Uuid, NaiveDate)>,
+    reminders: Vec<Reminder>,
+}
+
+impl ReminderInbox {
+    // Starts with the reminders that were dismissed last time the app was run
+    pub fn load() -> Self {
+        let dismissed = platform::read_state(DISMISSED_STATE)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let (uuid, due) = line.split_once(' ')?;
+                let due = NaiveDate::parse_from_str(due.trim(), DATE_FORMAT).ok()?;
+                Some((Uuid::parse_str(uuid).ok()?, due))
+            })
+            .collect();
+        Self {
+            dismissed,
+            reminders: Vec::new(),
+        }
+    }
+
+    // Finds the reminders that haven't been dismissed. Should be called once every frame.
+    pub fn check(&mut self, task_list: &TaskList, settings: &HookSettings) {
+        let today = Local::now().date_naive();
+        self.reminders = due(task_list, settings, today)
+            .into_iter()
+            .filter_map(|task| {
+                let due = task.due?;
+                (!self.dismissed.contains(&(task.uuid, due))).then(|| Reminder {
+                    uuid: task.uuid,
+                    summary: task.summary.clone(),
+                    due,
+                })
+            })
+            .collect();
+    }
+
+    pub fn reminders(&self) -> &[Reminder] {
+        &self.reminders
+    }
+
+    // Stops listing the reminder for a task, until its due date changes
+    pub fn dismiss(&mut self, uuid: Uuid) {
+        let Some(index) = self.reminders.iter().position(|r| r.uuid == uuid) else {
+            return;
+        };
+        let reminder = self.reminders.remove(index);
+        self.dismissed.retain(|(dismissed, _)| *dismissed != uuid);
+        self.dismissed.push((uuid, reminder.due));
+        self.save();
+    }
+
+    // Stops listing every reminder that's listed now
+    pub fn dismiss_all(&mut self) {
+        for reminder in self.reminders.drain(..) {
+            self.dismissed
+                .retain(|(dismissed, _)| *dismissed != reminder.uuid);
+            self.dismissed.push((reminder.uuid, reminder.due));
+        }
+        self.save();
+    }
+
+    fn save(&self) {
+        let text: String = self
+            .dismissed
+            .iter()
+            .map(|(uuid, due)| format!("{uuid} {}\n", due.format(DATE_FORMAT)))
+            .collect();
+        if let Err(e) = platform::write_state(DISMISSED_STATE, &text) {
+            eprintln!("could not save dismissed reminders: {e}");
+        }
+    }
+}
@@ -0,0 +1,313 @@
+// ----------------------------------------------------------------------------
+// Status reports, which summarise what happened in a list over a range of
+// days: which tasks were completed, how they break down by tag, how long
+// tasks took from being created to being completed, and what was left
+// overdue at the end. Reports are written as Markdown or HTML.
+// ----------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::agenda::escape_html;
+use crate::json::DATE_FORMAT;
+use crate::stats;
+use crate::task::{Task, TaskList};
+use crate::task_views::format_minutes;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        [Self::Markdown, Self::Html].iter()
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "Markdown",
+            Self::Html => "HTML",
+        }
+    }
+
+    // File extension used for reports in this format
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report<'a> {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    // Tasks completed from the start to the end, in the order they were completed
+    pub completed: Vec<&'a Task>,
+    // How many of the completed tasks had each tag. Untagged tasks aren't counted.
+    pub by_tag: BTreeMap<&'a str, usize>,
+    // The average time from a completed task being created to it being completed
+    pub average_cycle_time: Option<Duration>,
+    // Tasks that were still waiting to be done after their due date at the end
+    pub overdue: Vec<&'a Task>,
+}
+
+impl<'a> Report<'a> {
+    /// Gathers what happened in a list from `start` to `end`, including both.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::{Duration, NaiveDate};
+    /// use taskmaster_rs::report::Report;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let day = |n| NaiveDate::from_ymd_opt(2023, 8, n).unwrap();
+    /// let done = |created, completed, tag: &str| Task {
+    ///     created: day(created).and_hms_opt(9, 0, 0).unwrap(),
+    ///     completed: true,
+    ///     completed_at: day(completed).and_hms_opt(9, 0, 0),
+    ///     tags: vec![String::from(tag)],
+    ///     ..Task::default()
+    /// };
+    /// let list = TaskList {
+    ///     tasks: vec![
+    ///         done(1, 3, "work"),
+    ///         done(2, 6, "work"),
+    ///         done(1, 20, "home"),
+    ///         Task {
+    ///             created: day(1).and_hms_opt(9, 0, 0).unwrap(),
+    ///             due: Some(day(5)),
+    ///             ..Task::default()
+    ///         },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let report = Report::of(&list, day(1), day(7));
+    /// assert_eq!(report.completed.len(), 2);
+    /// assert_eq!(report.by_tag["work"], 2);
+    /// assert!(!report.by_tag.contains_key("home"));
+    /// assert_eq!(report.average_cycle_time, Some(Duration::days(3)));
+    /// assert_eq!(report.overdue.len(), 1);
+    /// ```
+    pub fn of(task_list: &'a TaskList, start: NaiveDate, end: NaiveDate) -> Self {
+        let mut completed: Vec<&Task> = stats::completions_by_day(task_list, start, end)
+            .into_values()
+            .flatten()
+            .collect();
+        completed.sort_by_key(|task| task.completed_at);
+
+        let mut by_tag = BTreeMap::new();
+        for tag in completed.iter().flat_map(|task| &task.tags) {
+            *by_tag.entry(tag.as_str()).or_default() += 1;
+        }
+
+        let cycle_times: Vec<Duration> = completed
+            .iter()
+            .filter_map(|task| Some(task.completed_at? - task.created))
+            .collect();
+        let average_cycle_time = i32::try_from(cycle_times.len())
+            .ok()
+            .filter(|count| *count > 0)
+            .map(|count| cycle_times.iter().fold(Duration::zero(), |sum, t| sum + *t) / count);
+
+        let overdue = task_list
+            .tasks
+            .iter()
+            .filter(|task| stats::remaining_on(task, end) && task.due.is_some_and(|due| due < end))
+            .collect();
+
+        Self {
+            start,
+            end,
+            completed,
+            by_tag,
+            average_cycle_time,
+            overdue,
+        }
+    }
+}
+
+/// Writes a report on what happened in a list from `start` to `end`.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::report::{self, ReportFormat};
+/// use taskmaster_rs::task::*;
+///
+/// let day = |n| NaiveDate::from_ymd_opt(2023, 8, n).unwrap();
+/// let list = TaskList {
+///     name: String::from("Work"),
+///     tasks: vec![
+///         Task {
+///             summary: String::from("Send report"),
+///             created: day(1).and_hms_opt(9, 0, 0).unwrap(),
+///             completed: true,
+///             completed_at: day(2).and_hms_opt(21, 0, 0),
+///             tags: vec![String::from("admin")],
+///             ..Task::default()
+///         },
+///         Task {
+///             summary: String::from("Book flights"),
+///             created: day(1).and_hms_opt(9, 0, 0).unwrap(),
+///             due: Some(day(3)),
+///             ..Task::default()
+///         },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     report::format(&list, day(1), day(7), ReportFormat::Markdown),
+///     "# Report for Work from 2023-08-01 to 2023-08-07
+///
+/// ## Completed (1)
+///
+/// - Send report (2023-08-02)
+///
+/// ## By tag
+///
+/// - admin: 1
+///
+/// ## Average cycle time
+///
+/// 1.5 days
+///
+/// ## Overdue (1)
+///
+/// - Book flights (due 2023-08-03)
+/// ",
+/// );
+/// ```
+pub fn format(
+    task_list: &TaskList,
+    start: NaiveDate,
+    end: NaiveDate,
+    format: ReportFormat,
+) -> String {
+    let report = Report::of(task_list, start, end);
+    let title = format!(
+        "Report for {} from {} to {}",
+        task_list.name,
+        start.format(DATE_FORMAT),
+        end.format(DATE_FORMAT)
+    );
+    match format {
+        ReportFormat::Markdown => markdown(&title, &report),
+        ReportFormat::Html => html(&title, &report),
+    }
+}
+
+// The lines of each section of a report, with their headings
+fn sections(report: &Report, escape: impl Fn(&str) -> String) -> [(String, Vec<String>); 3] {
+    let completed = report
+        .completed
+        .iter()
+        .map(|task| {
+            let mut line = escape(&task.summary);
+            if let Some(completed_at) = task.completed_at {
+                let _ = write!(line, " ({})", completed_at.format(DATE_FORMAT));
+            }
+            line
+        })
+        .collect();
+    let by_tag = report
+        .by_tag
+        .iter()
+        .map(|(tag, count)| format!("{}: {count}", escape(tag)))
+        .collect();
+    let overdue = report
+        .overdue
+        .iter()
+        .map(|task| {
+            let mut line = escape(&task.summary);
+            if let Some(due) = task.due {
+                let _ = write!(line, " (due {})", due.format(DATE_FORMAT));
+            }
+            line
+        })
+        .collect();
+
+    [
+        (format!("Completed ({})", report.completed.len()), completed),
+        (String::from("By tag"), by_tag),
+        (format!("Overdue ({})", report.overdue.len()), overdue),
+    ]
+}
+
+// How long tasks took on average, in days if they took more than one
+#[allow(clippy::cast_precision_loss)]
+fn describe_cycle_time(cycle_time: Option<Duration>) -> String {
+    match cycle_time {
+        None => String::from("Nothing was completed"),
+        Some(time) if time >= Duration::days(1) => {
+            format!("{:.1} days", time.num_minutes() as f64 / (24. * 60.))
+        }
+        Some(time) => format_minutes(u32::try_from(time.num_minutes()).unwrap_or_default()),
+    }
+}
+
+// Formats a report as Markdown
+fn markdown(title: &str, report: &Report) -> String {
+    fn list(text: &mut String, heading: &str, lines: &[String]) {
+        let _ = write!(text, "\n## {heading}\n\n");
+        if lines.is_empty() {
+            text.push_str("Nothing\n");
+        }
+        for line in lines {
+            let _ = writeln!(text, "- {line}");
+        }
+    }
+
+    let mut text = format!("# {title}\n");
+    let [completed, by_tag, overdue] = sections(report, str::to_string);
+    list(&mut text, &completed.0, &completed.1);
+    list(&mut text, &by_tag.0, &by_tag.1);
+    let _ = write!(
+        text,
+        "\n## Average cycle time\n\n{}\n",
+        describe_cycle_time(report.average_cycle_time)
+    );
+    list(&mut text, &overdue.0, &overdue.1);
+    text
+}
+
+// Formats a report as an HTML page
+fn html(title: &str, report: &Report) -> String {
+    fn list(html: &mut String, heading: &str, lines: &[String]) {
+        let _ = writeln!(html, "<h2>{heading}</h2>");
+        if lines.is_empty() {
+            html.push_str("<p>Nothing</p>\n");
+            return;
+        }
+        html.push_str("<ul>\n");
+        for line in lines {
+            let _ = writeln!(html, "<li>{line}</li>");
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let title = escape_html(title);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{title}</title></head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    let [completed, by_tag, overdue] = sections(report, escape_html);
+    list(&mut html, &completed.0, &completed.1);
+    list(&mut html, &by_tag.0, &by_tag.1);
+    let _ = writeln!(
+        html,
+        "<h2>Average cycle time</h2>\n<p>{}</p>",
+        describe_cycle_time(report.average_cycle_time)
+    );
+    list(&mut html, &overdue.0, &overdue.1);
+    html.push_str("</body>\n</html>\n");
+    html
+}
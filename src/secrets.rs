@@ -0,0 +1,253 @@
+// ----------------------------------------------------------------------------
+// Keeps passwords and tokens, like the one for publishing to a WebDAV server,
+// in the platform's keyring: the Keychain on macOS, the Credential Manager on
+// Windows, or the Secret Service on Linux. Where there's no keyring, like on
+// the web or a Linux desktop without one running, they're kept in a file
+// encrypted with a random key instead. The key is saved with the app's other
+// files, readable only by the user, so this only keeps secrets from being read
+// by accident, like when the folder is shared or backed up without the key file.
+//
+// Keyrings can't list what's in them, so the names of the stored secrets, and
+// where each one is, are kept too, for the user to look through.
+// ----------------------------------------------------------------------------
+
+use std::{collections::BTreeMap, error::Error, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::encryption::EncryptionKey;
+use crate::platform;
+
+// Name the app's secrets are kept under in the keyring
+#[cfg(not(target_arch = "wasm32"))]
+const SERVICE: &str = "taskmaster-rs";
+// Name the list of stored secrets is saved under
+const INDEX_STATE: &str = "secrets.json";
+// Names the encrypted secrets, and the key they're encrypted with, are saved under
+const FILE_STATE: &str = "secrets.enc";
+const KEY_STATE: &str = "secrets.key";
+
+// Names of the secrets the app stores
+pub const PUBLISH_PASSWORD: &str = "publish-password";
+pub const EMAIL_PASSWORD: &str = "email-password";
+pub const GITHUB_TOKEN: &str = "github-token";
+pub const JIRA_TOKEN: &str = "jira-token";
+pub const FEED_TOKEN: &str = "feed-token";
+pub const API_TOKEN: &str = "api-token";
+
+// Where a secret is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SecretLocation {
+    Keyring,
+    EncryptedFile,
+}
+
+impl SecretLocation {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Keyring => "System keyring",
+            Self::EncryptedFile => "Encrypted file",
+        }
+    }
+}
+
+// The name of every secret that's stored, and where it is
+pub fn stored() -> BTreeMap<String, SecretLocation> {
+    platform::read_state(INDEX_STATE)
+        .and_then(|text| {
+            serde_json::from_str(&text)
+                .map_err(|e| eprintln!("could not read {INDEX_STATE}: {e}"))
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+fn save_index(index: &BTreeMap<String, SecretLocation>) -> io::Result<()> {
+    let text = serde_json::to_string_pretty(index).map_err(io::Error::other)?;
+    platform::write_state(INDEX_STATE, &text)
+}
+
+// Gets a secret, if it's stored
+pub fn get(name: &str) -> Option<String> {
+    match stored().get(name)? {
+        SecretLocation::Keyring => keyring_get(name)
+            .map_err(|e| eprintln!("could not read {name} from the keyring: {e}"))
+            .ok(),
+        SecretLocation::EncryptedFile => read_file().remove(name),
+    }
+}
+
+// Stores a secret in the keyring, or the encrypted file if the keyring can't be used.
+// Storing an empty secret removes it.
+pub fn set(name: &str, secret: &str) -> Result<(), Box<dyn Error>> {
+    if secret.is_empty() {
+        return remove(name);
+    }
+
+    let mut file = read_file();
+    let location = match keyring_set(name, secret) {
+        Ok(()) => {
+            // A secret stored before the keyring could be used shouldn't be left in the file
+            if file.remove(name).is_some() {
+                write_file(&file)?;
+            }
+            SecretLocation::Keyring
+        }
+        Err(e) => {
+            eprintln!("could not store {name} in the keyring, so it's kept in a file: {e}");
+            file.insert(name.to_string(), secret.to_string());
+            write_file(&file)?;
+            SecretLocation::EncryptedFile
+        }
+    };
+
+    let mut index = stored();
+    if index.get(name) != Some(&location) {
+        index.insert(name.to_string(), location);
+        save_index(&index)?;
+    }
+    Ok(())
+}
+
+// Removes a secret from wherever it's stored
+pub fn remove(name: &str) -> Result<(), Box<dyn Error>> {
+    let mut index = stored();
+    if index.remove(name) == Some(SecretLocation::Keyring) {
+        keyring_remove(name)?;
+    }
+    let mut file = read_file();
+    if file.remove(name).is_some() {
+        write_file(&file)?;
+    }
+    save_index(&index)?;
+    Ok(())
+}
+
+// Each profile keeps its own secrets
+#[cfg(not(target_arch = "wasm32"))]
+fn keyring_entry(name: &str) -> keyring::Result<keyring::Entry> {
+    let user = match platform::profile() {
+        Some(profile) => format!("{profile}/{name}"),
+        None => name.to_string(),
+    };
+    keyring::Entry::new(SERVICE, &user)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn keyring_get(name: &str) -> Result<String, Box<dyn Error>> {
+    Ok(keyring_entry(name)?.get_password()?)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn keyring_set(name: &str, secret: &str) -> Result<(), Box<dyn Error>> {
+    Ok(keyring_entry(name)?.set_password(secret)?)
+}
+
+// Secrets that are already gone don't need removing
+#[cfg(not(target_arch = "wasm32"))]
+fn keyring_remove(name: &str) -> Result<(), Box<dyn Error>> {
+    match keyring_entry(name)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn keyring_get(_name: &str) -> Result<String, Box<dyn Error>> {
+    Err("browsers don't have a keyring".into())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn keyring_set(_name: &str, _secret: &str) -> Result<(), Box<dyn Error>> {
+    Err("browsers don't have a keyring".into())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn keyring_remove(_name: &str) -> Result<(), Box<dyn Error>> {
+    Ok(())
+}
+
+// The key the file of secrets is encrypted with, as hexadecimal, which is made the first time
+// it's needed
+fn file_key() -> io::Result<String> {
+    if let Some(key) = platform::read_state(KEY_STATE).filter(|key| !key.trim().is_empty()) {
+        return Ok(key.trim().to_string());
+    }
+    let key = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    platform::write_private_state(KEY_STATE, &key)?;
+    Ok(key)
+}
+
+// The key is already random, so it's used as it is. Deriving a key from it with Argon2,
+// like from a passphrase, would only make reading and writing secrets slow.
+fn cipher(key: &str) -> io::Result<EncryptionKey> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{KEY_STATE} isn't a key"),
+        )
+    };
+    if !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let bytes: Vec<u8> = key
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(invalid)?;
+    let bytes = bytes.try_into().map_err(|_| invalid())?;
+    Ok(EncryptionKey::from_bytes(bytes))
+}
+
+// The secrets kept in the encrypted file
+fn read_file() -> BTreeMap<String, String> {
+    let Some(text) = platform::read_state(FILE_STATE) else {
+        return BTreeMap::new();
+    };
+    let data = text.as_bytes();
+    let mut derived = false;
+    let decrypted = file_key()
+        .and_then(|key| Ok((cipher(&key)?, key)))
+        .map_err(|e| e.to_string())
+        .and_then(|(cipher, key)| {
+            cipher
+                .decrypt(data)
+                .or_else(|_| {
+                    // Older versions derived the file's key from the key file, like a passphrase
+                    derived = true;
+                    EncryptionKey::for_list(&key, data).and_then(|key| key.decrypt(data))
+                })
+                .map_err(|e| format!("{e:?}"))
+        })
+        .and_then(|json| serde_json::from_slice(&json).map_err(|e| e.to_string()));
+    match decrypted {
+        Ok(secrets) => {
+            // Files from older versions are encrypted again, so they're quick to read next time
+            if derived {
+                if let Err(e) = write_file(&secrets) {
+                    eprintln!("could not save {FILE_STATE}: {e}");
+                }
+            }
+            secrets
+        }
+        Err(e) => {
+            eprintln!("could not read {FILE_STATE}: {e}");
+            BTreeMap::new()
+        }
+    }
+}
+
+fn write_file(secrets: &BTreeMap<String, String>) -> io::Result<()> {
+    let json = serde_json::to_vec(secrets).map_err(io::Error::other)?;
+    let encrypted = cipher(&file_key()?)?.encrypt(&json);
+    platform::write_private_state(FILE_STATE, &String::from_utf8_lossy(&encrypted))
+}
@@ -0,0 +1,380 @@
+// ----------------------------------------------------------------------------
+// Optional embedded HTTP server, enabled with the `server` feature.
+// Exposes the app's task list over a small JSON API so that scripts,
+// Stream Deck buttons, and phones on the same network can add and
+// complete tasks while the app is running.
+//
+// Every request needs the API token from the settings, sent as
+// `Authorization: Bearer <token>`, and requests with a body have to send it
+// as `application/json`. Requests from web pages on other sites are refused,
+// so that visiting one can't change the list.
+//
+// Routes:
+//   GET    /list                  List name, color and task count
+//   PATCH  /list                  Rename the list, e.g. {"name": "Chores"}
+//   GET    /list.ics              The whole list as an iCal file
+//   GET    /tasks                 Every task in the list
+//   POST   /tasks                 Add a task, e.g. {"summary": "Buy milk"}
+//   GET    /tasks/{uuid}          A single task
+//   PATCH  /tasks/{uuid}          Change some of a task's fields
+//   DELETE /tasks/{uuid}          Remove a task
+//   POST   /tasks/{uuid}/complete Mark a task as complete
+// ----------------------------------------------------------------------------
+
+use std::{
+    error::Error,
+    io::{Cursor, Read},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::feed;
+use crate::json::{self, DATE_FORMAT};
+use crate::task::*;
+
+// Address the server listens on if none is given.
+// Only local clients can connect to this. To let a phone or another computer
+// connect, set TASKMASTER_SERVER_ADDRESS to this computer's address on a network
+// that's trusted, like 192.168.1.20:7878. Requests aren't encrypted, so anyone
+// else on the network could see the token.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+// Bodies bigger than this are refused rather than read, since no task needs this much
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+// Handle to a running server.
+// Requests are received on a background thread, but the task list can only be
+// accessed from the UI thread, so they're passed to the app to be answered.
+pub struct Server {
+    requests: Receiver<Request>,
+}
+
+impl Server {
+    // Starts listening for requests on the given address
+    pub fn start(address: &str, ctx: egui::Context) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let server = tiny_http::Server::http(address)?;
+        let (sender, requests) = mpsc::channel();
+
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                // If the app has closed, stop listening
+                if sender.send(request).is_err() {
+                    break;
+                }
+                // Wake the app up so that it answers the request straight away
+                ctx.request_repaint();
+            }
+        });
+
+        Ok(Self { requests })
+    }
+
+    // Answers every request that has arrived since this was last called.
+    // Only requests with the token are answered, so nothing is if there isn't one yet.
+    pub fn handle_requests(&self, task_list: &mut TaskList, token: &str) {
+        while let Ok(mut request) = self.requests.try_recv() {
+            let response = match check(&request, token) {
+                Ok(()) => route(&mut request, task_list),
+                Err(response) => response,
+            };
+            // If the client has already disconnected, there's nobody to tell
+            let _ = request.respond(response);
+        }
+    }
+}
+
+// Gets the address the server should listen on
+pub fn address() -> String {
+    std::env::var("TASKMASTER_SERVER_ADDRESS").unwrap_or_else(|_| DEFAULT_ADDRESS.to_string())
+}
+
+/// Whether a request's `Authorization` header has the API token.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::server;
+///
+/// assert!(server::authorized(Some("Bearer abc123"), "abc123"));
+/// assert!(!server::authorized(Some("Bearer abc12"), "abc123"));
+/// assert!(!server::authorized(Some("abc123"), "abc123"));
+/// assert!(!server::authorized(None, "abc123"));
+/// // Without a token, nobody can use the API
+/// assert!(!server::authorized(Some("Bearer "), ""));
+/// ```
+pub fn authorized(authorization: Option<&str>, token: &str) -> bool {
+    authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| feed::tokens_match(given.trim(), token))
+}
+
+/// Whether a request's `Origin` header, if it has one, is the server itself
+/// rather than a web page on another site.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::server;
+///
+/// assert!(server::same_origin(None, Some("127.0.0.1:7878")));
+/// assert!(server::same_origin(Some("http://127.0.0.1:7878"), Some("127.0.0.1:7878")));
+/// assert!(!server::same_origin(Some("https://example.com"), Some("127.0.0.1:7878")));
+/// assert!(!server::same_origin(Some("null"), Some("127.0.0.1:7878")));
+/// ```
+pub fn same_origin(origin: Option<&str>, host: Option<&str>) -> bool {
+    match origin {
+        None => true,
+        Some(origin) => host.is_some_and(|host| origin == format!("http://{host}")),
+    }
+}
+
+// The value of one of a request's headers, if it has it
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+// Refuses requests without the token, from other sites, or with a body that isn't JSON
+fn check(request: &Request, token: &str) -> Result<(), HttpResponse> {
+    if !authorized(header(request, "Authorization"), token) {
+        return Err(error(401, "the API token is missing or wrong"));
+    }
+    if !same_origin(header(request, "Origin"), header(request, "Host")) {
+        return Err(error(403, "requests from other sites aren't allowed"));
+    }
+    let has_body = request.body_length().is_some_and(|length| length > 0)
+        || header(request, "Transfer-Encoding").is_some();
+    let json = header(request, "Content-Type").is_some_and(|value| {
+        value
+            .split(';')
+            .next()
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+    });
+    if has_body && !json {
+        return Err(error(415, "request bodies have to be application/json"));
+    }
+    if request
+        .body_length()
+        .is_some_and(|length| length > MAX_BODY_BYTES)
+    {
+        return Err(error(413, "request body is too big"));
+    }
+    Ok(())
+}
+
+// Works out what a request is asking for and does it
+fn route(request: &mut Request, task_list: &mut TaskList) -> HttpResponse {
+    let url = request.url().to_string();
+    // Ignore any query string, and split the rest of the URL into its parts
+    let segments: Vec<&str> = url
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    // Bodies without a length could still be too big, so only read one more byte than is allowed
+    let mut body = String::new();
+    if request
+        .as_reader()
+        .take(MAX_BODY_BYTES as u64 + 1)
+        .read_to_string(&mut body)
+        .is_err()
+    {
+        return error(400, "request body is not valid UTF-8");
+    }
+    if body.len() > MAX_BODY_BYTES {
+        return error(413, "request body is too big");
+    }
+
+    match (request.method(), segments.as_slice()) {
+        (Method::Get, ["list"]) => json(200, &ListJson::from(&*task_list)),
+        (Method::Patch, ["list"]) => match serde_json::from_str::<ListPatch>(&body) {
+            Ok(patch) => {
+                if let Some(name) = patch.name {
+                    task_list.name = name;
+                }
+                json(200, &ListJson::from(&*task_list))
+            }
+            Err(e) => error(400, &e.to_string()),
+        },
+        (Method::Get, ["list.ics"]) => Response::from_string(task_list.to_ical_string())
+            .with_header(content_type("text/calendar")),
+        (Method::Get, ["tasks"]) => json(200, &task_list.tasks),
+        (Method::Post, ["tasks"]) => {
+            let mut task = Task::default();
+            match serde_json::from_str::<TaskPatch>(&body) {
+                Ok(patch) => {
+                    if let Err(e) = patch.apply(&mut task) {
+                        return error(400, &e);
+                    }
+                }
+                Err(e) => return error(400, &e.to_string()),
+            }
+            let response = json(201, &task);
+            task_list.add(task);
+            response
+        }
+        (method, ["tasks", id, rest @ ..]) => {
+            let Some(index) = uuid::Uuid::parse_str(id)
+                .ok()
+                .and_then(|uuid| task_list.tasks.iter().position(|task| task.uuid == uuid))
+            else {
+                return error(404, "no task with that UUID");
+            };
+
+            match (method, rest) {
+                (Method::Get, []) => json(200, &task_list.tasks[index]),
+                (Method::Patch, []) => match serde_json::from_str::<TaskPatch>(&body) {
+                    Ok(patch) => {
+                        let task = &mut task_list.tasks[index];
+                        match patch.apply(task) {
+                            Ok(()) => json(200, &*task),
+                            Err(e) => error(400, &e),
+                        }
+                    }
+                    Err(e) => error(400, &e.to_string()),
+                },
+                (Method::Delete, []) => {
+                    let task = task_list.tasks.remove(index);
+                    json(200, &task)
+                }
+                (Method::Post, ["complete"]) => {
+                    let task = &mut task_list.tasks[index];
+                    task.completed = true;
+                    task.status = Status::Completed;
+                    json(200, &*task)
+                }
+                _ => error(404, "not found"),
+            }
+        }
+        _ => error(404, "not found"),
+    }
+}
+
+// Creates a response containing a value serialized as JSON
+fn json<T: Serialize>(status: u16, value: &T) -> HttpResponse {
+    match serde_json::to_string(value) {
+        Ok(text) => Response::from_string(text)
+            .with_status_code(status)
+            .with_header(content_type("application/json")),
+        Err(e) => error(500, &e.to_string()),
+    }
+}
+
+// Creates a JSON response describing an error
+fn error(status: u16, message: &str) -> HttpResponse {
+    Response::from_string(serde_json::json!({ "error": message }).to_string())
+        .with_status_code(status)
+        .with_header(content_type("application/json"))
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes("Content-Type", value).expect("content type header should be valid")
+}
+
+// JSON representation of a task list
+#[derive(Serialize)]
+struct ListJson {
+    name: String,
+    color: String,
+    tasks: usize,
+}
+
+impl From<&TaskList> for ListJson {
+    fn from(list: &TaskList) -> Self {
+        Self {
+            name: list.name.clone(),
+            color: list.color.to_hex(),
+            tasks: list.tasks.len(),
+        }
+    }
+}
+
+// Fields of a task list that a client can change
+#[derive(Deserialize)]
+struct ListPatch {
+    name: Option<String>,
+}
+
+// Fields of a task that a client can set when adding or changing a task.
+// Fields that are left out keep their current value, and an empty
+// due date or URL removes it from the task.
+#[derive(Deserialize)]
+struct TaskPatch {
+    summary: Option<String>,
+    completed: Option<bool>,
+    description: Option<String>,
+    url: Option<String>,
+    progress: Option<u8>,
+    priority: Option<u8>,
+    status: Option<String>,
+    due: Option<String>,
+}
+
+impl TaskPatch {
+    // Changes a task's fields to the ones set in the patch,
+    // without changing anything if any of the fields are invalid
+    fn apply(self, task: &mut Task) -> Result<(), String> {
+        let status = match &self.status {
+            Some(status) => Some(
+                Status::from_ical(status).ok_or_else(|| format!("invalid status '{status}'"))?,
+            ),
+            None => None,
+        };
+        let due = match &self.due {
+            Some(due) if due.is_empty() => Some(None),
+            Some(due) => Some(Some(
+                chrono::NaiveDate::parse_from_str(due, DATE_FORMAT)
+                    .map_err(|_| format!("invalid due date '{due}', expected YYYY-MM-DD"))?,
+            )),
+            None => None,
+        };
+        if self
+            .progress
+            .is_some_and(|progress| progress > MAX_PROGRESS)
+        {
+            return Err(format!("progress must be between 0 and {MAX_PROGRESS}"));
+        }
+        if self
+            .priority
+            .is_some_and(|priority| priority > MAX_PRIORITY)
+        {
+            return Err(format!("priority must be between 0 and {MAX_PRIORITY}"));
+        }
+
+        if let Some(summary) = self.summary {
+            task.summary = summary;
+        }
+        if let Some(completed) = self.completed {
+            task.completed = completed;
+        }
+        if let Some(description) = self.description {
+            task.description = description;
+        }
+        if let Some(url) = self.url {
+            task.url = if url.is_empty() { None } else { Some(url) };
+        }
+        if let Some(progress) = self.progress {
+            task.progress = Percent::new(progress);
+        }
+        if let Some(priority) = self.priority {
+            task.priority = Priority::new(priority);
+        }
+        if let Some(status) = status {
+            task.completed = status == Status::Completed;
+            task.status = status;
+        }
+        if let Some(due) = due {
+            task.due = due;
+        }
+        Ok(())
+    }
+}
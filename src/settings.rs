@@ -0,0 +1,122 @@
+// ----------------------------------------------------------------------------
+// Holds the user's settings, which change how the app behaves.
+// Each part of the app that can be configured has its own settings struct,
+// which is kept alongside the code that uses it.
+// ----------------------------------------------------------------------------
+
+use std::slice::Iter;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agenda::AgendaSettings;
+use crate::backup::BackupSettings;
+use crate::feed::FeedSettings;
+use crate::filters::StaleSettings;
+use crate::git_store::GitSettings;
+use crate::github::GitHubSettings;
+use crate::hooks::HookSettings;
+use crate::jira::JiraSettings;
+use crate::network::NetworkSettings;
+use crate::notifications::NotificationSettings;
+use crate::platform;
+use crate::platform::autostart::StartupSettings;
+use crate::publish::PublishSettings;
+use crate::quiet_hours::QuietHoursSettings;
+use crate::share::ShareSettings;
+use crate::sounds::SoundSettings;
+use crate::tags::TagRegistry;
+use crate::task_views::RowSettings;
+use crate::timezone::TimeZonePolicy;
+use crate::ui_elements::celebration::CelebrationStyle;
+use crate::ui_elements::import_export::ExportSettings;
+use crate::webhooks::WebhookSettings;
+
+// Name the settings are saved under, in the folder of the profile being used
+const SETTINGS_STATE: &str = "settings.json";
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub layout: LayoutMode,
+    // Turns off animations, for people who find motion on screen distracting or uncomfortable
+    pub reduce_motion: bool,
+    // Shows the tasks due over the next week in a sidebar on the right
+    pub agenda_sidebar: bool,
+    pub celebration: CelebrationStyle,
+    pub stale: StaleSettings,
+    // How much of each task is shown in its row
+    pub rows: RowSettings,
+    // Encrypts saved and exported task lists with a passphrase
+    pub encrypt: bool,
+    pub backup: BackupSettings,
+    pub export: ExportSettings,
+    pub sounds: SoundSettings,
+    pub hooks: HookSettings,
+    pub webhooks: WebhookSettings,
+    // The chat channel tasks are shared to
+    pub share: ShareSettings,
+    pub notifications: NotificationSettings,
+    pub quiet_hours: QuietHoursSettings,
+    // What happens to the times on tasks when the time zone changes
+    pub time_zone: TimeZonePolicy,
+    pub agenda: AgendaSettings,
+    // Read from the platform each time, since it can be changed outside the app
+    #[serde(skip)]
+    pub startup: StartupSettings,
+    pub publish: PublishSettings,
+    // Serving the list as a calendar feed, for calendar apps to subscribe to
+    pub feed: FeedSettings,
+    // Has to be sent with every request to the HTTP API. Kept in the keyring.
+    #[serde(skip)]
+    pub api_token: String,
+    // How the app connects to other servers, for everything that talks to one
+    pub network: NetworkSettings,
+    pub git: GitSettings,
+    pub github: GitHubSettings,
+    pub jira: JiraSettings,
+    // Colors and descriptions of tags, which are saved on their own as soon as they're changed
+    #[serde(skip)]
+    pub tags: TagRegistry,
+}
+
+impl Settings {
+    // Reads the settings that were saved last time, without the secrets,
+    // which are kept in the keyring, or the tags, which are saved on their own
+    pub fn load() -> Self {
+        platform::read_state(SETTINGS_STATE)
+            .and_then(|text| {
+                serde_json::from_str(&text)
+                    .map_err(|e| eprintln!("could not read {SETTINGS_STATE}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(std::io::Error::other)
+            .and_then(|text| platform::write_state(SETTINGS_STATE, &text));
+        if let Err(e) = result {
+            eprintln!("could not save settings: {e}");
+        }
+    }
+}
+
+// Which layout the app uses.
+// The mobile layout is meant for narrow, touch screens like phones, and
+// moves adding tasks to the bottom of the screen, makes everything bigger
+// and easier to tap, and hides anything that doesn't fit.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayoutMode {
+    // Picks the layout based on how wide the window is
+    #[default]
+    Automatic,
+    Desktop,
+    Mobile,
+}
+
+impl LayoutMode {
+    pub fn iterator() -> Iter<'static, Self> {
+        [Self::Automatic, Self::Desktop, Self::Mobile].iter()
+    }
+}
@@ -0,0 +1,155 @@
+// ----------------------------------------------------------------------------
+// Shares a task to a team's chat channel, for handing it to someone or
+// letting everyone know about it. The task's summary, due date and link are
+// posted as a message to a Slack or Discord webhook, which are told apart by
+// their URL. Other chat apps, like Mattermost, take Slack's messages too.
+// Posting needs the app to be built with the `http` feature.
+// ----------------------------------------------------------------------------
+
+use std::error::Error;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::json::DATE_FORMAT;
+use crate::network::NetworkSettings;
+use crate::task::Task;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShareSettings {
+    // The channel's webhook, like https://hooks.slack.com/services/…
+    pub webhook_url: String,
+}
+
+// The chat apps messages can be formatted for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatApp {
+    Slack,
+    Discord,
+}
+
+impl ChatApp {
+    /// Which chat app a webhook belongs to, going by its URL.
+    /// Webhooks that aren't Discord's are sent Slack's messages.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::share::ChatApp;
+    ///
+    /// assert_eq!(ChatApp::for_url("https://discord.com/api/webhooks/1/abc"), ChatApp::Discord);
+    /// assert_eq!(ChatApp::for_url("https://hooks.slack.com/services/T0/B0/x"), ChatApp::Slack);
+    /// assert_eq!(ChatApp::for_url("https://chat.example.com/hooks/x"), ChatApp::Slack);
+    /// ```
+    pub fn for_url(url: &str) -> Self {
+        let host = url
+            .split_once("://")
+            .map_or(url, |(_, rest)| rest)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if ["discord.com", "discordapp.com"]
+            .iter()
+            .any(|discord| host == *discord || host.ends_with(&format!(".{discord}")))
+        {
+            Self::Discord
+        } else {
+            Self::Slack
+        }
+    }
+}
+
+/// The message a task is shared as, in the chat app's markdown.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::share::{self, ChatApp};
+/// use taskmaster_rs::task::Task;
+///
+/// let task = Task {
+///     summary: String::from("Review the budget"),
+///     due: NaiveDate::from_ymd_opt(2024, 5, 1),
+///     url: Some(String::from("https://example.com/budget")),
+///     ..Task::default()
+/// };
+/// assert_eq!(
+///     share::message(&task, ChatApp::Slack),
+///     "*Review the budget*\nDue 2024-05-01\n<https://example.com/budget>"
+/// );
+/// assert_eq!(
+///     share::message(&task, ChatApp::Discord),
+///     "**Review the budget**\nDue 2024-05-01\n<https://example.com/budget>"
+/// );
+/// ```
+pub fn message(task: &Task, app: ChatApp) -> String {
+    let bold = match app {
+        ChatApp::Slack => "*",
+        ChatApp::Discord => "**",
+    };
+    let mut lines = vec![format!("{bold}{}{bold}", task.summary.trim())];
+    if let Some(due) = task.due {
+        lines.push(format!("Due {}", due.format(DATE_FORMAT)));
+    }
+    if let Some(url) = task.url.as_deref().filter(|url| !url.trim().is_empty()) {
+        // Both apps link the URL without showing a preview of it when it's in angle brackets
+        lines.push(format!("<{}>", url.trim()));
+    }
+    lines.join("\n")
+}
+
+// The JSON that's posted to the webhook for a task
+pub fn payload(task: &Task, app: ChatApp) -> String {
+    let key = match app {
+        ChatApp::Slack => "text",
+        ChatApp::Discord => "content",
+    };
+    serde_json::json!({ key: message(task, app) }).to_string()
+}
+
+// Task views ask for a task to be shared through egui's memory, since they can't reach the settings
+fn request_id() -> egui::Id {
+    egui::Id::new("share_request")
+}
+
+// Asks for a task to be shared
+pub fn request(ctx: &egui::Context, uuid: uuid::Uuid) {
+    ctx.data_mut(|d| d.insert_temp(request_id(), uuid));
+}
+
+// The task that was last asked to be shared, if it hasn't been shared yet
+pub fn take_request(ctx: &egui::Context) -> Option<uuid::Uuid> {
+    ctx.data_mut(|d| {
+        let request = d.get_temp(request_id());
+        d.remove::<uuid::Uuid>(request_id());
+        request
+    })
+}
+
+// Posts a task to the channel in the background
+pub fn share(task: &Task, settings: &ShareSettings, network: &NetworkSettings) {
+    let url = settings.webhook_url.trim().to_string();
+    let body = payload(task, ChatApp::for_url(&url));
+    let network = network.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = post(&url, &body, &network) {
+            eprintln!("could not share task to {url}: {e}");
+        }
+    });
+}
+
+#[cfg(feature = "http")]
+fn post(url: &str, body: &str, network: &NetworkSettings) -> Result<(), Box<dyn Error>> {
+    network
+        .agent()?
+        .post(url)
+        .set("Content-Type", "application/json")
+        .send_string(body)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "http"))]
+fn post(_url: &str, _body: &str, _network: &NetworkSettings) -> Result<(), Box<dyn Error>> {
+    Err("this build of the app can't share tasks, it needs the `http` feature".into())
+}
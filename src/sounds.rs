@@ -0,0 +1,78 @@
+// ----------------------------------------------------------------------------
+// Sound effects which are played when a task is completed and when a task
+// becomes due soon, since a reminder that only shows up on screen is easy
+// to miss. Sounds are made of simple tones, so that no audio files have to
+// be shipped with the app.
+// ----------------------------------------------------------------------------
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::platform::{self, Tone};
+use crate::task::Percent;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    // Volume of every sound, as a percentage
+    pub volume: Percent,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: Percent::new(50),
+        }
+    }
+}
+
+// Sounds the app can play
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    // A short rising chime
+    Completed,
+    // Three quick beeps, repeated twice, to get the user's attention
+    Reminder,
+}
+
+impl Sound {
+    fn tones(self) -> Vec<Tone> {
+        match self {
+            Self::Completed => vec![tone(660., 90), tone(880., 160)],
+            Self::Reminder => {
+                let beeps = [tone(1000., 90), tone(0., 60)].repeat(3);
+                [beeps.as_slice(), &[tone(0., 250)], beeps.as_slice()].concat()
+            }
+        }
+    }
+}
+
+const fn tone(frequency: f32, millis: u64) -> Tone {
+    Tone {
+        frequency,
+        duration: Duration::from_millis(millis),
+    }
+}
+
+// Plays sounds, if the user has turned them on
+#[derive(Default)]
+pub struct SoundPlayer {
+    output: platform::AudioOutput,
+}
+
+impl SoundPlayer {
+    pub fn play(&mut self, sound: Sound, settings: &SoundSettings) {
+        if !settings.enabled || settings.volume.get() == 0 {
+            return;
+        }
+
+        let volume = f32::from(settings.volume.get()) / 100.;
+        if let Err(e) = self.output.play(&sound.tones(), volume) {
+            eprintln!("could not play sound: {e}");
+        }
+    }
+}
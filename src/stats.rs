@@ -0,0 +1,164 @@
+// ----------------------------------------------------------------------------
+// Statistics about a task list, like how many tasks were completed on each
+// day, which are shown in the statistics window.
+// ----------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::task::{Task, TaskList};
+
+/// The tasks that were completed on each day from `start` to `end`,
+/// including both. Days that nothing was completed on are left out.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::stats;
+/// use taskmaster_rs::task::*;
+///
+/// let day = |day| NaiveDate::from_ymd_opt(2023, 8, day).unwrap();
+/// let completed = |n| Task {
+///     completed: true,
+///     completed_at: day(n).and_hms_opt(9, 0, 0),
+///     ..Task::default()
+/// };
+/// let list = TaskList {
+///     tasks: vec![completed(7), completed(7), completed(8), completed(20), Task::default()],
+///     ..Default::default()
+/// };
+///
+/// let days = stats::completions_by_day(&list, day(1), day(10));
+/// assert_eq!(days.len(), 2);
+/// assert_eq!(days[&day(7)].len(), 2);
+/// assert_eq!(days[&day(8)].len(), 1);
+/// ```
+pub fn completions_by_day(
+    task_list: &TaskList,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> BTreeMap<NaiveDate, Vec<&Task>> {
+    let mut days: BTreeMap<NaiveDate, Vec<&Task>> = BTreeMap::new();
+    for task in task_list.tasks.iter().filter(|task| task.completed) {
+        let Some(day) = task.completed_at.map(|time| time.date()) else {
+            continue;
+        };
+        if (start..=end).contains(&day) {
+            days.entry(day).or_default().push(task);
+        }
+    }
+    days
+}
+
+// How much work a task stands for, in minutes. Tasks that haven't been
+// estimated count as an hour, so that they still show up on the burndown.
+const UNESTIMATED_MINUTES: u32 = 60;
+
+fn work(task: &Task) -> u32 {
+    task.estimate.unwrap_or(UNESTIMATED_MINUTES)
+}
+
+// Whether a task still needed doing at the end of a day
+pub(crate) fn remaining_on(task: &Task, day: NaiveDate) -> bool {
+    let created = task.created.date() <= day;
+    let completed = task.completed
+        && task
+            .completed_at
+            .map_or(true, |completed_at| completed_at.date() <= day);
+    created && !completed
+}
+
+/// How many minutes of work were left at the end of each day from `start` to
+/// `end`. Tasks count from the day they were created until the day they
+/// were completed, and tasks without an estimate count as an hour.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::stats;
+/// use taskmaster_rs::task::*;
+///
+/// let day = |n| NaiveDate::from_ymd_opt(2023, 8, n).unwrap();
+/// let list = TaskList {
+///     tasks: vec![
+///         Task { created: day(1).and_hms_opt(9, 0, 0).unwrap(), estimate: Some(30), ..Task::default() },
+///         Task {
+///             created: day(2).and_hms_opt(9, 0, 0).unwrap(),
+///             completed: true,
+///             completed_at: day(3).and_hms_opt(12, 0, 0),
+///             ..Task::default()
+///         },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let burndown = stats::burndown(&list, day(1), day(3));
+/// assert_eq!(burndown, [(day(1), 30), (day(2), 90), (day(3), 30)]);
+/// ```
+pub fn burndown(task_list: &TaskList, start: NaiveDate, end: NaiveDate) -> Vec<(NaiveDate, u32)> {
+    start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .map(|day| {
+            let remaining = task_list
+                .tasks
+                .iter()
+                .filter(|task| remaining_on(task, day))
+                .map(work)
+                .sum();
+            (day, remaining)
+        })
+        .collect()
+}
+
+/// Guesses when all of the work left in a list will be done, going by how
+/// much work was completed each day over the last `days` days. Gives back
+/// nothing if no work was completed then, since there's nothing to go by.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::stats;
+/// use taskmaster_rs::task::*;
+///
+/// let today = NaiveDate::from_ymd_opt(2023, 8, 10).unwrap();
+/// let done = Task {
+///     estimate: Some(120),
+///     completed: true,
+///     completed_at: today.and_hms_opt(9, 0, 0),
+///     ..Task::default()
+/// };
+/// let left = Task { estimate: Some(60), ..Task::default() };
+/// let list = TaskList { tasks: vec![done, left.clone(), left], ..Default::default() };
+///
+/// // Two hours were done in the last two days, so the two hours left take two more days
+/// assert_eq!(stats::forecast(&list, today, 2), NaiveDate::from_ymd_opt(2023, 8, 12));
+/// ```
+pub fn forecast(task_list: &TaskList, today: NaiveDate, days: u32) -> Option<NaiveDate> {
+    let since = today - chrono::Duration::days(i64::from(days));
+    let done: u32 = task_list
+        .tasks
+        .iter()
+        .filter(|task| {
+            task.completed
+                && task
+                    .completed_at
+                    .is_some_and(|time| time.date() > since && time.date() <= today)
+        })
+        .map(work)
+        .sum();
+    if done == 0 {
+        return None;
+    }
+    let remaining: u32 = task_list
+        .tasks
+        .iter()
+        .filter(|task| !task.completed)
+        .map(work)
+        .sum();
+    // Work done per day, rounding the days left up so the forecast isn't too hopeful
+    let days_left =
+        (u64::from(remaining) * u64::from(days) + u64::from(done) - 1) / u64::from(done);
+    today.checked_add_signed(chrono::Duration::days(i64::try_from(days_left).ok()?))
+}
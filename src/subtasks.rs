@@ -0,0 +1,132 @@
+// ----------------------------------------------------------------------------
+// Subtasks, which are tasks that belong to another task, like the steps of
+// a bigger piece of work. A subtask keeps the UUID of the task it belongs
+// to, which is saved in its RELATED-TO. Tasks can be demoted to subtasks of
+// the task above them, or promoted back up a level, like in an outline, and
+// a task's subtasks can be hidden, which Nextcloud Tasks keeps in the file.
+// ----------------------------------------------------------------------------
+
+use uuid::Uuid;
+
+use crate::task::TaskList;
+
+impl TaskList {
+    // Where the task with a UUID is in the list
+    fn index_of(&self, uuid: Uuid) -> Option<usize> {
+        self.tasks.iter().position(|task| task.uuid == uuid)
+    }
+
+    // How many levels of parents a task has. Parents that aren't in the list
+    // don't count, and a loop of parents stops once it comes back around.
+    pub fn depth(&self, index: usize) -> usize {
+        let mut depth = 0;
+        let mut parent = self.tasks.get(index).and_then(|task| task.parent);
+        while let Some(index) = parent.and_then(|uuid| self.index_of(uuid)) {
+            depth += 1;
+            if depth >= self.tasks.len() {
+                break;
+            }
+            parent = self.tasks[index].parent;
+        }
+        depth
+    }
+
+    /// Whether a task is hidden because one of the tasks it's under has its
+    /// subtasks hidden, like a collapsed task in Nextcloud Tasks.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let report = Task { hide_subtasks: true, ..Task::default() };
+    /// let numbers = Task { parent: Some(report.uuid), ..Task::default() };
+    /// let sums = Task { parent: Some(numbers.uuid), ..Task::default() };
+    /// let list = TaskList { tasks: vec![report, numbers, sums], ..Default::default() };
+    ///
+    /// assert!(!list.hidden_by_parent(0));
+    /// assert!(list.hidden_by_parent(1));
+    /// assert!(list.hidden_by_parent(2));
+    /// ```
+    pub fn hidden_by_parent(&self, index: usize) -> bool {
+        let mut levels = 0;
+        let mut parent = self.tasks.get(index).and_then(|task| task.parent);
+        while let Some(index) = parent.and_then(|uuid| self.index_of(uuid)) {
+            if self.tasks[index].hide_subtasks {
+                return true;
+            }
+            levels += 1;
+            if levels >= self.tasks.len() {
+                break;
+            }
+            parent = self.tasks[index].parent;
+        }
+        false
+    }
+
+    /// Makes a task a subtask of the task above it at the same level, like
+    /// indenting it in an outline. The task and its own subtasks keep their
+    /// UUIDs. Returns whether there was a task above it to go under.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let mut list = TaskList {
+    ///     tasks: vec![Task::default(), Task::default(), Task::default()],
+    ///     ..Default::default()
+    /// };
+    /// let uuids: Vec<_> = list.tasks.iter().map(|task| task.uuid).collect();
+    ///
+    /// // The first task has nothing above it
+    /// assert!(!list.demote(uuids[0]));
+    ///
+    /// assert!(list.demote(uuids[1]));
+    /// assert!(list.demote(uuids[2]));
+    /// assert_eq!(list.tasks[1].parent, Some(uuids[0]));
+    /// // Both are now under the first task, so the third goes under the second
+    /// assert!(list.demote(uuids[2]));
+    /// assert_eq!(list.tasks[2].parent, Some(uuids[1]));
+    /// assert_eq!(list.depth(2), 2);
+    ///
+    /// assert!(list.promote(uuids[2]));
+    /// assert_eq!(list.tasks[2].parent, Some(uuids[0]));
+    /// assert!(list.promote(uuids[2]));
+    /// assert_eq!(list.tasks[2].parent, None);
+    /// assert!(!list.promote(uuids[2]));
+    /// ```
+    pub fn demote(&mut self, uuid: Uuid) -> bool {
+        let Some(index) = self.index_of(uuid) else {
+            return false;
+        };
+        let parent = self.tasks[index].parent;
+        // Tasks with the same parent can't be below this one in the outline,
+        // so going under one can't make a loop
+        let Some(above) = self.tasks[..index]
+            .iter()
+            .rev()
+            .find(|task| task.parent == parent)
+            .map(|task| task.uuid)
+        else {
+            return false;
+        };
+        self.tasks[index].parent = Some(above);
+        true
+    }
+
+    // Moves a subtask up a level, so that it's at the same level as its parent.
+    // Returns whether the task was a subtask.
+    pub fn promote(&mut self, uuid: Uuid) -> bool {
+        let Some(index) = self.index_of(uuid) else {
+            return false;
+        };
+        let Some(parent) = self.tasks[index].parent else {
+            return false;
+        };
+        // A parent that isn't in the list has no parent of its own to move up to
+        let grandparent = self
+            .index_of(parent)
+            .and_then(|parent| self.tasks[parent].parent);
+        self.tasks[index].parent = grandparent;
+        true
+    }
+}
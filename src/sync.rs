@@ -0,0 +1,522 @@
+// ----------------------------------------------------------------------------
+// Binds each list to where it syncs to, like its own file, the WebDAV server
+// it's published to or a git repository, so that a shopping list can be
+// published for the family while a work list is only kept in git. Lists that
+// haven't been bound sync to everywhere that's turned on in the settings.
+// Bindings are saved with the app's other files, keyed by each list's UID, so
+// they follow a list when it's renamed or moved.
+//
+// Other services can be synced with by implementing SyncBackend, which only
+// has to fetch a list from the service and push it back. The app registers
+// each backend with its SyncManager, which calls them on another thread every
+// so often, works out which side changed since they last matched, and brings
+// changes from the service into the list.
+// ----------------------------------------------------------------------------
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::Duration,
+};
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::encryption::EncryptionKey;
+use crate::list_file::FileWatcher;
+use crate::settings::Settings;
+use crate::sync_log::SyncLog;
+use crate::task::{Task, TaskList};
+
+// Name the bindings are saved under
+const BINDINGS_STATE: &str = "sync-bindings.json";
+
+/// Why a backend couldn't sync
+#[allow(clippy::module_name_repetitions)]
+pub type SyncError = Box<dyn Error + Send + Sync>;
+
+/// Somewhere on a service that lists can be synced with, like a calendar on a
+/// CalDAV server or one of the task lists in an account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collection {
+    /// Identifies the collection to the backend, so it shouldn't change
+    pub id: String,
+    /// What the collection is called when picking where a list syncs to
+    pub name: String,
+}
+
+/// Trait for syncing lists with another service, which crates that run the
+/// app can implement to add their own services without changing this one.
+/// Backends are called on another thread, so they can take as long as they need.
+///
+/// Example:
+/// ```
+/// use std::sync::Mutex;
+/// use taskmaster_rs::sync::{Collection, SyncBackend, SyncError};
+/// use taskmaster_rs::task::*;
+///
+/// // Keeps lists in memory, as a service would keep them on a server
+/// #[derive(Default)]
+/// struct Memory(Mutex<Option<TaskList>>);
+///
+/// impl SyncBackend for Memory {
+///     fn id(&self) -> &'static str {
+///         "memory"
+///     }
+///     fn name(&self) -> &'static str {
+///         "Memory"
+///     }
+///     fn collections(&self) -> Result<Vec<Collection>, SyncError> {
+///         Ok(vec![Collection { id: "inbox".into(), name: "Inbox".into() }])
+///     }
+///     fn fetch(&self, _collection: Option<&str>) -> Result<Option<TaskList>, SyncError> {
+///         Ok(self.0.lock().unwrap().clone())
+///     }
+///     fn push(&self, _collection: Option<&str>, list: &TaskList) -> Result<String, SyncError> {
+///         *self.0.lock().unwrap() = Some(list.clone());
+///         Ok(format!("Kept {} tasks", list.tasks.len()))
+///     }
+/// }
+///
+/// let backend = Memory::default();
+/// let list = TaskList { tasks: vec![Task::default()], ..Default::default() };
+/// assert_eq!(backend.push(None, &list).unwrap(), "Kept 1 tasks");
+///
+/// // Lists changed on both sides are merged by default, keeping whichever
+/// // copy of each task was changed last
+/// let remote = backend.fetch(None).unwrap().unwrap();
+/// let local = TaskList { tasks: vec![Task::default()], ..list.clone() };
+/// assert_eq!(backend.resolve_conflict(&local, &remote).tasks.len(), 2);
+/// ```
+pub trait SyncBackend: Send + Sync {
+    /// Identifies the backend in the saved bindings, so it shouldn't change
+    fn id(&self) -> &'static str;
+
+    /// What the backend is called when picking where a list syncs to
+    fn name(&self) -> &'static str;
+
+    /// Whether lists that haven't been bound to a backend sync to this one
+    fn enabled(&self, _settings: &Settings) -> bool {
+        false
+    }
+
+    /// Whether a list can be bound to this backend
+    fn available(&self, _task_list: &TaskList) -> bool {
+        true
+    }
+
+    /// How often lists bound to the backend are synced
+    fn interval(&self) -> Duration {
+        Duration::from_secs(5 * 60)
+    }
+
+    /// The collections on the service that a list can be bound to. Backends that
+    /// only keep one list, or work out where it goes themselves, don't have any.
+    fn collections(&self) -> Result<Vec<Collection>, SyncError> {
+        Ok(Vec::new())
+    }
+
+    /// Gets the list as it is on the service, or nothing if it isn't there yet
+    fn fetch(&self, collection: Option<&str>) -> Result<Option<TaskList>, SyncError>;
+
+    /// Sends the list to the service, giving back what was done, for the sync log
+    fn push(&self, collection: Option<&str>, task_list: &TaskList) -> Result<String, SyncError>;
+
+    /// Works out what the list should be when it's changed both in the app
+    /// and on the service since they last matched. By default, the lists are
+    /// merged, keeping whichever copy of each task was changed last.
+    fn resolve_conflict(&self, local: &TaskList, remote: &TaskList) -> TaskList {
+        let mut merged = local.clone();
+        merged.merge(remote.clone());
+        merged
+    }
+}
+
+// What the app's own ways of syncing need from the rest of the app
+pub(crate) struct SyncContext<'a> {
+    pub settings: &'a Settings,
+    // The list is encrypted with this before it leaves the app, if it's given
+    pub key: Option<&'a EncryptionKey>,
+    pub log: &'a SyncLog,
+    // Watches the list's own file, which has to be told when the list is written to it
+    pub file_watcher: &'a mut FileWatcher,
+}
+
+// The app's own ways of syncing, like publishing, which are checked every frame
+// and work out for themselves when they need to do something
+pub(crate) trait ListSyncer {
+    fn id(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    fn enabled(&self, settings: &Settings) -> bool;
+
+    fn available(&self, _task_list: &TaskList) -> bool {
+        true
+    }
+
+    // Syncs the list if it's time to. Called every frame for the syncers the list is bound to.
+    fn sync(&mut self, ctx: &egui::Context, task_list: &TaskList, context: &mut SyncContext<'_>);
+
+    // Called every frame instead of sync when the list isn't bound to the syncer
+    fn skip(&mut self) {}
+}
+
+// Where a list syncs to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ListSync {
+    // Everywhere that's turned on in the settings
+    #[default]
+    Default,
+    // Nowhere
+    Off,
+    // Only the backend with this ID
+    Backend(String),
+    // Only a collection on the backend with this ID
+    Collection {
+        backend: String,
+        collection: String,
+    },
+}
+
+impl ListSync {
+    // The backend the list is bound to, if it's bound to one
+    pub fn backend(&self) -> Option<&str> {
+        match self {
+            Self::Backend(backend) | Self::Collection { backend, .. } => Some(backend),
+            Self::Default | Self::Off => None,
+        }
+    }
+
+    pub fn collection(&self) -> Option<&str> {
+        match self {
+            Self::Collection { collection, .. } => Some(collection),
+            _ => None,
+        }
+    }
+}
+
+// What a backend has done with the lists bound to it, which its thread shares
+#[derive(Default)]
+struct RemoteState {
+    // Whether the backend's thread is still syncing
+    running: bool,
+    // The tasks of each list, by UID, as they were when the list last matched the
+    // service, so that it can be told which side has changed since
+    synced: HashMap<uuid::Uuid, Vec<Task>>,
+    // The tasks a list should have after syncing, if they're different to the list's,
+    // and what the list's tasks were when syncing started
+    incoming: Option<(uuid::Uuid, Vec<Task>, Vec<Task>)>,
+    // The collections on the service, once they've been listed
+    collections: Vec<Collection>,
+}
+
+// Syncs lists with a backend that was registered with the app
+struct RemoteSync {
+    backend: Arc<dyn SyncBackend>,
+    state: Arc<Mutex<RemoteState>>,
+    // When lists were last synced with the backend, in seconds since the app started
+    last_check: Option<f64>,
+}
+
+impl RemoteSync {
+    // Starts listing the backend's collections in the background
+    fn new(backend: Arc<dyn SyncBackend>) -> Self {
+        let state = Arc::new(Mutex::new(RemoteState::default()));
+        let listed = Arc::clone(&state);
+        let lister = Arc::clone(&backend);
+        thread::spawn(move || match lister.collections() {
+            Ok(collections) => lock(&listed).collections = collections,
+            Err(e) => eprintln!("could not list the collections in {}: {e}", lister.name()),
+        });
+        Self {
+            backend,
+            state,
+            last_check: None,
+        }
+    }
+
+    // Syncs the list on another thread, if it's time to
+    fn sync(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        collection: Option<&str>,
+        log: &SyncLog,
+    ) {
+        let now = ctx.input(|i| i.time);
+        let interval = self.backend.interval().as_secs_f64().max(1.);
+        if self.last_check.is_some_and(|last| now - last < interval) || lock(&self.state).running {
+            return;
+        }
+        self.last_check = Some(now);
+        ctx.request_repaint_after(Duration::from_secs_f64(interval));
+        lock(&self.state).running = true;
+
+        let backend = Arc::clone(&self.backend);
+        let state = Arc::clone(&self.state);
+        let local = task_list.clone();
+        let collection = collection.map(str::to_string);
+        let sync = log.start(backend.name(), task_list);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let synced = lock(&state).synced.get(&local.uuid).cloned();
+            let result = sync_with(&*backend, collection.as_deref(), &local, synced.as_ref());
+            let mut state = lock(&state);
+            state.running = false;
+            match result {
+                Ok((tasks, message)) => {
+                    if tasks != local.tasks {
+                        state.incoming = Some((local.uuid, local.tasks, tasks.clone()));
+                    }
+                    state.synced.insert(local.uuid, tasks);
+                    sync.finish(Ok(message));
+                }
+                Err(e) => sync.finish(Err(e.to_string())),
+            }
+            ctx.request_repaint();
+        });
+    }
+}
+
+// Syncs a list with a backend, giving back the tasks the list should have, and what was done
+fn sync_with(
+    backend: &dyn SyncBackend,
+    collection: Option<&str>,
+    local: &TaskList,
+    synced: Option<&Vec<Task>>,
+) -> Result<(Vec<Task>, String), SyncError> {
+    let remote = backend.fetch(collection)?;
+    let local_changed = synced != Some(&local.tasks);
+    let remote_changed = remote
+        .as_ref()
+        .is_some_and(|remote| synced != Some(&remote.tasks));
+    let result = match &remote {
+        Some(remote) if local_changed && remote_changed => {
+            backend.resolve_conflict(local, remote).tasks
+        }
+        Some(remote) if remote_changed => remote.tasks.clone(),
+        _ => local.tasks.clone(),
+    };
+
+    if remote.as_ref().is_some_and(|remote| remote.tasks == result) {
+        return Ok((result, String::from("Already up to date")));
+    }
+    let pushed = TaskList {
+        tasks: result,
+        ..local.clone()
+    };
+    let message = backend.push(collection, &pushed)?;
+    Ok((pushed.tasks, message))
+}
+
+fn lock(state: &Mutex<RemoteState>) -> MutexGuard<'_, RemoteState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Every backend lists can sync to, and which ones each list is bound to.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::sync::{ListSync, SyncBackend, SyncError, SyncManager};
+/// use taskmaster_rs::task::TaskList;
+///
+/// struct Printer;
+///
+/// impl SyncBackend for Printer {
+///     fn id(&self) -> &'static str {
+///         "printer"
+///     }
+///     fn name(&self) -> &'static str {
+///         "Printer"
+///     }
+///     fn fetch(&self, _collection: Option<&str>) -> Result<Option<TaskList>, SyncError> {
+///         Ok(None)
+///     }
+///     fn push(&self, _collection: Option<&str>, list: &TaskList) -> Result<String, SyncError> {
+///         println!("{}", list.to_ical_string());
+///         Ok(String::from("Printed"))
+///     }
+/// }
+///
+/// let mut manager = SyncManager::default();
+/// manager.register(Printer);
+/// assert_eq!(manager.backends(), vec![("printer", "Printer")]);
+///
+/// let list = TaskList::default();
+/// assert_eq!(manager.binding(&list), &ListSync::Default);
+/// manager.bind(&list, ListSync::Backend(String::from("printer")));
+/// assert_eq!(manager.binding(&list), &ListSync::Backend(String::from("printer")));
+/// ```
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct SyncManager {
+    // The app's own ways of syncing
+    syncers: Vec<Box<dyn ListSyncer>>,
+    // Backends that were registered by crates that run the app
+    remotes: Vec<RemoteSync>,
+    bindings: BTreeMap<uuid::Uuid, ListSync>,
+}
+
+impl SyncManager {
+    // Reads where lists were bound to sync to last time
+    pub fn load() -> Self {
+        let bindings = crate::platform::read_state(BINDINGS_STATE)
+            .and_then(|text| {
+                serde_json::from_str(&text)
+                    .map_err(|e| eprintln!("could not read {BINDINGS_STATE}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        Self {
+            bindings,
+            ..Self::default()
+        }
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_string_pretty(&self.bindings)
+            .map_err(std::io::Error::other)
+            .and_then(|text| crate::platform::write_state(BINDINGS_STATE, &text));
+        if let Err(e) = result {
+            eprintln!("could not save where lists sync to: {e}");
+        }
+    }
+
+    // Adds one of the app's own ways of syncing
+    pub(crate) fn add_syncer(&mut self, syncer: Box<dyn ListSyncer>) {
+        self.syncers.push(syncer);
+    }
+
+    /// Adds a backend that lists can be bound to. Backends with the same ID
+    /// as one that's already been registered replace it.
+    pub fn register(&mut self, backend: impl SyncBackend + 'static) {
+        self.remotes
+            .retain(|added| added.backend.id() != backend.id());
+        self.remotes.push(RemoteSync::new(Arc::new(backend)));
+    }
+
+    // Keeps the backends registered with another manager, like the one for the
+    // profile that's being left, since they were registered for the whole app
+    pub(crate) fn keep_registered(&mut self, other: &mut Self) {
+        for remote in other.remotes.drain(..) {
+            let id = remote.backend.id();
+            if !self.remotes.iter().any(|kept| kept.backend.id() == id) {
+                self.remotes.push(remote);
+            }
+        }
+    }
+
+    /// The ID and name of every backend, in the order they were added
+    pub fn backends(&self) -> Vec<(&'static str, &'static str)> {
+        let syncers = self
+            .syncers
+            .iter()
+            .map(|syncer| (syncer.id(), syncer.name()));
+        let remotes = self
+            .remotes
+            .iter()
+            .map(|remote| (remote.backend.id(), remote.backend.name()));
+        syncers.chain(remotes).collect()
+    }
+
+    /// Every backend a list can be bound to, with the collections on it that have been listed
+    pub fn available(
+        &self,
+        task_list: &TaskList,
+    ) -> Vec<(&'static str, &'static str, Vec<Collection>)> {
+        let syncers = self
+            .syncers
+            .iter()
+            .filter(|syncer| syncer.available(task_list))
+            .map(|syncer| (syncer.id(), syncer.name(), Vec::new()));
+        let remotes = self
+            .remotes
+            .iter()
+            .filter(|remote| remote.backend.available(task_list))
+            .map(|remote| {
+                let collections = lock(&remote.state).collections.clone();
+                (remote.backend.id(), remote.backend.name(), collections)
+            });
+        syncers.chain(remotes).collect()
+    }
+
+    pub fn binding(&self, task_list: &TaskList) -> &ListSync {
+        static DEFAULT: ListSync = ListSync::Default;
+        self.bindings.get(&task_list.uuid).unwrap_or(&DEFAULT)
+    }
+
+    /// Binds a list to sync somewhere, and saves it
+    pub fn bind(&mut self, task_list: &TaskList, binding: ListSync) {
+        if *self.binding(task_list) == binding {
+            return;
+        }
+        if binding == ListSync::Default {
+            self.bindings.remove(&task_list.uuid);
+        } else {
+            self.bindings.insert(task_list.uuid, binding);
+        }
+        self.save();
+    }
+
+    // Hands the list to the backends it's bound to. Should be called once every frame.
+    pub(crate) fn check(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        context: &mut SyncContext<'_>,
+    ) {
+        let binding = self.binding(task_list).clone();
+        let bound = |id: &str, enabled: bool, available: bool| match &binding {
+            ListSync::Default => enabled,
+            bound => bound.backend() == Some(id) && available,
+        };
+        for syncer in &mut self.syncers {
+            if bound(
+                syncer.id(),
+                syncer.enabled(context.settings),
+                syncer.available(task_list),
+            ) {
+                syncer.sync(ctx, task_list, context);
+            } else {
+                syncer.skip();
+            }
+        }
+        for remote in &mut self.remotes {
+            let backend = &remote.backend;
+            if bound(
+                backend.id(),
+                backend.enabled(context.settings),
+                backend.available(task_list),
+            ) {
+                remote.sync(ctx, task_list, binding.collection(), context.log);
+            }
+        }
+    }
+
+    // Takes the changes a backend brought from its service into the list, if
+    // there are any. Lists that have been changed while they were syncing are
+    // merged with the changes, rather than losing what was changed.
+    pub(crate) fn take_incoming(&self, task_list: &mut TaskList) {
+        for remote in &self.remotes {
+            let mut state = lock(&remote.state);
+            if state.incoming.as_ref().map(|(list, ..)| *list) != Some(task_list.uuid) {
+                continue;
+            }
+            let Some((_, synced_from, tasks)) = state.incoming.take() else {
+                continue;
+            };
+            if task_list.tasks == synced_from {
+                task_list.tasks = tasks;
+            } else {
+                task_list.merge(TaskList {
+                    tasks,
+                    ..TaskList::default()
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+// ----------------------------------------------------------------------------
+// Keeps track of lists being synced to other places, like being published to
+// a WebDAV server or pushed to a git remote: whether a sync is going on or
+// the last one failed, when each list was last synced, and a log of what
+// happened, so that it can be worked out why a change didn't get somewhere.
+// Syncs run on other threads, so the log is shared with them.
+// ----------------------------------------------------------------------------
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use chrono::{Local, NaiveDateTime, Timelike};
+
+use crate::platform;
+use crate::task::TaskList;
+
+// Name the times lists were last synced are saved under
+const LAST_SYNCED_STATE: &str = "last-synced.json";
+// How many entries the log keeps, dropping the oldest ones
+const MAX_ENTRIES: usize = 500;
+
+// Whether anything is being synced, or what went wrong with the last sync that failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum SyncStatus {
+    Idle,
+    Syncing,
+    Error(String),
+}
+
+// Something that happened while syncing
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct SyncEntry {
+    pub time: NaiveDateTime,
+    // What did the syncing, like "Publish" or "Git"
+    pub source: &'static str,
+    // Name of the list that was synced
+    pub list: String,
+    pub message: String,
+    pub failed: bool,
+}
+
+#[derive(Default)]
+struct State {
+    // How many syncs haven't finished yet
+    running: usize,
+    // Why the last sync failed, until one succeeds
+    error: Option<String>,
+    entries: VecDeque<SyncEntry>,
+    // When each list, by UID, last synced without a problem
+    last_synced: BTreeMap<uuid::Uuid, NaiveDateTime>,
+    // Whether the times have changed since they were saved
+    unsaved: bool,
+}
+
+/// The status and log of every sync. Cloning it gives another handle to the
+/// same log, which can be sent to the thread doing the syncing.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::sync_log::{SyncLog, SyncStatus};
+/// use taskmaster_rs::task::TaskList;
+///
+/// let log = SyncLog::default();
+/// let list = TaskList::default();
+///
+/// let sync = log.start("Publish", &list);
+/// assert_eq!(log.status(), SyncStatus::Syncing);
+/// sync.finish(Err(String::from("server not found")));
+/// assert_eq!(log.status(), SyncStatus::Error(String::from("server not found")));
+/// assert_eq!(log.last_synced(&list), None);
+///
+/// log.start("Publish", &list).finish(Ok(String::from("Published")));
+/// assert_eq!(log.status(), SyncStatus::Idle);
+/// assert!(log.last_synced(&list).is_some());
+/// assert_eq!(log.entries().len(), 2);
+/// assert!(log.entries()[0].failed);
+/// ```
+#[derive(Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct SyncLog(Arc<Mutex<State>>);
+
+impl SyncLog {
+    // Reads when lists were last synced, as it was saved last time
+    pub fn load() -> Self {
+        let last_synced = platform::read_state(LAST_SYNCED_STATE)
+            .and_then(|text| {
+                serde_json::from_str::<BTreeMap<uuid::Uuid, String>>(&text)
+                    .map_err(|e| eprintln!("could not read {LAST_SYNCED_STATE}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(list, time)| {
+                let time = NaiveDateTime::parse_from_str(&time, crate::json::DATE_TIME_FORMAT);
+                Some((list, time.ok()?))
+            })
+            .collect();
+        Self(Arc::new(Mutex::new(State {
+            last_synced,
+            ..State::default()
+        })))
+    }
+
+    // Saves when lists were last synced, if it's changed. Should be called once every frame.
+    pub fn save_if_changed(&self) {
+        let mut state = self.state();
+        if !state.unsaved {
+            return;
+        }
+        state.unsaved = false;
+        let times: BTreeMap<&uuid::Uuid, String> = state
+            .last_synced
+            .iter()
+            .map(|(list, time)| (list, time.format(crate::json::DATE_TIME_FORMAT).to_string()))
+            .collect();
+        let result = serde_json::to_string_pretty(&times)
+            .map_err(std::io::Error::other)
+            .and_then(|text| platform::write_state(LAST_SYNCED_STATE, &text));
+        if let Err(e) = result {
+            eprintln!("could not save when lists were last synced: {e}");
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Notes that a list has started syncing, giving back what to tell when it's finished
+    pub fn start(&self, source: &'static str, task_list: &TaskList) -> RunningSync {
+        self.state().running += 1;
+        RunningSync {
+            log: self.clone(),
+            source,
+            list_uuid: task_list.uuid,
+            list_name: task_list.name.clone(),
+            finished: false,
+        }
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        let state = self.state();
+        if state.running > 0 {
+            SyncStatus::Syncing
+        } else if let Some(error) = &state.error {
+            SyncStatus::Error(error.clone())
+        } else {
+            SyncStatus::Idle
+        }
+    }
+
+    // When a list last synced without a problem
+    pub fn last_synced(&self, task_list: &TaskList) -> Option<NaiveDateTime> {
+        self.state().last_synced.get(&task_list.uuid).copied()
+    }
+
+    // Everything in the log, oldest first
+    pub fn entries(&self) -> Vec<SyncEntry> {
+        self.state().entries.iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state();
+        state.entries.clear();
+        state.error = None;
+    }
+}
+
+// A sync that's going on, which has to be told how it went
+pub struct RunningSync {
+    log: SyncLog,
+    source: &'static str,
+    list_uuid: uuid::Uuid,
+    list_name: String,
+    finished: bool,
+}
+
+impl RunningSync {
+    // Logs how the sync went, with a message saying what was done or what went wrong
+    pub fn finish(mut self, result: Result<String, String>) {
+        self.finished = true;
+        let now = Local::now()
+            .naive_local()
+            .with_nanosecond(0)
+            .unwrap_or_default();
+        let mut state = self.log.state();
+        state.running = state.running.saturating_sub(1);
+        let failed = result.is_err();
+        let message = match result {
+            Ok(message) => {
+                state.error = None;
+                state.last_synced.insert(self.list_uuid, now);
+                state.unsaved = true;
+                message
+            }
+            Err(error) => {
+                state.error = Some(error.clone());
+                error
+            }
+        };
+        state.entries.push_back(SyncEntry {
+            time: now,
+            source: self.source,
+            list: std::mem::take(&mut self.list_name),
+            message,
+            failed,
+        });
+        if state.entries.len() > MAX_ENTRIES {
+            state.entries.pop_front();
+        }
+    }
+}
+
+// A sync that was never finished, like when its thread panicked, isn't still going on
+impl Drop for RunningSync {
+    fn drop(&mut self) {
+        if !self.finished {
+            let mut state = self.log.state();
+            state.running = state.running.saturating_sub(1);
+        }
+    }
+}
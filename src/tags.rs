@@ -0,0 +1,230 @@
+// ----------------------------------------------------------------------------
+// Tags label tasks, like "work" or "errands", and are kept in a task's
+// CATEGORIES. The app keeps a registry of tags with a color and description
+// for each, which is shared by every list and saved with the app's other
+// files. Tags can be renamed, or merged by renaming one to another.
+// The colors of the tags a list uses are kept in its file too, so that
+// they're known when the file is opened on another computer.
+// ----------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+
+use eframe::egui::Color32;
+use serde::{Deserialize, Serialize};
+
+use crate::color::Color;
+use crate::platform;
+use crate::task::TaskList;
+
+// Name the registry is saved under
+const TAGS_STATE: &str = "tags.json";
+
+// What the app knows about a tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagInfo {
+    #[serde(with = "crate::json::color")]
+    pub color: Color32,
+    pub description: String,
+}
+
+impl Default for TagInfo {
+    fn default() -> Self {
+        Self {
+            color: Color32::GRAY,
+            description: String::new(),
+        }
+    }
+}
+
+/// Every tag the app knows about, with its color and description.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::tags::TagRegistry;
+///
+/// let mut tags = TagRegistry::default();
+/// tags.add("work");
+/// tags.get_mut("work").unwrap().description = String::from("Day job");
+/// tags.add("job");
+///
+/// // Renaming a tag to one that already exists merges them, keeping the existing one's details
+/// tags.rename("work", "job");
+/// assert_eq!(tags.names().collect::<Vec<_>>(), ["job"]);
+/// assert_eq!(tags.get("job").unwrap().description, "");
+///
+/// tags.rename("job", "career");
+/// assert!(tags.get("career").is_some());
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TagRegistry(BTreeMap<String, TagInfo>);
+
+impl TagRegistry {
+    // Reads the registry that was saved last time
+    pub fn load() -> Self {
+        platform::read_state(TAGS_STATE)
+            .and_then(|text| {
+                serde_json::from_str(&text)
+                    .map_err(|e| eprintln!("could not read {TAGS_STATE}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(std::io::Error::other)
+            .and_then(|text| platform::write_state(TAGS_STATE, &text));
+        if let Err(e) = result {
+            eprintln!("could not save tags: {e}");
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TagInfo> {
+        self.0.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut TagInfo> {
+        self.0.get_mut(name)
+    }
+
+    // Every tag's name, in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    // Adds a tag with the default details, unless it's already known
+    pub fn add(&mut self, name: &str) {
+        if !self.0.contains_key(name) {
+            self.0.insert(name.to_string(), TagInfo::default());
+        }
+    }
+
+    // Adds every tag used in a list that isn't known yet.
+    // Returns whether any were added.
+    pub fn add_from(&mut self, task_list: &TaskList) -> bool {
+        let before = self.0.len();
+        for task in &task_list.tasks {
+            for tag in &task.tags {
+                self.add(tag);
+            }
+        }
+        self.0.len() != before
+    }
+
+    /// The colors of the known tags which are used in a list.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::color::Color;
+    /// use taskmaster_rs::tags::TagRegistry;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let list = TaskList {
+    ///     tasks: vec![Task { tags: vec!["work".into(), "new".into()], ..Task::default() }],
+    ///     ..Default::default()
+    /// };
+    /// let mut tags = TagRegistry::default();
+    /// tags.add("work");
+    /// tags.add("home");
+    ///
+    /// let colors = tags.colors_for(&list);
+    /// assert_eq!(colors.keys().collect::<Vec<_>>(), ["work"]);
+    ///
+    /// // Tags that are already known keep their color
+    /// let mut other = TagRegistry::default();
+    /// other.add("work");
+    /// other.get_mut("work").unwrap().color = Color::from_rgb(83, 130, 163).into();
+    /// assert!(!other.add_colors(&colors));
+    /// assert_eq!(other.colors_for(&list)["work"], Color::from_rgb(83, 130, 163));
+    /// ```
+    pub fn colors_for(&self, task_list: &TaskList) -> BTreeMap<String, Color> {
+        let mut colors = BTreeMap::new();
+        for tag in task_list.tasks.iter().flat_map(|task| &task.tags) {
+            if let Some(info) = self.0.get(tag) {
+                if !colors.contains_key(tag) {
+                    colors.insert(tag.clone(), info.color.into());
+                }
+            }
+        }
+        colors
+    }
+
+    // Adds tags with the colors a list's file gave them, unless they're already known.
+    // Returns whether any were added.
+    pub fn add_colors(&mut self, colors: &BTreeMap<String, Color>) -> bool {
+        let before = self.0.len();
+        for (name, color) in colors {
+            self.0.entry(name.clone()).or_insert_with(|| TagInfo {
+                color: (*color).into(),
+                ..TagInfo::default()
+            });
+        }
+        self.0.len() != before
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.0.remove(name);
+    }
+
+    // Renames a tag. If there's already a tag with the new name,
+    // the two are merged and the existing one's details are kept.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        if let Some(info) = self.0.remove(from) {
+            self.0.entry(to.to_string()).or_insert(info);
+        }
+    }
+}
+
+impl TaskList {
+    /// Renames a tag on every task in the list, giving back how many tasks
+    /// were changed. Tasks that already had the new tag just lose the old
+    /// one, which merges the two tags.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let mut list = TaskList {
+    ///     tasks: vec![
+    ///         Task { tags: vec!["work".into(), "urgent".into()], ..Task::default() },
+    ///         Task { tags: vec!["work".into(), "job".into()], ..Task::default() },
+    ///         Task { tags: vec!["home".into()], ..Task::default() },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(list.rename_tag("work", "job"), 2);
+    /// assert_eq!(list.tasks[0].tags, ["job", "urgent"]);
+    /// assert_eq!(list.tasks[1].tags, ["job"]);
+    /// assert_eq!(list.tasks[2].tags, ["home"]);
+    /// ```
+    pub fn rename_tag(&mut self, from: &str, to: &str) -> usize {
+        if from == to {
+            return 0;
+        }
+        let mut changed = 0;
+        for task in &mut self.tasks {
+            let Some(index) = task.tags.iter().position(|tag| tag == from) else {
+                continue;
+            };
+            if task.tags.iter().any(|tag| tag == to) {
+                task.tags.remove(index);
+            } else {
+                task.tags[index] = to.to_string();
+            }
+            changed += 1;
+        }
+        changed
+    }
+
+    // How many tasks in the list have a tag
+    pub fn count_tag(&self, name: &str) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| task.tags.iter().any(|tag| tag == name))
+            .count()
+    }
+}
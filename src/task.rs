@@ -3,22 +3,192 @@
 // ----------------------------------------------------------------------------
 
 use chrono::prelude::*;
-use eframe::egui;
-use std::{cell::RefCell, cmp::Ordering, rc::Rc, slice::Iter};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+    slice::Iter,
+};
 
-// Holds the data for a task
-#[derive(Clone, Debug, PartialEq, Eq)]
+use crate::color::Color;
+use crate::layouts::ListLayout;
+
+// The highest a task's progress can be, as a percentage
+pub const MAX_PROGRESS: u8 = 100;
+// The highest a task's priority can be. iCal priorities go from 1 to 9, and 0 means no priority.
+pub const MAX_PRIORITY: u8 = 9;
+
+/// A percentage, like how far through a task is, which can't be more than
+/// `MAX_PROGRESS`. Anything higher is lowered to it.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::task::Percent;
+///
+/// assert_eq!(Percent::new(47).get(), 47);
+/// assert_eq!(Percent::new(250).get(), 100);
+/// assert_eq!(Percent::checked(250), None);
+/// ```
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(from = "u8", into = "u8")]
+pub struct Percent(u8);
+
+impl Percent {
+    pub const fn new(percent: u8) -> Self {
+        if percent > MAX_PROGRESS {
+            Self(MAX_PROGRESS)
+        } else {
+            Self(percent)
+        }
+    }
+
+    // The percentage, if it isn't too high
+    pub const fn checked(percent: u8) -> Option<Self> {
+        if percent > MAX_PROGRESS {
+            None
+        } else {
+            Some(Self(percent))
+        }
+    }
+
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for Percent {
+    fn from(percent: u8) -> Self {
+        Self::new(percent)
+    }
+}
+
+impl From<Percent> for u8 {
+    fn from(percent: Percent) -> Self {
+        percent.0
+    }
+}
+
+impl std::fmt::Display for Percent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A task's priority, from 1 for the highest up to `MAX_PRIORITY` for the
+/// lowest, or 0 for no priority. Anything higher is lowered to `MAX_PRIORITY`.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::task::Priority;
+///
+/// assert_eq!(Priority::new(3).get(), 3);
+/// assert_eq!(Priority::new(12).get(), 9);
+/// assert!(!Priority::NONE.is_set());
+/// ```
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(from = "u8", into = "u8")]
+pub struct Priority(u8);
+
+impl Priority {
+    pub const NONE: Self = Self(0);
+
+    pub const fn new(priority: u8) -> Self {
+        if priority > MAX_PRIORITY {
+            Self(MAX_PRIORITY)
+        } else {
+            Self(priority)
+        }
+    }
+
+    // The priority, if it isn't too high
+    pub const fn checked(priority: u8) -> Option<Self> {
+        if priority > MAX_PRIORITY {
+            None
+        } else {
+            Some(Self(priority))
+        }
+    }
+
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    // Whether the task has a priority at all
+    pub const fn is_set(self) -> bool {
+        self.0 != 0
+    }
+}
+
+impl From<u8> for Priority {
+    fn from(priority: u8) -> Self {
+        Self::new(priority)
+    }
+}
+
+impl From<Priority> for u8 {
+    fn from(priority: Priority) -> Self {
+        priority.0
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+// Holds the data for a task.
+// Fields that are missing when a task is deserialized are the same as in the default task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Task {
     pub uuid: uuid::Uuid,
     pub summary: String,
     pub completed: bool,
     pub description: String,
-    pub progress: u8,
-    pub priority: u8,
+    pub url: Option<String>,
+    pub progress: Percent,
+    pub priority: Priority,
     pub status: Status,
     pub due: Option<NaiveDate>,
+    #[serde(with = "crate::json::date_time")]
     pub created: NaiveDateTime,
-    pub show_modal: Rc<RefCell<bool>>,
+    // When the task is planned to be worked on, which is separate from when it's due
+    pub scheduled: Option<TimeBlock>,
+    // Labels for grouping tasks, like "work" or "errands", which are kept in CATEGORIES
+    pub tags: Vec<String>,
+    // When the task was last changed in any way, in local time, kept in LAST-MODIFIED
+    #[serde(with = "crate::json::date_time")]
+    pub last_touched: NaiveDateTime,
+    // How long the task is expected to take, in minutes
+    pub estimate: Option<u32>,
+    // The day the task was picked for My Day. It's only in My Day on that day,
+    // so that My Day starts empty every morning.
+    pub my_day: Option<NaiveDate>,
+    // How the task repeats, as the text of an RRULE, like "FREQ=WEEKLY"
+    pub recurrence: Option<String>,
+    // Occurrences of a repeating task that were skipped, kept in EXDATE
+    pub exception_dates: Vec<NaiveDate>,
+    // The task this is a subtask of, kept in RELATED-TO
+    pub parent: Option<uuid::Uuid>,
+    // When the task was completed, in local time, kept in COMPLETED
+    pub completed_at: Option<NaiveDateTime>,
+    // Where the task goes when the list is in its manual order, kept in
+    // X-APPLE-SORT-ORDER, which Apple Reminders orders its lists by
+    pub sort_order: Option<i64>,
+    // Whether the task is pinned, which Apple Reminders calls flagged
+    pub pinned: bool,
+    // Whether the task's subtasks are hidden in the list, kept in
+    // X-OC-HIDESUBTASKS, which is where Nextcloud Tasks keeps it
+    pub hide_subtasks: bool,
+    // Where the task is in its status's column on the kanban board, kept in
+    // X-TASKMASTER-BOARD-POSITION, with lower numbers first
+    pub board_position: Option<i64>,
 }
 
 // Define default task
@@ -29,92 +199,425 @@ impl Default for Task {
             summary: String::from("New task"),
             completed: false,
             description: String::new(),
-            progress: 0,
-            priority: 0,
+            url: None,
+            progress: Percent::default(),
+            priority: Priority::NONE,
             status: Status::InProgress,
             due: None,
-            created: chrono::Utc::now().naive_local(),
-            show_modal: Rc::new(RefCell::new(false)),
+            created: Local::now().naive_local(),
+            scheduled: None,
+            tags: Vec::new(),
+            last_touched: Local::now().naive_local(),
+            estimate: None,
+            my_day: None,
+            recurrence: None,
+            exception_dates: Vec::new(),
+            parent: None,
+            completed_at: None,
+            sort_order: None,
+            pinned: false,
+            hide_subtasks: false,
+            board_position: None,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+// A block of time on the day planner that a task is scheduled for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeBlock {
+    #[serde(with = "crate::json::date_time")]
+    pub start: NaiveDateTime,
+    // How long the block lasts
+    pub minutes: u32,
+}
+
+impl TimeBlock {
+    pub fn end(&self) -> NaiveDateTime {
+        self.start + chrono::Duration::minutes(self.minutes.into())
+    }
+}
+
+impl Task {
+    /// Whether the task is still open but hasn't been touched for at least
+    /// the given number of days, so it's probably been forgotten about.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::{Duration, Local};
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let now = Local::now().naive_local();
+    /// let task = Task { last_touched: now - Duration::days(20), ..Task::default() };
+    ///
+    /// assert!(task.is_stale(now, 14));
+    /// assert!(!task.is_stale(now, 30));
+    /// assert!(!Task { completed: true, ..task }.is_stale(now, 14));
+    /// ```
+    pub fn is_stale(&self, now: NaiveDateTime, days: u32) -> bool {
+        !self.completed && now - self.last_touched >= chrono::Duration::days(days.into())
+    }
+
+    // Whether the task was picked for My Day on the given day
+    pub fn in_my_day(&self, today: NaiveDate) -> bool {
+        self.my_day == Some(today)
+    }
+
+    /// Finds the UUIDs of every task referenced in this task's description
+    /// using the `[[task-uuid]]` link syntax.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let linked = uuid::Uuid::new_v4();
+    /// let task = Task {
+    ///     description: format!("Blocked by [[{linked}]], see [[not a uuid]]"),
+    ///     ..Task::default()
+    /// };
+    ///
+    /// assert_eq!(task.linked_uuids(), vec![linked]);
+    /// ```
+    pub fn linked_uuids(&self) -> Vec<uuid::Uuid> {
+        self.description
+            .split("[[")
+            // The text before the first [[ can't be a link
+            .skip(1)
+            .filter_map(|text| text.split_once("]]"))
+            // Anything between the brackets that isn't a UUID is ignored
+            .filter_map(|(id, _)| uuid::Uuid::parse_str(id.trim()).ok())
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(default)]
 #[allow(clippy::module_name_repetitions)]
 pub struct TaskList {
+    // Stays the same when the list is renamed or moved, so anything kept about
+    // the list, like its layout or where it syncs to, can be found again
+    pub uuid: uuid::Uuid,
     pub name: String,
+    pub description: Option<String>,
     pub tasks: Vec<Task>,
-    pub color: egui::Color32,
+    pub color: Color,
+    #[serde(with = "crate::json::date_time")]
+    pub created: NaiveDateTime,
+    // When a task in the list was last added, changed or removed
+    #[serde(with = "crate::json::date_time")]
+    pub modified: NaiveDateTime,
+    // How the list was last laid out, so that it's laid out the same when its file is opened elsewhere
+    pub layout: Option<ListLayout>,
+    // The colors of the tags used in the list, for the same reason
+    pub tag_colors: BTreeMap<String, Color>,
+    // The file the list was opened from, which it's reloaded from when the app starts.
+    // Where the file is only matters on this computer, so it isn't part of the list's data.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
 }
 
 impl Default for TaskList {
     fn default() -> Self {
+        // Files only keep times to the second
+        let now = Local::now()
+            .naive_local()
+            .with_nanosecond(0)
+            .unwrap_or_default();
         Self {
+            uuid: uuid::Uuid::new_v4(),
             name: "New list".to_string(),
+            description: None,
             tasks: vec![],
-            color: egui::Color32::DEBUG_COLOR,
+            color: Color::from_rgb(0, 200, 0),
+            created: now,
+            modified: now,
+            layout: None,
+            tag_colors: BTreeMap::new(),
+            path: None,
         }
     }
 }
 
 impl TaskList {
     // Sort a task list based on a TaskSort passed into the function.
-    #[rustfmt::skip]
+    // This changes the order the tasks are stored and exported in, so the app
+    // only uses display_order, which leaves the list as it is.
     pub fn sort(&mut self, sort_by: TaskSort) {
-        match sort_by {
-            TaskSort::None => {}
-            TaskSort::Summary => self.tasks.sort_by(|a, b| a.summary.to_lowercase().cmp(&b.summary.to_lowercase())),
-            TaskSort::Completed => self.tasks.sort_by(|a, b| a.completed.cmp(&b.completed)),
-            TaskSort::Description => self.tasks.sort_by(|a, b| { a.description.to_lowercase().cmp(&b.description.to_lowercase()) }),
-            TaskSort::Progress => self.tasks.sort_by(|a, b| a.progress.cmp(&b.progress)),
-            TaskSort::Priority => self.tasks.sort_by(|a, b| a.priority.cmp(&b.priority)),
-            TaskSort::Status => self.tasks.sort_by(|a, b| a.status.partial_cmp(&b.status).expect("could not compare statuses for sorting")),
-            // This makes sure that tasks with due dates show up before ones without
-            // As well as making sure that the sooner the date, the higher up the task
-            TaskSort::Due => {
-                self.tasks.sort_by(|a, b| {
-                    a.due.map_or(Ordering::Equal, |a_due| {
-                        b.due.map_or(Ordering::Greater, |b_due| {
-                            a_due.cmp(&b_due)
-                        })
-                    })
-                });
-            },
+        self.tasks.sort_by(|a, b| sort_by.compare(a, b));
+    }
+
+    /// Works out which order the tasks should be shown in, without changing
+    /// the order they're stored in. Returns the index of each task in `tasks`,
+    /// in the order they should be shown. Tasks that compare as equal are
+    /// kept in the order they're stored in, so `TaskSort::None` keeps the
+    /// list's own order.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let list = TaskList {
+    ///     tasks: vec![
+    ///         Task { summary: "Water plants".to_string(), ..Task::default() },
+    ///         Task { summary: "buy milk".to_string(), ..Task::default() },
+    ///         Task { summary: "Call Sam".to_string(), ..Task::default() },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(list.display_order(TaskSort::Summary), vec![1, 2, 0]);
+    /// assert_eq!(list.display_order(TaskSort::None), vec![0, 1, 2]);
+    /// assert_eq!(list.tasks[0].summary, "Water plants");
+    /// ```
+    pub fn display_order(&self, sort_by: TaskSort) -> Vec<usize> {
+        self.display_order_with(sort_by, false)
+    }
+
+    /// Works out which order the tasks should be shown in like `display_order`,
+    /// going from last to first if `descending`. Tasks that compare as equal
+    /// are still kept in the order they're stored in.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let task = |summary: &str, status| Task { summary: summary.to_string(), status, ..Task::default() };
+    /// let list = TaskList {
+    ///     tasks: vec![
+    ///         task("Book venue", Status::Completed),
+    ///         task("Write report", Status::NeedsAction),
+    ///         task("Send invites", Status::Completed),
+    ///         task("Plan menu", Status::InProgress),
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(list.display_order_with(TaskSort::Status, false), vec![1, 3, 0, 2]);
+    /// assert_eq!(list.display_order_with(TaskSort::Status, true), vec![0, 2, 3, 1]);
+    /// ```
+    pub fn display_order_with(&self, sort_by: TaskSort, descending: bool) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.tasks.len()).collect();
+        if sort_by != TaskSort::None {
+            order.sort_by(|&a, &b| {
+                let ordering = sort_by.compare(&self.tasks[a], &self.tasks[b]);
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
         }
+        order
     }
 
-    // Adds a task to a task list, and executes any other required code
-    pub fn add(&mut self, task: Task) {
+    /// Counts the incomplete tasks in the list which are due on the given day,
+    /// and the ones which are overdue.
+    ///
+    /// Example:
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let today = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap();
+    /// let list = TaskList {
+    ///     tasks: vec![
+    ///         Task { due: Some(today), ..Task::default() },
+    ///         Task { due: today.pred_opt(), ..Task::default() },
+    ///         Task { due: Some(today), completed: true, ..Task::default() },
+    ///         Task { due: today.succ_opt(), ..Task::default() },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(list.due_counts(today), DueCounts { due_today: 1, overdue: 1 });
+    /// ```
+    pub fn due_counts(&self, today: NaiveDate) -> DueCounts {
+        let mut counts = DueCounts::default();
+        for task in self.tasks.iter().filter(|task| !task.completed) {
+            match task.due {
+                Some(due) if due == today => counts.due_today += 1,
+                Some(due) if due < today => counts.overdue += 1,
+                _ => {}
+            }
+        }
+        counts
+    }
+
+    /// Splits the list into the tasks that still need doing and the ones that
+    /// have been completed, so that completed tasks can be archived separately.
+    /// Both lists keep this list's name, color and file.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let list = TaskList {
+    ///     tasks: vec![
+    ///         Task { summary: "Write report".into(), ..Task::default() },
+    ///         Task { summary: "Book venue".into(), completed: true, ..Task::default() },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let (open, completed) = list.split_completed();
+    /// assert_eq!(open.tasks.len(), 1);
+    /// assert_eq!(open.tasks[0].summary, "Write report");
+    /// assert_eq!(completed.tasks.len(), 1);
+    /// assert_eq!(completed.tasks[0].summary, "Book venue");
+    /// ```
+    pub fn split_completed(&self) -> (Self, Self) {
+        let (completed, open) = self.tasks.iter().cloned().partition(|task| task.completed);
+        let list = |tasks| Self {
+            uuid: self.uuid,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            tasks,
+            color: self.color,
+            created: self.created,
+            modified: self.modified,
+            layout: self.layout.clone(),
+            tag_colors: self.tag_colors.clone(),
+            path: self.path.clone(),
+        };
+        (list(open), list(completed))
+    }
+
+    // Adds a task to a task list, and executes any other required code.
+    // If a task with the same UUID is already in the list, the new task is
+    // given a new UUID, since tasks with the same UUID can't be told apart.
+    pub fn add(&mut self, mut task: Task) {
+        if self.tasks.iter().any(|existing| existing.uuid == task.uuid) {
+            task.uuid = uuid::Uuid::new_v4();
+        }
         self.tasks.push(task);
     }
+
+    /// Finds every UUID that is used by more than one task in the list,
+    /// which can happen when a list file has been edited by hand.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    ///
+    /// let task = Task::default();
+    /// let mut list = TaskList {
+    ///     tasks: vec![task.clone(), task.clone(), Task::default()],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(list.uuid_collisions(), vec![task.uuid]);
+    ///
+    /// assert_eq!(list.regenerate_colliding_uuids(), 1);
+    /// assert!(list.uuid_collisions().is_empty());
+    /// assert_eq!(list.tasks[0].uuid, task.uuid);
+    /// ```
+    pub fn uuid_collisions(&self) -> Vec<uuid::Uuid> {
+        let mut seen = HashSet::new();
+        let mut collisions = Vec::new();
+        for task in &self.tasks {
+            if !seen.insert(task.uuid) && !collisions.contains(&task.uuid) {
+                collisions.push(task.uuid);
+            }
+        }
+        collisions
+    }
+
+    // Gives a new UUID to every task that has the same UUID as a task before it in the list.
+    // Returns how many tasks were given new UUIDs.
+    pub fn regenerate_colliding_uuids(&mut self) -> usize {
+        let mut seen = HashSet::new();
+        let mut regenerated = 0;
+        for task in &mut self.tasks {
+            if !seen.insert(task.uuid) {
+                task.uuid = uuid::Uuid::new_v4();
+                seen.insert(task.uuid);
+                regenerated += 1;
+            }
+        }
+        regenerated
+    }
+}
+
+// How many incomplete tasks in a list need doing soon
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DueCounts {
+    pub due_today: usize,
+    pub overdue: usize,
 }
 
-// The STATUS field of a VTODO can only have certain values.
-// This enum is used to choose between the valid values of this field.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+/// The STATUS field of a VTODO can only have certain values.
+/// This enum is used to choose between the valid values of this field.
+/// It's serialized the same way as in iCal files, like "NEEDS-ACTION".
+/// Some apps write other values, which are kept as they are in `Custom`
+/// so that they're written back the same way.
+///
+/// Statuses are ordered by how far along a task is, which is the order
+/// they're declared in: tasks that need doing, then ones in progress, then
+/// completed ones, with cancelled tasks last. Custom statuses come after
+/// every standard one.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::task::Status;
+///
+/// let mut statuses = vec![Status::Cancelled, Status::Completed, Status::NeedsAction, Status::InProgress];
+/// statuses.sort();
+/// assert_eq!(
+///     statuses,
+///     vec![Status::NeedsAction, Status::InProgress, Status::Completed, Status::Cancelled]
+/// );
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum Status {
     NeedsAction,
     #[default]
     InProgress,
     Completed,
     Cancelled,
+    // A status that isn't one of the standard ones, like X-WAITING.
+    // It shouldn't be the name of a standard status.
+    Custom(String),
 }
 
+// The standard statuses, in the order they're shown to pick from
+static STATUSES: [Status; 4] = [
+    Status::InProgress,
+    Status::NeedsAction,
+    Status::Completed,
+    Status::Cancelled,
+];
+
 impl Status {
-    // Returns an array of values of the enum, for other code to iterate over
+    // Returns an array of the standard statuses, for other code to iterate over
     pub fn iterator() -> Iter<'static, Self> {
-        return [
-            Self::InProgress,
-            Self::NeedsAction,
-            Self::Completed,
-            Self::Cancelled,
-        ]
-        .iter();
+        STATUSES.iter()
+    }
+
+    // What the status is called in the app
+    pub fn name(&self) -> &str {
+        match self {
+            Self::NeedsAction => "Needs Action",
+            Self::InProgress => "In Progress",
+            Self::Completed => "Completed",
+            Self::Cancelled => "Cancelled",
+            Self::Custom(value) => value,
+        }
+    }
+}
+
+impl From<String> for Status {
+    fn from(value: String) -> Self {
+        Self::from_ical_or_custom(&value)
+    }
+}
+
+impl From<Status> for String {
+    fn from(status: Status) -> Self {
+        status.to_ical().to_string()
     }
 }
 
 // Enum used for sorting task lists
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(clippy::module_name_repetitions)]
 pub enum TaskSort {
     #[default]
@@ -126,9 +629,40 @@ pub enum TaskSort {
     Priority,
     Status,
     Due,
+    // The order the tasks were put in by hand, in another app like Apple Reminders
+    Manual,
 }
 
 impl TaskSort {
+    // Compares two tasks by the field being sorted by
+    #[rustfmt::skip]
+    pub fn compare(self, a: &Task, b: &Task) -> Ordering {
+        match self {
+            Self::None => Ordering::Equal,
+            Self::Summary => a.summary.to_lowercase().cmp(&b.summary.to_lowercase()),
+            Self::Completed => a.completed.cmp(&b.completed),
+            Self::Description => a.description.to_lowercase().cmp(&b.description.to_lowercase()),
+            Self::Progress => a.progress.cmp(&b.progress),
+            Self::Priority => a.priority.cmp(&b.priority),
+            Self::Status => a.status.cmp(&b.status),
+            // This makes sure that tasks with due dates show up before ones without
+            // As well as making sure that the sooner the date, the higher up the task
+            Self::Due => match (a.due, b.due) {
+                (Some(a_due), Some(b_due)) => a_due.cmp(&b_due),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            // Tasks that haven't been put in order go after the ones that have
+            Self::Manual => match (a.sort_order, b.sort_order) {
+                (Some(a_order), Some(b_order)) => a_order.cmp(&b_order),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        }
+    }
+
     // Returns an array of values of the enum, for other code to iterate over
     pub fn iterator() -> Iter<'static, Self> {
         return [
@@ -140,6 +674,7 @@ impl TaskSort {
             Self::Priority,
             Self::Status,
             Self::Due,
+            Self::Manual,
         ]
         .iter();
     }
@@ -5,131 +5,1688 @@
 // use it to display the task list.
 // ----------------------------------------------------------------------------
 
-use convert_case::Case;
-use convert_case::Casing;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use eframe::egui;
 use egui::{RichText, Ui};
+use serde::{Deserialize, Serialize};
 
+use crate::board::Swimlanes;
+use crate::filters::{QuickFilters, TaskFilter};
+use crate::json::DATE_FORMAT;
+use crate::layouts::ListLayout;
+use crate::parser::LineEnding;
+use crate::platform;
+use crate::query::Query;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::share;
 use crate::task::*;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::transfer::TransferMode;
 use crate::ui_elements;
+use crate::ui_elements::swipe_row::SwipeAction;
 
 /// Trait that every task view must implement
 /// This ensures that every task view includes a function for displaying itself,
 /// as well as allowing the program to ask for any task view and display it
 pub trait TaskView {
+    /// The name of the view, which is shown in the view selector
+    fn name(&self) -> &str;
+
     /// Takes a UI, a task list, and some extra parameters,and uses them to
     /// display the list in whatever view is implementing the function
-    fn display(ui: &mut Ui, task_list: &mut TaskList, show_completed_tasks: bool);
+    fn display(&mut self, ui: &mut Ui, task_list: &mut TaskList, options: &ViewOptions);
+
+    /// Keeps how the view is set up, like the kanban board's column limits,
+    /// in a list's layout. Views with nothing to keep don't need to implement it.
+    fn save_layout(&self, _layout: &mut ListLayout) {}
+
+    /// Sets the view up the way it was when a list's layout was kept
+    fn apply_layout(&mut self, _layout: &ListLayout) {}
+}
+
+/// Options which change how a task view displays a task list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewOptions {
+    /// Whether tasks that have been completed are shown
+    pub show_completed_tasks: bool,
+    /// Whether the view should be laid out for a narrow, touch screen,
+    /// leaving out anything that doesn't fit and making things easy to tap
+    pub compact: bool,
+    /// Which field the tasks are shown sorted by. Views should show tasks in
+    /// `TaskList::display_order_with`, rather than sorting the list itself, so that
+    /// the order the tasks are stored and exported in doesn't change.
+    pub sort_by: TaskSort,
+    /// Whether tasks are sorted from last to first
+    pub sort_descending: bool,
+    /// Which tasks are shown, apart from whether completed ones are
+    pub filter: TaskFilter,
+    /// The chips above the list that are turned on, which narrow the filter down further
+    pub quick_filters: QuickFilters,
+    /// The expression typed into the filter box, if there's one that could be parsed
+    pub query: Option<Query>,
+    /// How many days a task can go untouched before it's stale
+    pub stale_days: u32,
+    /// How many characters of a task's description are shown next to its summary,
+    /// before the row is clicked open to show all of it. None are shown if it's 0.
+    pub description_chars: usize,
+    /// Which of a task's details are shown in its row in this view
+    pub fields: RowFields,
+}
+
+/// Which of a task's details are shown in its row, apart from its summary
+/// and description. Tasks only show the ones they have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RowFields {
+    pub due: bool,
+    pub priority: bool,
+    pub progress: bool,
+    pub status: bool,
+}
+
+impl Default for RowFields {
+    fn default() -> Self {
+        Self {
+            due: true,
+            priority: true,
+            progress: true,
+            status: true,
+        }
+    }
+}
+
+/// Settings for how much of each task is shown in its row, which can be
+/// different in each view
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RowSettings {
+    /// How many characters of a task's description are shown in its row
+    pub description_chars: usize,
+    /// The fields shown in each view's rows, by the view's name.
+    /// Views that aren't in here show all of them.
+    pub views: BTreeMap<String, RowFields>,
+}
+
+impl Default for RowSettings {
+    fn default() -> Self {
+        Self {
+            description_chars: 20,
+            views: BTreeMap::new(),
+        }
+    }
 }
 
-pub struct ClassicView;
+impl RowSettings {
+    /// The fields shown in a view's rows.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task_views::{RowFields, RowSettings};
+    ///
+    /// let mut rows = RowSettings::default();
+    /// rows.fields_mut("Classic").priority = false;
+    ///
+    /// assert!(!rows.fields("Classic").priority);
+    /// assert!(rows.fields("Classic").due);
+    /// assert_eq!(rows.fields("Week"), RowFields::default());
+    /// ```
+    pub fn fields(&self, view: &str) -> RowFields {
+        self.views.get(view).copied().unwrap_or_default()
+    }
+
+    pub fn fields_mut(&mut self, view: &str) -> &mut RowFields {
+        self.views.entry(view.to_string()).or_default()
+    }
+}
+
+impl ViewOptions {
+    /// Whether a task should be shown, given whether completed tasks are shown and the filters
+    pub fn shows(&self, task: &Task, now: NaiveDateTime) -> bool {
+        (self.show_completed_tasks || !task.completed)
+            && self.filter.matches(task, now, self.stale_days)
+            && self.quick_filters.matches(task, now.date())
+            && self
+                .query
+                .as_ref()
+                .map_or(true, |query| query.matches(task, now.date()))
+    }
+}
+
+/// Holds every task view that the app can display.
+/// Views from other crates can be registered here to make them show up in
+/// the view selector alongside the built-in ones.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::task::TaskList;
+/// use taskmaster_rs::task_views::{TaskView, TaskViewRegistry, ViewOptions};
+/// use eframe::egui::Ui;
+///
+/// struct CountView;
+///
+/// impl TaskView for CountView {
+///     fn name(&self) -> &str {
+///         "Count"
+///     }
+///
+///     fn display(&mut self, ui: &mut Ui, task_list: &mut TaskList, _options: &ViewOptions) {
+///         ui.label(format!("{} tasks", task_list.tasks.len()));
+///     }
+/// }
+///
+/// let mut views = TaskViewRegistry::default();
+/// views.register(Box::new(CountView));
+///
+/// assert_eq!(
+///     views.names().collect::<Vec<_>>(),
+///     vec!["Classic", "Day planner", "My Day", "Week", "Count"]
+/// );
+/// ```
+#[allow(clippy::module_name_repetitions)]
+pub struct TaskViewRegistry {
+    views: Vec<Box<dyn TaskView>>,
+}
+
+// By default, only the built-in views are registered
+impl Default for TaskViewRegistry {
+    fn default() -> Self {
+        Self {
+            views: vec![
+                Box::new(ClassicView::default()),
+                Box::new(DayPlannerView::default()),
+                Box::new(MyDayView::default()),
+                Box::new(WeekView::default()),
+                Box::new(KanbanView::default()),
+            ],
+        }
+    }
+}
+
+impl TaskViewRegistry {
+    /// Adds a view to the end of the view selector
+    pub fn register(&mut self, view: Box<dyn TaskView>) {
+        self.views.push(view);
+    }
+
+    /// Returns the names of every registered view, in the order they were registered
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.views.iter().map(|view| view.name())
+    }
+
+    /// Gets the view at a position in the view selector
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut dyn TaskView> {
+        self.views
+            .get_mut(index)
+            .map(|view| &mut **view as &mut dyn TaskView)
+    }
+
+    /// Keeps how every view is set up in a list's layout
+    pub fn save_layout(&self, layout: &mut ListLayout) {
+        for view in &self.views {
+            view.save_layout(layout);
+        }
+    }
+
+    /// Sets every view up the way a list's layout says
+    pub fn apply_layout(&mut self, layout: &ListLayout) {
+        for view in &mut self.views {
+            view.apply_layout(layout);
+        }
+    }
+}
+
+// How long, in seconds, a swiped task can be brought back for
+const UNDO_SECONDS: f64 = 5.;
+// How long, in seconds, rows take to slide open and closed
+const ROW_ANIMATION_SECONDS: f32 = 0.2;
+// How long, in seconds, the line through a task takes to draw when it's checked off
+const STRIKE_ANIMATION_SECONDS: f32 = 0.3;
+// How far subtasks are indented for each level, which is also how far
+// a task's handle has to be dragged sideways to move it a level
+const INDENT_WIDTH: f32 = 20.;
+
+#[derive(Default)]
+pub struct ClassicView {
+    // The last thing that was done to a task by swiping it, so it can be undone
+    undo: Option<Undo>,
+    // Tasks that were in the list last frame, so that new ones can be animated in
+    seen: HashSet<uuid::Uuid>,
+    // Whether the view has been shown before. Tasks that are there the first
+    // time it's shown aren't animated in.
+    started: bool,
+    // Tasks whose rows are sliding closed, which are removed once they've closed
+    removing: HashSet<uuid::Uuid>,
+    // How tall each task's row was, with the space after it, when it was last shown
+    heights: HashMap<uuid::Uuid, f32>,
+}
+
+// A copy of a task from before it was changed or removed by swiping it
+struct Undo {
+    message: &'static str,
+    task: Task,
+    // Where the task was in the list
+    index: usize,
+    // Whether the task was removed from the list, rather than changed
+    removed: bool,
+    // Time after which the task can no longer be brought back
+    expires: f64,
+}
+
+impl ClassicView {
+    // Shows a bar which lets the last swipe be undone, until it expires
+    fn undo_bar(&mut self, ui: &mut Ui, task_list: &mut TaskList) {
+        let now = ui.input(|i| i.time);
+        // Once the undo has expired, it's dropped
+        let Some(undo) = self.undo.take().filter(|undo| undo.expires > now) else {
+            return;
+        };
+
+        let mut undone = false;
+        ui.horizontal(|ui| {
+            ui.label(undo.message);
+            undone = ui.button("Undo").clicked();
+        });
+
+        if undone {
+            if undo.removed {
+                let index = undo.index.min(task_list.tasks.len());
+                task_list.tasks.insert(index, undo.task);
+            } else if let Some(task) = task_list
+                .tasks
+                .iter_mut()
+                .find(|task| task.uuid == undo.task.uuid)
+            {
+                *task = undo.task;
+            }
+        } else {
+            // Make sure the bar goes away once it expires, even if nothing else happens
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_secs_f64(undo.expires - now));
+            self.undo = Some(undo);
+        }
+    }
+}
 
 impl TaskView for ClassicView {
-    fn display(ui: &mut Ui, task_list: &mut TaskList, show_completed_tasks: bool) {
+    fn name(&self) -> &str {
+        "Classic"
+    }
+
+    #[allow(clippy::too_many_lines, clippy::cast_precision_loss)]
+    fn display(&mut self, ui: &mut Ui, task_list: &mut TaskList, options: &ViewOptions) {
         ui.spacing_mut().item_spacing.y = 3.5;
 
-        // Iterates over each task in the task list, keeping or removing each task
-        // based on a returned boolean which is determined within each iteration
-        // In this case, the returned boolean indicates whether the task's
-        // remove button has been pressed. This allows for removal of an element
-        // from the vector of tasks without getting an IndexOutOfRange error
-        // when the iterator gets to a place where a removed task used to be,
-        // which is what would happen with something like a for loop.
-        task_list.tasks.retain_mut(|task| {
-            // This is the boolean which determines whether a task is removed from the vector
-            let mut keep = true;
-
-            // If show_completed_tasks is enabled, completed tasks will be shown, otherwise they will be hidden
-            if show_completed_tasks || !task.completed {
-                ui.separator();
+        // Let the last swipe be undone
+        self.undo_bar(ui, task_list);
+        let now = ui.input(|i| i.time);
 
-                ui.horizontal(|ui| {
-                    // Expand to fit window
-                    ui.set_width(ui.available_width());
+        let row_time = ui_elements::animation_time(ui, ROW_ANIMATION_SECONDS);
+        let strike_time = ui_elements::animation_time(ui, STRIKE_ANIMATION_SECONDS);
+        let mut seen = HashSet::new();
+        let clock = Local::now().naive_local();
 
-                    // Create rich text containing the task's summary
-                    let mut task_text = RichText::new(&task.summary);
-                    if task.completed {
-                        task_text = task_text.strikethrough();
-                    }
+        // Collect the details needed to show links between tasks in their modals
+        let link_targets = ui_elements::task_modal::LinkTarget::from_list(task_list);
+
+        // Tasks whose rows have finished closing after being removed,
+        // which are taken out of the list once every task has been shown
+        let mut removed = HashSet::new();
+
+        // Goes through the tasks in the order they're shown in, which doesn't
+        // change the order they're stored in
+        // A task that was moved a level in the outline, and whether it was moved down a level
+        let mut outline = None;
+        // Tasks which have subtasks, whose subtasks can be hidden
+        let parents: HashSet<uuid::Uuid> = task_list
+            .tasks
+            .iter()
+            .filter_map(|task| task.parent)
+            .collect();
+        // Which tasks are picked for the toolbar above the list, the tasks that
+        // are shown, in order, and the task whose handle was clicked, if one was
+        let mut selection = ui_elements::selection::shared(ui.ctx());
+        let mut shown_tasks = Vec::new();
+        let mut clicked = None;
+
+        for index in task_list.display_order_with(options.sort_by, options.sort_descending) {
+            let depth = task_list.depth(index);
+            let hidden_by_parent = task_list.hidden_by_parent(index);
+            let task = &mut task_list.tasks[index];
+            // Removing a task first slides its row closed, then removes it
+            let mut remove = false;
+
+            // Rows slide open when tasks are added, and closed when they're hidden or removed
+            let row_id = egui::Id::new(("swipe_row", task.uuid));
+            let shown_id = row_id.with("shown");
+            if self.started && !self.seen.contains(&task.uuid) {
+                // Tasks that weren't in the list last frame start closed, so they slide open
+                ui.ctx().animate_bool_with_time(shown_id, false, 0.);
+            }
+            seen.insert(task.uuid);
+            let removing = self.removing.contains(&task.uuid);
+
+            // Tasks are hidden if they're completed and completed tasks aren't shown,
+            // if they don't match the filter, or if a task they're under hides its subtasks
+            let shown = options.shows(task, clock) && !removing && !hidden_by_parent;
+            if shown {
+                shown_tasks.push(task.uuid);
+            }
+            let amount = ui.ctx().animate_bool_with_time(shown_id, shown, row_time);
+
+            // Rows that are scrolled out of sight take up as much space as they did when
+            // they were last shown, without being laid out, so long lists scroll smoothly
+            let top = ui.cursor().top();
+            let clip = ui.clip_rect();
+            let hidden_height = self.heights.get(&task.uuid).copied().filter(|height| {
+                amount >= 1. && (top + height < clip.top() || top > clip.bottom())
+            });
+
+            if let Some(height) = hidden_height {
+                let spacing = ui.spacing().item_spacing.y;
+                ui.allocate_space(egui::vec2(ui.available_width(), (height - spacing).max(0.)));
+            } else if amount > 0. {
+                // Whether the row has been clicked open to show the whole description
+                let expanded_id = row_id.with("expanded");
+                let mut expanded = ui.data(|d| d.get_temp(expanded_id)).unwrap_or(false);
+
+                // Rows can be swiped to complete, delete or snooze them
+                let swiped = ui_elements::reveal(ui, row_id.with("reveal"), amount, |ui| {
+                    ui.separator();
+                    ui_elements::swipe_row::show(ui, row_id, |ui| {
+                        ui.horizontal(|ui| {
+                            // Expand to fit window
+                            ui.set_width(ui.available_width());
+
+                            // Subtasks are indented under the task they belong to, and
+                            // dragging a task's handle sideways moves it a level.
+                            // Clicking the handle picks the task.
+                            ui.add_space(depth as f32 * INDENT_WIDTH);
+                            let (handle, demote) =
+                                outline_handle(ui, task.uuid, selection.contains(task.uuid));
+                            if let Some(demote) = demote {
+                                outline = Some((task.uuid, demote));
+                            }
+                            if handle.clicked() {
+                                clicked = Some((task.uuid, ui.input(|i| i.modifiers.shift)));
+                            }
+
+                            // How far through being checked off the task is
+                            let struck = ui.ctx().animate_bool_with_time(
+                                row_id.with("struck"),
+                                task.completed,
+                                strike_time,
+                            );
+
+                            // Create rich text containing the task's summary, marked if it's pinned
+                            let mut task_text = if task.pinned {
+                                RichText::new(format!("📌 {}", task.summary))
+                            } else {
+                                RichText::new(&task.summary)
+                            };
+                            if struck >= 1. {
+                                task_text = task_text.strikethrough();
+                            }
+
+                            // Create a checkbox with previously created text
+                            let checkbox = ui.checkbox(&mut task.completed, task_text);
+                            let checkbox = ui_elements::task_tooltip::attach(checkbox, task);
+                            checkbox.context_menu(|ui| {
+                                if ui.button("Make subtask of the task above").clicked() {
+                                    outline = Some((task.uuid, true));
+                                    ui.close_menu();
+                                }
+                                if task.parent.is_some() && ui.button("Move up a level").clicked() {
+                                    outline = Some((task.uuid, false));
+                                    ui.close_menu();
+                                }
+                                if ui
+                                    .button(if task.pinned { "Unpin" } else { "Pin" })
+                                    .clicked()
+                                {
+                                    task.pinned = !task.pinned;
+                                    ui.close_menu();
+                                }
+                                if parents.contains(&task.uuid)
+                                    && ui
+                                        .button(if task.hide_subtasks {
+                                            "Show subtasks"
+                                        } else {
+                                            "Hide subtasks"
+                                        })
+                                        .clicked()
+                                {
+                                    task.hide_subtasks = !task.hide_subtasks;
+                                    ui.close_menu();
+                                }
+
+                                // Web builds can't reach other lists' files
+                                #[cfg(not(target_arch = "wasm32"))]
+                                for mode in [TransferMode::Move, TransferMode::Copy] {
+                                    if ui.button(format!("{} to list…", mode.name())).clicked() {
+                                        ui_elements::transfer::request(ui.ctx(), task.uuid, mode);
+                                        ui.close_menu();
+                                    }
+                                }
+
+                                // Web builds can't post to other sites, which don't allow it
+                                #[cfg(not(target_arch = "wasm32"))]
+                                if ui.button("Share to channel").clicked() {
+                                    share::request(ui.ctx(), task.uuid);
+                                    ui.close_menu();
+                                }
+                            });
+
+                            // While the task is being checked off, draw the line
+                            // through its summary growing across it
+                            if struck > 0. && struck < 1. {
+                                let left = checkbox.rect.left()
+                                    + ui.spacing().icon_width
+                                    + ui.spacing().icon_spacing;
+                                let right = egui::lerp(left..=checkbox.rect.right(), struck);
+                                ui.painter().hline(
+                                    left..=right,
+                                    checkbox.rect.center().y,
+                                    egui::Stroke::new(1., ui.visuals().text_color()),
+                                );
+                            }
+
+                            // Show the start of the task's description, if there's room for it.
+                            // Clicking it expands the row to show all of it.
+                            let preview =
+                                description_preview(&task.description, options.description_chars);
+                            if let Some(preview) = preview.filter(|_| !options.compact) {
+                                let mut desc_text = RichText::new(preview);
+                                if struck >= 1. {
+                                    desc_text = desc_text.strikethrough();
+                                }
+                                let hint = if expanded {
+                                    "Click to hide the description"
+                                } else {
+                                    "Click to show the whole description"
+                                };
+                                if ui
+                                    .add(egui::Label::new(desc_text).sense(egui::Sense::click()))
+                                    .on_hover_text(hint)
+                                    .clicked()
+                                {
+                                    expanded = !expanded;
+                                    ui.data_mut(|d| d.insert_temp(expanded_id, expanded));
+                                }
+                            }
+
+                            // Right-aligned, right-to-left UI segment
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    // Click this to show a modal with a task's full details
+                                    // Doesn't spawn it if there's already one present
+                                    if ui.button("···").clicked() {
+                                        ui_elements::task_modal::set_open(
+                                            ui.ctx(),
+                                            task.uuid,
+                                            true,
+                                        );
+                                    };
 
-                    // Create rich text containing the task's description
-                    let mut desc_text = task.description.replace('\n', " ");
-                    if desc_text.trim().len() > 20 {
-                        desc_text.truncate(20);
-                        desc_text = desc_text.trim().to_string() + "...";
+                                    // If the button is clicked, mark task for removal
+                                    if ui.button("✖").clicked() {
+                                        remove = true;
+                                    }
+
+                                    // If the task has a URL, show a link icon which opens it
+                                    if let Some(url) = &task.url {
+                                        ui.hyperlink_to("🔗", url).on_hover_text(url);
+                                    }
+
+                                    // On narrow screens there's no room for anything else,
+                                    // so the rest of the task's details are left to its modal
+                                    if options.compact {
+                                        return;
+                                    }
+
+                                    // Point out tasks that seem to have been forgotten about
+                                    if task.is_stale(clock, options.stale_days) {
+                                        let days = (clock - task.last_touched).num_days();
+                                        ui.label("🕸").on_hover_text(format!(
+                                            "Stale: not touched for {days} days"
+                                        ));
+                                    }
+
+                                    // Values are only shown until the row's edit button is
+                                    // pressed, so they can't be changed by accident
+                                    let editing_id = row_id.with("editing");
+                                    let mut editing =
+                                        ui.data(|d| d.get_temp(editing_id)).unwrap_or(false);
+                                    if ui
+                                        .selectable_label(editing, "✏")
+                                        .on_hover_text(if editing {
+                                            "Stop editing"
+                                        } else {
+                                            "Edit the task's details here"
+                                        })
+                                        .clicked()
+                                    {
+                                        editing = !editing;
+                                        ui.data_mut(|d| d.insert_temp(editing_id, editing));
+                                    }
+                                    row_details(ui, task, options.fields, editing);
+                                },
+                            );
+                        });
+
+                        // An expanded row shows the whole description under the task
+                        if expanded && !options.compact {
+                            full_description(ui, row_id, &task.description);
+                        }
+                    })
+                });
+
+                // Do whatever the row was swiped to do, keeping a copy of the task
+                // from before so that it can be undone
+                if let Some(action) = swiped {
+                    let (message, removed) = match action {
+                        SwipeAction::Complete => ("Task completed", false),
+                        SwipeAction::Delete => ("Task deleted", true),
+                        SwipeAction::Snooze => ("Task snoozed until tomorrow", false),
+                    };
+                    self.undo = Some(Undo {
+                        message,
+                        task: task.clone(),
+                        index,
+                        removed,
+                        expires: now + UNDO_SECONDS,
+                    });
+
+                    match action {
+                        SwipeAction::Complete => {
+                            task.completed = true;
+                            task.status = Status::Completed;
+                        }
+                        SwipeAction::Delete => remove = true,
+                        SwipeAction::Snooze => {
+                            task.due = chrono::Local::now().date_naive().succ_opt();
+                        }
                     }
-                    let mut desc_text = RichText::new(desc_text);
-                    if task.completed {
-                        desc_text = desc_text.strikethrough();
+                }
+
+                if task.status == Status::Completed {
+                    task.completed = true;
+                }
+                self.heights.insert(task.uuid, ui.cursor().top() - top);
+            } else if removing {
+                // Once a removed task's row has closed, it can actually be removed
+                self.removing.remove(&task.uuid);
+                removed.insert(task.uuid);
+            }
+
+            if remove {
+                self.removing.insert(task.uuid);
+                ui.ctx().request_repaint();
+            }
+
+            // Spawn a modal if told to
+            if ui_elements::task_modal::is_open(ui.ctx(), task.uuid) {
+                ui_elements::task_modal::spawn(task, &link_targets, ui.ctx());
+            }
+        }
+
+        // Shift-clicking picks every task between the last one clicked and this one,
+        // and Ctrl+A picks every task that's shown, unless text is being typed
+        let select_all = !ui.ctx().wants_keyboard_input()
+            && ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::A));
+        if clicked.is_some() || select_all {
+            if let Some((uuid, range)) = clicked {
+                selection.click(uuid, &shown_tasks, range);
+            }
+            if select_all {
+                selection.select_all(&shown_tasks);
+            }
+            ui_elements::selection::set(ui.ctx(), selection);
+        }
+
+        if let Some((uuid, demote)) = outline {
+            if demote {
+                task_list.demote(uuid);
+            } else {
+                task_list.promote(uuid);
+            }
+        }
+
+        if !removed.is_empty() {
+            task_list.tasks.retain(|task| !removed.contains(&task.uuid));
+        }
+
+        self.heights.retain(|uuid, _| seen.contains(uuid));
+        self.seen = seen;
+        self.started = true;
+    }
+}
+
+// Shows the task's details that are picked for the view's rows, right to left,
+// as labels, or as widgets to change them with while the row is being edited
+fn row_details(ui: &mut Ui, task: &mut Task, fields: RowFields, editing: bool) {
+    // If the task has a due date, display it
+    if let Some(due) = task.due.as_mut().filter(|_| fields.due) {
+        if editing {
+            // Each row's date picker needs its own ID, or they'd all open together
+            let id = task.uuid.to_string();
+            ui.add(egui_extras::DatePickerButton::new(due).id_source(&id));
+        } else {
+            ui.label(format!("📅 {}", due.format(DATE_FORMAT)));
+        }
+    }
+
+    // If the task's priority isn't zero, display it
+    if task.priority.is_set() && fields.priority {
+        if editing {
+            ui_elements::priority_slider(ui, &mut task.priority);
+        } else {
+            ui.label(format!("Priority {}", task.priority));
+        }
+    }
+
+    // If the task's progress isn't zero, display it
+    if task.progress.get() != 0 && fields.progress {
+        if editing {
+            ui_elements::percentage_slider(ui, &mut task.progress);
+        } else {
+            ui.label(format!("{}%", task.progress));
+        }
+    }
+
+    // Show the task's status, if it's not the usual one
+    if task.status != Status::InProgress && fields.status {
+        if editing {
+            // Combo box IDs are normally generated using their label.
+            // If two elements have the same label, weird stuff happens
+            // So an id is generated using the `uuid` library.
+            egui::ComboBox::new(task.uuid.to_u128_le(), "Status")
+                .selected_text(task.status.name()) // Show selected status
+                .show_ui(ui, |ui| {
+                    for status in Status::iterator() {
+                        // Iterate over possible statuses and show each as an option
+                        ui.selectable_value(&mut task.status, status.clone(), status.name());
                     }
+                });
+        } else {
+            ui.label(task.status.name());
+        }
+    }
+}
+
+// How many characters of a description are laid out at a time when its row is
+// expanded, so that huge descriptions don't slow the list down
+const EXPANDED_CHARS: usize = 2000;
+
+/// The start of a task's description that's shown in its row, all on one line,
+/// or nothing if the description is empty or no characters are shown.
+/// Only the characters that are shown are looked at, however long it is.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::task_views;
+///
+/// assert_eq!(task_views::description_preview("Milk\nEggs", 20).as_deref(), Some("Milk Eggs"));
+/// assert_eq!(task_views::description_preview("Crème brûlée", 5).as_deref(), Some("Crème..."));
+/// assert_eq!(task_views::description_preview(" ", 20), None);
+/// assert_eq!(task_views::description_preview("Milk", 0), None);
+/// ```
+pub fn description_preview(description: &str, chars: usize) -> Option<String> {
+    let mut preview: String = description
+        .chars()
+        .take(chars + 1)
+        .map(|c| if c == '\n' { ' ' } else { c })
+        .collect();
+    if preview.chars().count() > chars {
+        preview = preview
+            .chars()
+            .take(chars)
+            .collect::<String>()
+            .trim()
+            .to_string()
+            + "...";
+    }
+    (chars > 0 && !preview.trim().is_empty()).then_some(preview)
+}
+
+// Shows the whole of a task's description, wrapped, under its row. Very long
+// descriptions are shown a part at a time, with a button to show more.
+fn full_description(ui: &mut Ui, row_id: egui::Id, description: &str) {
+    let shown_id = row_id.with("description_chars");
+    let shown: usize = ui.data(|d| d.get_temp(shown_id)).unwrap_or(EXPANDED_CHARS);
+    // Cut the description at a character, rather than part way through one
+    let end = description
+        .char_indices()
+        .nth(shown)
+        .map_or(description.len(), |(index, _)| index);
+
+    ui.add(egui::Label::new(&description[..end]).wrap(true));
+    if end < description.len() && ui.button("Show more").clicked() {
+        ui.data_mut(|d| d.insert_temp(shown_id, shown + EXPANDED_CHARS));
+    }
+}
+
+// Shows a handle which moves a task a level in the outline when it's dragged
+// sideways, like indenting a line, and which is highlighted if the task is picked.
+// Returns the handle, and whether it was dragged right, to make the task a
+// subtask of the one above it, or left, to move it up a level.
+fn outline_handle(ui: &mut Ui, uuid: uuid::Uuid, selected: bool) -> (egui::Response, Option<bool>) {
+    let mut text = RichText::new("⠿");
+    text = if selected {
+        text.background_color(ui.visuals().selection.bg_fill)
+    } else {
+        text.weak()
+    };
+    let response = ui
+        .add(egui::Label::new(text).sense(egui::Sense::click_and_drag()))
+        .on_hover_text(
+            "Drag right to make this a subtask, or left to move it up a level. \
+             Click to select, or shift-click to select a range.",
+        );
+    (response.clone(), outline_move(ui, &response, uuid))
+}
 
-                    // Create a checkbox with previously created text
-                    ui.checkbox(&mut task.completed, task_text);
+// Whether a task's handle was let go of far enough to the right, or to the left
+fn outline_move(ui: &mut Ui, response: &egui::Response, uuid: uuid::Uuid) -> Option<bool> {
+    let id = egui::Id::new(("outline_handle", uuid));
 
-                    if !task.description.is_empty() {
-                        // Show task description
-                        ui.label(desc_text);
+    if response.hovered() || response.dragged() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+    }
+    if response.drag_started() {
+        let start = ui.input(|i| i.pointer.press_origin());
+        ui.data_mut(|d| d.insert_temp(id, start));
+    }
+    if !response.drag_released() {
+        return None;
+    }
+
+    let start = ui.data(|d| d.get_temp::<Option<egui::Pos2>>(id)).flatten();
+    ui.data_mut(|d| d.remove::<Option<egui::Pos2>>(id));
+    let start = start?;
+    let end = ui.input(|i| i.pointer.interact_pos())?;
+    let moved = end.x - start.x;
+    (moved.abs() >= INDENT_WIDTH).then_some(moved > 0.)
+}
+
+// How long, in minutes, each slot on the day planner is
+const SLOT_MINUTES: u32 = 30;
+// How long, in minutes, tasks are scheduled for when they're first dropped onto the planner
+const DEFAULT_BLOCK_MINUTES: u32 = 60;
+
+/// Shows a day split into half hour slots, which tasks can be dragged onto.
+///
+/// This plans when tasks will be worked on, which is kept separate from when
+/// they're due. The planned blocks of time can be exported as events for calendar apps.
+pub struct DayPlannerView {
+    // The day being planned
+    day: NaiveDate,
+    // The task being dragged, if there is one
+    dragging: Option<uuid::Uuid>,
+    // Stores the file dialog to choose where to export the schedule to
+    export_dialog: Option<platform::ExportDialog>,
+}
+
+// The planner starts on today
+impl Default for DayPlannerView {
+    fn default() -> Self {
+        Self {
+            day: Local::now().date_naive(),
+            dragging: None,
+            export_dialog: None,
+        }
+    }
+}
+
+// Something that was done to a task on the planner, which is applied once
+// every task has been shown
+enum PlannerAction {
+    Schedule(uuid::Uuid, NaiveDateTime),
+    Unschedule(uuid::Uuid),
+}
+
+impl DayPlannerView {
+    // Bar for picking the day, and exporting the schedule
+    fn day_bar(&mut self, ui: &mut Ui, task_list: &TaskList) {
+        let ctx = ui.ctx().clone();
+        ui.horizontal(|ui| {
+            if ui.button("◀").on_hover_text("Previous day").clicked() {
+                self.day = self.day.pred_opt().unwrap_or(self.day);
+            }
+            ui.label(RichText::new(self.day.format("%A %-d %B %Y").to_string()).strong());
+            if ui.button("▶").on_hover_text("Next day").clicked() {
+                self.day = self.day.succ_opt().unwrap_or(self.day);
+            }
+            if ui.button("Today").clicked() {
+                self.day = Local::now().date_naive();
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .button("Export schedule")
+                    .on_hover_text("Save the planned tasks as events, for calendar apps")
+                    .clicked()
+                {
+                    let file_name = format!("{} schedule.ics", task_list.name);
+                    self.export_dialog = Some(platform::ExportDialog::open(&ctx, &file_name));
+                }
+            });
+        });
+
+        // If somewhere to export to has been picked using the dialog
+        if let Some(dialog) = &mut self.export_dialog {
+            if let Some(target) = dialog.show(&ctx) {
+                let schedule = task_list.schedule_to_ical_string_with(LineEnding::default());
+                if let Err(e) = target.write(schedule.as_bytes()) {
+                    eprintln!("could not export schedule: {e}");
+                }
+                self.export_dialog = None;
+            }
+        }
+    }
+
+    // Shows a task which can be dragged onto the planner
+    fn drag_source(&mut self, ui: &mut Ui, task: &Task, text: String) {
+        drag_source(ui, &mut self.dragging, task, text);
+    }
+}
+
+// Shows a task which can be dragged somewhere else in a view, or a gap where
+// it was while it's being dragged. The task being dragged is kept in `dragging`.
+fn drag_source(ui: &mut Ui, dragging: &mut Option<uuid::Uuid>, task: &Task, text: String) {
+    let id = egui::Id::new(("dragged_task", task.uuid));
+
+    if *dragging == Some(task.uuid) {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+        // The task follows the pointer while it's being dragged
+        if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+            egui::Area::new(id.with("dragged"))
+                .order(egui::Order::Tooltip)
+                .fixed_pos(pointer)
+                .interactable(false)
+                .show(ui.ctx(), |ui| {
+                    ui_elements::basic_frame()
+                        .fill(ui.visuals().window_fill())
+                        .show(ui, |ui| ui.label(&task.summary));
+                });
+        }
+        ui.weak(text);
+        return;
+    }
+
+    let response = ui.add(egui::Label::new(text).sense(egui::Sense::drag()));
+    let response = ui_elements::task_tooltip::attach(response, task);
+    if response.hovered() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+    }
+    if response.drag_started() {
+        *dragging = Some(task.uuid);
+    }
+}
+
+impl TaskView for DayPlannerView {
+    fn name(&self) -> &str {
+        "Day planner"
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn display(&mut self, ui: &mut Ui, task_list: &mut TaskList, options: &ViewOptions) {
+        self.day_bar(ui, task_list);
+        ui.separator();
+
+        let mut actions = Vec::new();
+        // Dragged tasks are dropped when the pointer is let go of
+        let released = ui.input(|i| i.pointer.any_released());
+        let day_start = self.day.and_time(NaiveTime::MIN);
+        let order = task_list.display_order_with(options.sort_by, options.sort_descending);
+        let clock = Local::now().naive_local();
+
+        ui.columns(2, |columns| {
+            // Tasks that haven't been planned yet, which can be dragged onto a slot.
+            // Planned tasks can be dragged back here to unplan them.
+            let unscheduled = columns[0]
+                .vertical(|ui| {
+                    ui.label(RichText::new("Not planned").strong());
+                    for &index in &order {
+                        let task = &task_list.tasks[index];
+                        if task.scheduled.is_some() || !options.shows(task, clock) {
+                            continue;
+                        }
+                        self.drag_source(ui, task, task.summary.clone());
                     }
+                    // Leave room to drop tasks onto, even when every task has been planned
+                    ui.allocate_space(egui::vec2(ui.available_width(), 40.));
+                })
+                .response;
+            if let Some(uuid) = self.dragging {
+                if released && columns[0].rect_contains_pointer(unscheduled.rect) {
+                    actions.push(PlannerAction::Unschedule(uuid));
+                }
+            }
 
-                    // Right-aligned, right-to-left UI segment
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        // Click this to show a modal with a task's full details
-                        // Doesn't spawn it if there's already one present
-                        if ui.button("···").clicked() {
-                            *task.show_modal.borrow_mut() = true;
-                        };
+            // The day's slots, with the tasks that start in each of them
+            let ui = &mut columns[1];
+            for slot in 0..(24 * 60 / SLOT_MINUTES) {
+                let slot_start = day_start + Duration::minutes((slot * SLOT_MINUTES).into());
+                let slot_end = slot_start + Duration::minutes(SLOT_MINUTES.into());
 
-                        // If the button is clicked, mark task for removal
-                        keep = !ui.button("✖").clicked();
+                // Slots that a planned task covers are shaded in behind it
+                let background = ui.painter().add(egui::Shape::Noop);
+                let row = ui
+                    .horizontal(|ui| {
+                        ui.set_width(ui.available_width());
+                        ui.label(
+                            RichText::new(slot_start.format("%H:%M").to_string())
+                                .monospace()
+                                .weak(),
+                        );
 
-                        // If the task has a due date, display it
-                        if let Some(mut due) = task.due {
-                            ui.add(egui_extras::DatePickerButton::new(&mut due));
+                        for &index in &order {
+                            let task = &task_list.tasks[index];
+                            let Some(block) = task.scheduled else {
+                                continue;
+                            };
+                            if block.start < slot_start || block.start >= slot_end {
+                                continue;
+                            }
+                            let text = format!(
+                                "{} ({}–{})",
+                                task.summary,
+                                block.start.format("%H:%M"),
+                                block.end().format("%H:%M")
+                            );
+                            self.drag_source(ui, task, text);
+
+                            // How long the task is planned for can be changed here
+                            let task = &mut task_list.tasks[index];
+                            if let Some(block) = &mut task.scheduled {
+                                ui.add(
+                                    egui::DragValue::new(&mut block.minutes)
+                                        .clamp_range(SLOT_MINUTES..=24 * 60)
+                                        .speed(1.)
+                                        .suffix(" min"),
+                                );
+                            }
                         }
+                    })
+                    .response;
+
+                let covered = task_list.tasks.iter().any(|task| {
+                    task.scheduled
+                        .is_some_and(|block| block.start < slot_end && block.end() > slot_start)
+                });
+                let hovered = self.dragging.is_some() && ui.rect_contains_pointer(row.rect);
+                if hovered || covered {
+                    let color = if hovered {
+                        ui.visuals().selection.bg_fill
+                    } else {
+                        ui.visuals().faint_bg_color
+                    };
+                    ui.painter().set(
+                        background,
+                        egui::Shape::rect_filled(row.rect.expand(1.), 2., color),
+                    );
+                }
+                if hovered && released {
+                    if let Some(uuid) = self.dragging {
+                        actions.push(PlannerAction::Schedule(uuid, slot_start));
+                    }
+                }
+            }
+        });
+
+        if released {
+            self.dragging = None;
+        }
+
+        for action in actions {
+            match action {
+                PlannerAction::Schedule(uuid, start) => {
+                    if let Some(task) = task_list.tasks.iter_mut().find(|task| task.uuid == uuid) {
+                        // Moving a planned task keeps how long it's planned for
+                        let minutes = task
+                            .scheduled
+                            .map_or(DEFAULT_BLOCK_MINUTES, |block| block.minutes);
+                        task.scheduled = Some(TimeBlock { start, minutes });
+                    }
+                }
+                PlannerAction::Unschedule(uuid) => {
+                    if let Some(task) = task_list.tasks.iter_mut().find(|task| task.uuid == uuid) {
+                        task.scheduled = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shows the tasks picked for today, which are dragged in from the rest of
+/// the list, with how long they're expected to take altogether.
+///
+/// Tasks are only in My Day on the day they were picked, so it starts empty
+/// every morning. Tasks that weren't finished can be carried over to the next day.
+#[derive(Default)]
+pub struct MyDayView {
+    // The task being dragged, if there is one
+    dragging: Option<uuid::Uuid>,
+}
+
+// Something that was done to a task in My Day, which is applied once every task has been shown
+enum MyDayAction {
+    Add(uuid::Uuid),
+    Remove(uuid::Uuid),
+    // Pick the unfinished tasks from earlier days for today too
+    CarryOver,
+    // Take the unfinished tasks from earlier days out of My Day
+    LeaveBehind,
+}
+
+/// Writes a number of minutes as hours and minutes, like "1h 30m".
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::task_views::format_minutes;
+///
+/// assert_eq!(format_minutes(90), "1h 30m");
+/// assert_eq!(format_minutes(120), "2h");
+/// assert_eq!(format_minutes(45), "45m");
+/// ```
+pub fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
 
-                        // If the task's priority isn't zero, display it
-                        if task.priority != 0 {
-                            ui.add(egui::Slider::new(&mut task.priority, 0..=10));
+impl TaskView for MyDayView {
+    fn name(&self) -> &str {
+        "My Day"
+    }
+
+    fn display(&mut self, ui: &mut Ui, task_list: &mut TaskList, options: &ViewOptions) {
+        let mut actions = Vec::new();
+        let released = ui.input(|i| i.pointer.any_released());
+        let clock = Local::now().naive_local();
+        let today = clock.date();
+        let order = task_list.display_order_with(options.sort_by, options.sort_descending);
+
+        // Ask what to do with tasks left over from an earlier day
+        let left_over = task_list
+            .tasks
+            .iter()
+            .filter(|task| !task.completed && task.my_day.is_some_and(|day| day < today))
+            .count();
+        if left_over > 0 {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{left_over} tasks from an earlier day weren't finished."
+                ));
+                if ui.button("Carry over to today").clicked() {
+                    actions.push(MyDayAction::CarryOver);
+                }
+                if ui.button("Leave them").clicked() {
+                    actions.push(MyDayAction::LeaveBehind);
+                }
+            });
+            ui.separator();
+        }
+
+        ui.columns(2, |columns| {
+            // Tasks that could be picked for today
+            let others = columns[0]
+                .vertical(|ui| {
+                    ui.label(RichText::new("Tasks").strong());
+                    for &index in &order {
+                        let task = &task_list.tasks[index];
+                        if task.in_my_day(today) || !options.shows(task, clock) {
+                            continue;
                         }
+                        drag_source(ui, &mut self.dragging, task, task.summary.clone());
+                    }
+                    ui.allocate_space(egui::vec2(ui.available_width(), 40.));
+                })
+                .response;
+            if let Some(uuid) = self.dragging {
+                if released && columns[0].rect_contains_pointer(others.rect) {
+                    actions.push(MyDayAction::Remove(uuid));
+                }
+            }
 
-                        // If the task's priority isn't zero, display it
-                        if task.progress != 0 {
-                            ui_elements::percentage_slider(ui, &mut task.progress);
+            // Tasks picked for today, with how long they'll take
+            let my_day = columns[1]
+                .vertical(|ui| {
+                    let picked = || {
+                        order
+                            .iter()
+                            .map(|&index| &task_list.tasks[index])
+                            .filter(|task| task.in_my_day(today))
+                    };
+                    let total: u32 = picked().filter_map(|task| task.estimate).sum();
+                    let left: u32 = picked()
+                        .filter(|task| !task.completed)
+                        .filter_map(|task| task.estimate)
+                        .sum();
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("My Day").strong());
+                        ui.label(format!(
+                            "{} planned, {} left",
+                            format_minutes(total),
+                            format_minutes(left)
+                        ));
+                    });
+
+                    for &index in &order {
+                        let task = &task_list.tasks[index];
+                        if !task.in_my_day(today)
+                            || (task.completed && !options.show_completed_tasks)
+                        {
+                            continue;
                         }
+                        ui.horizontal(|ui| {
+                            let task = &mut task_list.tasks[index];
+                            ui.checkbox(&mut task.completed, "");
+                            drag_source(ui, &mut self.dragging, task, task.summary.clone());
 
-                        // Create dropdown containing the task's status
-                        if task.status != Status::InProgress {
-                            // Combo box IDs are normally generated using their label.
-                            // If two elements have the same label, weird stuff happens
-                            // So an id is generated using the `uuid` library.
-                            egui::ComboBox::new(task.uuid.to_u128_le(), "Status")
-                                .selected_text(format!("{:?}", &task.status).to_case(Case::Title)) // Show selected status
-                                .show_ui(ui, |ui| {
-                                    for status in Status::iterator() {
-                                        // Iterate over possible statuses and show each as an option
-                                        ui.selectable_value(
-                                            &mut task.status,
-                                            *status,
-                                            format!("{status:?}").to_case(Case::Title),
-                                        );
-                                    }
-                                });
+                            // How long the task is expected to take, where 0 means no estimate
+                            let mut estimate = task.estimate.unwrap_or_default();
+                            ui.add(
+                                egui::DragValue::new(&mut estimate)
+                                    .clamp_range(0..=24 * 60)
+                                    .speed(1.)
+                                    .suffix(" min"),
+                            )
+                            .on_hover_text("How long the task should take");
+                            task.estimate = (estimate > 0).then_some(estimate);
+                        });
+                    }
+                    ui.allocate_space(egui::vec2(ui.available_width(), 40.));
+                })
+                .response;
+            if let Some(uuid) = self.dragging {
+                if released && columns[1].rect_contains_pointer(my_day.rect) {
+                    actions.push(MyDayAction::Add(uuid));
+                }
+            }
+        });
+
+        if released {
+            self.dragging = None;
+        }
+
+        for action in actions {
+            match action {
+                MyDayAction::Add(uuid) | MyDayAction::Remove(uuid) => {
+                    let picked = matches!(action, MyDayAction::Add(_));
+                    if let Some(task) = task_list.tasks.iter_mut().find(|task| task.uuid == uuid) {
+                        task.my_day = picked.then_some(today);
+                    }
+                }
+                MyDayAction::CarryOver | MyDayAction::LeaveBehind => {
+                    let day = matches!(action, MyDayAction::CarryOver).then_some(today);
+                    for task in &mut task_list.tasks {
+                        if !task.completed && task.my_day.is_some_and(|picked| picked < today) {
+                            task.my_day = day;
                         }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shows a week at a time, with the tasks due on each day grouped under it.
+/// Tasks can be dragged between days to change when they're due, or onto the
+/// group of tasks with no due date to take theirs away.
+///
+/// Weeks start on Monday and are numbered the ISO 8601 way, like most planners.
+pub struct WeekView {
+    // The Monday that starts the week being shown
+    week_start: NaiveDate,
+    // The task being dragged, if there is one
+    dragging: Option<uuid::Uuid>,
+}
+
+// The view starts on this week
+impl Default for WeekView {
+    fn default() -> Self {
+        Self {
+            week_start: week_start(Local::now().date_naive()),
+            dragging: None,
+        }
+    }
+}
+
+/// The Monday that starts the ISO week a day is in.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::task_views::week_start;
+///
+/// let sunday = NaiveDate::from_ymd_opt(2023, 8, 13).unwrap();
+/// assert_eq!(week_start(sunday), NaiveDate::from_ymd_opt(2023, 8, 7).unwrap());
+/// ```
+pub fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday().into())
+}
+
+/// Describes the ISO week a day is in, which can belong to the year before
+/// or after the day's own around New Year.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::task_views::week_name;
+///
+/// assert_eq!(week_name(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap()), "Week 32 of 2023");
+/// assert_eq!(week_name(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()), "Week 53 of 2020");
+/// ```
+pub fn week_name(day: NaiveDate) -> String {
+    let week = day.iso_week();
+    format!("Week {} of {}", week.week(), week.year())
+}
+
+impl WeekView {
+    // Bar for picking the week
+    fn week_bar(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("◀").on_hover_text("Previous week").clicked() {
+                self.week_start -= Duration::weeks(1);
+            }
+            let last_day = self.week_start + Duration::days(6);
+            ui.label(RichText::new(week_name(self.week_start)).strong());
+            ui.weak(format!(
+                "{} to {}",
+                self.week_start.format("%-d %B"),
+                last_day.format("%-d %B")
+            ));
+            if ui.button("▶").on_hover_text("Next week").clicked() {
+                self.week_start += Duration::weeks(1);
+            }
+            if ui.button("This week").clicked() {
+                self.week_start = week_start(Local::now().date_naive());
+            }
+        });
+    }
+
+    // Shows a group of tasks which can be collapsed, and which tasks can be
+    // dropped onto. Returns whether the task being dragged was dropped on it.
+    fn group(
+        &mut self,
+        ui: &mut Ui,
+        task_list: &mut TaskList,
+        order: &[usize],
+        heading: RichText,
+        id: egui::Id,
+        in_group: impl Fn(&Task) -> bool,
+    ) -> bool {
+        let count = order
+            .iter()
+            .filter(|&&index| in_group(&task_list.tasks[index]))
+            .count();
+        let response = egui::CollapsingHeader::new(heading)
+            .id_source(id)
+            .default_open(true)
+            .show(ui, |ui| {
+                for &index in order {
+                    if !in_group(&task_list.tasks[index]) {
+                        continue;
+                    }
+                    ui.horizontal(|ui| {
+                        let task = &mut task_list.tasks[index];
+                        ui.checkbox(&mut task.completed, "");
+                        drag_source(ui, &mut self.dragging, task, task.summary.clone());
                     });
-                });
+                }
+                if count == 0 {
+                    ui.weak("Nothing due");
+                }
+            });
 
-                if task.status == Status::Completed {
-                    task.completed = true;
+        let rect = response
+            .body_response
+            .map_or(response.header_response.rect, |body| {
+                body.rect.union(response.header_response.rect)
+            });
+        let released = ui.input(|i| i.pointer.any_released());
+        self.dragging.is_some() && released && ui.rect_contains_pointer(rect)
+    }
+}
+
+impl TaskView for WeekView {
+    fn name(&self) -> &str {
+        "Week"
+    }
+
+    fn display(&mut self, ui: &mut Ui, task_list: &mut TaskList, options: &ViewOptions) {
+        self.week_bar(ui);
+        ui.separator();
+
+        let clock = Local::now().naive_local();
+        let today = clock.date();
+        let order: Vec<usize> = task_list
+            .display_order_with(options.sort_by, options.sort_descending)
+            .into_iter()
+            .filter(|&index| options.shows(&task_list.tasks[index], clock))
+            .collect();
+        // Where the task being dragged was dropped, which is nothing for no due date
+        let mut dropped = None;
+
+        for day in self.week_start.iter_days().take(7) {
+            let mut heading = RichText::new(day.format("%A %-d %B").to_string());
+            if day == today {
+                heading = heading.strong();
+            }
+            let id = egui::Id::new(("week_day", day));
+            let due_on_day = |task: &Task| task.due == Some(day);
+            if self.group(ui, task_list, &order, heading, id, due_on_day) {
+                dropped = Some(Some(day));
+            }
+        }
+
+        ui.separator();
+        let heading = RichText::new("No due date");
+        let id = egui::Id::new("week_no_due_date");
+        let no_due_date = |task: &Task| task.due.is_none();
+        if self.group(ui, task_list, &order, heading, id, no_due_date) {
+            dropped = Some(None);
+        }
+
+        if ui.input(|i| i.pointer.any_released()) {
+            if let (Some(uuid), Some(due)) = (self.dragging.take(), dropped) {
+                if let Some(task) = task_list.tasks.iter_mut().find(|task| task.uuid == uuid) {
+                    task.due = due;
                 }
             }
+        }
+    }
+}
 
-            // Spawn a modal if told to
-            if *task.show_modal.borrow() {
-                ui_elements::task_modal::spawn(task, ui.ctx());
+/// Shows the tasks on a kanban board, with a column for each status. Cards are
+/// dragged to another column to change their status, or up and down a column to
+/// put them in order, which is kept whatever the list is sorted by.
+///
+/// Each column can have a work in progress limit, set by right-clicking its
+/// heading, which is highlighted when the column has more cards than that.
+/// Cards can also be kept from being dropped into columns that are full. Both
+/// are kept in the list's layout, so each list's board has its own limits.
+///
+/// The board can be split into swimlanes by tag or priority, which can each be
+/// collapsed, so big boards are easier to find things on. Cards are moved
+/// between columns within their own lane. Which lanes the board is split into
+/// is kept in the list's layout too.
+///
+/// Cards show just their summary, until they're expanded to show their
+/// description, when they're due and how far along they are. Which cards are
+/// expanded is kept for each task in the list's layout, so they stay that way
+/// when they're moved, the board is split into other lanes, or the app restarts.
+#[derive(Default)]
+pub struct KanbanView {
+    // The task being dragged, if there is one
+    dragging: Option<uuid::Uuid>,
+    // The most cards each status's column should have, for columns that have a limit
+    limits: BTreeMap<Status, usize>,
+    // Whether cards can't be dropped into a column that's already at its limit
+    block_full_columns: bool,
+    // What the board is split into swimlanes by
+    swimlanes: Swimlanes,
+    // The tasks whose cards are expanded
+    expanded: BTreeSet<uuid::Uuid>,
+}
+
+impl KanbanView {
+    // Shows the heading of a status's column, with how many cards it has, which is
+    // highlighted if there are more than its limit. Right-clicking it sets the limit.
+    fn column_heading(&mut self, ui: &mut Ui, status: &Status, count: usize) {
+        let limit = self.limits.get(status).copied();
+        let mut heading = RichText::new(status.name()).strong();
+        if limit.is_some_and(|limit| count > limit) {
+            heading = heading.color(ui.visuals().error_fg_color);
+        }
+        let count = match limit {
+            Some(limit) => format!("{count}/{limit}"),
+            None => count.to_string(),
+        };
+
+        let heading = ui
+            .horizontal(|ui| {
+                ui.label(heading);
+                ui.weak(count);
+            })
+            .response;
+        let id = egui::Id::new(("kanban_heading", status));
+        ui.interact(heading.rect, id, egui::Sense::click())
+            .on_hover_text("Right-click to set how many cards the column can have")
+            .context_menu(|ui| {
+                let mut limit = limit.unwrap_or(0);
+                ui.horizontal(|ui| {
+                    ui.label("Work in progress limit");
+                    ui.add(egui::DragValue::new(&mut limit).clamp_range(0..=99));
+                })
+                .response
+                .on_hover_text("0 for no limit");
+                if limit == 0 {
+                    self.limits.remove(status);
+                } else {
+                    self.limits.insert(status.clone(), limit);
+                }
+                ui.checkbox(
+                    &mut self.block_full_columns,
+                    "Don't let cards be dropped into full columns",
+                );
+            });
+    }
+
+    // Shows a status's column of cards in a swimlane. `total` is how many cards the
+    // column has in every lane. If the pointer is over the column while a card is
+    // being dragged, and it can be dropped here, returns the card the dragged one
+    // would go above, which is nothing if it would go at the bottom.
+    fn column(
+        &mut self,
+        ui: &mut Ui,
+        task_list: &TaskList,
+        status: &Status,
+        column: &[usize],
+        total: usize,
+        droppable: bool,
+    ) -> Option<Option<uuid::Uuid>> {
+        // Where each card is, apart from the one being dragged, to work out where it goes
+        let mut cards = Vec::new();
+        for &index in column {
+            let task = &task_list.tasks[index];
+            let card = self.card(ui, task);
+            if self.dragging != Some(task.uuid) {
+                cards.push((task.uuid, card.rect));
+            }
+        }
+        // Leave room to drop cards onto, even when the column is empty
+        let (bottom, _) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 40.), egui::Sense::hover());
+
+        let dragging = self.dragging.filter(|_| droppable)?;
+        // A full column can't take any more cards, apart from moving its own
+        let full = self.limits.get(status).is_some_and(|&limit| total >= limit);
+        let from_column = task_list
+            .tasks
+            .iter()
+            .any(|task| task.uuid == dragging && task.status == *status);
+        if self.block_full_columns && full && !from_column {
+            return None;
+        }
+        let pointer = ui
+            .ctx()
+            .pointer_interact_pos()
+            .filter(|pointer| ui.min_rect().contains(*pointer))?;
+        let above = cards
+            .iter()
+            .find(|(_, rect)| rect.center().y > pointer.y)
+            .map(|(uuid, rect)| (*uuid, rect.top()));
+
+        // Show where the card will go
+        let y = above.map_or(bottom.top(), |(_, top)| top) - ui.spacing().item_spacing.y / 2.;
+        ui.painter().hline(
+            ui.min_rect().x_range(),
+            y,
+            egui::Stroke::new(2., ui.visuals().selection.bg_fill),
+        );
+        Some(above.map(|(uuid, _)| uuid))
+    }
+
+    // Shows a task's card, with a button to expand it
+    fn card(&mut self, ui: &mut Ui, task: &Task) -> egui::Response {
+        let card_id = egui::Id::new(("kanban_card", task.uuid));
+        let expanded = self.expanded.contains(&task.uuid);
+
+        ui_elements::basic_frame()
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.horizontal(|ui| {
+                    let toggle = ui
+                        .small_button(if expanded { "⏷" } else { "⏵" })
+                        .on_hover_text(if expanded { "Collapse" } else { "Expand" });
+                    if toggle.clicked() && !self.expanded.remove(&task.uuid) {
+                        self.expanded.insert(task.uuid);
+                    }
+                    drag_source(ui, &mut self.dragging, task, task.summary.clone());
+                });
+                if !expanded {
+                    return;
+                }
+
+                if !task.description.trim().is_empty() {
+                    full_description(ui, card_id, &task.description);
+                }
+                if let Some(due) = task.due {
+                    ui.label(format!("📅 {}", due.format(DATE_FORMAT)));
+                }
+                let progress = task.progress.get();
+                if progress != 0 {
+                    ui.add(
+                        egui::ProgressBar::new(f32::from(progress) / f32::from(MAX_PROGRESS))
+                            .show_percentage(),
+                    );
+                }
+            })
+            .response
+    }
+
+    // Shows a swimlane's cards, in a column for each status. Returns the column the
+    // dragged card would be dropped in, if the pointer is over one, and the card it
+    // would go above.
+    fn lane(
+        &mut self,
+        ui: &mut Ui,
+        task_list: &TaskList,
+        statuses: &[Status],
+        lane: &[usize],
+        totals: &[usize],
+        droppable: bool,
+    ) -> Option<(usize, Option<uuid::Uuid>)> {
+        let mut dropped = None;
+        ui.columns(statuses.len(), |columns| {
+            for (i, (ui, status)) in columns.iter_mut().zip(statuses).enumerate() {
+                let column = task_list.board_column(status, lane);
+                if let Some(above) =
+                    self.column(ui, task_list, status, &column, totals[i], droppable)
+                {
+                    dropped = Some((i, above));
+                }
             }
+        });
+        dropped
+    }
+}
+
+impl TaskView for KanbanView {
+    fn name(&self) -> &str {
+        "Kanban"
+    }
+
+    fn save_layout(&self, layout: &mut ListLayout) {
+        layout.column_limits.clone_from(&self.limits);
+        layout.block_full_columns = self.block_full_columns;
+        layout.swimlanes = self.swimlanes;
+        layout.expanded_cards.clone_from(&self.expanded);
+    }
+
+    fn apply_layout(&mut self, layout: &ListLayout) {
+        self.limits.clone_from(&layout.column_limits);
+        self.block_full_columns = layout.block_full_columns;
+        self.swimlanes = layout.swimlanes;
+        self.expanded.clone_from(&layout.expanded_cards);
+    }
 
-            keep // If this is false, task is removed from the vector
+    fn display(&mut self, ui: &mut Ui, task_list: &mut TaskList, options: &ViewOptions) {
+        // Cards of tasks that were removed aren't kept expanded
+        if !self.expanded.is_empty() {
+            let uuids: HashSet<uuid::Uuid> = task_list.tasks.iter().map(|t| t.uuid).collect();
+            self.expanded.retain(|uuid| uuids.contains(uuid));
+        }
+        let clock = Local::now().naive_local();
+        let order: Vec<usize> = task_list
+            .display_order_with(options.sort_by, options.sort_descending)
+            .into_iter()
+            .filter(|&index| options.shows(&task_list.tasks[index], clock))
+            .collect();
+        let statuses = task_list.board_statuses();
+        let totals: Vec<usize> = statuses
+            .iter()
+            .map(|status| {
+                order
+                    .iter()
+                    .filter(|&&index| task_list.tasks[index].status == *status)
+                    .count()
+            })
+            .collect();
+        let lanes = task_list.swimlanes(self.swimlanes, &order);
+        // The column the card being dragged was dropped on, and the card it went above
+        let mut dropped = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Swimlanes");
+            egui::ComboBox::from_id_source("kanban_swimlanes")
+                .selected_text(self.swimlanes.name())
+                .show_ui(ui, |ui| {
+                    for &swimlanes in Swimlanes::iterator() {
+                        ui.selectable_value(&mut self.swimlanes, swimlanes, swimlanes.name());
+                    }
+                });
         });
+        ui.columns(statuses.len(), |columns| {
+            for ((ui, status), &total) in columns.iter_mut().zip(&statuses).zip(&totals) {
+                self.column_heading(ui, status, total);
+                ui.separator();
+            }
+        });
+
+        for (name, lane) in &lanes {
+            // Cards can't be moved into another lane, as that would change their tags or priority
+            let droppable = self.dragging.map_or(true, |uuid| {
+                lane.iter()
+                    .any(|&index| task_list.tasks[index].uuid == uuid)
+            });
+            let lane_dropped = if self.swimlanes == Swimlanes::None {
+                self.lane(ui, task_list, &statuses, lane, &totals, droppable)
+            } else {
+                egui::CollapsingHeader::new(format!("{name} ({})", lane.len()))
+                    .id_source(("kanban_lane", name))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        self.lane(ui, task_list, &statuses, lane, &totals, droppable)
+                    })
+                    .body_returned
+                    .flatten()
+            };
+            dropped = dropped.or(lane_dropped);
+        }
+
+        if ui.input(|i| i.pointer.any_released()) {
+            if let (Some(uuid), Some((column, above))) = (self.dragging.take(), dropped) {
+                task_list.move_on_board(uuid, &statuses[column], above);
+            }
+        }
     }
 }
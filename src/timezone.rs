@@ -0,0 +1,219 @@
+// ----------------------------------------------------------------------------
+// Notices when the computer's time zone changes, like when travelling, so
+// the user can choose what happens to the times on their tasks. Times are
+// kept in local time, so they can either stay the same on the clock, or be
+// moved so that they stay at the same moment as before.
+// Daylight saving doesn't count as a change, since a time zone is told apart
+// by its offsets from UTC in both winter and summer.
+// ----------------------------------------------------------------------------
+
+use chrono::{Datelike, Duration, Local, NaiveDate, Offset, TimeZone};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::platform;
+use crate::task::TaskList;
+
+// Name that the time zone the app last saw is saved under
+const ZONE_STATE: &str = "time-zone";
+// How often, in seconds, the time zone is checked
+const CHECK_INTERVAL: f64 = 60.;
+
+// What to do with the times on tasks when the time zone changes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub enum TimeZonePolicy {
+    // Ask every time
+    #[default]
+    Ask,
+    // Times stay the same on the clock, so 9:00 stays 9:00
+    FollowLocal,
+    // Times move so they stay at the same moment, so 9:00 in London becomes 4:00 in New York
+    Anchored,
+}
+
+impl TimeZonePolicy {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        [Self::Ask, Self::FollowLocal, Self::Anchored].iter()
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Ask => "Ask me",
+            Self::FollowLocal => "Keep the same local times",
+            Self::Anchored => "Keep the same moments in time",
+        }
+    }
+}
+
+// A time zone, told apart by how far ahead of UTC it is, in seconds, in winter and summer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZoneOffsets {
+    pub january: i32,
+    pub july: i32,
+}
+
+impl ZoneOffsets {
+    // The computer's time zone
+    pub fn local() -> Self {
+        let year = Local::now().year();
+        let offset = |month| {
+            NaiveDate::from_ymd_opt(year, month, 1)
+                .and_then(|day| day.and_hms_opt(12, 0, 0))
+                .map_or(0, |time| {
+                    Local
+                        .from_utc_datetime(&time)
+                        .offset()
+                        .fix()
+                        .local_minus_utc()
+                })
+        };
+        Self {
+            january: offset(1),
+            july: offset(7),
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let (january, july) = text.trim().split_once(',')?;
+        Some(Self {
+            january: january.parse().ok()?,
+            july: july.parse().ok()?,
+        })
+    }
+
+    // How far ahead of UTC the zone is now, going by whether the computer's clocks
+    // are on winter or summer time
+    fn now(self, local: Self) -> i32 {
+        let offset = Local::now().offset().fix().local_minus_utc();
+        if offset == local.july && local.july != local.january {
+            self.july
+        } else {
+            self.january
+        }
+    }
+
+    /// Describes the zone by how far ahead of UTC it is in winter.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::timezone::ZoneOffsets;
+    ///
+    /// let zone = ZoneOffsets { january: -5 * 3600, july: -4 * 3600 };
+    /// assert_eq!(zone.describe(), "UTC-05:00");
+    /// let zone = ZoneOffsets { january: 5 * 3600 + 1800, july: 5 * 3600 + 1800 };
+    /// assert_eq!(zone.describe(), "UTC+05:30");
+    /// ```
+    pub fn describe(self) -> String {
+        let sign = if self.january < 0 { '-' } else { '+' };
+        let minutes = self.january.unsigned_abs() / 60;
+        format!("UTC{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+}
+
+// The time zone changing from one to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TimeZoneChange {
+    pub from: ZoneOffsets,
+    pub to: ZoneOffsets,
+}
+
+impl TimeZoneChange {
+    // How far the clocks moved, which anchored times are moved by
+    pub fn shift(self) -> Duration {
+        Duration::seconds(i64::from(self.to.now(self.to) - self.from.now(self.to)))
+    }
+}
+
+/// Moves every time on a list and its tasks, so that they stay at the same
+/// moment after the clocks have moved. Due dates are whole days, so they
+/// stay the same.
+///
+/// Example:
+/// ```
+/// use chrono::{Duration, NaiveDate};
+/// use taskmaster_rs::task::*;
+/// use taskmaster_rs::timezone;
+///
+/// let nine = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap().and_hms_opt(9, 0, 0).unwrap();
+/// let mut list = TaskList::default();
+/// list.add(Task::builder().summary("Call").scheduled(nine, 30).build().unwrap());
+///
+/// // Flying from London to New York
+/// timezone::shift_times(&mut list, Duration::hours(-5));
+/// let block = list.tasks[0].scheduled.unwrap();
+/// assert_eq!(block.start, nine - Duration::hours(5));
+/// assert_eq!(block.minutes, 30);
+/// ```
+pub fn shift_times(task_list: &mut TaskList, by: Duration) {
+    task_list.created += by;
+    task_list.modified += by;
+    for task in &mut task_list.tasks {
+        task.created += by;
+        task.last_touched += by;
+        if let Some(block) = &mut task.scheduled {
+            block.start += by;
+        }
+        if let Some(completed_at) = &mut task.completed_at {
+            *completed_at += by;
+        }
+    }
+}
+
+// Keeps track of the time zone, including between runs, and notices when it changes
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TimeZoneWatcher {
+    // The time zone the user last chose what to do about
+    known: Option<ZoneOffsets>,
+    // When the time zone was last checked, in seconds since the app started
+    last_check: Option<f64>,
+}
+
+impl TimeZoneWatcher {
+    // Starts from the time zone the app was in last time it was run
+    pub fn load() -> Self {
+        Self {
+            known: platform::read_state(ZONE_STATE).and_then(|text| ZoneOffsets::parse(&text)),
+            last_check: None,
+        }
+    }
+
+    // Returns the change if the time zone is different to the one that was
+    // last accepted. Should be called once every frame.
+    pub fn check(&mut self, ctx: &egui::Context) -> Option<TimeZoneChange> {
+        let now = ctx.input(|i| i.time);
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(CHECK_INTERVAL));
+        if self
+            .last_check
+            .is_some_and(|last| now - last < CHECK_INTERVAL)
+        {
+            return None;
+        }
+        self.last_check = Some(now);
+
+        let local = ZoneOffsets::local();
+        match self.known {
+            // The first time the app is run, there's nothing to change from
+            None => {
+                self.accept(local);
+                None
+            }
+            Some(known) if known != local => Some(TimeZoneChange {
+                from: known,
+                to: local,
+            }),
+            Some(_) => None,
+        }
+    }
+
+    // Takes a time zone as the one the user's times are in, once they've chosen what to do
+    pub fn accept(&mut self, zone: ZoneOffsets) {
+        self.known = Some(zone);
+        let text = format!("{},{}", zone.january, zone.july);
+        if let Err(e) = platform::write_state(ZONE_STATE, &text) {
+            eprintln!("could not save the time zone: {e}");
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// ----------------------------------------------------------------------------
+// Moving and copying tasks to other lists. The other list is read from its
+// file, the task is added to it, and the file is written again, so the list
+// doesn't have to be open. Moved tasks keep their UUID, so that calendar apps
+// syncing both lists see the same task, while copies get a new one.
+// ----------------------------------------------------------------------------
+
+use std::io;
+use std::path::Path;
+
+use crate::encryption;
+use crate::parser::{ExportFormat, LineEnding};
+use crate::platform;
+use crate::task::{Task, TaskList};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferMode {
+    Move,
+    Copy,
+}
+
+impl TransferMode {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Move => "Move",
+            Self::Copy => "Copy",
+        }
+    }
+}
+
+impl TaskList {
+    /// Adds a task that's being moved or copied from another list.
+    /// Copies get a new UUID. The task it was a subtask of stays behind in
+    /// the other list, so it isn't a subtask in this one.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::task::*;
+    /// use taskmaster_rs::transfer::TransferMode;
+    ///
+    /// let task = Task { parent: Some(uuid::Uuid::new_v4()), ..Task::default() };
+    /// let mut list = TaskList::default();
+    ///
+    /// list.receive(&task, TransferMode::Move);
+    /// list.receive(&task, TransferMode::Copy);
+    /// assert_eq!(list.tasks[0].uuid, task.uuid);
+    /// assert_ne!(list.tasks[1].uuid, task.uuid);
+    /// assert_eq!(list.tasks[0].parent, None);
+    /// ```
+    pub fn receive(&mut self, task: &Task, mode: TransferMode) {
+        let mut task = task.clone();
+        if mode == TransferMode::Copy {
+            task.uuid = uuid::Uuid::new_v4();
+        }
+        task.parent = None;
+        self.add(task);
+    }
+}
+
+// Moves or copies a task into the list in a file, which is written the way lists are
+// exported. Moved tasks still have to be removed from the list they came from, once
+// this has succeeded.
+pub fn send(
+    task: &Task,
+    path: &Path,
+    mode: TransferMode,
+    line_ending: LineEnding,
+    format: ExportFormat,
+) -> io::Result<()> {
+    let data = platform::read_file(path)?;
+    // Encrypted lists would need their passphrase to be added to
+    if encryption::is_encrypted(&data) {
+        return Err(io::Error::other(
+            "the list is encrypted, so tasks can't be added to it without opening it",
+        ));
+    }
+    let mut list = TaskList::from_ical_reader(data.as_slice())
+        .map_err(|e| io::Error::other(format!("the list could not be read: {e:?}")))?;
+    list.receive(task, mode);
+    list.to_ical_file(path, line_ending, format)
+}
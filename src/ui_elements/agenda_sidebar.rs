@@ -0,0 +1,52 @@
+// ----------------------------------------------------------------------------
+// Sidebar on the right of the window with the tasks due over the next seven
+// days, a day at a time, which is shown whichever view the list is in.
+// Clicking a task goes to it in the classic view and opens it.
+// ----------------------------------------------------------------------------
+
+use chrono::{Duration, Local};
+use eframe::egui;
+use egui::RichText;
+
+use crate::task::TaskList;
+
+use super::task_tooltip;
+
+// How many days are shown, starting with today
+const DAYS: i64 = 7;
+
+// Shows the sidebar's contents. Returns the task that was clicked, if one was.
+pub fn show(ui: &mut egui::Ui, task_list: &TaskList) -> Option<uuid::Uuid> {
+    ui.heading("Next 7 days");
+
+    let today = Local::now().date_naive();
+    let mut clicked = None;
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for offset in 0..DAYS {
+            let day = today + Duration::days(offset);
+            let heading = match offset {
+                0 => String::from("Today"),
+                1 => String::from("Tomorrow"),
+                _ => day.format("%A %-d %B").to_string(),
+            };
+            ui.label(RichText::new(heading).strong());
+
+            let mut due = task_list
+                .tasks
+                .iter()
+                .filter(|task| !task.completed && task.due == Some(day))
+                .peekable();
+            if due.peek().is_none() {
+                ui.weak("Nothing due");
+            }
+            for task in due {
+                let response = ui.link(&task.summary);
+                if task_tooltip::attach(response, task).clicked() {
+                    clicked = Some(task.uuid);
+                }
+            }
+            ui.add_space(4.);
+        }
+    });
+    clicked
+}
@@ -0,0 +1,176 @@
+// ----------------------------------------------------------------------------
+// A small celebration which is shown when the last task in the view that
+// still needed doing is checked off, either as a message banner or as
+// confetti falling over the app.
+// ----------------------------------------------------------------------------
+
+use std::slice::Iter;
+use std::sync::mpsc;
+
+use eframe::egui;
+use egui::{Align2, Color32, Id, LayerId, Order, Pos2, RichText, Shape};
+use serde::{Deserialize, Serialize};
+
+use crate::events::TaskEvent;
+use crate::task::TaskList;
+use crate::task_views::ViewOptions;
+
+// How long, in seconds, a celebration is shown for
+const DURATION: f64 = 3.;
+const CONFETTI_COUNT: u32 = 80;
+const CONFETTI_SIZE: f32 = 6.;
+const CONFETTI_COLORS: [Color32; 5] = [
+    Color32::from_rgb(230, 80, 80),
+    Color32::from_rgb(240, 190, 60),
+    Color32::from_rgb(90, 190, 100),
+    Color32::from_rgb(80, 150, 230),
+    Color32::from_rgb(180, 100, 220),
+];
+
+// How the app celebrates every task being completed
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CelebrationStyle {
+    Off,
+    #[default]
+    Banner,
+    // Confetti is shown as a banner instead if the user has asked for less motion
+    Confetti,
+}
+
+impl CelebrationStyle {
+    pub fn iterator() -> Iter<'static, Self> {
+        [Self::Off, Self::Banner, Self::Confetti].iter()
+    }
+}
+
+// Watches for the moment that the last task in the view that needed doing is
+// completed, and shows a celebration when it happens
+#[derive(Default)]
+pub struct Celebration {
+    events: Option<mpsc::Receiver<TaskEvent>>,
+    // When the celebration that's being shown started, if there is one
+    started: Option<f64>,
+}
+
+impl Celebration {
+    // Checks for a celebration whenever these events say a task was completed
+    pub fn listen(&mut self, events: mpsc::Receiver<TaskEvent>) {
+        self.events = Some(events);
+    }
+
+    // Checks whether a celebration should start, and shows it if one is running.
+    // Should be called once every frame, with how the view is showing the list.
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        view: &ViewOptions,
+        style: CelebrationStyle,
+    ) {
+        let now = ctx.input(|i| i.time);
+
+        // It's only worth celebrating if the tasks that were left were completed,
+        // rather than removed from the list or replaced by importing another one.
+        // The list is only looked through when a task has just been completed.
+        let completed: Vec<_> = self
+            .events
+            .iter()
+            .flat_map(mpsc::Receiver::try_iter)
+            .filter_map(|event| match event {
+                TaskEvent::Completed(task) => Some(task),
+                _ => None,
+            })
+            .collect();
+        if !completed.is_empty() && style != CelebrationStyle::Off {
+            let time = chrono::Local::now().naive_local();
+            // The completed task counts even if the view hides completed tasks
+            let with_completed = ViewOptions {
+                show_completed_tasks: true,
+                ..view.clone()
+            };
+            let finished = completed
+                .iter()
+                .any(|task| with_completed.shows(task, time))
+                && !task_list
+                    .tasks
+                    .iter()
+                    .any(|task| !task.completed && view.shows(task, time));
+            if finished {
+                self.started = Some(now);
+            }
+        }
+
+        let Some(started) = self.started else {
+            return;
+        };
+        let elapsed = now - started;
+        if elapsed > DURATION {
+            self.started = None;
+            return;
+        }
+
+        // Keep drawing frames until the celebration is over
+        ctx.request_repaint();
+
+        let motion_reduced = ctx.style().animation_time <= 0.;
+        #[allow(clippy::cast_possible_truncation)]
+        if style == CelebrationStyle::Confetti && !motion_reduced {
+            confetti(ctx, elapsed as f32);
+        }
+        banner(ctx);
+    }
+}
+
+// Message at the top of the screen saying everything is done
+fn banner(ctx: &egui::Context) {
+    egui::Area::new("celebration_banner")
+        .anchor(Align2::CENTER_TOP, egui::vec2(0., 20.))
+        .interactable(false)
+        .order(Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(RichText::new("🎉 Every task is done!").heading());
+            });
+        });
+}
+
+// Pieces of confetti falling from the top of the screen and spinning
+#[allow(clippy::cast_precision_loss, clippy::suboptimal_flops)]
+fn confetti(ctx: &egui::Context, elapsed: f32) {
+    let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("confetti")));
+    let screen = ctx.screen_rect();
+
+    for piece in 0..CONFETTI_COUNT {
+        // Every piece starts at a different place and falls at a different speed,
+        // so that they don't all move together
+        let x = screen.left() + random(piece, 0) * screen.width();
+        let delay = random(piece, 1) * 0.5;
+        let speed = 0.3 + random(piece, 2) * 0.4;
+        let y = screen.top() - CONFETTI_SIZE + (elapsed - delay).max(0.) * speed * screen.height();
+        let sway = (elapsed * 3. + piece as f32).sin() * 15.;
+        let center = Pos2::new(x + sway, y);
+
+        // Spin the piece by turning the corners of a square around its center
+        let angle = elapsed * (2. + random(piece, 3) * 4.) + piece as f32;
+        let corners = (0..4)
+            .map(|corner| {
+                let corner_angle = angle + corner as f32 * std::f32::consts::FRAC_PI_2;
+                center + egui::Vec2::angled(corner_angle) * CONFETTI_SIZE / 2.
+            })
+            .collect();
+
+        let color = CONFETTI_COLORS[piece as usize % CONFETTI_COLORS.len()];
+        painter.add(Shape::convex_polygon(corners, color, egui::Stroke::NONE));
+    }
+}
+
+// Gets a number between 0 and 1 which looks random, but is always the
+// same for the same piece of confetti, so that pieces move smoothly
+#[allow(clippy::cast_precision_loss)]
+fn random(piece: u32, property: u32) -> f32 {
+    let mut n = piece.wrapping_mul(747_796_405) ^ property.wrapping_mul(2_891_336_453);
+    n ^= n >> 16;
+    n = n.wrapping_mul(0x7feb_352d);
+    n ^= n >> 15;
+    (n % 10_000) as f32 / 10_000.
+}
@@ -0,0 +1,116 @@
+// ----------------------------------------------------------------------------
+// Window which lists the commits of the task list in its git repository,
+// shows what each one changed, and lets the user restore the list as it was.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::git_store::{self, Commit, GitSettings};
+
+pub struct HistoryWindow {
+    settings: GitSettings,
+    // The list's file in the repository
+    file: String,
+    // Commits are read once, when the window is opened, rather than every frame.
+    // Reading them fails if git isn't installed or there's no repository yet.
+    commits: Result<Vec<Commit>, String>,
+    // The commit being looked at, and what it changed
+    selected: Option<(String, String)>,
+}
+
+impl HistoryWindow {
+    // Reads the history of the file that a list with this name is kept in
+    pub fn open(settings: &GitSettings, list_name: &str) -> Self {
+        let file = git_store::file_name(list_name);
+        // Lists can be bound to git without it being turned on for every list
+        let commits = if settings.directory.trim().is_empty() {
+            Err(String::from(
+                "There's no git repository to keep lists in. One can be set in the settings.",
+            ))
+        } else {
+            git_store::history(settings, &file).map_err(|e| e.to_string())
+        };
+        Self {
+            settings: settings.clone(),
+            file,
+            commits,
+            selected: None,
+        }
+    }
+
+    // Shows the window, and gives back the list as it was in a commit once one has been picked
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<Vec<u8>> {
+        let mut picked = None;
+
+        egui::Window::new("History").open(open).show(ctx, |ui| {
+            let commits = match &self.commits {
+                Ok(commits) if commits.is_empty() => {
+                    ui.label("Nothing has been committed yet.");
+                    return;
+                }
+                Ok(commits) => commits,
+                Err(e) => {
+                    ui.label(e.as_str());
+                    return;
+                }
+            };
+            ui.label("Restoring a version replaces the current list, which is backed up first.");
+
+            egui::ScrollArea::vertical()
+                .id_source("history_commits")
+                .max_height(200.)
+                .show(ui, |ui| {
+                    egui::Grid::new("history_list")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for commit in commits {
+                                let selected = self
+                                    .selected
+                                    .as_ref()
+                                    .is_some_and(|(hash, _)| *hash == commit.hash);
+                                let time = commit.time.format("%Y-%m-%d %H:%M:%S").to_string();
+                                if ui.selectable_label(selected, time).clicked() {
+                                    let diff =
+                                        git_store::diff(&self.settings, &commit.hash, &self.file)
+                                            .unwrap_or_else(|e| e.to_string());
+                                    self.selected = Some((commit.hash.clone(), diff));
+                                }
+                                ui.label(&commit.message);
+                                if ui.button("Restore").clicked() {
+                                    match git_store::read_version(
+                                        &self.settings,
+                                        &commit.hash,
+                                        &self.file,
+                                    ) {
+                                        Ok(data) => picked = Some(data),
+                                        Err(e) => eprintln!("could not read old version: {e}"),
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            // What the picked commit changed, as a diff of the iCal file
+            if let Some((_, diff)) = &self.selected {
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_source("history_diff")
+                    .max_height(300.)
+                    .show(ui, |ui| {
+                        for line in diff.lines() {
+                            let color = match line.chars().next() {
+                                Some('+') if !line.starts_with("+++") => egui::Color32::GREEN,
+                                Some('-') if !line.starts_with("---") => egui::Color32::RED,
+                                _ => ui.visuals().text_color(),
+                            };
+                            ui.label(egui::RichText::new(line).monospace().color(color));
+                        }
+                    });
+            }
+        });
+
+        picked
+    }
+}
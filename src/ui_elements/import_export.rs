@@ -0,0 +1,527 @@
+// ----------------------------------------------------------------------------
+// Panel at the top of the app for opening and exporting task list files,
+// restoring backups, renaming the list and opening the settings.
+// ----------------------------------------------------------------------------
+
+use std::borrow::Cow;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::compat;
+use crate::encryption::{self, EncryptionKey};
+use crate::network::NetworkSettings;
+use crate::parser::{ExportFormat, LineEnding, ParseFromFileError};
+use crate::platform;
+use crate::profiles;
+use crate::reminders::ReminderInbox;
+use crate::sync::{ListSync, SyncManager};
+use crate::sync_log::SyncLog;
+use crate::task::TaskList;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::url_import::{self, Download};
+use crate::validator;
+
+// The end of the name of the file completed tasks are archived to, next to the exported
+// list, which goes after the list's own name
+pub const ARCHIVE_EXTENSION: &str = "archive.ics";
+
+// Settings for how task lists are exported
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportSettings {
+    // How lines end in exported files
+    pub line_ending: LineEnding,
+    // Whether tasks are exported as todo items, calendar events, or both
+    pub format: ExportFormat,
+    // Exports completed tasks to a separate archive file instead of the list's file,
+    // since some calendar apps slow down a lot with thousands of completed tasks
+    pub separate_archive: bool,
+    // Puts each task's progress and status in step, which Nextcloud Tasks expects
+    pub nextcloud_tasks: bool,
+}
+
+impl ExportSettings {
+    // The list as it's exported, with any changes other apps need made to it
+    fn prepared(self, task_list: &TaskList) -> Cow<'_, TaskList> {
+        if self.nextcloud_tasks {
+            Cow::Owned(compat::for_nextcloud_tasks(task_list))
+        } else {
+            Cow::Borrowed(task_list)
+        }
+    }
+
+    fn to_ical(self, task_list: &TaskList) -> String {
+        task_list.to_ical_string_as(self.line_ending, self.format)
+    }
+
+    // Converts a task list to the iCal text of the exported file, which leaves
+    // out completed tasks if they're archived separately
+    pub fn ical_text(self, task_list: &TaskList) -> String {
+        let task_list = self.prepared(task_list);
+        if self.separate_archive {
+            self.to_ical(&task_list.split_completed().0)
+        } else {
+            self.to_ical(&task_list)
+        }
+    }
+
+    // Converts the archive of a list's completed tasks to iCal text, if they're
+    // archived separately, keeping the tasks from the archive that was there before
+    pub fn archive_text(self, task_list: &TaskList, archived: Option<TaskList>) -> Option<String> {
+        self.separate_archive
+            .then(|| self.to_ical(&self.prepared(task_list).archive(archived)))
+    }
+}
+
+/// The name of the archive of completed tasks for a list exported to a file,
+/// which is named after the list's file so lists in the same folder each have their own.
+///
+/// Example:
+/// ```
+/// use std::path::Path;
+/// use taskmaster_rs::ui_elements::import_export::archive_file_name;
+///
+/// assert_eq!(archive_file_name(Path::new("/home/me/work.ics"), false), "work.archive.ics");
+/// assert_eq!(archive_file_name(Path::new("work.ics.enc"), true), "work.archive.ics.enc");
+/// assert_eq!(archive_file_name(Path::new("tasks"), false), "tasks.archive.ics");
+/// ```
+pub fn archive_file_name(list_file: &Path, encrypted: bool) -> String {
+    let name = list_file
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let name = name.strip_suffix(".enc").unwrap_or(&name);
+    let name = name.strip_suffix(".ics").unwrap_or(name);
+    if encrypted {
+        format!("{name}.{ARCHIVE_EXTENSION}.enc")
+    } else {
+        format!("{name}.{ARCHIVE_EXTENSION}")
+    }
+}
+
+// Reads the archive of completed tasks that was written before, if there is one, so
+// the tasks in it can be kept. An archive that's there but can't be read is an error,
+// so that it isn't written over.
+pub fn read_archive(path: &Path, key: Option<&EncryptionKey>) -> io::Result<Option<TaskList>> {
+    let data = match platform::read_file(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let data = if encryption::is_encrypted(&data) {
+        key.and_then(|key| key.decrypt(&data).ok()).ok_or_else(|| {
+            io::Error::other("it's encrypted with a passphrase other than the list's")
+        })?
+    } else {
+        data
+    };
+    TaskList::from_ical_reader(data.as_slice())
+        .map(Some)
+        .map_err(|_| io::Error::other("it isn't a task list file"))
+}
+
+// Something the user did in the panel which the rest of the app has to handle
+pub enum ImportExportAction {
+    // A file was picked to be imported
+    Import(platform::PickedFile),
+    // The picked file couldn't be read
+    ImportFailed,
+    // Open the window for restoring a backup
+    Restore,
+    // Open the window showing the list's history in git
+    History,
+    // Open the window for merging lists and splitting this one
+    MergeSplit,
+    // Read the list again from the file it's bound to
+    Reload,
+    // The list was exported, to the file at the path if the platform has one
+    Exported(Option<PathBuf>),
+    ToggleSettings,
+    ToggleStatistics,
+    // Open the window for managing tags
+    ManageTags,
+    // Open or close the window with the log of syncs
+    ToggleSyncLog,
+    // Change where the list syncs to
+    BindSync(ListSync),
+    // Switch to the profile with this name, making it if it doesn't exist
+    SwitchProfile(String),
+}
+
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ImportExportPanel {
+    // Stores the file dialog to choose which task list file to import
+    import_dialog: Option<platform::ImportDialog>,
+    // Stores the file dialog to choose where to export the task list to a file
+    export_dialog: Option<platform::ExportDialog>,
+    // Problems found in the last exported file
+    export_warnings: Vec<validator::Warning>,
+    // Why the last export failed, if it did
+    export_error: Option<String>,
+    // Name being typed for a new profile
+    new_profile: String,
+    // URL being typed in to import a list from
+    #[cfg(not(target_arch = "wasm32"))]
+    import_url: String,
+    // The list being downloaded from a URL, if there is one
+    #[cfg(not(target_arch = "wasm32"))]
+    download: Option<Download>,
+    // Why the last download failed
+    #[cfg(not(target_arch = "wasm32"))]
+    download_error: Option<String>,
+}
+
+impl ImportExportPanel {
+    // Menu for switching between profiles, and making new ones.
+    // Returns the name of the profile to switch to, once one has been picked.
+    fn profile_menu(&mut self, ui: &mut egui::Ui) -> Option<String> {
+        let current = profiles::current();
+        let mut picked = None;
+        ui.menu_button(format!("👤 {current}"), |ui| {
+            for name in profiles::names() {
+                if ui.selectable_label(name == current, &name).clicked() {
+                    picked = Some(name);
+                }
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.new_profile)
+                    .on_hover_text("Name of a new profile");
+                let valid = profiles::is_valid_name(&self.new_profile);
+                if ui.add_enabled(valid, egui::Button::new("Add")).clicked() {
+                    picked = Some(std::mem::take(&mut self.new_profile));
+                }
+            });
+        })
+        .response
+        .on_hover_text("Profiles keep separate lists and settings");
+
+        if picked.is_some() {
+            ui.close_menu();
+        }
+        picked.filter(|name| *name != current)
+    }
+
+    // Opens the dialog for picking a file to import
+    pub fn open_import_dialog(&mut self, ctx: &egui::Context) {
+        // Create and open a file dialog
+        let dialog = platform::ImportDialog::open(ctx);
+        // This makes sure that the import and export dialogs
+        // aren't open at the same time
+        self.export_dialog = None;
+        // Stores the dialog in the panel's state
+        // This means it can be used in later update loops
+        self.import_dialog = Some(dialog);
+    }
+
+    // Field for importing a list from a URL. Downloaded lists are imported like a
+    // picked file, apart from not being bound to it, so they go through the same preview.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn url_import(
+        &mut self,
+        ui: &mut egui::Ui,
+        network: &NetworkSettings,
+    ) -> Option<ImportExportAction> {
+        if let Some(download) = &self.download {
+            ui.spinner();
+            ui.label("Downloading…").on_hover_text(download.url());
+            let result = download.take_result()?;
+            self.download = None;
+            return match result {
+                Ok(contents) => Some(ImportExportAction::Import(platform::PickedFile {
+                    path: None,
+                    contents,
+                })),
+                Err(e) => {
+                    self.download_error = Some(e);
+                    None
+                }
+            };
+        }
+
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut self.import_url)
+                .hint_text("https://… or webcal://…")
+                .desired_width(200.),
+        );
+        let url = url_import::download_url(&self.import_url);
+        let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        let clicked = ui
+            .add_enabled(url.is_some(), egui::Button::new("Import from URL"))
+            .clicked();
+        if let Some(url) = url.filter(|_| clicked || entered) {
+            self.download_error = None;
+            self.download = Some(Download::start(ui.ctx(), url, network));
+        }
+        if let Some(e) = &self.download_error {
+            ui.label("⚠ Download failed").on_hover_text(e.as_str());
+        }
+        None
+    }
+
+    // Opens the dialog for choosing where to export the list to
+    pub fn open_export_dialog(
+        &mut self,
+        ctx: &egui::Context,
+        task_list: &TaskList,
+        encrypted: bool,
+        settings: ExportSettings,
+    ) {
+        // Create and open a file export dialog
+        // Encrypted lists get their own extension, since calendar apps can't open them
+        let extension = if encrypted { "ics.enc" } else { "ics" };
+        let file_name = format!("{}.{extension}", task_list.name);
+        let dialog = platform::ExportDialog::open(ctx, &file_name);
+        // This makes sure that the import and export dialogs
+        // aren't open at the same time
+        self.import_dialog = None;
+        // Stores the dialog in the panel's state
+        // This means it can be used in later update loops
+        self.export_dialog = Some(dialog);
+        // Check the file for anything calendar apps might not accept
+        self.export_warnings = validator::validate(&settings.ical_text(task_list));
+    }
+
+    // Shows the panel. Exported lists are encrypted if a key is given.
+    // The error from the last import is shown, if it failed, and a warning
+    // if the file the list is bound to has changed on disk, next to the bell
+    // listing the reminders that haven't been dismissed and how syncing is going.
+    #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        task_list: &mut TaskList,
+        key: Option<&EncryptionKey>,
+        settings: ExportSettings,
+        network: &NetworkSettings,
+        import_error: Option<&ParseFromFileError>,
+        changed_on_disk: bool,
+        reminders: &mut ReminderInbox,
+        sync: &SyncManager,
+        sync_log: &SyncLog,
+    ) -> Option<ImportExportAction> {
+        let ctx = ui.ctx().clone();
+        let mut action = None;
+
+        // Import a task list file
+        ui.horizontal(|ui| {
+            if ui.button("Open file").clicked() {
+                self.open_import_dialog(&ctx);
+            }
+
+            // Web builds can't download from other sites, which don't allow it
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(downloaded) = self.url_import(ui, network) {
+                action = Some(downloaded);
+            }
+
+            if ui.button("Restore from backup…").clicked() {
+                action = Some(ImportExportAction::Restore);
+            }
+
+            if ui.button("Merge / split…").clicked() {
+                action = Some(ImportExportAction::MergeSplit);
+            }
+
+            // Web builds can't keep the list in git
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.button("History…").clicked() {
+                action = Some(ImportExportAction::History);
+            }
+
+            // Lists opened from a file can be read from it again, such as
+            // after another app has changed it
+            if let Some(path) = &task_list.path {
+                if ui
+                    .button("⟳ Reload from disk")
+                    .on_hover_text(path.display().to_string())
+                    .clicked()
+                {
+                    action = Some(ImportExportAction::Reload);
+                }
+                if changed_on_disk {
+                    ui.label("⚠ Changed on disk").on_hover_text(
+                        "The file has been changed since the list was opened. \
+                        Reload it to see the changes, or export over it to keep this list.",
+                    );
+                }
+            }
+
+            // If there is an import dialog stored in the panel's state
+            if let Some(dialog) = &mut self.import_dialog {
+                // If a file has been picked using the dialog
+                if let Some(file) = dialog.show(&ctx) {
+                    action = Some(file.map_or(
+                        ImportExportAction::ImportFailed,
+                        ImportExportAction::Import,
+                    ));
+                }
+            }
+
+            // If the last import was unsuccessful.
+            // Successful parses are shown in the import preview window instead
+            if let Some(e) = import_error {
+                // Create an appropriate string from all the possible errors
+                let err_str = match e {
+                    ParseFromFileError::InvalidFile => "Invalid task list file",
+                    ParseFromFileError::NonTaskItem => "File contained items that were not todo items. Was it exported from calendar software?",
+                    ParseFromFileError::InvalidField => "File contains invalid data",
+                    ParseFromFileError::Cancelled => "Import was cancelled",
+                };
+                // Display a label showing the error
+                ui.label(err_str);
+            }
+
+            // Button which opens the settings window
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("⚙").on_hover_text("Settings").clicked() {
+                    action = Some(ImportExportAction::ToggleSettings);
+                }
+                if ui.button("🏷").on_hover_text("Tags").clicked() {
+                    action = Some(ImportExportAction::ManageTags);
+                }
+                if ui.button("📊").on_hover_text("Statistics").clicked() {
+                    action = Some(ImportExportAction::ToggleStatistics);
+                }
+                super::reminder_bell::show(ui, reminders);
+                if super::sync_log::status(ui, sync_log, task_list) {
+                    action = Some(ImportExportAction::ToggleSyncLog);
+                }
+                if let Some(profile) = self.profile_menu(ui) {
+                    action = Some(ImportExportAction::SwitchProfile(profile));
+                }
+            });
+        });
+
+        // Export task list to a file
+        ui.horizontal(|ui| {
+            if ui.button("Export task list").clicked() {
+                self.open_export_dialog(&ctx, task_list, key.is_some(), settings);
+            }
+
+            // Point out anything in the exported file that calendar apps might not accept
+            if !self.export_warnings.is_empty() {
+                let warnings: Vec<String> = self
+                    .export_warnings
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                ui.label(format!("⚠ {} problems", warnings.len()))
+                    .on_hover_text(format!(
+                        "Some calendar apps may not open the exported file:\n{}",
+                        warnings.join("\n")
+                    ));
+            }
+
+            // If there is an export dialog stored in the panel's state
+            if let Some(dialog) = &mut self.export_dialog {
+                // If somewhere to export to has been picked using the dialog
+                if let Some(target) = dialog.show(&ctx) {
+                    let path = target.path();
+                    let text = settings.ical_text(task_list);
+                    match export(target, task_list, &text, key, settings) {
+                        Ok(()) => {
+                            self.export_error = None;
+                            action = Some(ImportExportAction::Exported(path));
+                        }
+                        Err(e) => self.export_error = Some(format!("Could not export: {e}")),
+                    }
+                }
+            }
+            if let Some(e) = &self.export_error {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+
+            // This lets you change the list's name before exporting it
+            ui.label("List name:");
+            ui.text_edit_singleline(&mut task_list.name);
+
+            if let Some(binding) = sync_picker(ui, sync, task_list) {
+                action = Some(ImportExportAction::BindSync(binding));
+            }
+        });
+
+        action
+    }
+}
+
+// Writes a list's iCal text, from ExportSettings::ical_text, to where it's exported or
+// the file it's bound to, encrypted if a key is given. If completed tasks are archived
+// separately, they're merged into the archive next to it, which is written first, so
+// they're never only in memory. Downloads in web builds have no folder to read the
+// archive from, so it only has the list's own tasks.
+pub fn export(
+    target: platform::ExportTarget,
+    task_list: &TaskList,
+    text: &str,
+    key: Option<&EncryptionKey>,
+    settings: ExportSettings,
+) -> io::Result<()> {
+    let encrypt = |text: &str| {
+        key.map_or_else(
+            || text.as_bytes().to_vec(),
+            |key| key.encrypt(text.as_bytes()),
+        )
+    };
+    let path = target
+        .path()
+        .unwrap_or_else(|| PathBuf::from(format!("{}.ics", task_list.name)));
+    let archive_target = target.sibling(&archive_file_name(&path, key.is_some()));
+    let archived = match archive_target.path() {
+        Some(archive_path) if settings.separate_archive => read_archive(&archive_path, key)
+            .map_err(|e| {
+                io::Error::other(format!("{} can't be read, {e}", archive_path.display()))
+            })?,
+        _ => None,
+    };
+
+    if let Some(archive) = settings.archive_text(task_list, archived) {
+        archive_target.write(&encrypt(&archive))?;
+    }
+    target.write(&encrypt(text))
+}
+
+// Lets the user pick where the list syncs to, giving back what they picked
+fn sync_picker(ui: &mut egui::Ui, sync: &SyncManager, task_list: &TaskList) -> Option<ListSync> {
+    let available = sync.available(task_list);
+    let mut choices = vec![
+        (
+            ListSync::Default,
+            String::from("Everywhere turned on in settings"),
+        ),
+        (ListSync::Off, String::from("Nowhere")),
+    ];
+    for (backend, name, collections) in available {
+        choices.push((ListSync::Backend(backend.to_string()), name.to_string()));
+        choices.extend(collections.into_iter().map(|collection| {
+            let binding = ListSync::Collection {
+                backend: backend.to_string(),
+                collection: collection.id,
+            };
+            (binding, format!("{name} / {}", collection.name))
+        }));
+    }
+
+    let current = sync.binding(task_list);
+    // Lists can be bound to a backend that's no longer there, like one from another build
+    let current_name = choices
+        .iter()
+        .find(|(binding, _)| binding == current)
+        .map_or("Unknown", |(_, name)| name.as_str());
+    let mut picked = current.clone();
+    ui.label("Sync to:");
+    egui::ComboBox::from_id_source("list_sync")
+        .selected_text(current_name)
+        .show_ui(ui, |ui| {
+            for (binding, name) in &choices {
+                ui.selectable_value(&mut picked, binding.clone(), name);
+            }
+        })
+        .response
+        .on_hover_text("Where changes to this list are synced to");
+    (picked != *current).then_some(picked)
+}
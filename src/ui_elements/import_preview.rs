@@ -0,0 +1,233 @@
+// ----------------------------------------------------------------------------
+// Window which shows the tasks in an imported list before they're imported,
+// so that the user can pick which ones to import, and whether to add them
+// to the current list or replace it. Big files are read a few hundred tasks
+// a frame, so that the app doesn't freeze while they're being read.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::duplicates::{self, DuplicateAction};
+use crate::json::DATE_FORMAT;
+use crate::parser::{IcalImport, ImportWarning, ParseFromFileError};
+use crate::task::{Task, TaskList};
+
+// How many tasks are read from a file each frame
+const TASKS_PER_FRAME: usize = 250;
+
+// What the user chose to do with the imported tasks
+pub enum ImportAction {
+    // Add the picked tasks to the current list, doing what the user picked
+    // with ones that look like tasks already in it
+    Add(Vec<Task>, DuplicateAction),
+    // Replace the current list with one containing only the picked tasks
+    Replace(TaskList),
+    Cancel,
+    // The file couldn't be read
+    Failed(ParseFromFileError),
+}
+
+pub struct ImportPreview {
+    list: TaskList,
+    // Whether each task in the list has been picked to be imported
+    picked: Vec<bool>,
+    duplicate_action: DuplicateAction,
+    // The file being read, until all of its tasks are in the preview
+    import: Option<IcalImport>,
+    // What couldn't be read from the file, and was skipped
+    warnings: Vec<ImportWarning>,
+}
+
+impl ImportPreview {
+    // Every task starts off picked
+    pub fn new(list: TaskList) -> Self {
+        let picked = vec![true; list.tasks.len()];
+        Self {
+            list,
+            picked,
+            duplicate_action: DuplicateAction::default(),
+            import: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    // Previews a file which is still being read, adding its tasks as they're read
+    pub fn loading(import: IcalImport) -> Self {
+        Self {
+            import: Some(import),
+            ..Self::new(TaskList::default())
+        }
+    }
+
+    // Reads the next few tasks from the file, if it's still being read
+    fn read_more(&mut self, ctx: &egui::Context) -> Result<(), ParseFromFileError> {
+        let Some(import) = &mut self.import else {
+            return Ok(());
+        };
+
+        let tasks = import.next_chunk(TASKS_PER_FRAME)?;
+        self.picked.resize(self.picked.len() + tasks.len(), true);
+        // The list's name and color can come after some of its tasks in the file
+        let mut all_tasks = std::mem::take(&mut self.list.tasks);
+        all_tasks.extend(tasks);
+        self.list = TaskList {
+            tasks: all_tasks,
+            ..import.list().clone()
+        };
+
+        if import.is_finished() {
+            self.warnings = import.warnings().to_vec();
+            self.import = None;
+        } else {
+            ctx.request_repaint();
+        }
+        Ok(())
+    }
+
+    // The tasks that have been picked
+    fn picked_tasks(&self) -> Vec<Task> {
+        self.list
+            .tasks
+            .iter()
+            .zip(&self.picked)
+            .filter(|(_, picked)| **picked)
+            .map(|(task, _)| task.clone())
+            .collect()
+    }
+
+    // Shows the window. The current list is used to point out tasks that are already in it.
+    #[allow(clippy::too_many_lines)]
+    pub fn show(&mut self, ctx: &egui::Context, current: &TaskList) -> Option<ImportAction> {
+        if let Err(e) = self.read_more(ctx) {
+            return Some(ImportAction::Failed(e));
+        }
+        let loading = self.import.as_ref().map(IcalImport::progress);
+
+        let mut action = None;
+
+        egui::Window::new("Import tasks")
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if let Some(progress) = loading {
+                    ui.label(format!(
+                        "Reading the file... {} tasks so far.",
+                        self.list.tasks.len()
+                    ));
+                    ui.add(egui::ProgressBar::new(progress).show_percentage());
+                } else {
+                    ui.label(format!(
+                        "The file contains the list '{}', with {} tasks. Pick the tasks to import.",
+                        self.list.name,
+                        self.list.tasks.len()
+                    ));
+                }
+
+                // Parts of the file that couldn't be read were skipped, rather than refusing the file
+                if !self.warnings.is_empty() {
+                    egui::CollapsingHeader::new(format!(
+                        "⚠ {} things in the file couldn't be read and were skipped",
+                        self.warnings.len()
+                    ))
+                    .id_source("import_warnings")
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_source("import_warnings_scroll")
+                            .max_height(100.)
+                            .show(ui, |ui| {
+                                for warning in &self.warnings {
+                                    ui.label(warning.to_string());
+                                }
+                            });
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("Pick all").clicked() {
+                        self.picked.fill(true);
+                    }
+                    if ui.button("Pick none").clicked() {
+                        self.picked.fill(false);
+                    }
+                });
+
+                // Only the rows that can be seen are shown, since there can be thousands
+                let row_height = ui.spacing().interact_size.y;
+                let row_count = self.list.tasks.len();
+                egui::ScrollArea::vertical().max_height(300.).show_rows(
+                    ui,
+                    row_height,
+                    row_count,
+                    |ui, rows| {
+                        egui::Grid::new("import_preview")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                let tasks = &self.list.tasks[rows.clone()];
+                                for (task, picked) in tasks.iter().zip(&mut self.picked[rows]) {
+                                    ui.checkbox(picked, &task.summary);
+                                    ui.label(task.due.map_or_else(String::new, |due| {
+                                        due.format(DATE_FORMAT).to_string()
+                                    }));
+                                    ui.label(if task.completed { "Completed" } else { "" });
+                                    match duplicates::find(current, task) {
+                                        Some(index) => {
+                                            ui.label("⚠ Duplicate").on_hover_text(format!(
+                                                "Looks like '{}', which is already in the list",
+                                                current.tasks[index].summary
+                                            ));
+                                        }
+                                        None => {
+                                            ui.label("");
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    },
+                );
+
+                // Only matters when adding, since replacing the list leaves nothing to duplicate
+                ui.horizontal(|ui| {
+                    ui.label("Tasks that are already in the list");
+                    egui::ComboBox::from_id_source("import_duplicate_action")
+                        .selected_text(self.duplicate_action.name())
+                        .show_ui(ui, |ui| {
+                            for action in DuplicateAction::iterator() {
+                                ui.selectable_value(
+                                    &mut self.duplicate_action,
+                                    *action,
+                                    action.name(),
+                                );
+                            }
+                        });
+                });
+
+                let picked_count = self.picked.iter().filter(|picked| **picked).count();
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(loading.is_none() && picked_count > 0, |ui| {
+                        if ui
+                            .button(format!("Add {picked_count} tasks to current list"))
+                            .clicked()
+                        {
+                            action = Some(ImportAction::Add(
+                                self.picked_tasks(),
+                                self.duplicate_action,
+                            ));
+                        }
+                        if ui.button("Replace current list").clicked() {
+                            action = Some(ImportAction::Replace(TaskList {
+                                tasks: self.picked_tasks(),
+                                ..self.list.clone()
+                            }));
+                        }
+                    });
+                    // Cancelling also stops the file being read
+                    if ui.button("Cancel").clicked() {
+                        action = Some(ImportAction::Cancel);
+                    }
+                });
+            });
+
+        action
+    }
+}
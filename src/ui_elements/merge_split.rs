@@ -0,0 +1,168 @@
+// ----------------------------------------------------------------------------
+// Window for merging another list file into the current list, and for
+// splitting some of the current list's tasks out into a new list file.
+// Both show what they'll do before they do it.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::encryption;
+use crate::merge::SplitBy;
+use crate::platform;
+use crate::task::TaskList;
+
+#[derive(Default)]
+pub struct MergeSplitWindow {
+    // Dialog for picking the list to merge in
+    import_dialog: Option<platform::ImportDialog>,
+    // The list picked to be merged in
+    incoming: Option<TaskList>,
+    // Which tasks to split out, and the name of the list they go into
+    split_by: Option<SplitBy>,
+    split_name: String,
+    // Dialog for picking where the split off list is saved
+    export_dialog: Option<platform::ExportDialog>,
+    // Why the last merge or split didn't work
+    error: Option<String>,
+}
+
+impl MergeSplitWindow {
+    // Shows the window. Returns whether the list was changed.
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool, task_list: &mut TaskList) -> bool {
+        let mut changed = false;
+
+        egui::Window::new("Merge and split lists")
+            .open(open)
+            .show(ctx, |ui| {
+                ui.heading("Merge");
+                changed |= self.merge_section(ui, task_list);
+
+                ui.separator();
+                ui.heading("Split");
+                changed |= self.split_section(ui, task_list);
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+
+        changed
+    }
+
+    fn merge_section(&mut self, ui: &mut egui::Ui, task_list: &mut TaskList) -> bool {
+        if ui.button("Pick a list to merge in…").clicked() {
+            self.import_dialog = Some(platform::ImportDialog::open(ui.ctx()));
+        }
+        if let Some(file) = self.import_dialog.as_mut().and_then(|d| d.show(ui.ctx())) {
+            self.import_dialog = None;
+            self.incoming = None;
+            match file {
+                Ok(file) if encryption::is_encrypted(&file.contents) => {
+                    self.error = Some(String::from(
+                        "Encrypted lists have to be opened before they can be merged",
+                    ));
+                }
+                Ok(file) => match TaskList::from_ical_reader(file.contents.as_slice()) {
+                    Ok(list) => {
+                        self.incoming = Some(list);
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(format!("The list could not be read: {e:?}")),
+                },
+                Err(e) => self.error = Some(format!("The file could not be read: {e}")),
+            }
+        }
+
+        let Some(incoming) = &self.incoming else {
+            return false;
+        };
+        let summary = task_list.merge_summary(incoming);
+        ui.label(format!(
+            "Merging '{}' adds {} tasks, and updates {} tasks that were changed there \
+            more recently. {} tasks are already in this list.",
+            incoming.name, summary.added, summary.updated, summary.unchanged
+        ));
+        if ui.button("Merge into this list").clicked() {
+            if let Some(incoming) = self.incoming.take() {
+                task_list.merge(incoming);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn split_section(&mut self, ui: &mut egui::Ui, task_list: &mut TaskList) -> bool {
+        // Tasks can be split out by any tag used in the list
+        let mut tags: Vec<&String> = task_list.tasks.iter().flat_map(|t| &t.tags).collect();
+        tags.sort();
+        tags.dedup();
+
+        ui.horizontal(|ui| {
+            ui.label("Move tasks");
+            egui::ComboBox::from_id_source("split_by")
+                .selected_text(
+                    self.split_by
+                        .as_ref()
+                        .map_or_else(|| String::from("Pick which tasks"), SplitBy::name),
+                )
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.split_by, Some(SplitBy::Completed), "Completed");
+                    for tag in tags {
+                        let by = SplitBy::Tag(tag.clone());
+                        let name = by.name();
+                        ui.selectable_value(&mut self.split_by, Some(by), name);
+                    }
+                });
+            ui.label("into a new list called");
+            ui.text_edit_singleline(&mut self.split_name);
+        });
+
+        let Some(by) = &self.split_by else {
+            return false;
+        };
+        let indices = task_list.split_indices(by);
+        egui::ScrollArea::vertical()
+            .id_source("split_preview")
+            .max_height(150.)
+            .show(ui, |ui| {
+                for &index in &indices {
+                    ui.label(&task_list.tasks[index].summary);
+                }
+            });
+
+        let name = self.split_name.trim();
+        let can_split = !indices.is_empty() && !name.is_empty();
+        if ui
+            .add_enabled(
+                can_split,
+                egui::Button::new(format!("Split {} tasks into a new list…", indices.len())),
+            )
+            .clicked()
+        {
+            self.export_dialog = Some(platform::ExportDialog::open(
+                ui.ctx(),
+                &format!("{name}.ics"),
+            ));
+        }
+
+        let Some(target) = self.export_dialog.as_mut().and_then(|d| d.show(ui.ctx())) else {
+            return false;
+        };
+        self.export_dialog = None;
+        // The tasks are only taken out of this list once the new one has been saved
+        let mut remaining = task_list.clone();
+        let split = remaining.split_off(name, by);
+        match target.write(split.to_ical_string().as_bytes()) {
+            Ok(()) => {
+                *task_list = remaining;
+                self.split_by = None;
+                self.error = None;
+                true
+            }
+            Err(e) => {
+                self.error = Some(format!("The new list could not be saved: {e}"));
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// ----------------------------------------------------------------------------
+// Window which lists the reminders that were missed while the computer was
+// asleep or the app was closed, so that nothing slips by unnoticed.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::json::DATE_FORMAT;
+use crate::task::Task;
+
+#[derive(Default)]
+pub struct MissedRemindersWindow {
+    tasks: Vec<Task>,
+}
+
+impl MissedRemindersWindow {
+    pub const fn new(tasks: Vec<Task>) -> Self {
+        Self { tasks }
+    }
+
+    // Adds reminders that were missed again while the window was still open
+    pub fn extend(&mut self, tasks: Vec<Task>) {
+        for task in tasks {
+            if !self.tasks.iter().any(|shown| shown.uuid == task.uuid) {
+                self.tasks.push(task);
+            }
+        }
+    }
+
+    // Shows the window. Returns false once it's been dismissed.
+    pub fn show(&self, ctx: &egui::Context) -> bool {
+        let mut open = true;
+        let mut dismissed = false;
+
+        egui::Window::new("While you were away")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label("These tasks became due soon while Taskmaster wasn't running:");
+
+                egui::ScrollArea::vertical()
+                    .max_height(300.)
+                    .show(ui, |ui| {
+                        for task in &self.tasks {
+                            ui.horizontal(|ui| {
+                                ui.label(&task.summary);
+                                if let Some(due) = task.due {
+                                    ui.weak(format!("due {}", due.format(DATE_FORMAT)));
+                                }
+                            });
+                        }
+                    });
+
+                dismissed = ui.button("Dismiss").clicked();
+            });
+
+        open && !dismissed
+    }
+}
@@ -3,19 +3,55 @@
 // Or which were too big to put somewhere else.
 //-----------------------------------------------------------------------------
 
+pub mod agenda_sidebar;
+pub mod celebration;
+pub mod history;
+pub mod import_export;
+pub mod import_preview;
+pub mod merge_split;
+pub mod missed_reminders;
+pub mod passphrase;
+pub mod paste_list;
+pub mod reminder_bell;
+pub mod restore;
+pub mod selection;
+pub mod settings;
+pub mod sidebar;
+pub mod statistics;
+pub mod swipe_row;
+pub mod sync_log;
+pub mod tag_manager;
 pub mod task_edit;
+pub mod task_input;
+pub mod task_list;
 pub mod task_modal;
+pub mod task_tooltip;
+pub mod time_zone;
+pub mod transfer;
 
 use eframe::egui;
-use egui::{Response, Ui};
+use egui::{Id, Rect, Response, Ui};
+
+use crate::task::{Percent, Priority, MAX_PRIORITY, MAX_PROGRESS};
 
 // Custom percentage slider
-pub fn percentage_slider(ui: &mut Ui, percent: &mut u8) -> Response {
-    ui.add(
-        egui::Slider::new(percent, 0..=100)
+pub fn percentage_slider(ui: &mut Ui, percent: &mut Percent) -> Response {
+    let mut value = percent.get();
+    let response = ui.add(
+        egui::Slider::new(&mut value, 0..=MAX_PROGRESS)
             .show_value(true)
             .custom_formatter(|n, _| n.to_string() + "%"),
-    )
+    );
+    *percent = Percent::new(value);
+    response
+}
+
+// Slider for a task's priority, from 0 for no priority
+pub fn priority_slider(ui: &mut Ui, priority: &mut Priority) -> Response {
+    let mut value = priority.get();
+    let response = ui.add(egui::Slider::new(&mut value, 0..=MAX_PRIORITY));
+    *priority = Priority::new(value);
+    response
 }
 
 // Commonly used frame for different panels of the application
@@ -27,3 +63,73 @@ pub fn basic_frame() -> egui::Frame {
             color: egui::Color32::GRAY,
         })
 }
+
+// Makes widgets bigger and further apart so that they're easy to tap on a touch screen
+pub fn touch_spacing(ui: &mut Ui) {
+    let spacing = ui.spacing_mut();
+    spacing.interact_size.y = 32.;
+    spacing.button_padding = egui::vec2(10., 6.);
+    spacing.icon_width = 20.;
+    spacing.icon_width_inner = 12.;
+}
+
+// Shows some contents, optionally inside a collapsing header so they can be
+// hidden away when there isn't much room for them
+pub fn collapsible<R>(
+    ui: &mut Ui,
+    collapsible: bool,
+    heading: &str,
+    add_contents: impl FnOnce(&mut Ui) -> R,
+) -> Option<R> {
+    if collapsible {
+        egui::CollapsingHeader::new(heading)
+            .show(ui, add_contents)
+            .body_returned
+    } else {
+        Some(add_contents(ui))
+    }
+}
+
+// Gets how long an animation should take, which is no time at all
+// if the user has turned motion off
+pub fn animation_time(ui: &Ui, seconds: f32) -> f32 {
+    if ui.style().animation_time > 0. {
+        seconds
+    } else {
+        0.
+    }
+}
+
+// Shows only the top part of some contents, for sliding them open and closed.
+// An amount of 0 hides the contents completely and 1 shows all of them.
+// The id has to stay the same between frames, so that the height of the
+// contents can be remembered.
+pub fn reveal<R>(ui: &mut Ui, id: Id, amount: f32, add_contents: impl FnOnce(&mut Ui) -> R) -> R {
+    if amount >= 1. {
+        return add_contents(ui);
+    }
+
+    // Ease in and out, so that the contents don't start and stop moving suddenly
+    let amount = amount * amount * (-2_f32).mul_add(amount, 3.);
+
+    // The contents have to be cut off before they're drawn, so the height
+    // they had last frame is used to work out how much of them to show
+    let start = ui.cursor().min;
+    let width = ui.available_width();
+    let height = ui.data(|d| d.get_temp::<f32>(id)).unwrap_or_default() * amount;
+    let shown = Rect::from_min_size(start, egui::vec2(width, height));
+
+    let mut contents_ui = ui.child_ui_with_id_source(
+        Rect::from_min_size(start, egui::vec2(width, f32::INFINITY)),
+        *ui.layout(),
+        id,
+    );
+    contents_ui.set_clip_rect(ui.clip_rect().intersect(shown));
+    let inner = add_contents(&mut contents_ui);
+
+    let full_height = contents_ui.min_rect().height();
+    ui.data_mut(|d| d.insert_temp(id, full_height));
+    ui.allocate_rect(shown, egui::Sense::hover());
+
+    inner
+}
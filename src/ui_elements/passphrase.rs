@@ -0,0 +1,117 @@
+// ----------------------------------------------------------------------------
+// Window which asks the user for a passphrase, either to unlock an encrypted
+// task list or to choose the passphrase that lists are encrypted with.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+// What the user did with the window
+pub enum PassphraseResponse {
+    Submitted(String),
+    Cancelled,
+}
+
+pub struct PassphraseWindow {
+    title: String,
+    message: String,
+    // Whether the passphrase has to be typed twice, for choosing a new one
+    confirm: bool,
+    // Whether the window can be closed without entering a passphrase
+    cancellable: bool,
+    passphrase: String,
+    confirmation: String,
+    error: Option<String>,
+}
+
+impl PassphraseWindow {
+    // Window for entering a passphrase that has already been chosen
+    pub fn unlock(title: &str, message: &str, cancellable: bool) -> Self {
+        Self {
+            title: title.to_string(),
+            message: message.to_string(),
+            confirm: false,
+            cancellable,
+            passphrase: String::new(),
+            confirmation: String::new(),
+            error: None,
+        }
+    }
+
+    // Window for choosing a new passphrase
+    pub fn choose(title: &str, message: &str) -> Self {
+        Self {
+            confirm: true,
+            ..Self::unlock(title, message, true)
+        }
+    }
+
+    // Shows a message saying what was wrong with the last passphrase that was entered
+    pub fn set_error(&mut self, error: &str) {
+        self.error = Some(error.to_string());
+        self.passphrase.clear();
+        self.confirmation.clear();
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<PassphraseResponse> {
+        let mut response = None;
+
+        egui::Window::new(&self.title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(&self.message);
+
+                egui::Grid::new("passphrase_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Passphrase");
+                        let field =
+                            ui.add(egui::TextEdit::singleline(&mut self.passphrase).password(true));
+                        ui.end_row();
+
+                        if self.confirm {
+                            ui.label("Confirm passphrase");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.confirmation).password(true),
+                            );
+                            ui.end_row();
+                        } else if field.lost_focus()
+                            && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                        {
+                            response = self.submit();
+                        }
+                    });
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("OK").clicked() {
+                        response = self.submit();
+                    }
+                    if self.cancellable && ui.button("Cancel").clicked() {
+                        response = Some(PassphraseResponse::Cancelled);
+                    }
+                });
+            });
+
+        response
+    }
+
+    // Checks the passphrase that was entered, and gives it back if it can be used
+    fn submit(&mut self) -> Option<PassphraseResponse> {
+        if self.passphrase.is_empty() {
+            self.set_error("Enter a passphrase");
+            return None;
+        }
+        if self.confirm && self.passphrase != self.confirmation {
+            self.set_error("The passphrases don't match");
+            return None;
+        }
+        Some(PassphraseResponse::Submitted(std::mem::take(
+            &mut self.passphrase,
+        )))
+    }
+}
@@ -0,0 +1,127 @@
+// ----------------------------------------------------------------------------
+// Window for turning a pasted list, like notes from a meeting, into tasks.
+// Every line becomes a task, and the tasks are previewed as the text is
+// typed, so that the tags, priorities and due dates read from each line
+// can be checked before they're all added at once.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::duplicates::{self, DuplicateAction};
+use crate::heuristics;
+use crate::json::DATE_FORMAT;
+use crate::task::{Task, TaskList};
+
+#[derive(Default)]
+pub struct PasteListWindow {
+    text: String,
+    duplicate_action: DuplicateAction,
+}
+
+impl PasteListWindow {
+    // Shows the window. The current list is used to point out tasks that are already in it.
+    // Gives back the tasks to add, and what to do with ones that are already in the list.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        current: &TaskList,
+    ) -> Option<(Vec<Task>, DuplicateAction)> {
+        let mut added = None;
+        let today = chrono::Local::now().date_naive();
+
+        egui::Window::new("Paste list").open(open).show(ctx, |ui| {
+            ui.label(
+                "Each line becomes a task. Lines can have tags (@errands), \
+                a priority (!3) and a due date (due:2023-08-25, due:today or due:tomorrow).",
+            );
+            egui::ScrollArea::vertical()
+                .id_source("paste_list_text")
+                .max_height(200.)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.text)
+                            .hint_text("- Book the venue @events !2 due:tomorrow")
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+
+            let tasks = heuristics::tasks_from_lines(&self.text, today);
+
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .id_source("paste_list_preview")
+                .max_height(200.)
+                .show(ui, |ui| {
+                    egui::Grid::new("paste_list_preview")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for task in &tasks {
+                                preview_row(ui, task, current);
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            ui.horizontal(|ui| {
+                ui.label("Tasks that are already in the list");
+                egui::ComboBox::from_id_source("paste_list_duplicate_action")
+                    .selected_text(self.duplicate_action.name())
+                    .show_ui(ui, |ui| {
+                        for action in DuplicateAction::iterator() {
+                            ui.selectable_value(&mut self.duplicate_action, *action, action.name());
+                        }
+                    });
+            });
+
+            let count = tasks.len();
+            if ui
+                .add_enabled(count > 0, egui::Button::new(format!("Add {count} tasks")))
+                .clicked()
+            {
+                added = Some((tasks, self.duplicate_action));
+                self.text.clear();
+            }
+        });
+
+        added
+    }
+}
+
+// Shows what was read from one line
+fn preview_row(ui: &mut egui::Ui, task: &Task, current: &TaskList) {
+    let summary = if task.completed {
+        format!("✔ {}", task.summary)
+    } else {
+        task.summary.clone()
+    };
+    ui.label(summary);
+    ui.label(
+        task.tags
+            .iter()
+            .map(|tag| format!("@{tag}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    ui.label(if task.priority.is_set() {
+        format!("!{}", task.priority)
+    } else {
+        String::new()
+    });
+    ui.label(
+        task.due
+            .map_or_else(String::new, |due| due.format(DATE_FORMAT).to_string()),
+    );
+    match duplicates::find(current, task) {
+        Some(index) => {
+            ui.label("⚠ Duplicate").on_hover_text(format!(
+                "Looks like '{}', which is already in the list",
+                current.tasks[index].summary
+            ));
+        }
+        None => {
+            ui.label("");
+        }
+    }
+}
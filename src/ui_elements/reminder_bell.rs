@@ -0,0 +1,83 @@
+// ----------------------------------------------------------------------------
+// Bell which lists the tasks that are overdue or due soon, with a badge
+// counting them. Each reminder can be dismissed, or clicked to open its
+// task. This works even when the desktop doesn't show notifications.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::json::DATE_FORMAT;
+use crate::reminders::ReminderInbox;
+
+use super::task_modal;
+
+// How big the badge with the count on it is
+const BADGE_RADIUS: f32 = 7.;
+
+pub fn show(ui: &mut egui::Ui, inbox: &mut ReminderInbox) {
+    let today = chrono::Local::now().date_naive();
+    let count = inbox.reminders().len();
+
+    let response = ui
+        .menu_button("🔔", |ui| {
+            if count == 0 {
+                ui.label("No reminders");
+                return;
+            }
+
+            let mut dismissed = None;
+            let mut opened = None;
+            egui::ScrollArea::vertical()
+                .max_height(300.)
+                .show(ui, |ui| {
+                    for reminder in inbox.reminders() {
+                        ui.horizontal(|ui| {
+                            if ui.button("✖").on_hover_text("Dismiss").clicked() {
+                                dismissed = Some(reminder.uuid);
+                            }
+                            if ui.link(&reminder.summary).clicked() {
+                                opened = Some(reminder.uuid);
+                            }
+                            let due = reminder.due.format(DATE_FORMAT);
+                            if reminder.due < today {
+                                ui.colored_label(
+                                    ui.visuals().error_fg_color,
+                                    format!("overdue, {due}"),
+                                );
+                            } else {
+                                ui.weak(format!("due {due}"));
+                            }
+                        });
+                    }
+                });
+
+            ui.separator();
+            if ui.button("Dismiss all").clicked() {
+                inbox.dismiss_all();
+                ui.close_menu();
+            }
+            if let Some(uuid) = dismissed {
+                inbox.dismiss(uuid);
+            }
+            if let Some(uuid) = opened {
+                task_modal::set_open(ui.ctx(), uuid, true);
+                ui.close_menu();
+            }
+        })
+        .response
+        .on_hover_text("Reminders");
+
+    // The badge goes over the bell's top right corner
+    if count > 0 {
+        let center = response.rect.right_top();
+        let painter = ui.painter();
+        painter.circle_filled(center, BADGE_RADIUS, ui.visuals().error_fg_color);
+        painter.text(
+            center,
+            egui::Align2::CENTER_CENTER,
+            count.to_string(),
+            egui::FontId::proportional(BADGE_RADIUS * 1.4),
+            egui::Color32::WHITE,
+        );
+    }
+}
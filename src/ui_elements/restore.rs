@@ -0,0 +1,124 @@
+// ----------------------------------------------------------------------------
+// Window which lists the backups of the task list, with a preview of each,
+// and lets the user pick one to restore.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::backup::{self, BackupSettings, Snapshot};
+use crate::encryption::{self, EncryptionKey};
+use crate::task::TaskList;
+
+// What can be shown about a snapshot without restoring it
+enum Preview {
+    List {
+        name: String,
+        total: usize,
+        completed: usize,
+    },
+    // Encrypted with a passphrase other than the current one
+    Encrypted,
+    Unreadable,
+}
+
+impl Preview {
+    fn new(data: &[u8], key: Option<&EncryptionKey>) -> Self {
+        let list = if encryption::is_encrypted(data) {
+            match key.and_then(|key| key.decrypt(data).ok()) {
+                Some(list) => TaskList::from_ical_reader(list.as_slice()),
+                None => return Self::Encrypted,
+            }
+        } else {
+            TaskList::from_ical_reader(data)
+        };
+
+        list.map_or(Self::Unreadable, |list| Self::List {
+            total: list.tasks.len(),
+            completed: list.tasks.iter().filter(|task| task.completed).count(),
+            name: list.name,
+        })
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::List {
+                name,
+                total,
+                completed,
+            } => format!("'{name}', {total} tasks ({completed} completed)"),
+            Self::Encrypted => String::from("Encrypted with a different passphrase"),
+            Self::Unreadable => String::from("Can't be read"),
+        }
+    }
+}
+
+struct Entry {
+    snapshot: Snapshot,
+    data: Vec<u8>,
+    preview: Preview,
+}
+
+pub struct RestoreWindow {
+    // Snapshots are read once, when the window is opened, rather than every frame
+    entries: Vec<Entry>,
+}
+
+impl RestoreWindow {
+    // Reads every snapshot, using the key to preview encrypted ones
+    pub fn open(settings: &BackupSettings, key: Option<&EncryptionKey>) -> Self {
+        let entries = backup::snapshots(settings)
+            .into_iter()
+            .filter_map(|snapshot| {
+                let data = snapshot.read(settings)?;
+                let preview = Preview::new(&data, key);
+                Some(Entry {
+                    snapshot,
+                    data,
+                    preview,
+                })
+            })
+            .collect();
+        Self { entries }
+    }
+
+    // Shows the window, and gives back the contents of a snapshot once one has been picked
+    pub fn show(&self, ctx: &egui::Context, open: &mut bool) -> Option<Vec<u8>> {
+        let mut picked = None;
+
+        egui::Window::new("Restore from backup")
+            .open(open)
+            .show(ctx, |ui| {
+                if self.entries.is_empty() {
+                    ui.label("There are no backups yet. They can be turned on in the settings.");
+                    return;
+                }
+                ui.label("Restoring a backup replaces the current list, which is backed up first.");
+
+                egui::ScrollArea::vertical()
+                    .max_height(300.)
+                    .show(ui, |ui| {
+                        egui::Grid::new("backup_list")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for entry in &self.entries {
+                                    ui.label(
+                                        entry.snapshot.time.format("%Y-%m-%d %H:%M:%S").to_string(),
+                                    );
+                                    ui.label(entry.preview.describe());
+                                    let readable = !matches!(entry.preview, Preview::Unreadable);
+                                    if ui
+                                        .add_enabled(readable, egui::Button::new("Restore"))
+                                        .clicked()
+                                    {
+                                        picked = Some(entry.data.clone());
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        picked
+    }
+}
@@ -0,0 +1,160 @@
+// ----------------------------------------------------------------------------
+// Tasks picked in the task list, so something can be done to all of them at
+// once from the toolbar above the list. Tasks are picked by clicking their
+// handles, shift-clicking to pick every task between the last one clicked
+// and this one, or pressing Ctrl+A to pick every task that's shown.
+//
+// The app keeps which tasks are picked by UUID, so they stay picked when the
+// list is sorted differently, and shares them with the views each frame.
+// ----------------------------------------------------------------------------
+
+use std::collections::HashSet;
+
+use eframe::egui;
+use uuid::Uuid;
+
+use crate::task::{Status, TaskList};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    selected: HashSet<Uuid>,
+    // The task that was clicked last, which shift-clicking picks every task from
+    anchor: Option<Uuid>,
+}
+
+impl Selection {
+    pub fn contains(&self, uuid: Uuid) -> bool {
+        self.selected.contains(&uuid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.selected.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.anchor = None;
+    }
+
+    /// Picks a task that was clicked, or unpicks it if it was picked. With
+    /// `range`, every task shown between the last task clicked and this one
+    /// is picked too. `shown` is every task that's shown, in the order it's shown in.
+    ///
+    /// Example:
+    /// ```
+    /// use taskmaster_rs::ui_elements::selection::Selection;
+    ///
+    /// let shown: Vec<_> = (0..5).map(|_| uuid::Uuid::new_v4()).collect();
+    /// let mut selection = Selection::default();
+    ///
+    /// selection.click(shown[1], &shown, false);
+    /// selection.click(shown[3], &shown, true);
+    /// assert_eq!(selection.len(), 3);
+    /// assert!(selection.contains(shown[2]));
+    ///
+    /// // Ranges can go up the list as well
+    /// selection.click(shown[0], &shown, true);
+    /// assert_eq!(selection.len(), 4);
+    ///
+    /// selection.click(shown[2], &shown, false);
+    /// assert!(!selection.contains(shown[2]));
+    /// ```
+    pub fn click(&mut self, uuid: Uuid, shown: &[Uuid], range: bool) {
+        let position = |uuid| shown.iter().position(|&shown| shown == uuid);
+        let anchor = self.anchor.filter(|_| range).and_then(position);
+        match (anchor, position(uuid)) {
+            (Some(anchor), Some(clicked)) => {
+                let (first, last) = (anchor.min(clicked), anchor.max(clicked));
+                self.selected.extend(&shown[first..=last]);
+            }
+            _ => {
+                if !self.selected.remove(&uuid) {
+                    self.selected.insert(uuid);
+                }
+            }
+        }
+        self.anchor = Some(uuid);
+    }
+
+    // Picks every task that's shown
+    pub fn select_all(&mut self, shown: &[Uuid]) {
+        self.selected.extend(shown);
+    }
+
+    // Unpicks tasks that aren't in the list any more
+    pub fn retain(&mut self, task_list: &TaskList) {
+        if self.is_empty() {
+            return;
+        }
+        let uuids: HashSet<Uuid> = task_list.tasks.iter().map(|task| task.uuid).collect();
+        self.selected.retain(|uuid| uuids.contains(uuid));
+        self.anchor = self.anchor.filter(|uuid| uuids.contains(uuid));
+    }
+}
+
+fn shared_id() -> egui::Id {
+    egui::Id::new("task_selection")
+}
+
+fn changed_id() -> egui::Id {
+    egui::Id::new("task_selection_changed")
+}
+
+// Lets the views see which tasks are picked this frame
+pub fn share(ctx: &egui::Context, selection: &Selection) {
+    ctx.data_mut(|d| d.insert_temp(shared_id(), selection.clone()));
+}
+
+// Which tasks are picked, as shared by the app
+pub fn shared(ctx: &egui::Context) -> Selection {
+    ctx.data(|d| d.get_temp(shared_id())).unwrap_or_default()
+}
+
+// Tells the app that a view changed which tasks are picked
+pub fn set(ctx: &egui::Context, selection: Selection) {
+    ctx.data_mut(|d| d.insert_temp(changed_id(), selection));
+}
+
+// The tasks a view has picked this frame, if it changed them
+pub fn take_changed(ctx: &egui::Context) -> Option<Selection> {
+    ctx.data_mut(|d| {
+        let selection = d.get_temp(changed_id());
+        d.remove::<Selection>(changed_id());
+        selection
+    })
+}
+
+// Shows the toolbar for doing something to every picked task, while any are picked
+pub fn toolbar(ui: &mut egui::Ui, selection: &mut Selection, task_list: &mut TaskList) {
+    if selection.is_empty() {
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(format!("{} selected", selection.len()));
+
+        if ui.button("✔ Complete").clicked() {
+            for task in &mut task_list.tasks {
+                if selection.contains(task.uuid) {
+                    task.completed = true;
+                    task.status = Status::Completed;
+                }
+            }
+        }
+
+        if ui.button("✖ Delete").clicked() {
+            task_list
+                .tasks
+                .retain(|task| !selection.contains(task.uuid));
+            selection.clear();
+        }
+
+        if ui.button("Clear selection").clicked() {
+            selection.clear();
+        }
+    });
+}
@@ -0,0 +1,901 @@
+// ----------------------------------------------------------------------------
+// Window which lets the user change the app's settings.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+use egui::Ui;
+
+use crate::agenda::{AgendaFormat, AgendaSettings};
+use crate::backup::BackupSettings;
+use crate::config::CONFIG_FILE;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::feed::{self, FeedSettings};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::git_store::GitSettings;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::github::GitHubSettings;
+use crate::hooks::HookSettings;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::jira::JiraSettings;
+use crate::list_file;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::network::NetworkSettings;
+use crate::notifications::NotificationSettings;
+use crate::parser::{ExportFormat, LineEnding};
+use crate::platform;
+use crate::platform::autostart::{self, StartupSettings};
+use crate::publish::PublishSettings;
+use crate::quiet_hours::QuietHoursSettings;
+use crate::secrets;
+use crate::settings::{LayoutMode, Settings};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::share::ShareSettings;
+use crate::sounds::SoundSettings;
+use crate::timezone::TimeZonePolicy;
+use crate::ui_elements::import_export::{ExportSettings, ARCHIVE_EXTENSION};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::webhooks::WebhookSettings;
+
+use super::celebration::CelebrationStyle;
+use super::{basic_frame, percentage_slider};
+
+pub fn window(ctx: &egui::Context, open: &mut bool, settings: &mut Settings) {
+    egui::Window::new("Settings").open(open).show(ctx, |ui| {
+        // Set spacing between panels
+        ui.spacing_mut().item_spacing = egui::Vec2::splat(7.5);
+
+        basic_frame().show(ui, |ui| {
+            appearance(ui, settings);
+        });
+
+        basic_frame().show(ui, |ui| {
+            encryption(ui, &mut settings.encrypt);
+        });
+
+        basic_frame().show(ui, |ui| {
+            backups(ui, &mut settings.backup);
+        });
+
+        basic_frame().show(ui, |ui| {
+            export(ui, &mut settings.export);
+        });
+
+        basic_frame().show(ui, |ui| {
+            sounds(ui, &mut settings.sounds);
+        });
+
+        basic_frame().show(ui, |ui| {
+            hooks(ui, &mut settings.hooks);
+        });
+
+        // Web builds can't send requests to other sites, which don't allow it
+        #[cfg(not(target_arch = "wasm32"))]
+        basic_frame().show(ui, |ui| {
+            webhooks(ui, &mut settings.webhooks);
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        basic_frame().show(ui, |ui| {
+            share(ui, &mut settings.share);
+        });
+
+        basic_frame().show(ui, |ui| {
+            notifications(ui, &mut settings.notifications);
+        });
+
+        basic_frame().show(ui, |ui| {
+            quiet_hours(ui, &mut settings.quiet_hours);
+        });
+
+        basic_frame().show(ui, |ui| {
+            time_zone(ui, &mut settings.time_zone);
+        });
+
+        basic_frame().show(ui, |ui| {
+            agenda(ui, &mut settings.agenda);
+        });
+
+        basic_frame().show(ui, |ui| {
+            publish(ui, &mut settings.publish);
+        });
+
+        // Web builds can't listen for connections
+        #[cfg(not(target_arch = "wasm32"))]
+        basic_frame().show(ui, |ui| {
+            calendar_feed(ui, &mut settings.feed);
+        });
+
+        // The HTTP API is only there when the app is built with it
+        #[cfg(feature = "server")]
+        basic_frame().show(ui, |ui| {
+            http_api(ui, &mut settings.api_token);
+        });
+
+        basic_frame().show(ui, |ui| {
+            saved_passwords(ui, settings);
+        });
+
+        // Web builds connect to servers however the browser does
+        #[cfg(not(target_arch = "wasm32"))]
+        basic_frame().show(ui, |ui| {
+            network(ui, &mut settings.network);
+        });
+
+        // Web builds can't run git
+        #[cfg(not(target_arch = "wasm32"))]
+        basic_frame().show(ui, |ui| {
+            git(ui, &mut settings.git);
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        basic_frame().show(ui, |ui| {
+            github(ui, &mut settings.github);
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        basic_frame().show(ui, |ui| {
+            jira(ui, &mut settings.jira);
+        });
+
+        // Web builds can't be launched at login
+        if autostart::SUPPORTED {
+            basic_frame().show(ui, |ui| {
+                startup(ui, &mut settings.startup);
+            });
+        }
+
+        // Point power users to the settings that can only be changed by hand
+        if let Some(path) = platform::state_path(CONFIG_FILE) {
+            ui.label(format!(
+                "Keyboard shortcuts and theme colors can be changed in {}",
+                path.display()
+            ));
+        }
+    });
+
+    // Passwords are kept when their field loses focus, which a field that's being typed in
+    // doesn't when the window is closed, so they're all kept then too
+    let open_id = egui::Id::new("settings_window_open");
+    let was_open = ctx.data(|d| d.get_temp(open_id)).unwrap_or(false);
+    ctx.data_mut(|d| d.insert_temp(open_id, *open));
+    if was_open && !*open {
+        store_secret(secrets::EMAIL_PASSWORD, &settings.agenda.email.password);
+        store_secret(secrets::PUBLISH_PASSWORD, &settings.publish.password);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            store_secret(secrets::GITHUB_TOKEN, &settings.github.token);
+            store_secret(secrets::JIRA_TOKEN, &settings.jira.token);
+        }
+    }
+}
+
+// Settings for how the app looks and moves
+fn appearance(ui: &mut Ui, settings: &mut Settings) {
+    ui.heading("Appearance");
+    ui.label(
+        "The mobile layout is made for narrow, touch screens. \
+        Automatic uses it whenever the window is narrow.",
+    );
+
+    egui::Grid::new("appearance_settings")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Layout");
+            egui::ComboBox::from_id_source("layout_mode")
+                .selected_text(format!("{:?}", settings.layout))
+                .show_ui(ui, |ui| {
+                    for mode in LayoutMode::iterator() {
+                        ui.selectable_value(&mut settings.layout, *mode, format!("{mode:?}"));
+                    }
+                });
+            ui.end_row();
+
+            ui.label("When every task in view is done");
+            egui::ComboBox::from_id_source("celebration_style")
+                .selected_text(format!("{:?}", settings.celebration))
+                .show_ui(ui, |ui| {
+                    for style in CelebrationStyle::iterator() {
+                        ui.selectable_value(
+                            &mut settings.celebration,
+                            *style,
+                            format!("{style:?}"),
+                        );
+                    }
+                });
+            ui.end_row();
+
+            ui.label("Tasks are stale after");
+            ui.add(
+                egui::DragValue::new(&mut settings.stale.days)
+                    .clamp_range(1..=365)
+                    .suffix(" days"),
+            )
+            .on_hover_text(
+                "Open tasks that haven't been changed for this long are marked as stale",
+            );
+            ui.end_row();
+
+            ui.label("Description preview");
+            ui.add(
+                egui::DragValue::new(&mut settings.rows.description_chars)
+                    .clamp_range(0..=200)
+                    .suffix(" characters"),
+            )
+            .on_hover_text(
+                "How much of each task's description is shown in its row. \
+                 Clicking the description shows all of it.",
+            );
+            ui.end_row();
+        });
+
+    ui.checkbox(&mut settings.reduce_motion, "Reduce motion")
+        .on_hover_text("Turns off animations");
+    ui.checkbox(&mut settings.agenda_sidebar, "Show the next 7 days")
+        .on_hover_text("Shows the tasks due each day this week in a sidebar on the right");
+}
+
+// Setting for encrypting task lists
+fn encryption(ui: &mut Ui, encrypt: &mut bool) {
+    ui.heading("Encryption");
+    ui.label(
+        "Encrypted task lists can only be opened with their passphrase. \
+        If the passphrase is forgotten, the list can't be recovered.",
+    );
+    ui.checkbox(encrypt, "Encrypt saved and exported task lists");
+}
+
+// Settings for automatic backups of the task list
+fn backups(ui: &mut Ui, backup: &mut BackupSettings) {
+    ui.heading("Backups");
+    ui.checkbox(&mut backup.enabled, "Back up the task list automatically");
+
+    ui.add_enabled_ui(backup.enabled, |ui| {
+        egui::Grid::new("backup_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Back up every");
+                ui.add(
+                    egui::DragValue::new(&mut backup.interval_minutes)
+                        .clamp_range(1..=1440)
+                        .suffix(" minutes"),
+                );
+                ui.end_row();
+
+                ui.label("Backups to keep");
+                ui.add(egui::DragValue::new(&mut backup.keep).clamp_range(1..=1000));
+                ui.end_row();
+
+                // Web builds keep backups in the browser, so there's no folder to pick
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.label("Save to folder");
+                    ui.text_edit_singleline(&mut backup.directory);
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+// Settings for exported files
+fn export(ui: &mut Ui, export: &mut ExportSettings) {
+    ui.heading("Export");
+    ui.horizontal(|ui| {
+        ui.label("Export as");
+        egui::ComboBox::from_id_source("export_format")
+            .selected_text(export.format.name())
+            .show_ui(ui, |ui| {
+                for option in ExportFormat::iterator() {
+                    ui.selectable_value(&mut export.format, *option, option.name());
+                }
+            });
+    })
+    .response
+    .on_hover_text(
+        "Some calendar apps, like Google Calendar, ignore tasks, \
+         but show events on the day each task is due",
+    );
+    ui.horizontal(|ui| {
+        ui.label("Line endings");
+        egui::ComboBox::from_id_source("line_ending")
+            .selected_text(export.line_ending.name())
+            .show_ui(ui, |ui| {
+                for ending in LineEnding::iterator() {
+                    ui.selectable_value(&mut export.line_ending, *ending, ending.name());
+                }
+            });
+    })
+    .response
+    .on_hover_text("Some older programs can only open files with LF line endings");
+    ui.checkbox(
+        &mut export.separate_archive,
+        format!("Move completed tasks to a separate .{ARCHIVE_EXTENSION} file"),
+    )
+    .on_hover_text(
+        "Keeps the exported file small, for calendar apps that slow down \
+         with lots of completed tasks. The archive is named after the list's \
+         file, and tasks are added to it rather than written over it.",
+    );
+    ui.checkbox(
+        &mut export.nextcloud_tasks,
+        "Keep progress and status in step for Nextcloud Tasks",
+    )
+    .on_hover_text(
+        "Completed tasks are exported as 100% done, and tasks that have \
+         been started as in progress",
+    );
+}
+
+// Settings for the sounds played when a task is completed or due soon
+fn sounds(ui: &mut Ui, sounds: &mut SoundSettings) {
+    ui.heading("Sounds");
+    ui.checkbox(
+        &mut sounds.enabled,
+        "Play sounds when tasks are completed or due soon",
+    );
+
+    ui.add_enabled_ui(sounds.enabled, |ui| {
+        ui.horizontal(|ui| {
+            let volume_label = ui.label("Volume");
+            percentage_slider(ui, &mut sounds.volume).labelled_by(volume_label.id);
+        });
+    });
+}
+
+// Settings for the commands that are run when something happens to a task
+fn hooks(ui: &mut Ui, hooks: &mut HookSettings) {
+    ui.heading("Hooks");
+    ui.label(
+        "Shell commands which are run when something happens to a task. \
+        The task is given to the command as JSON on standard input \
+        and in the TASKMASTER_TASK environment variable.",
+    );
+
+    egui::Grid::new("hook_settings")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Task added");
+            ui.text_edit_singleline(&mut hooks.on_added);
+            ui.end_row();
+
+            ui.label("Task completed");
+            ui.text_edit_singleline(&mut hooks.on_completed);
+            ui.end_row();
+
+            ui.label("Task due soon");
+            ui.text_edit_singleline(&mut hooks.on_due_soon);
+            ui.end_row();
+
+            ui.label("Due soon means due within");
+            ui.add(egui::DragValue::new(&mut hooks.due_soon_days).suffix(" days"));
+            ui.end_row();
+        });
+}
+
+// Settings for sending what happens to tasks to other services
+#[cfg(not(target_arch = "wasm32"))]
+fn webhooks(ui: &mut Ui, webhooks: &mut WebhookSettings) {
+    ui.heading("Webhooks");
+    ui.label(
+        "URLs which are sent the task as JSON when something happens to it, \
+        like a Slack or Discord webhook or a home automation server. \
+        Events that can't be sent are tried again a few times.",
+    );
+    ui.add(
+        egui::TextEdit::multiline(&mut webhooks.urls)
+            .hint_text("One URL on each line")
+            .desired_rows(2),
+    );
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut webhooks.on_added, "Task added");
+        ui.checkbox(&mut webhooks.on_completed, "Task completed");
+        ui.checkbox(&mut webhooks.on_due_soon, "Task due soon");
+    });
+    ui.horizontal(|ui| {
+        ui.label("Tries before giving up");
+        ui.add(egui::DragValue::new(&mut webhooks.attempts).clamp_range(1..=10));
+    });
+}
+
+// Settings for the chat channel tasks are shared to
+#[cfg(not(target_arch = "wasm32"))]
+fn share(ui: &mut Ui, share: &mut ShareSettings) {
+    ui.heading("Sharing");
+    ui.label(
+        "Tasks can be shared to a Slack or Discord channel from their right-click menu, \
+        with their summary, due date and link.",
+    );
+    ui.horizontal(|ui| {
+        ui.label("Channel webhook");
+        ui.add(
+            egui::TextEdit::singleline(&mut share.webhook_url)
+                .hint_text("https://hooks.slack.com/services/…"),
+        )
+        .on_hover_text("The channel's incoming webhook, from Slack's or Discord's settings");
+    });
+}
+
+// Settings for the desktop notifications shown when a task is due soon
+fn notifications(ui: &mut Ui, notifications: &mut NotificationSettings) {
+    ui.heading("Notifications");
+    ui.checkbox(
+        &mut notifications.enabled,
+        "Show a notification when a task becomes due soon",
+    );
+    if platform::notification::ACTIONS_SUPPORTED {
+        ui.label("Tasks can be completed or snoozed for an hour from their notifications.");
+    }
+}
+
+// Settings for when reminders are held back instead of going off
+fn quiet_hours(ui: &mut Ui, quiet_hours: &mut QuietHoursSettings) {
+    ui.heading("Quiet hours");
+    ui.label(
+        "Reminders for tasks that become due soon are held back during quiet hours, \
+        and go off once they're over.",
+    );
+
+    ui.checkbox(&mut quiet_hours.enabled, "Keep reminders quiet every night");
+    ui.add_enabled_ui(quiet_hours.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("From");
+            ui.add(egui::DragValue::new(&mut quiet_hours.start_hour).clamp_range(0..=23));
+            ui.label(":");
+            ui.add(egui::DragValue::new(&mut quiet_hours.start_minute).clamp_range(0..=59));
+            ui.label("to");
+            ui.add(egui::DragValue::new(&mut quiet_hours.end_hour).clamp_range(0..=23));
+            ui.label(":");
+            ui.add(egui::DragValue::new(&mut quiet_hours.end_minute).clamp_range(0..=59));
+        });
+    });
+    ui.checkbox(
+        &mut quiet_hours.weekends,
+        "Keep reminders quiet all weekend",
+    );
+
+    // Any list the app knows about can be muted, as well as any that already are
+    let mut lists = list_file::known_lists();
+    for muted in &quiet_hours.muted_lists {
+        if !lists.contains(muted) {
+            lists.push(muted.clone());
+        }
+    }
+    if lists.is_empty() {
+        return;
+    }
+    ui.label("Never remind me about tasks in");
+    for path in lists {
+        let mut muted = quiet_hours.is_muted(Some(&path));
+        let name = path
+            .file_stem()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned();
+        if ui
+            .checkbox(&mut muted, name)
+            .on_hover_text(path.display().to_string())
+            .changed()
+        {
+            quiet_hours.set_muted(&path, muted);
+        }
+    }
+}
+
+// Setting for what happens to the times on tasks when the time zone changes
+fn time_zone(ui: &mut Ui, policy: &mut TimeZonePolicy) {
+    ui.heading("Time zone");
+    ui.horizontal(|ui| {
+        ui.label("When the time zone changes");
+        egui::ComboBox::from_id_source("time_zone_policy")
+            .selected_text(policy.name())
+            .show_ui(ui, |ui| {
+                for option in TimeZonePolicy::iterator() {
+                    ui.selectable_value(policy, *option, option.name());
+                }
+            });
+    });
+}
+
+// Settings for the agenda of today's and overdue tasks which is made every day
+fn agenda(ui: &mut Ui, agenda: &mut AgendaSettings) {
+    ui.heading("Daily agenda");
+    ui.checkbox(
+        &mut agenda.enabled,
+        "Make an agenda of today's and overdue tasks every day",
+    );
+
+    ui.add_enabled_ui(agenda.enabled, |ui| {
+        egui::Grid::new("agenda_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Time");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut agenda.hour).clamp_range(0..=23));
+                    ui.label(":");
+                    ui.add(egui::DragValue::new(&mut agenda.minute).clamp_range(0..=59));
+                });
+                ui.end_row();
+
+                ui.label("Format");
+                egui::ComboBox::from_id_source("agenda_format")
+                    .selected_text(format!("{:?}", agenda.format))
+                    .show_ui(ui, |ui| {
+                        for format in AgendaFormat::iterator() {
+                            ui.selectable_value(&mut agenda.format, *format, format!("{format:?}"));
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Save to folder");
+                ui.text_edit_singleline(&mut agenda.directory)
+                    .on_hover_text("Leave empty to not save the agenda to a file");
+                ui.end_row();
+            });
+
+        ui.checkbox(&mut agenda.email.enabled, "Email the agenda");
+
+        ui.add_enabled_ui(agenda.email.enabled, |ui| {
+            let email = &mut agenda.email;
+            egui::Grid::new("agenda_email_settings")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("SMTP server");
+                    ui.text_edit_singleline(&mut email.server);
+                    ui.end_row();
+
+                    ui.label("Port");
+                    ui.add(egui::DragValue::new(&mut email.port));
+                    ui.end_row();
+
+                    ui.label("Username");
+                    ui.text_edit_singleline(&mut email.username);
+                    ui.end_row();
+
+                    ui.label("Password");
+                    if ui
+                        .add(egui::TextEdit::singleline(&mut email.password).password(true))
+                        .lost_focus()
+                    {
+                        store_secret(secrets::EMAIL_PASSWORD, &email.password);
+                    }
+                    ui.end_row();
+
+                    ui.label("From");
+                    ui.text_edit_singleline(&mut email.from);
+                    ui.end_row();
+
+                    ui.label("To");
+                    ui.text_edit_singleline(&mut email.to);
+                    ui.end_row();
+                });
+        });
+    });
+}
+
+// Keeps a password, in the keyring if there is one. Passwords are kept once they've
+// been typed, rather than after every key, since storing them can be slow.
+fn store_secret(name: &str, secret: &str) {
+    if let Err(e) = secrets::set(name, secret) {
+        eprintln!("could not store {name}: {e}");
+    }
+}
+
+// Every stored password, where it's kept, and a button to forget it
+fn saved_passwords(ui: &mut Ui, settings: &mut Settings) {
+    ui.heading("Saved passwords");
+    let stored = secrets::stored();
+    if stored.is_empty() {
+        ui.label("No passwords are saved.");
+        return;
+    }
+
+    egui::Grid::new("saved_passwords")
+        .num_columns(3)
+        .show(ui, |ui| {
+            for (name, location) in &stored {
+                ui.label(name);
+                ui.label(location.name());
+                if ui.button("Remove").clicked() {
+                    if let Err(e) = secrets::remove(name) {
+                        eprintln!("could not remove {name}: {e}");
+                    }
+                    match name.as_str() {
+                        secrets::PUBLISH_PASSWORD => settings.publish.password.clear(),
+                        secrets::EMAIL_PASSWORD => settings.agenda.email.password.clear(),
+                        secrets::GITHUB_TOKEN => settings.github.token.clear(),
+                        secrets::JIRA_TOKEN => settings.jira.token.clear(),
+                        secrets::FEED_TOKEN => settings.feed.token.clear(),
+                        secrets::API_TOKEN => settings.api_token.clear(),
+                        _ => (),
+                    }
+                }
+                ui.end_row();
+            }
+        });
+}
+
+// Settings for publishing a read-only copy of the list for other people to subscribe to
+fn publish(ui: &mut Ui, publish: &mut PublishSettings) {
+    ui.heading("Publishing");
+    ui.label(
+        "Uploads a read-only copy of the list to a WebDAV server, like Nextcloud, \
+        so that other people can subscribe to it from their calendar apps.",
+    );
+    ui.checkbox(&mut publish.enabled, "Publish the task list");
+
+    ui.add_enabled_ui(publish.enabled, |ui| {
+        egui::Grid::new("publish_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("URL");
+                ui.text_edit_singleline(&mut publish.url)
+                    .on_hover_text("Where to upload the list, ending in .ics");
+                ui.end_row();
+
+                ui.label("Username");
+                ui.text_edit_singleline(&mut publish.username);
+                ui.end_row();
+
+                ui.label("Password");
+                if ui
+                    .add(egui::TextEdit::singleline(&mut publish.password).password(true))
+                    .lost_focus()
+                {
+                    store_secret(secrets::PUBLISH_PASSWORD, &publish.password);
+                }
+                ui.end_row();
+
+                ui.label("Upload every");
+                ui.add(
+                    egui::DragValue::new(&mut publish.interval_minutes)
+                        .clamp_range(1..=1440)
+                        .suffix(" minutes"),
+                );
+                ui.end_row();
+
+                ui.label("Publish as");
+                egui::ComboBox::from_id_source("publish_format")
+                    .selected_text(publish.format.name())
+                    .show_ui(ui, |ui| {
+                        for option in ExportFormat::iterator() {
+                            ui.selectable_value(&mut publish.format, *option, option.name());
+                        }
+                    });
+                ui.end_row();
+            });
+
+        ui.checkbox(&mut publish.include_completed, "Include completed tasks");
+    });
+}
+
+// Settings for serving the list as a calendar feed from this computer
+#[cfg(not(target_arch = "wasm32"))]
+fn calendar_feed(ui: &mut Ui, feed: &mut FeedSettings) {
+    ui.heading("Calendar feed");
+    ui.label(
+        "Serves the list as a calendar feed, which calendar apps can subscribe to \
+        and see changes as soon as they're made.",
+    );
+    if !cfg!(feature = "server") {
+        ui.label("This build of the app can't serve the feed, it needs the `server` feature.");
+        return;
+    }
+    if ui
+        .checkbox(&mut feed.enabled, "Serve the task list as a calendar feed")
+        .changed()
+        && feed.enabled
+        && feed.token.is_empty()
+    {
+        feed.token = feed::new_token();
+        store_secret(secrets::FEED_TOKEN, &feed.token);
+    }
+
+    ui.add_enabled_ui(feed.enabled, |ui| {
+        egui::Grid::new("feed_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Port");
+                ui.add(egui::DragValue::new(&mut feed.port).clamp_range(1024..=65535));
+                ui.end_row();
+
+                ui.label("Feed URL");
+                ui.horizontal(|ui| {
+                    let url = feed.url();
+                    ui.monospace(&url);
+                    if ui.small_button("Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = url);
+                    }
+                    if ui
+                        .small_button("New token")
+                        .on_hover_text("Calendars subscribed with the old URL stop being updated")
+                        .clicked()
+                    {
+                        feed.token = feed::new_token();
+                        store_secret(secrets::FEED_TOKEN, &feed.token);
+                    }
+                });
+                ui.end_row();
+            });
+        ui.checkbox(
+            &mut feed.on_network,
+            "Let other devices on the network subscribe",
+        )
+        .on_hover_text("They subscribe with this computer's address in place of localhost");
+    });
+}
+
+// The token scripts need to send to use the HTTP API
+#[cfg(feature = "server")]
+fn http_api(ui: &mut Ui, token: &mut String) {
+    ui.heading("HTTP API");
+    ui.label(format!(
+        "Scripts can add and change tasks through the API at http://{}, \
+        sending the token as \"Authorization: Bearer <token>\".",
+        crate::server::address()
+    ));
+
+    ui.horizontal(|ui| {
+        if token.is_empty() {
+            ui.label("The API can't be used until a token is made.");
+        } else {
+            ui.monospace(token.as_str());
+            if ui.small_button("Copy").clicked() {
+                ui.output_mut(|o| o.copied_text = token.clone());
+            }
+        }
+        if ui
+            .small_button("New token")
+            .on_hover_text("Scripts using the old token stop working")
+            .clicked()
+        {
+            *token = feed::new_token();
+            store_secret(secrets::API_TOKEN, token);
+        }
+    });
+}
+
+// Settings for how the app connects to servers, like through an office's proxy
+#[cfg(not(target_arch = "wasm32"))]
+fn network(ui: &mut Ui, network: &mut NetworkSettings) {
+    ui.heading("Network");
+    ui.label(
+        "Used for publishing, importing from URLs and anything else that connects to a server.",
+    );
+
+    egui::Grid::new("network_settings")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Proxy");
+            ui.add(
+                egui::TextEdit::singleline(&mut network.proxy)
+                    .hint_text("http://proxy.example.com:8080"),
+            )
+            .on_hover_text("Leave empty to use the proxy set in HTTPS_PROXY, if there is one");
+            ui.end_row();
+
+            ui.label("Certificate authorities");
+            ui.text_edit_singleline(&mut network.ca_bundle)
+                .on_hover_text("A PEM file of certificates to trust as well as the usual ones");
+            ui.end_row();
+
+            ui.label("Give up after");
+            ui.add(
+                egui::DragValue::new(&mut network.timeout_seconds)
+                    .clamp_range(1..=600)
+                    .suffix(" seconds"),
+            );
+            ui.end_row();
+        });
+}
+
+// Settings for keeping the list in a git repository
+#[cfg(not(target_arch = "wasm32"))]
+fn git(ui: &mut Ui, git: &mut GitSettings) {
+    ui.heading("Git");
+    ui.label(
+        "Commits the list to a git repository whenever it changes, so that its history \
+        can be browsed and old versions restored. Needs git to be installed.",
+    );
+    ui.checkbox(&mut git.enabled, "Keep the task list in git");
+
+    ui.add_enabled_ui(git.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Repository folder");
+            ui.text_edit_singleline(&mut git.directory)
+                .on_hover_text("Made into a repository if it isn't one already");
+        });
+        ui.checkbox(&mut git.push, "Push to the remote after every commit")
+            .on_hover_text("Keeps the list in sync with other computers that use the same remote");
+    });
+}
+
+// Settings for syncing a list with the GitHub issues assigned to the user
+#[cfg(not(target_arch = "wasm32"))]
+fn github(ui: &mut Ui, github: &mut GitHubSettings) {
+    ui.heading("GitHub");
+    ui.label(
+        "Lists bound to GitHub issues, next to the list's name, are kept as the issues \
+        assigned to you. Closing an issue completes its task, and completing a task closes it.",
+    );
+
+    egui::Grid::new("github_settings")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Access token");
+            if ui
+                .add(egui::TextEdit::singleline(&mut github.token).password(true))
+                .on_hover_text("A personal access token which can read and write issues")
+                .lost_focus()
+            {
+                store_secret(secrets::GITHUB_TOKEN, &github.token);
+            }
+            ui.end_row();
+
+            ui.label("Repositories");
+            ui.add(
+                egui::TextEdit::multiline(&mut github.repos)
+                    .hint_text("owner/name, one on each line")
+                    .desired_rows(2),
+            )
+            .on_hover_text("Leave empty to look in every repository");
+            ui.end_row();
+        });
+}
+
+// Settings for importing the Jira issues assigned to the user
+#[cfg(not(target_arch = "wasm32"))]
+fn jira(ui: &mut Ui, jira: &mut JiraSettings) {
+    ui.heading("Jira");
+    ui.label(
+        "Lists bound to Jira issues, next to the list's name, are filled with the open issues \
+        assigned to you. They're read-only: changes made here are replaced when they're refreshed.",
+    );
+
+    egui::Grid::new("jira_settings")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Site");
+            ui.add(egui::TextEdit::singleline(&mut jira.site).hint_text("example.atlassian.net"));
+            ui.end_row();
+
+            ui.label("Email");
+            ui.text_edit_singleline(&mut jira.email);
+            ui.end_row();
+
+            ui.label("API token");
+            if ui
+                .add(egui::TextEdit::singleline(&mut jira.token).password(true))
+                .lost_focus()
+            {
+                store_secret(secrets::JIRA_TOKEN, &jira.token);
+            }
+            ui.end_row();
+
+            ui.label("Projects");
+            ui.add(egui::TextEdit::singleline(&mut jira.projects).hint_text("APP, WEB"))
+                .on_hover_text("Leave empty to import from every project");
+            ui.end_row();
+
+            ui.label("Refresh every");
+            ui.add(
+                egui::DragValue::new(&mut jira.refresh_minutes)
+                    .clamp_range(1..=24 * 60)
+                    .suffix(" min"),
+            );
+            ui.end_row();
+        });
+}
+
+// Settings for launching the app when the user logs in
+fn startup(ui: &mut Ui, startup: &mut StartupSettings) {
+    ui.heading("Startup");
+    ui.checkbox(
+        &mut startup.launch_at_login,
+        "Launch Taskmaster when you log in",
+    )
+    .on_hover_text("So that reminders work without having to open the app");
+
+    ui.add_enabled_ui(startup.launch_at_login, |ui| {
+        ui.checkbox(&mut startup.start_minimized, "Start minimized");
+    });
+}
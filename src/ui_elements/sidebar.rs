@@ -0,0 +1,82 @@
+// ----------------------------------------------------------------------------
+// Sidebar listing the open list and the other lists the app knows about,
+// with how many tasks in each still need doing and how many are overdue.
+// Hovering over a list breaks its pending tasks down by priority.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::counters::{ListCounters, ListCounts};
+use crate::task::TaskList;
+
+pub fn show(ui: &mut egui::Ui, task_list: &TaskList, counters: &ListCounters) {
+    ui.heading("Lists");
+
+    let name = if task_list.name.is_empty() {
+        "This list"
+    } else {
+        task_list.name.as_str()
+    };
+    row(ui, name, Some(counters.current()), true);
+
+    let mut total = *counters.current();
+    for other in counters.others() {
+        row(ui, &other.name, other.counts.as_ref(), false)
+            .on_hover_text(other.path.display().to_string());
+        if let Some(counts) = &other.counts {
+            total.add(counts);
+        }
+    }
+
+    // Only worth showing when there's more than one list to add up
+    if !counters.others().is_empty() {
+        ui.separator();
+        row(ui, "All lists", Some(&total), false);
+    }
+}
+
+// Shows a list's name and counts
+fn row(ui: &mut egui::Ui, name: &str, counts: Option<&ListCounts>, open: bool) -> egui::Response {
+    let response = ui
+        .horizontal(|ui| {
+            if open {
+                ui.strong(name);
+            } else {
+                ui.label(name);
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let Some(counts) = counts else {
+                    ui.weak("–");
+                    return;
+                };
+                if counts.overdue > 0 {
+                    ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {}", counts.overdue));
+                }
+                ui.label(counts.pending.to_string());
+            });
+        })
+        .response;
+
+    match counts {
+        Some(counts) => response.on_hover_ui(|ui| priority_breakdown(ui, counts)),
+        None => response.on_hover_text("This list is encrypted or couldn't be read"),
+    }
+}
+
+// How many pending tasks have each priority
+fn priority_breakdown(ui: &mut egui::Ui, counts: &ListCounts) {
+    ui.label(format!(
+        "{} pending, {} overdue",
+        counts.pending, counts.overdue
+    ));
+    for (priority, count) in counts.by_priority.iter().enumerate().rev() {
+        if *count == 0 {
+            continue;
+        }
+        if priority == 0 {
+            ui.label(format!("No priority: {count}"));
+        } else {
+            ui.label(format!("Priority {priority}: {count}"));
+        }
+    }
+}
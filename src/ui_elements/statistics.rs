@@ -0,0 +1,317 @@
+// ----------------------------------------------------------------------------
+// Window showing statistics about the list. The activity tab has a heatmap
+// of how many tasks were completed on each day of the last year, with a
+// square for each day and a column for each week. The insights tab plots
+// how much work has been left in the list over time, and forecasts when
+// it'll all be done. The report tab writes a report on a range of days to
+// a file, to be sent on as a status update.
+// ----------------------------------------------------------------------------
+
+use chrono::{Datelike, Duration, NaiveDate};
+use eframe::egui;
+use egui::plot::{Legend, Line, LineStyle, Plot, PlotPoints, VLine};
+use egui::{Color32, Rect, Sense};
+
+use crate::json::DATE_FORMAT;
+use crate::platform;
+use crate::report::{self, Report, ReportFormat};
+use crate::stats;
+use crate::task::TaskList;
+use crate::task_views::format_minutes;
+
+// How big each day's square on the heatmap is, and the gap between them
+const CELL_SIZE: f32 = 11.;
+const CELL_GAP: f32 = 2.;
+// How many weeks the heatmap goes back, including this one
+const HEATMAP_WEEKS: u16 = 53;
+
+const EMPTY_COLOR: Color32 = Color32::from_gray(60);
+const LOW_COLOR: Color32 = Color32::from_rgb(14, 68, 41);
+const HIGH_COLOR: Color32 = Color32::from_rgb(57, 211, 83);
+
+// How many days of completed work the forecast goes by
+const VELOCITY_DAYS: u32 = 14;
+// How far back the burndown goes at most, in days
+const BURNDOWN_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum StatisticsTab {
+    #[default]
+    Activity,
+    Insights,
+    Report,
+}
+
+pub struct StatisticsWindow {
+    tab: StatisticsTab,
+    // The days to report on, including both
+    report_start: NaiveDate,
+    report_end: NaiveDate,
+    report_format: ReportFormat,
+    // Dialog for picking where the report is saved
+    export_dialog: Option<platform::ExportDialog>,
+    // What happened when the last report was saved
+    report_status: Option<Result<String, String>>,
+}
+
+impl Default for StatisticsWindow {
+    fn default() -> Self {
+        // Reports cover the last week by default
+        let today = chrono::Local::now().date_naive();
+        Self {
+            tab: StatisticsTab::default(),
+            report_start: today - Duration::days(6),
+            report_end: today,
+            report_format: ReportFormat::default(),
+            export_dialog: None,
+            report_status: None,
+        }
+    }
+}
+
+impl StatisticsWindow {
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool, task_list: &TaskList) {
+        let today = chrono::Local::now().date_naive();
+        egui::Window::new("Statistics").open(open).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.tab, StatisticsTab::Activity, "Activity");
+                ui.selectable_value(&mut self.tab, StatisticsTab::Insights, "Insights");
+                ui.selectable_value(&mut self.tab, StatisticsTab::Report, "Report");
+            });
+            ui.separator();
+
+            match self.tab {
+                StatisticsTab::Activity => {
+                    ui.heading("Completed tasks");
+                    heatmap(ui, task_list, today);
+                }
+                StatisticsTab::Insights => insights(ui, task_list, today),
+                StatisticsTab::Report => self.report(ui, task_list),
+            }
+        });
+    }
+
+    // Picks the days and format of a report, and saves it to a file
+    fn report(&mut self, ui: &mut egui::Ui, task_list: &TaskList) {
+        ui.horizontal(|ui| {
+            ui.label("From");
+            ui.add(
+                egui_extras::DatePickerButton::new(&mut self.report_start)
+                    .id_source("report_start"),
+            );
+            ui.label("to");
+            ui.add(
+                egui_extras::DatePickerButton::new(&mut self.report_end).id_source("report_end"),
+            );
+        });
+        if self.report_end < self.report_start {
+            self.report_end = self.report_start;
+        }
+
+        let summary = Report::of(task_list, self.report_start, self.report_end);
+        ui.label(format!(
+            "{} tasks completed and {} overdue",
+            summary.completed.len(),
+            summary.overdue.len()
+        ));
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("report_format")
+                .selected_text(self.report_format.name())
+                .show_ui(ui, |ui| {
+                    for format in ReportFormat::iterator() {
+                        ui.selectable_value(&mut self.report_format, *format, format.name());
+                    }
+                });
+            if ui.button("Generate report…").clicked() {
+                let name = format!(
+                    "report-{}.{}",
+                    self.report_end.format(DATE_FORMAT),
+                    self.report_format.extension()
+                );
+                self.export_dialog = Some(platform::ExportDialog::open(ui.ctx(), &name));
+            }
+        });
+
+        if let Some(target) = self.export_dialog.as_mut().and_then(|d| d.show(ui.ctx())) {
+            self.export_dialog = None;
+            let path = target.path();
+            let contents = report::format(
+                task_list,
+                self.report_start,
+                self.report_end,
+                self.report_format,
+            );
+            self.report_status = Some(match target.write(contents.as_bytes()) {
+                Ok(()) => Ok(path.map_or_else(
+                    || String::from("The report was saved"),
+                    |path| format!("The report was saved to {}", path.display()),
+                )),
+                Err(e) => Err(format!("The report could not be saved: {e}")),
+            });
+        }
+
+        match &self.report_status {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(error)) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            None => {}
+        }
+    }
+}
+
+// The color of a day's square, which is brighter the more tasks were completed
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn cell_color(count: usize, most: usize) -> Color32 {
+    if count == 0 {
+        return EMPTY_COLOR;
+    }
+    let amount = count as f32 / most.max(1) as f32;
+    let lerp = |low: u8, high: u8| egui::lerp(f32::from(low)..=f32::from(high), amount) as u8;
+    Color32::from_rgb(
+        lerp(LOW_COLOR.r(), HIGH_COLOR.r()),
+        lerp(LOW_COLOR.g(), HIGH_COLOR.g()),
+        lerp(LOW_COLOR.b(), HIGH_COLOR.b()),
+    )
+}
+
+// Shows how many tasks were completed on each day of the last year.
+// Hovering over a day lists the tasks that were completed on it.
+fn heatmap(ui: &mut egui::Ui, task_list: &TaskList, today: NaiveDate) {
+    // Columns are weeks starting on Monday, with this week in the last column
+    let this_week = today - Duration::days(today.weekday().num_days_from_monday().into());
+    let start = this_week - Duration::weeks(i64::from(HEATMAP_WEEKS - 1));
+    let days = stats::completions_by_day(task_list, start, today);
+    let most = days.values().map(Vec::len).max().unwrap_or_default();
+
+    let step = CELL_SIZE + CELL_GAP;
+    let size = egui::vec2(f32::from(HEATMAP_WEEKS) * step, 7. * step);
+    let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
+    let painter = ui.painter_at(rect);
+    let pointer = response.hover_pos();
+
+    let mut hovered = None;
+    for week in 0..HEATMAP_WEEKS {
+        for weekday in 0..7_u16 {
+            let day = start + Duration::days(i64::from(week * 7 + weekday));
+            if day > today {
+                break;
+            }
+            let offset = egui::vec2(f32::from(week) * step, f32::from(weekday) * step);
+            let cell = Rect::from_min_size(rect.min + offset, egui::vec2(CELL_SIZE, CELL_SIZE));
+            let count = days.get(&day).map_or(0, Vec::len);
+            painter.rect_filled(cell, 2., cell_color(count, most));
+            if pointer.is_some_and(|pointer| cell.contains(pointer)) {
+                hovered = Some(day);
+            }
+        }
+    }
+
+    if let Some(day) = hovered {
+        response.on_hover_ui_at_pointer(|ui| {
+            ui.strong(day.format("%A %-d %B %Y").to_string());
+            match days.get(&day) {
+                Some(tasks) => {
+                    for task in tasks {
+                        ui.label(format!("✔ {}", task.summary));
+                    }
+                }
+                None => {
+                    ui.label("Nothing was completed");
+                }
+            }
+        });
+    }
+
+    let total: usize = days.values().map(Vec::len).sum();
+    ui.label(format!("{total} tasks completed in the last year"));
+}
+
+// Where a day goes along the burndown's x axis, which counts days from today
+#[allow(clippy::cast_precision_loss)]
+fn plot_x(day: NaiveDate, today: NaiveDate) -> f64 {
+    (day - today).num_days() as f64
+}
+
+// Plots how much work has been left in the list each day, and when it's forecast
+// to be done going by how much work has been completed lately
+#[allow(clippy::cast_possible_truncation)]
+fn insights(ui: &mut egui::Ui, task_list: &TaskList, today: NaiveDate) {
+    let Some(first) = task_list.tasks.iter().map(|task| task.created.date()).min() else {
+        ui.label("There are no tasks in this list yet.");
+        return;
+    };
+    let start = first.max(today - Duration::days(BURNDOWN_DAYS)).min(today);
+    let burndown = stats::burndown(task_list, start, today);
+    let remaining = burndown.last().map_or(0, |(_, minutes)| *minutes);
+    let forecast = stats::forecast(task_list, today, VELOCITY_DAYS);
+    // The last day anything left in the list is due
+    let last_due = task_list
+        .tasks
+        .iter()
+        .filter(|task| !task.completed)
+        .filter_map(|task| task.due)
+        .max();
+
+    ui.label(format!(
+        "{} of work left. Tasks without an estimate count as an hour.",
+        format_minutes(remaining)
+    ));
+    match forecast {
+        Some(forecast) => {
+            ui.label(format!(
+                "At the pace of the last {VELOCITY_DAYS} days, everything will be done by {}.",
+                forecast.format("%-d %B %Y")
+            ));
+            if let Some(last_due) = last_due.filter(|last_due| forecast > *last_due) {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "⚠ That's after the last due date, {}.",
+                        last_due.format("%-d %B %Y")
+                    ),
+                );
+            }
+        }
+        None => {
+            ui.label(format!(
+                "Nothing was completed in the last {VELOCITY_DAYS} days, so there's no forecast."
+            ));
+        }
+    }
+
+    let hours = |minutes: u32| f64::from(minutes) / 60.;
+    let points: Vec<[f64; 2]> = burndown
+        .iter()
+        .map(|(day, minutes)| [plot_x(*day, today), hours(*minutes)])
+        .collect();
+    Plot::new("burndown")
+        .height(200.)
+        .legend(Legend::default())
+        .label_formatter(move |name, point| {
+            let day = today + Duration::days(point.x.round() as i64);
+            format!("{name}\n{}\n{:.1} hours", day.format("%-d %b %Y"), point.y)
+        })
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(points)).name("Work left (hours)"));
+            if let Some(forecast) = forecast {
+                let line = vec![[0., hours(remaining)], [plot_x(forecast, today), 0.]];
+                plot_ui.line(
+                    Line::new(PlotPoints::from(line))
+                        .style(LineStyle::dashed_loose())
+                        .name("Forecast"),
+                );
+            }
+            if let Some(last_due) = last_due {
+                plot_ui.vline(VLine::new(plot_x(last_due, today)).name("Last due date"));
+            }
+        });
+    ui.weak("Days from today");
+}
@@ -0,0 +1,147 @@
+// ----------------------------------------------------------------------------
+// A row which can be swiped sideways, for touch screens.
+// Swiping a row to the right past a threshold completes it, and swiping it
+// to the left slides it over to reveal buttons to delete or snooze it.
+// When the row is let go, it animates back to where it should rest.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+use egui::{Align2, Color32, FontId, Id, Rect, Sense, Shape, Ui};
+
+// How far a row has to be swiped right before letting go completes it
+const COMPLETE_DISTANCE: f32 = 100.;
+// How wide the area holding the revealed buttons is
+const ACTIONS_WIDTH: f32 = 150.;
+// How quickly a row slides back into place after being let go.
+// Higher values are faster.
+const SETTLE_SPEED: f32 = 20.;
+
+const COMPLETE_COLOR: Color32 = Color32::from_rgb(60, 140, 60);
+const DELETE_COLOR: Color32 = Color32::from_rgb(170, 50, 50);
+
+// Something that the user asked to do to a row by swiping it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeAction {
+    Complete,
+    Delete,
+    Snooze,
+}
+
+// State of a row which is kept between frames
+#[derive(Debug, Clone, Copy, Default)]
+struct SwipeState {
+    // How far the row's contents are currently moved to the right
+    offset: f32,
+    // Whether the row has been swiped left and is showing its buttons
+    open: bool,
+}
+
+// Shows a row which can be swiped.
+// The id has to be unique to the row and stay the same between frames,
+// so that the row remembers how far it has been swiped.
+// Returns the action the user picked this frame, if any.
+pub fn show(ui: &mut Ui, id: Id, add_contents: impl FnOnce(&mut Ui)) -> Option<SwipeAction> {
+    let mut state: SwipeState = ui.data(|d| d.get_temp(id)).unwrap_or_default();
+    let mut action = None;
+
+    // Reserve a place for the background, so that it's drawn behind the contents
+    let background = ui.painter().add(Shape::Noop);
+
+    // Draw the contents moved sideways by however far the row has been swiped,
+    // cutting off anything that's been moved outside of the row
+    let start = ui.cursor().min;
+    let width = ui.available_width();
+    let mut contents_ui = ui.child_ui_with_id_source(
+        Rect::from_min_size(
+            start + egui::vec2(state.offset, 0.),
+            egui::vec2(width, f32::INFINITY),
+        ),
+        *ui.layout(),
+        id,
+    );
+    let row_clip = Rect::from_x_y_ranges(start.x..=start.x + width, ui.clip_rect().y_range());
+    contents_ui.set_clip_rect(ui.clip_rect().intersect(row_clip));
+    add_contents(&mut contents_ui);
+
+    // The row takes up the space its contents would if it wasn't swiped.
+    // It senses drags after the contents do, so that widgets in the row which
+    // can be dragged, like sliders, still work.
+    let rect = Rect::from_min_size(start, egui::vec2(width, contents_ui.min_rect().height()));
+    ui.allocate_rect(rect, Sense::hover());
+    let response = ui.interact(rect, id, Sense::click_and_drag());
+
+    if response.dragged() {
+        // Follow the pointer, but don't let the row be swiped off the screen
+        state.offset = (state.offset + response.drag_delta().x).clamp(-width, width);
+    } else {
+        if response.drag_released() {
+            // Decide what letting go of the row does based on where it was let go
+            if state.offset > COMPLETE_DISTANCE {
+                action = Some(SwipeAction::Complete);
+                state.open = false;
+            } else {
+                state.open = state.offset < -ACTIONS_WIDTH / 2.;
+            }
+        } else if response.clicked() {
+            // Tapping an open row closes it
+            state.open = false;
+        }
+
+        // Slide towards where the row rests, or jump straight there if motion is turned off
+        let target = if state.open { -ACTIONS_WIDTH } else { 0. };
+        if (target - state.offset).abs() < 0.5 || super::animation_time(ui, 1.) == 0. {
+            state.offset = target;
+        } else {
+            let dt = ui.input(|i| i.stable_dt);
+            state.offset += (target - state.offset) * (1. - (-dt * SETTLE_SPEED).exp());
+            ui.ctx().request_repaint();
+        }
+    }
+
+    // Show what will happen when the row is let go behind the contents
+    if state.offset > 0. {
+        let color = if state.offset > COMPLETE_DISTANCE {
+            COMPLETE_COLOR
+        } else {
+            ui.visuals().faint_bg_color
+        };
+        ui.painter().set(
+            background,
+            Shape::rect_filled(rect, ui.visuals().widgets.noninteractive.rounding, color),
+        );
+        ui.painter().text(
+            rect.left_center() + egui::vec2(10., 0.),
+            Align2::LEFT_CENTER,
+            "✔",
+            FontId::default(),
+            ui.visuals().strong_text_color(),
+        );
+    } else if state.offset < 0. {
+        // Put the buttons in the space the row has uncovered
+        let uncovered = Rect::from_min_max(
+            egui::pos2(rect.right() + state.offset, rect.top()),
+            rect.max,
+        );
+        let mut actions_ui = ui.child_ui_with_id_source(
+            uncovered,
+            egui::Layout::right_to_left(egui::Align::Center),
+            id.with("actions"),
+        );
+        actions_ui.set_clip_rect(ui.clip_rect().intersect(uncovered));
+        if actions_ui
+            .add(egui::Button::new("Delete").fill(DELETE_COLOR))
+            .clicked()
+        {
+            action = Some(SwipeAction::Delete);
+            state.open = false;
+        }
+        if actions_ui.button("Snooze").clicked() {
+            action = Some(SwipeAction::Snooze);
+            state.open = false;
+        }
+    }
+
+    ui.data_mut(|d| d.insert_temp(id, state));
+
+    action
+}
@@ -0,0 +1,80 @@
+// ----------------------------------------------------------------------------
+// Shows how syncing is going: a status next to the bell, and a window with
+// the log of every sync, for working out why a change didn't get somewhere.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::sync_log::{SyncLog, SyncStatus};
+use crate::task::TaskList;
+
+// Shows whether the list is syncing, when it last synced, or why syncing failed.
+// Returns true if it was clicked, to open or close the log.
+pub fn status(ui: &mut egui::Ui, log: &SyncLog, task_list: &TaskList) -> bool {
+    let response = match log.status() {
+        SyncStatus::Syncing => {
+            let response = ui.add(egui::Label::new("Syncing…").sense(egui::Sense::click()));
+            ui.spinner();
+            response
+        }
+        SyncStatus::Error(e) => ui
+            .add(
+                egui::Label::new(
+                    egui::RichText::new("⚠ Sync failed").color(ui.visuals().error_fg_color),
+                )
+                .sense(egui::Sense::click()),
+            )
+            .on_hover_text(e),
+        SyncStatus::Idle => match log.last_synced(task_list) {
+            Some(time) => ui
+                .add(
+                    egui::Label::new(format!("✔ Synced {}", time.format("%H:%M")))
+                        .sense(egui::Sense::click()),
+                )
+                .on_hover_text(format!("Last synced {}", time.format("%Y-%m-%d %H:%M:%S"))),
+            // Lists that are never synced don't need a status
+            None if log.entries().is_empty() => return false,
+            None => ui.add(egui::Label::new("Not synced").sense(egui::Sense::click())),
+        },
+    };
+    response
+        .on_hover_cursor(egui::CursorIcon::PointingHand)
+        .clicked()
+}
+
+// Shows the window with everything in the log, newest first
+pub fn window(ctx: &egui::Context, open: &mut bool, log: &SyncLog) {
+    egui::Window::new("Sync log").open(open).show(ctx, |ui| {
+        let entries = log.entries();
+        if entries.is_empty() {
+            ui.label("Nothing has been synced yet.");
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .id_source("sync_log_entries")
+            .max_height(300.)
+            .show(ui, |ui| {
+                egui::Grid::new("sync_log_list")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for entry in entries.iter().rev() {
+                            ui.label(entry.time.format("%Y-%m-%d %H:%M:%S").to_string());
+                            ui.label(entry.source);
+                            ui.label(&entry.list);
+                            if entry.failed {
+                                ui.colored_label(ui.visuals().error_fg_color, &entry.message);
+                            } else {
+                                ui.label(&entry.message);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if ui.button("Clear").clicked() {
+            log.clear();
+        }
+    });
+}
@@ -0,0 +1,131 @@
+// ----------------------------------------------------------------------------
+// Window which lists every tag the app knows about, where each one's color
+// and description can be changed, and tags can be renamed or merged.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::tags::TagRegistry;
+use crate::task::TaskList;
+
+#[derive(Default)]
+pub struct TagManagerWindow {
+    // The tag being renamed, and what it's being renamed to
+    renaming: Option<(String, String)>,
+    // Name of a tag being added
+    new_tag: String,
+}
+
+impl TagManagerWindow {
+    // Shows the window. Renaming a tag changes it on every task in the list too.
+    // Returns whether the registry was changed, so that it can be saved.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        tags: &mut TagRegistry,
+        task_list: &mut TaskList,
+    ) -> bool {
+        // Tags that were typed in while editing tasks are added as they're found
+        let mut changed = tags.add_from(task_list);
+
+        egui::Window::new("Tags").open(open).show(ctx, |ui| {
+            let names: Vec<String> = tags.names().map(String::from).collect();
+            if names.is_empty() {
+                ui.label("There are no tags yet. Tasks can be tagged when they're edited.");
+            }
+
+            egui::ScrollArea::vertical()
+                .max_height(300.)
+                .show(ui, |ui| {
+                    egui::Grid::new("tag_list")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for name in &names {
+                                changed |= self.row(ui, name, tags, task_list);
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.new_tag);
+                let name = self.new_tag.trim();
+                let can_add = !name.is_empty() && !name.contains(',') && tags.get(name).is_none();
+                if ui
+                    .add_enabled(can_add, egui::Button::new("Add tag"))
+                    .clicked()
+                {
+                    tags.add(name);
+                    self.new_tag.clear();
+                    changed = true;
+                }
+            });
+        });
+
+        changed
+    }
+
+    // Shows one tag. Returns whether it was changed.
+    fn row(
+        &mut self,
+        ui: &mut egui::Ui,
+        name: &str,
+        tags: &mut TagRegistry,
+        task_list: &mut TaskList,
+    ) -> bool {
+        let mut changed = false;
+        let Some(info) = tags.get_mut(name) else {
+            return false;
+        };
+
+        changed |= ui.color_edit_button_srgba(&mut info.color).changed();
+        let uses = task_list.count_tag(name);
+        ui.label(name)
+            .on_hover_text(format!("Used by {uses} tasks in this list"));
+        changed |= ui
+            .add(egui::TextEdit::singleline(&mut info.description).hint_text("Description"))
+            .changed();
+
+        ui.horizontal(|ui| match &mut self.renaming {
+            Some((tag, new_name)) if *tag == name => {
+                ui.text_edit_singleline(new_name);
+                let new_name = new_name.trim().to_string();
+                // Renaming to a tag that already exists merges the two
+                let label = if tags.get(&new_name).is_some() {
+                    "Merge"
+                } else {
+                    "Rename"
+                };
+                let valid = !new_name.is_empty() && !new_name.contains(',') && new_name != name;
+                if ui.add_enabled(valid, egui::Button::new(label)).clicked() {
+                    task_list.rename_tag(name, &new_name);
+                    tags.rename(name, &new_name);
+                    self.renaming = None;
+                    changed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    self.renaming = None;
+                }
+            }
+            _ => {
+                if ui.button("Rename…").clicked() {
+                    self.renaming = Some((name.to_string(), name.to_string()));
+                }
+                // Tags that are still used would only be added again
+                if ui
+                    .add_enabled(uses == 0, egui::Button::new("🗑"))
+                    .on_hover_text("Remove tag")
+                    .clicked()
+                {
+                    tags.remove(name);
+                    changed = true;
+                }
+            }
+        });
+
+        changed
+    }
+}
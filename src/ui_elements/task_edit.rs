@@ -5,13 +5,13 @@
 // The lite version only has a field for task summary and completion
 // ----------------------------------------------------------------------------
 
-use convert_case::{Case, Casing};
 use eframe::egui::{self, Ui};
 use egui_extras::DatePickerButton;
 
+use crate::recurrence::{Frequency, Repeat};
 use crate::task::Status;
 
-use super::percentage_slider;
+use super::{percentage_slider, priority_slider};
 
 pub fn lite(ui: &mut Ui, task: &mut crate::task::Task) {
     ui.horizontal(|ui| {
@@ -39,8 +39,7 @@ pub fn full(ui: &mut Ui, task: &mut crate::task::Task) {
         percentage_slider(ui, &mut task.progress).labelled_by(progress_label.id);
 
         let priority_label = ui.label("Priority");
-        ui.add(egui::Slider::new(&mut task.priority, 0..=10))
-            .labelled_by(priority_label.id);
+        priority_slider(ui, &mut task.priority).labelled_by(priority_label.id);
     });
 
     // Task description input
@@ -48,17 +47,51 @@ pub fn full(ui: &mut Ui, task: &mut crate::task::Task) {
     ui.text_edit_multiline(&mut task.description)
         .labelled_by(desc_label.id);
 
+    // Task URL input, an empty field means the task has no URL
+    ui.horizontal(|ui| {
+        let url_label = ui.label("URL");
+        let mut url = task.url.clone().unwrap_or_default();
+        ui.text_edit_singleline(&mut url).labelled_by(url_label.id);
+        task.url = if url.trim().is_empty() {
+            None
+        } else {
+            Some(url)
+        };
+    });
+
+    // Task tags input, written as a list separated by commas
+    ui.horizontal(|ui| {
+        let tags_label = ui.label("Tags");
+        // The text is kept while it's being typed in, so that a comma at the end isn't lost
+        let id = ui.id().with(("tags", task.uuid));
+        let mut text = ui
+            .data_mut(|d| d.get_temp::<String>(id))
+            .unwrap_or_else(|| task.tags.join(", "));
+        let response = ui
+            .text_edit_singleline(&mut text)
+            .labelled_by(tags_label.id);
+        if response.changed() {
+            task.tags.clear();
+            for tag in text.split(',').map(str::trim) {
+                if !tag.is_empty() && !task.tags.iter().any(|t| t == tag) {
+                    task.tags.push(tag.to_string());
+                }
+            }
+        }
+        if response.has_focus() {
+            ui.data_mut(|d| d.insert_temp(id, text));
+        } else {
+            ui.data_mut(|d| d.remove::<String>(id));
+        }
+    });
+
     // Task status input
     egui::ComboBox::from_label("Status")
-        .selected_text(format!("{:?}", &task.status).to_case(Case::Title)) // Show selected status
+        .selected_text(task.status.name()) // Show selected status
         .show_ui(ui, |ui| {
             for status in Status::iterator() {
                 // Iterate over possible statuses and show each as an option
-                ui.selectable_value(
-                    &mut task.status,
-                    *status,
-                    format!("{status:?}",).to_case(Case::Title),
-                );
+                ui.selectable_value(&mut task.status, status.clone(), status.name());
             }
         });
 
@@ -82,6 +115,8 @@ pub fn full(ui: &mut Ui, task: &mut crate::task::Task) {
         }
     });
 
+    repeats(ui, task);
+
     // Task complete checkbox
     ui.checkbox(&mut task.completed, "Task is complete");
 
@@ -89,3 +124,50 @@ pub fn full(ui: &mut Ui, task: &mut crate::task::Task) {
         task.completed = true;
     }
 }
+
+// How often the task repeats, and a button to skip the occurrence it's due on.
+// Rules that are too complicated to edit here are shown as they're written.
+fn repeats(ui: &mut Ui, task: &mut crate::task::Task) {
+    ui.horizontal(|ui| {
+        let repeat = task.repeat();
+        if let (Some(rule), None) = (&task.recurrence, repeat) {
+            ui.label(format!("Repeats: {rule}"));
+        } else {
+            let mut frequency = repeat.map(|repeat| repeat.frequency);
+            let mut interval = repeat.map_or(1, |repeat| repeat.interval);
+
+            ui.label("Repeats");
+            egui::ComboBox::from_id_source("repeat_frequency")
+                .selected_text(frequency.map_or("Never", Frequency::name))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut frequency, None, "Never");
+                    for option in Frequency::iterator() {
+                        ui.selectable_value(&mut frequency, Some(*option), option.name());
+                    }
+                });
+            if let Some(frequency) = frequency {
+                ui.label("every");
+                ui.add(egui::DragValue::new(&mut interval).clamp_range(1..=365));
+                ui.label(frequency.unit());
+            }
+
+            let edited = frequency.map(|frequency| Repeat {
+                frequency,
+                interval,
+            });
+            if edited != repeat {
+                task.recurrence = edited.map(Repeat::to_rrule);
+            }
+        }
+
+        // Moves the task on to its next occurrence without completing it
+        if task.next_occurrence().is_some()
+            && ui
+                .button("Skip this occurrence")
+                .on_hover_text("Move the task to when it's next due, without completing it")
+                .clicked()
+        {
+            task.skip_occurrence();
+        }
+    });
+}
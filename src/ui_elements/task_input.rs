@@ -0,0 +1,136 @@
+// ----------------------------------------------------------------------------
+// Panel for making new tasks and adding them to the list. On wide screens
+// it's shown above the list, with a simple and a full version, and on
+// narrow screens it's a bar at the bottom of the screen.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::heuristics;
+use crate::platform;
+use crate::task::Task;
+use crate::ui_elements;
+
+// A task the user made, for the rest of the app to add to the list
+pub enum TaskInputAction {
+    // A task the user filled in, which should be checked for duplicates first
+    Add(Task),
+    // A task guessed from the clipboard, with its modal open so the guesses can be checked
+    FromClipboard(Task),
+    // Open the window for turning a pasted list into tasks
+    PasteList,
+}
+
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TaskInputPanel {
+    // The task being filled in
+    input_task: Task,
+    show_full_edit: bool,
+}
+
+impl TaskInputPanel {
+    // A task with the summary and completion of the input task.
+    // Used by both the simplified task editing UI and the mobile layout's add bar.
+    fn quick_task(&self) -> Task {
+        Task::builder()
+            .summary(self.input_task.summary.clone())
+            .completed(self.input_task.completed)
+            .build()
+            .expect("a task with only a summary should always be valid")
+    }
+
+    // Shows the panel, for wide screens
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<TaskInputAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            // Expand to fit window
+            ui.set_width(ui.available_width());
+
+            // Decides whether to show the simple task editing UI
+            // or a simplified/minimal version
+            if self.show_full_edit {
+                // Full task editing UI
+                ui.vertical(|ui| {
+                    ui_elements::task_edit::full(ui, &mut self.input_task);
+
+                    if ui.button("Add task").clicked() {
+                        // Add input task to the list of tasks
+                        action = Some(TaskInputAction::Add(self.input_task.clone()));
+                        self.input_task.uuid = uuid::Uuid::new_v4();
+                    }
+                });
+            } else {
+                // Simplified task editing UI
+                ui.horizontal(|ui| {
+                    if ui.button("+").clicked() {
+                        // Add input task to the list of tasks
+                        action = Some(TaskInputAction::Add(self.quick_task()));
+                    }
+
+                    ui_elements::task_edit::lite(ui, &mut self.input_task);
+                });
+            }
+
+            // This is the button which switches full and simplified UIs
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Max), |ui| {
+                let btn_str = if self.show_full_edit { "⏶" } else { "⏷" };
+                if ui.button(btn_str).clicked() {
+                    self.show_full_edit = !self.show_full_edit;
+                }
+
+                // Creates a task from an email or mailto: link that has been copied
+                if ui
+                    .button("📋")
+                    .on_hover_text("New task from clipboard")
+                    .clicked()
+                {
+                    // If the clipboard can't be read or doesn't contain text, nothing happens
+                    if let Some(text) = platform::clipboard_text() {
+                        let task = heuristics::task_from_email(&text);
+                        // Open the new task's modal so that the guessed fields can be checked
+                        ui_elements::task_modal::set_open(ui.ctx(), task.uuid, true);
+                        action = Some(TaskInputAction::FromClipboard(task));
+                    }
+                }
+
+                if ui
+                    .button("📝")
+                    .on_hover_text("Add tasks from a pasted list")
+                    .clicked()
+                {
+                    action = Some(TaskInputAction::PasteList);
+                }
+            });
+        });
+
+        action
+    }
+
+    // Shows the bar that tasks are added from on narrow screens, which is at the
+    // bottom of the screen, where it's easy to reach with a thumb
+    pub fn show_add_bar(&mut self, ui: &mut egui::Ui) -> Option<TaskInputAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            let add_clicked = ui.button("+").clicked();
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.input_task.summary)
+                    .hint_text("New task")
+                    .desired_width(f32::INFINITY),
+            );
+            let enter_pressed =
+                response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            if add_clicked || enter_pressed {
+                action = Some(TaskInputAction::Add(self.quick_task()));
+                // Clear the input so the next task can be typed straight away
+                self.input_task.summary.clear();
+                response.request_focus();
+            }
+        });
+
+        action
+    }
+}
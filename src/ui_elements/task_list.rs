@@ -0,0 +1,268 @@
+// ----------------------------------------------------------------------------
+// Panel which shows the task list in whichever view the user picked, with
+// a bar above it for picking the view and sorting the list.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::filters::{QuickFilter, QuickFilters, TaskFilter};
+use crate::layouts::ListLayout;
+use crate::query::{self, Query, QueryError};
+use crate::task::*;
+use crate::task_views::*;
+
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TaskListPanel {
+    // Stores every view the task list can be shown in, and which one is being used
+    views: TaskViewRegistry,
+    current_view: usize,
+    show_completed_tasks: bool,
+    sort_by: TaskSort,
+    sort_descending: bool,
+    filter: TaskFilter,
+    // The filter chips that are turned on
+    quick_filters: QuickFilters,
+    // What's typed into the filter box, and what it was parsed into
+    query_text: String,
+    query: Option<Result<Query, QueryError>>,
+}
+
+impl TaskListPanel {
+    // Adds a task view to the view selector
+    pub fn register_view(&mut self, view: Box<dyn TaskView>) {
+        self.views.register(view);
+    }
+
+    // Starts showing completed tasks.
+    // This prevents confusion from a newly added task not being shown
+    // if it's already marked as complete when it's added to the list.
+    pub const fn show_completed(&mut self) {
+        self.show_completed_tasks = true;
+    }
+
+    // Switches to the view with the given name, ignoring case.
+    // Returns whether there was a view with that name.
+    pub fn select_view(&mut self, name: &str) -> bool {
+        let index = self
+            .views
+            .names()
+            .position(|view| view.eq_ignore_ascii_case(name));
+        if let Some(index) = index {
+            self.current_view = index;
+        }
+        index.is_some()
+    }
+
+    // How the list is laid out now, to be kept for when it's shown again
+    pub fn layout(&self) -> ListLayout {
+        let mut layout = ListLayout {
+            view: self
+                .views
+                .names()
+                .nth(self.current_view)
+                .unwrap_or_default()
+                .to_string(),
+            show_completed_tasks: self.show_completed_tasks,
+            sort_by: self.sort_by,
+            sort_descending: self.sort_descending,
+            filter: self.filter,
+            ..Default::default()
+        };
+        self.views.save_layout(&mut layout);
+        layout
+    }
+
+    // Lays the list out the way it was before
+    pub fn apply_layout(&mut self, layout: &ListLayout) {
+        self.select_view(&layout.view);
+        self.show_completed_tasks = layout.show_completed_tasks;
+        self.sort_by = layout.sort_by;
+        self.sort_descending = layout.sort_descending;
+        self.filter = layout.filter;
+        self.views.apply_layout(layout);
+    }
+
+    // Shows or hides completed tasks, such as from a keyboard shortcut
+    pub const fn toggle_completed(&mut self) {
+        self.show_completed_tasks = !self.show_completed_tasks;
+    }
+
+    // Shows the panel. Compact views are laid out for narrow, touch screens.
+    // Tasks are stale after going untouched for the given number of days.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        task_list: &mut TaskList,
+        compact: bool,
+        stale_days: u32,
+        rows: &mut RowSettings,
+    ) {
+        // Top bar, with sorting and other list options
+        self.top_bar(ui, rows);
+        self.quick_filter_bar(ui, task_list);
+        self.query_bar(ui);
+
+        // Scrollable area that shows all the tasks. Rows aren't all the same height,
+        // so rather than being given a range of rows, views leave out the rows that
+        // are outside the area's clip rectangle themselves.
+        let options = self.options(compact, stale_days, rows);
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            // Display tasks in the chosen view
+            if let Some(view) = self.views.get_mut(self.current_view) {
+                view.display(ui, task_list, &options);
+            }
+        });
+    }
+
+    // How the chosen view is showing the list, which decides which tasks are in it
+    pub fn options(&self, compact: bool, stale_days: u32, rows: &RowSettings) -> ViewOptions {
+        let view = self
+            .views
+            .names()
+            .nth(self.current_view)
+            .unwrap_or_default();
+        ViewOptions {
+            show_completed_tasks: self.show_completed_tasks,
+            compact,
+            sort_by: self.sort_by,
+            sort_descending: self.sort_descending,
+            filter: self.filter,
+            quick_filters: self.quick_filters.clone(),
+            query: self.query.clone().and_then(Result::ok),
+            stale_days,
+            description_chars: rows.description_chars,
+            fields: rows.fields(view),
+        }
+    }
+
+    // Chips that narrow the list down to tasks that are overdue, have a tag, and so on.
+    // Tags that are turned on keep their chip when no tasks have them any more.
+    fn quick_filter_bar(&mut self, ui: &mut egui::Ui, task_list: &TaskList) {
+        let mut tags: Vec<&String> = task_list.tasks.iter().flat_map(|t| &t.tags).collect();
+        tags.extend(self.quick_filters.tags());
+        tags.sort();
+        tags.dedup();
+        let chips: Vec<QuickFilter> = QuickFilter::BUILT_IN
+            .into_iter()
+            .chain(tags.into_iter().cloned().map(QuickFilter::Tag))
+            .collect();
+
+        ui.horizontal_wrapped(|ui| {
+            for chip in chips {
+                let on = self.quick_filters.contains(&chip);
+                if ui.selectable_label(on, chip.name()).clicked() {
+                    self.quick_filters.toggle(chip);
+                }
+            }
+            if !self.quick_filters.is_empty() && ui.small_button("✖ Clear").clicked() {
+                self.quick_filters.clear();
+            }
+        });
+    }
+
+    // Box for typing a filter expression, like `tag:home AND NOT status:cancelled`,
+    // which suggests fields while they're typed and says what's wrong with it
+    fn query_bar(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            changed = ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.query_text)
+                        .hint_text("Filter, like tag:home AND NOT status:cancelled"),
+                )
+                .changed();
+
+            // Clicking a field finishes the word being typed with it
+            for field in query::completions(&self.query_text) {
+                if ui.small_button(field).clicked() {
+                    let typed = self.query_text.trim_end_matches(char::is_alphabetic);
+                    self.query_text = format!("{typed}{field}:");
+                    changed = true;
+                }
+            }
+        });
+
+        if changed {
+            self.query =
+                (!self.query_text.trim().is_empty()).then(|| Query::parse(&self.query_text));
+        }
+        if let Some(Err(e)) = &self.query {
+            ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+        }
+    }
+
+    // Top bar, with sorting and other list options
+    fn top_bar(&mut self, ui: &mut egui::Ui, rows: &mut RowSettings) {
+        ui.horizontal(|ui| {
+            // Dropdown to choose which view to show the task list in
+            let selected_view = self
+                .views
+                .names()
+                .nth(self.current_view)
+                .unwrap_or_default()
+                .to_string();
+            egui::ComboBox::from_id_source("view_selector")
+                .selected_text(&selected_view)
+                .show_ui(ui, |ui| {
+                    for (index, name) in self.views.names().enumerate() {
+                        ui.selectable_value(&mut self.current_view, index, name);
+                    }
+                });
+
+            // Menu to choose which of each task's details are shown in this view's rows
+            ui.menu_button("Fields", |ui| {
+                let fields = rows.fields_mut(&selected_view);
+                ui.checkbox(&mut fields.due, "Due date");
+                ui.checkbox(&mut fields.priority, "Priority");
+                ui.checkbox(&mut fields.progress, "Progress");
+                ui.checkbox(&mut fields.status, "Status");
+            })
+            .response
+            .on_hover_text("Which details are shown next to each task in this view");
+
+            ui.label("| ");
+
+            // Checkbox to show tasks that have been completed
+            ui.checkbox(&mut self.show_completed_tasks, "Show completed tasks");
+
+            ui.label("| ");
+
+            // Dropdown to choose a built-in filter
+            egui::ComboBox::from_id_source("filter_selector")
+                .selected_text(self.filter.name())
+                .show_ui(ui, |ui| {
+                    for filter in TaskFilter::iterator() {
+                        ui.selectable_value(&mut self.filter, *filter, filter.name());
+                    }
+                });
+
+            ui.label("| ");
+
+            // Dropdown to choose which field to sort by.
+            // This only changes the order tasks are shown in, not the order they're saved in.
+            ui.label("Sort by");
+            egui::ComboBox::from_label("")
+                .selected_text(format!("{:?}", &self.sort_by)) // Show selected sort field
+                .show_ui(ui, |ui| {
+                    for sort_by in TaskSort::iterator() {
+                        // Iterate over sortable fields and display each as an option
+                        ui.selectable_value(&mut self.sort_by, *sort_by, format!("{sort_by:?}"));
+                    }
+                });
+            // Button to flip the order tasks are sorted in
+            let (arrow, hover) = if self.sort_descending {
+                ("⬇", "Sorted last to first")
+            } else {
+                ("⬆", "Sorted first to last")
+            };
+            if ui
+                .add_enabled(self.sort_by != TaskSort::None, egui::Button::new(arrow))
+                .on_hover_text(hover)
+                .clicked()
+            {
+                self.sort_descending = !self.sort_descending;
+            }
+        });
+    }
+}
@@ -3,25 +3,130 @@
 // ----------------------------------------------------------------------------
 
 use eframe::egui;
+use egui::{RichText, Ui};
 
-use crate::task::Task;
+use crate::task::{Task, TaskList};
 
 use super::*;
 
-pub fn spawn(task: &mut Task, ctx: &egui::Context) {
+// The details of a task that are needed to show links to it from other tasks.
+// These are collected before any task is edited, so that a modal can show
+// the other tasks in the list without needing to borrow the whole list.
+#[derive(Clone)]
+pub struct LinkTarget {
+    pub uuid: uuid::Uuid,
+    pub summary: String,
+    // The tasks that this task links to
+    pub links: Vec<uuid::Uuid>,
+}
+
+impl LinkTarget {
+    // Collects link targets for every task in a list
+    pub fn from_list(task_list: &TaskList) -> Vec<Self> {
+        task_list
+            .tasks
+            .iter()
+            .map(|task| Self {
+                uuid: task.uuid,
+                summary: task.summary.clone(),
+                links: task.linked_uuids(),
+            })
+            .collect()
+    }
+}
+
+// Whether a task's modal is open is kept in egui's memory rather than in the task,
+// so that tasks only hold their own details
+fn open_id(uuid: uuid::Uuid) -> egui::Id {
+    egui::Id::new(("task_modal_open", uuid))
+}
+
+// Whether the modal for the task with the given UUID is open
+pub fn is_open(ctx: &egui::Context, uuid: uuid::Uuid) -> bool {
+    ctx.data(|d| d.get_temp(open_id(uuid))).unwrap_or_default()
+}
+
+// Opens or closes the modal for the task with the given UUID
+pub fn set_open(ctx: &egui::Context, uuid: uuid::Uuid, open: bool) {
+    ctx.data_mut(|d| d.insert_temp(open_id(uuid), open));
+}
+
+pub fn spawn(task: &mut Task, link_targets: &[LinkTarget], ctx: &egui::Context) {
+    let mut open = is_open(ctx, task.uuid);
     egui::Window::new(format!("Edit task: {}", task.summary))
         .id(task.uuid.to_string().into())
-        .open(&mut task.show_modal.clone().borrow_mut())
+        .open(&mut open)
         .show(ctx, |ui| {
-            // Set global ui scale
-            ctx.set_pixels_per_point(1.75);
-
             // Set spacing between panels
             ui.spacing_mut().item_spacing = egui::Vec2::splat(7.5);
 
             crate::ui_elements::basic_frame().show(ui, |ui| {
                 // Task editing UI
                 task_edit::full(ui, task);
-            })
+            });
+
+            crate::ui_elements::basic_frame().show(ui, |ui| {
+                // Links to and from other tasks
+                links(ui, task, link_targets);
+            });
         });
+    set_open(ctx, task.uuid, open);
+}
+
+// Shows the tasks linked in a task's description as clickable chips,
+// the tasks that link to it, and a picker for adding new links
+fn links(ui: &mut Ui, task: &mut Task, link_targets: &[LinkTarget]) {
+    let linked = task.linked_uuids();
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Links:");
+        for target in link_targets
+            .iter()
+            .filter(|target| target.uuid != task.uuid && linked.contains(&target.uuid))
+        {
+            link_chip(ui, target);
+        }
+
+        // Dropdown which adds a link to the chosen task to the end of the description
+        egui::ComboBox::new(("link_task", task.uuid), "")
+            .selected_text("Link task")
+            .show_ui(ui, |ui| {
+                for target in link_targets
+                    .iter()
+                    .filter(|target| target.uuid != task.uuid)
+                {
+                    if ui.selectable_label(false, &target.summary).clicked() {
+                        if !task.description.is_empty()
+                            && !task.description.ends_with(char::is_whitespace)
+                        {
+                            task.description.push(' ');
+                        }
+                        task.description.push_str(&format!("[[{}]]", target.uuid));
+                    }
+                }
+            });
+    });
+
+    // Tasks whose descriptions link to this one
+    let backlinks: Vec<&LinkTarget> = link_targets
+        .iter()
+        .filter(|target| target.uuid != task.uuid && target.links.contains(&task.uuid))
+        .collect();
+    if !backlinks.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Linked from:");
+            for target in backlinks {
+                link_chip(ui, target);
+            }
+        });
+    }
+}
+
+// A small rounded button which opens the linked task's modal when clicked
+fn link_chip(ui: &mut Ui, target: &LinkTarget) {
+    let chip =
+        egui::Button::new(RichText::new(format!("🔗 {}", target.summary)).small()).rounding(8.);
+    if ui.add(chip).clicked() {
+        set_open(ui.ctx(), target.uuid, true);
+    }
 }
@@ -0,0 +1,56 @@
+// ----------------------------------------------------------------------------
+// Tooltip shown when the pointer rests on a task in any of the views, with
+// its whole summary, its description wrapped, when it's due, its tags and
+// how far along it is, so a task can be looked over without opening it.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+use egui::{Response, RichText, Ui};
+
+use crate::json::DATE_FORMAT;
+use crate::task::{Task, MAX_PROGRESS};
+
+// How wide the tooltip can get before its text wraps
+const MAX_WIDTH: f32 = 320.;
+// How many characters of a description are shown, so huge ones don't fill the screen
+const DESCRIPTION_CHARS: usize = 500;
+
+// Shows the tooltip for a task when the response is hovered
+pub fn attach(response: Response, task: &Task) -> Response {
+    response.on_hover_ui(|ui| show(ui, task))
+}
+
+// The contents of the tooltip
+pub fn show(ui: &mut Ui, task: &Task) {
+    ui.set_max_width(MAX_WIDTH);
+
+    ui.add(egui::Label::new(RichText::new(&task.summary).strong()).wrap(true));
+
+    if !task.description.trim().is_empty() {
+        // Cut the description at a character, rather than part way through one
+        let description = match task.description.char_indices().nth(DESCRIPTION_CHARS) {
+            Some((end, _)) => format!("{}…", task.description[..end].trim_end()),
+            None => task.description.clone(),
+        };
+        ui.add(egui::Label::new(description).wrap(true));
+    }
+
+    if let Some(due) = task.due {
+        ui.label(format!("Due {}", due.format(DATE_FORMAT)));
+    }
+
+    if !task.tags.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            for tag in &task.tags {
+                ui.label(RichText::new(format!("#{tag}")).weak());
+            }
+        });
+    }
+
+    let progress = task.progress.get();
+    if progress != 0 {
+        ui.add(
+            egui::ProgressBar::new(f32::from(progress) / f32::from(MAX_PROGRESS)).show_percentage(),
+        );
+    }
+}
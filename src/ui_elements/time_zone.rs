@@ -0,0 +1,59 @@
+// ----------------------------------------------------------------------------
+// Window which asks what to do with the times on tasks when the computer's
+// time zone has changed, like after travelling.
+// ----------------------------------------------------------------------------
+
+use eframe::egui;
+
+use crate::timezone::{TimeZoneChange, TimeZonePolicy};
+
+pub struct TimeZoneWindow {
+    change: TimeZoneChange,
+    // Whether to do the same thing next time without asking
+    remember: bool,
+}
+
+impl TimeZoneWindow {
+    pub const fn new(change: TimeZoneChange) -> Self {
+        Self {
+            change,
+            remember: false,
+        }
+    }
+
+    pub const fn change(&self) -> TimeZoneChange {
+        self.change
+    }
+
+    // Shows the window. Returns what to do once the user has chosen, and
+    // whether to do it from now on without asking.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(TimeZonePolicy, bool)> {
+        let mut chosen = None;
+
+        egui::Window::new("Time zone changed")
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Your time zone has changed from {} to {}.",
+                    self.change.from.describe(),
+                    self.change.to.describe()
+                ));
+                ui.label(
+                    "Times on tasks, like when they're scheduled on the day planner, can stay \
+                    the same on the clock, or move so that they're at the same moment as before. \
+                    Due dates stay on the same days either way.",
+                );
+
+                ui.horizontal(|ui| {
+                    for policy in [TimeZonePolicy::FollowLocal, TimeZonePolicy::Anchored] {
+                        if ui.button(policy.name()).clicked() {
+                            chosen = Some(policy);
+                        }
+                    }
+                });
+                ui.checkbox(&mut self.remember, "Do this from now on without asking");
+            });
+
+        chosen.map(|policy| (policy, self.remember))
+    }
+}
@@ -0,0 +1,126 @@
+// ----------------------------------------------------------------------------
+// Window for moving or copying a task to another list. The lists to pick
+// from are the files lists have been opened from before, and they can be
+// searched by name, since there can be a lot of them.
+// ----------------------------------------------------------------------------
+
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+
+use crate::list_file;
+use crate::task::Task;
+use crate::transfer::TransferMode;
+
+// Task views ask for the window to be opened through egui's memory, since
+// they can't reach the rest of the app
+fn request_id() -> egui::Id {
+    egui::Id::new("transfer_request")
+}
+
+// Asks for the window to be opened for a task
+pub fn request(ctx: &egui::Context, uuid: uuid::Uuid, mode: TransferMode) {
+    ctx.data_mut(|d| d.insert_temp(request_id(), (uuid, mode)));
+}
+
+// The task the window was last asked to be opened for, if it hasn't been opened yet
+pub fn take_request(ctx: &egui::Context) -> Option<(uuid::Uuid, TransferMode)> {
+    ctx.data_mut(|d| {
+        let request = d.get_temp(request_id());
+        d.remove::<(uuid::Uuid, TransferMode)>(request_id());
+        request
+    })
+}
+
+pub struct TransferWindow {
+    // The task being moved or copied
+    pub uuid: uuid::Uuid,
+    summary: String,
+    pub mode: TransferMode,
+    // Lists the task can be sent to
+    lists: Vec<PathBuf>,
+    search: String,
+    picked: Option<PathBuf>,
+    // Why the last attempt didn't work
+    error: Option<String>,
+}
+
+// The name a list is shown with, which is its file's name
+fn list_name(path: &Path) -> String {
+    path.file_stem()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+impl TransferWindow {
+    // The list the task is in can't be picked
+    pub fn new(task: &Task, mode: TransferMode, current: Option<&Path>) -> Self {
+        let lists = list_file::known_lists()
+            .into_iter()
+            .filter(|path| Some(path.as_path()) != current)
+            .collect();
+        Self {
+            uuid: task.uuid,
+            summary: task.summary.clone(),
+            mode,
+            lists,
+            search: String::new(),
+            picked: None,
+            error: None,
+        }
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    // Shows the window. Returns the list to send the task to, once one is picked.
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<PathBuf> {
+        let mut sent = None;
+        let title = format!("{} task to list", self.mode.name());
+
+        egui::Window::new(title).open(open).show(ctx, |ui| {
+            ui.label(format!("{} '{}' to:", self.mode.name(), self.summary));
+
+            if self.lists.is_empty() {
+                ui.label("There are no other lists yet. Lists are added here once they're opened from a file.");
+                return;
+            }
+
+            let selected = self
+                .picked
+                .as_deref()
+                .map_or_else(|| String::from("Pick a list"), list_name);
+            egui::ComboBox::from_id_source("transfer_list")
+                .selected_text(selected)
+                .width(250.)
+                .show_ui(ui, |ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search"))
+                        .request_focus();
+                    let search = self.search.to_lowercase();
+                    for path in &self.lists {
+                        let name = list_name(path);
+                        if !name.to_lowercase().contains(&search) {
+                            continue;
+                        }
+                        ui.selectable_value(&mut self.picked, Some(path.clone()), name)
+                            .on_hover_text(path.display().to_string());
+                    }
+                });
+
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+
+            if ui
+                .add_enabled(self.picked.is_some(), egui::Button::new(self.mode.name()))
+                .clicked()
+            {
+                sent = self.picked.clone();
+            }
+        });
+
+        sent
+    }
+}
@@ -0,0 +1,116 @@
+// ----------------------------------------------------------------------------
+// Downloads task lists from a URL, like a list that another app publishes,
+// so that they can be imported the same way as a file that was opened.
+// Downloads happen on another thread, follow redirects and can be gzipped.
+// webcal:// links, which calendar apps use for subscriptions, are downloaded
+// over HTTPS. Downloading needs the app to be built with the `http` feature.
+// ----------------------------------------------------------------------------
+
+use std::{
+    error::Error,
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+};
+
+use eframe::egui;
+
+use crate::network::NetworkSettings;
+
+// Downloads bigger than this many bytes are stopped, since they can't be a task list anyone made
+#[cfg(feature = "http")]
+const MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Checks that a URL can be downloaded from, giving back the URL to download.
+/// webcal:// links are downloaded over HTTPS.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::url_import;
+///
+/// assert_eq!(
+///     url_import::download_url(" https://example.com/tasks.ics "),
+///     Some(String::from("https://example.com/tasks.ics"))
+/// );
+/// assert_eq!(
+///     url_import::download_url("webcal://example.com/tasks.ics"),
+///     Some(String::from("https://example.com/tasks.ics"))
+/// );
+/// assert_eq!(url_import::download_url("ftp://example.com/tasks.ics"), None);
+/// assert_eq!(url_import::download_url("https://"), None);
+/// ```
+pub fn download_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let (scheme, rest) = url.split_once("://")?;
+    let scheme = scheme.to_ascii_lowercase();
+    let scheme = match scheme.as_str() {
+        "http" | "https" => scheme.as_str(),
+        "webcal" | "webcals" => "https",
+        _ => return None,
+    };
+    if rest.is_empty() || rest.starts_with('/') {
+        return None;
+    }
+    Some(format!("{scheme}://{rest}"))
+}
+
+// A list being downloaded in the background
+pub struct Download {
+    url: String,
+    // What was downloaded, or why it couldn't be, once the download has finished
+    result: Arc<Mutex<Option<Result<Vec<u8>, String>>>>,
+}
+
+impl Download {
+    // Starts downloading from a URL, which should have been checked with download_url.
+    // The app is woken up when the download finishes.
+    pub fn start(ctx: &egui::Context, url: String, network: &NetworkSettings) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let finished = Arc::clone(&result);
+        let ctx = ctx.clone();
+        let thread_url = url.clone();
+        let network = network.clone();
+        thread::spawn(move || {
+            let downloaded = fetch(&thread_url, &network).map_err(|e| e.to_string());
+            *finished.lock().unwrap_or_else(PoisonError::into_inner) = Some(downloaded);
+            ctx.request_repaint();
+        });
+        Self { url, result }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    // What was downloaded, once the download has finished
+    pub fn take_result(&self) -> Option<Result<Vec<u8>, String>> {
+        self.result
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
+
+#[cfg(feature = "http")]
+fn fetch(url: &str, network: &NetworkSettings) -> Result<Vec<u8>, Box<dyn Error>> {
+    use std::io::Read;
+
+    let response = network
+        .agent()?
+        .get(url)
+        .set("Accept", "text/calendar, */*;q=0.8")
+        .call()?;
+    let mut contents = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_SIZE + 1)
+        .read_to_end(&mut contents)?;
+    if contents.len() as u64 > MAX_DOWNLOAD_SIZE {
+        return Err("the file is too big to be a task list".into());
+    }
+    Ok(contents)
+}
+
+#[cfg(not(feature = "http"))]
+fn fetch(_url: &str, _network: &NetworkSettings) -> Result<Vec<u8>, Box<dyn Error>> {
+    Err("this build of the app can't download lists, it needs the `http` feature".into())
+}
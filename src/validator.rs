@@ -0,0 +1,218 @@
+// ----------------------------------------------------------------------------
+// Checks iCal text against the parts of RFC 5545 that calendar apps are
+// strict about, so that problems with an exported file can be pointed out
+// before it's sent to Apple Calendar, Google Calendar or Thunderbird.
+// ----------------------------------------------------------------------------
+
+use std::fmt;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+// Lines longer than this many bytes have to be folded onto several lines
+const MAX_LINE_LENGTH: usize = 75;
+
+// Something in iCal text that doesn't follow RFC 5545
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    // Line the problem is on, counting from 1, or 0 if it's about the whole file
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "Line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+// Properties that have to appear exactly once in a component
+const REQUIRED_CALENDAR_PROPERTIES: [&str; 2] = ["PRODID", "VERSION"];
+const REQUIRED_TODO_PROPERTIES: [&str; 2] = ["UID", "DTSTAMP"];
+const REQUIRED_EVENT_PROPERTIES: [&str; 3] = ["UID", "DTSTAMP", "DTSTART"];
+
+// The STATUS values that a VTODO can have
+const TODO_STATUSES: [&str; 4] = ["NEEDS-ACTION", "COMPLETED", "IN-PROCESS", "CANCELLED"];
+
+/// Checks iCal text for anything that doesn't follow RFC 5545, such as
+/// missing properties, invalid values and lines that don't end with CRLF.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::validator;
+///
+/// let ical = "BEGIN:VCALENDAR\r\n\
+///     VERSION:2.0\r\n\
+///     PRODID:-//example//\r\n\
+///     BEGIN:VTODO\r\n\
+///     UID:1234\r\n\
+///     CREATED:20230801T150000\r\n\
+///     PRIORITY:12\r\n\
+///     END:VTODO\r\n\
+///     END:VCALENDAR\r\n";
+///
+/// let warnings: Vec<String> = validator::validate(ical)
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(
+///     warnings,
+///     vec![
+///         "Line 6: CREATED must be a date and time in UTC, like 20230807T090000Z",
+///         "Line 7: PRIORITY must be a number from 0 to 9",
+///         "Line 4: VTODO is missing DTSTAMP",
+///     ]
+/// );
+/// ```
+pub fn validate(ical: &str) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    if ical
+        .split('\n')
+        .any(|line| !line.is_empty() && !line.ends_with('\r'))
+    {
+        warnings.push(Warning {
+            line: 0,
+            message: String::from("Lines must end with CRLF, not just LF"),
+        });
+    }
+
+    // Long lines have to be checked before they're unfolded
+    for (index, line) in ical.lines().enumerate() {
+        if line.len() > MAX_LINE_LENGTH {
+            warnings.push(Warning {
+                line: index + 1,
+                message: format!("Lines longer than {MAX_LINE_LENGTH} bytes must be folded"),
+            });
+        }
+    }
+
+    // Components that are open, with the line they started on and the properties they have
+    let mut components: Vec<(String, usize, Vec<String>)> = Vec::new();
+
+    for (line_number, line) in unfold(ical) {
+        let mut warn = |message: String| {
+            warnings.push(Warning {
+                line: line_number,
+                message,
+            });
+        };
+
+        let Some((name, value)) = split_property(&line) else {
+            warn(String::from("Line isn't a property"));
+            continue;
+        };
+
+        match name.as_str() {
+            "BEGIN" => components.push((value.to_string(), line_number, Vec::new())),
+            "END" => match components.pop() {
+                Some((component, start, properties)) if component == value => {
+                    let required: &[&str] = match component.as_str() {
+                        "VCALENDAR" => &REQUIRED_CALENDAR_PROPERTIES,
+                        "VTODO" => &REQUIRED_TODO_PROPERTIES,
+                        "VEVENT" => &REQUIRED_EVENT_PROPERTIES,
+                        _ => &[],
+                    };
+                    for property in required {
+                        match properties.iter().filter(|name| name == property).count() {
+                            0 => warnings.push(Warning {
+                                line: start,
+                                message: format!("{component} is missing {property}"),
+                            }),
+                            1 => (),
+                            _ => warnings.push(Warning {
+                                line: start,
+                                message: format!("{component} has more than one {property}"),
+                            }),
+                        }
+                    }
+                }
+                _ => warn(format!("END:{value} doesn't match a BEGIN")),
+            },
+            _ => match components.last_mut() {
+                Some((component, _, properties)) => {
+                    if let Some(message) = check_value(component, &name, value) {
+                        warn(message);
+                    }
+                    properties.push(name);
+                }
+                None => warn(format!("{name} is outside of a component")),
+            },
+        }
+    }
+
+    for (component, start, _) in components {
+        warnings.push(Warning {
+            line: start,
+            message: format!("{component} is never ended"),
+        });
+    }
+
+    warnings
+}
+
+// Joins folded lines back together, keeping the number of the line each one started on
+fn unfold(ical: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in ical.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, previous))) => previous.push_str(rest),
+            _ if line.is_empty() => (),
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+// Splits a line into its property name, without parameters, and its value
+fn split_property(line: &str) -> Option<(String, &str)> {
+    let (name, value) = line.split_once(':')?;
+    let name = name.split(';').next()?.to_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+    Some((name, value))
+}
+
+// Checks that a property's value is the right type, and returns what's wrong if it isn't
+fn check_value(component: &str, name: &str, value: &str) -> Option<String> {
+    let valid = match name {
+        // These have to be in UTC, rather than in a time zone or floating
+        "DTSTAMP" | "CREATED" | "LAST-MODIFIED" | "COMPLETED" => {
+            value.ends_with('Z') && is_date_time(value)
+        }
+        "DUE" | "DTSTART" | "DTEND" => is_date_time(value) || is_date(value),
+        "PRIORITY" => value.parse::<u8>().is_ok_and(|priority| priority <= 9),
+        "PERCENT-COMPLETE" => value.parse::<u8>().is_ok_and(|percent| percent <= 100),
+        // Other components have their own statuses
+        "STATUS" => component != "VTODO" || TODO_STATUSES.contains(&value),
+        "UID" => !value.trim().is_empty(),
+        _ => true,
+    };
+    if valid {
+        return None;
+    }
+
+    Some(match name {
+        "DTSTAMP" | "CREATED" | "LAST-MODIFIED" | "COMPLETED" => {
+            format!("{name} must be a date and time in UTC, like 20230807T090000Z")
+        }
+        "DUE" | "DTSTART" | "DTEND" => format!("{name} must be a date or a date and time"),
+        "PRIORITY" => format!("{name} must be a number from 0 to 9"),
+        "PERCENT-COMPLETE" => format!("{name} must be a number from 0 to 100"),
+        "STATUS" => format!("{value} isn't a valid STATUS for a VTODO"),
+        _ => format!("{name} can't be empty"),
+    })
+}
+
+fn is_date_time(value: &str) -> bool {
+    let value = value.strip_suffix('Z').unwrap_or(value);
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").is_ok()
+}
+
+fn is_date(value: &str) -> bool {
+    NaiveDate::parse_from_str(value, "%Y%m%d").is_ok()
+}
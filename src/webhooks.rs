@@ -0,0 +1,213 @@
+// ----------------------------------------------------------------------------
+// Webhooks send what happens to tasks to other services, like a Slack or
+// Discord channel or a home automation server. When a task is added,
+// completed or becomes due soon, a JSON payload describing the event is
+// POSTed to every URL the user has set up. Sending happens on another
+// thread, and is tried again a few times, waiting longer each time, if the
+// server can't be reached or has a problem of its own. Sending needs the app
+// to be built with the `http` feature.
+// ----------------------------------------------------------------------------
+
+use std::collections::HashSet;
+use std::sync::mpsc;
+#[cfg(feature = "http")]
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::events::TaskEvent;
+use crate::hooks::{self, HookEvent, HookSettings};
+use crate::network::NetworkSettings;
+use crate::task::*;
+
+// The longest to wait before trying to send an event again, in seconds
+#[cfg(feature = "http")]
+const MAX_RETRY_DELAY: u64 = 5 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct WebhookSettings {
+    // Where events are sent to, one URL on each line
+    pub urls: String,
+    pub on_added: bool,
+    pub on_completed: bool,
+    pub on_due_soon: bool,
+    // How many times to try sending each event before giving up
+    pub attempts: u32,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            urls: String::new(),
+            on_added: true,
+            on_completed: true,
+            on_due_soon: true,
+            attempts: 5,
+        }
+    }
+}
+
+impl WebhookSettings {
+    // The URLs events are sent to, leaving out empty lines
+    pub fn url_list(&self) -> Vec<String> {
+        self.urls
+            .lines()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub const fn sends(&self, event: HookEvent) -> bool {
+        match event {
+            HookEvent::Added => self.on_added,
+            HookEvent::Completed => self.on_completed,
+            HookEvent::DueSoon => self.on_due_soon,
+        }
+    }
+}
+
+/// Creates the JSON that's sent for an event which happened to a task in a list.
+///
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use taskmaster_rs::hooks::HookEvent;
+/// use taskmaster_rs::task::*;
+/// use taskmaster_rs::webhooks;
+///
+/// let list = TaskList { name: "Home".into(), ..Default::default() };
+/// let task = Task { summary: "Water the plants".into(), ..Task::default() };
+/// let time = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(9, 30, 0).unwrap();
+///
+/// let payload = webhooks::payload(HookEvent::Completed, &list, &task, time);
+/// let json: serde_json::Value = serde_json::from_str(&payload).unwrap();
+/// assert_eq!(json["event"], "task-completed");
+/// assert_eq!(json["time"], "2024-05-01T09:30:00");
+/// assert_eq!(json["list"]["name"], "Home");
+/// assert_eq!(json["task"]["summary"], "Water the plants");
+/// ```
+pub fn payload(event: HookEvent, task_list: &TaskList, task: &Task, time: NaiveDateTime) -> String {
+    serde_json::json!({
+        "event": event.name(),
+        "time": time.format(crate::json::DATE_TIME_FORMAT).to_string(),
+        "list": {
+            "uid": task_list.uuid,
+            "name": task_list.name,
+        },
+        "task": task,
+    })
+    .to_string()
+}
+
+// Sends webhooks for changes to a task list, which it hears about from the
+// list's events, and for tasks that become due soon
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct WebhookSender {
+    // Events for changes to the list, once it has subscribed to them
+    events: Option<mpsc::Receiver<TaskEvent>>,
+    // Tasks which have already been sent as due soon
+    due_soon: HashSet<uuid::Uuid>,
+}
+
+impl WebhookSender {
+    // Starts sending webhooks for the events that are sent to the given receiver
+    pub fn listen(&mut self, events: mpsc::Receiver<TaskEvent>) {
+        self.events = Some(events);
+    }
+
+    // Sends webhooks for every event since the list was last checked, and for tasks
+    // that are due soon, which are held back while it's quiet, like hooks are.
+    // Should be called once every frame.
+    pub fn check(
+        &mut self,
+        task_list: &TaskList,
+        settings: &WebhookSettings,
+        hooks: &HookSettings,
+        network: &NetworkSettings,
+        quiet: bool,
+    ) {
+        let urls = settings.url_list();
+        let now = chrono::Local::now().naive_local();
+        let send = |event: HookEvent, task: &Task| {
+            if !urls.is_empty() && settings.sends(event) {
+                let body = payload(event, task_list, task, now);
+                deliver(urls.clone(), body, settings.attempts, network.clone());
+            }
+        };
+
+        for event in self.events.iter().flat_map(mpsc::Receiver::try_iter) {
+            match event {
+                TaskEvent::Added(task) => send(HookEvent::Added, &task),
+                TaskEvent::Completed(task) => send(HookEvent::Completed, &task),
+                TaskEvent::Removed(_) | TaskEvent::Updated(_) => (),
+            }
+        }
+
+        if quiet {
+            return;
+        }
+
+        // Only send each task once when it becomes due soon
+        let today = now.date();
+        for task in &task_list.tasks {
+            if hooks::is_due_soon(task, hooks, today) && self.due_soon.insert(task.uuid) {
+                send(HookEvent::DueSoon, task);
+            }
+        }
+    }
+}
+
+// How long to wait before trying to send an event again, doubling every time
+#[cfg(feature = "http")]
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(2_u64.saturating_pow(attempt).min(MAX_RETRY_DELAY))
+}
+
+// Sends an event to every URL in the background, trying again if it fails
+#[cfg(feature = "http")]
+fn deliver(urls: Vec<String>, body: String, attempts: u32, network: NetworkSettings) {
+    std::thread::spawn(move || {
+        let agent = match network.agent() {
+            Ok(agent) => agent,
+            Err(e) => {
+                eprintln!("could not send webhooks: {e}");
+                return;
+            }
+        };
+        for url in &urls {
+            for attempt in 1..=attempts.max(1) {
+                let result = agent
+                    .post(url)
+                    .set("Content-Type", "application/json")
+                    .send_string(&body);
+                match result {
+                    Ok(_) => break,
+                    // The server won't take the event however many times it's sent,
+                    // unless it's asking for it to be sent more slowly
+                    Err(ureq::Error::Status(code, _)) if code < 500 && code != 429 => {
+                        eprintln!("webhook {url} refused the event with status {code}");
+                        break;
+                    }
+                    Err(e) if attempt == attempts.max(1) => {
+                        eprintln!("could not send webhook to {url}, giving up: {e}");
+                    }
+                    Err(_) => std::thread::sleep(retry_delay(attempt)),
+                }
+            }
+        }
+    });
+}
+
+#[cfg(not(feature = "http"))]
+#[allow(clippy::needless_pass_by_value)]
+fn deliver(urls: Vec<String>, _body: String, _attempts: u32, _network: NetworkSettings) {
+    eprintln!(
+        "could not send webhooks to {}: this build of the app can't send them, it needs the `http` feature",
+        urls.join(", ")
+    );
+}
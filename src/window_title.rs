@@ -0,0 +1,83 @@
+// ----------------------------------------------------------------------------
+// Keeps the window's title showing how many tasks are due today and
+// overdue, so that they can be seen from the taskbar without opening the app.
+// ----------------------------------------------------------------------------
+
+use std::fmt::Write;
+
+use chrono::{Local, NaiveDate, NaiveTime};
+use eframe::egui;
+
+use crate::platform;
+use crate::task::{DueCounts, TaskList};
+
+// The app's name, which the window's title starts with
+pub const WINDOW_TITLE: &str = "Taskmaster";
+
+/// Creates the window title for a list with the given number of tasks
+/// due today and overdue. Counts of zero are left out.
+///
+/// Example:
+/// ```
+/// use taskmaster_rs::task::DueCounts;
+/// use taskmaster_rs::window_title;
+///
+/// let counts = DueCounts { due_today: 3, overdue: 1 };
+/// assert_eq!(window_title::text(counts), "Taskmaster — 3 due today, 1 overdue");
+///
+/// let counts = DueCounts { due_today: 0, overdue: 2 };
+/// assert_eq!(window_title::text(counts), "Taskmaster — 2 overdue");
+///
+/// assert_eq!(window_title::text(DueCounts::default()), "Taskmaster");
+/// ```
+pub fn text(counts: DueCounts) -> String {
+    let mut parts = Vec::new();
+    if counts.due_today > 0 {
+        parts.push(format!("{} due today", counts.due_today));
+    }
+    if counts.overdue > 0 {
+        parts.push(format!("{} overdue", counts.overdue));
+    }
+
+    let mut title = WINDOW_TITLE.to_string();
+    if !parts.is_empty() {
+        let _ = write!(title, " — {}", parts.join(", "));
+    }
+    title
+}
+
+// Updates the window's title when the tasks change or the day changes
+#[derive(Default)]
+pub struct WindowTitle {
+    // The title the window was last given, so it's only changed when it needs to be
+    current: String,
+    // The day the title was last worked out on
+    day: Option<NaiveDate>,
+}
+
+impl WindowTitle {
+    // Changes the title if the counts in it have changed.
+    // Should be called once every frame.
+    pub fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame, task_list: &TaskList) {
+        let now = Local::now().naive_local();
+        let today = now.date();
+
+        let title = text(task_list.due_counts(today));
+        if title != self.current {
+            platform::set_window_title(frame, &title);
+            self.current = title;
+        }
+
+        // Tasks that were due today become overdue at midnight, even if nothing
+        // else happens, so make sure the app wakes up then to update the title
+        if self.day != Some(today) {
+            self.day = Some(today);
+            let midnight = today
+                .succ_opt()
+                .map(|tomorrow| tomorrow.and_time(NaiveTime::MIN));
+            if let Some(wait) = midnight.and_then(|midnight| (midnight - now).to_std().ok()) {
+                ctx.request_repaint_after(wait);
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+// ----------------------------------------------------------------------------
+// Tests with files exported from other task apps, which check that each of
+// them imports with everything in the right place, despite each app's quirks,
+// and exports again without anything changing.
+// ----------------------------------------------------------------------------
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Europe::Berlin;
+
+use taskmaster_rs::compat::{self, Producer};
+use taskmaster_rs::task::*;
+
+// The files, as the apps exported them. Some aren't valid UTF-8 on their own.
+const APPLE_REMINDERS: &[u8] = include_bytes!("fixtures/apple_reminders.ics");
+const THUNDERBIRD: &[u8] = include_bytes!("fixtures/thunderbird.ics");
+const NEXTCLOUD_TASKS: &[u8] = include_bytes!("fixtures/nextcloud_tasks.ics");
+const OPENTASKS: &[u8] = include_bytes!("fixtures/opentasks.ics");
+
+fn import(bytes: &[u8]) -> TaskList {
+    TaskList::from_ical_reader(bytes).expect("could not import the file")
+}
+
+fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+// A time in Berlin, moved into local time like a time with TZID=Europe/Berlin is
+fn berlin(year: i32, month: u32, day: u32, hour: u32) -> NaiveDateTime {
+    Berlin
+        .with_ymd_and_hms(year, month, day, hour, 0, 0)
+        .unwrap()
+        .with_timezone(&Local)
+        .naive_local()
+}
+
+fn task<'a>(list: &'a TaskList, summary: &str) -> &'a Task {
+    list.tasks
+        .iter()
+        .find(|task| task.summary == summary)
+        .unwrap_or_else(|| panic!("there's no task called {summary}"))
+}
+
+#[test]
+fn producers_are_detected() {
+    for (bytes, producer) in [
+        (APPLE_REMINDERS, Producer::AppleReminders),
+        (THUNDERBIRD, Producer::Thunderbird),
+        (NEXTCLOUD_TASKS, Producer::NextcloudTasks),
+        (OPENTASKS, Producer::OpenTasks),
+    ] {
+        assert_eq!(
+            compat::detect_producer(&compat::decode(bytes)),
+            producer,
+            "{}",
+            producer.name()
+        );
+    }
+    let exported = import(THUNDERBIRD).to_ical_string();
+    assert_eq!(compat::detect_producer(&exported), Producer::Taskmaster);
+}
+
+#[test]
+fn fixtures_round_trip() {
+    for bytes in [APPLE_REMINDERS, THUNDERBIRD, NEXTCLOUD_TASKS, OPENTASKS] {
+        let list = import(bytes);
+        let ical = list.to_ical_string();
+        let parsed = TaskList::from_ical_str(&ical).expect("could not import the exported file");
+        assert_eq!(parsed, list, "{ical}");
+    }
+}
+
+#[test]
+fn apple_reminders_imports() {
+    let list = import(APPLE_REMINDERS);
+    assert_eq!(list.name, "Groceries");
+    assert_eq!(list.tasks.len(), 2);
+
+    // The alarm's UID and description don't belong to the task
+    let milk = task(&list, "Buy oat milk");
+    assert_eq!(
+        milk.uuid.to_string(),
+        "3c5e1d7a-9b2f-4e61-8a0d-5f7c2b9e4a13"
+    );
+    assert_eq!(milk.description, "");
+    assert_eq!(milk.due, date(2024, 5, 3));
+    assert_eq!(milk.priority, Priority::new(1));
+    assert!(!milk.completed);
+    assert!(milk.pinned);
+
+    let bread = task(&list, "Bread");
+    assert!(bread.completed);
+    assert!(bread.completed_at.is_some());
+    assert_eq!(bread.progress.get(), 100);
+    assert!(!bread.pinned);
+
+    // Reminders shows the list in its own order, rather than the file's
+    let order: Vec<&str> = list
+        .display_order_with(TaskSort::Manual, false)
+        .into_iter()
+        .map(|index| list.tasks[index].summary.as_str())
+        .collect();
+    assert_eq!(order, ["Bread", "Buy oat milk"]);
+}
+
+#[test]
+fn apple_reminders_properties_are_written_back() {
+    let ical = import(APPLE_REMINDERS).to_ical_string();
+    assert!(ical.contains("X-APPLE-SORT-ORDER:735998101\r\nX-APPLE-FLAGGED:TRUE\r\n"));
+    assert!(ical.contains("X-APPLE-SORT-ORDER:735998050\r\n"));
+}
+
+#[test]
+fn thunderbird_imports() {
+    let list = import(THUNDERBIRD);
+    assert_eq!(list.tasks.len(), 2);
+
+    // The line was folded in the middle of the ü
+    let kitchen = task(&list, "Renovate the kitchen");
+    assert_eq!(
+        kitchen.description,
+        "Tiles for the floor and paint for the walls. \
+        The new Küche should be finished before the guests arrive."
+    );
+    // The times are in Berlin, so they're moved into local time
+    assert_eq!(kitchen.due, Some(berlin(2024, 5, 17, 17).date()));
+    assert_eq!(kitchen.status, Status::InProgress);
+    assert_eq!(kitchen.progress.get(), 40);
+    assert_eq!(kitchen.tags, vec!["Home"]);
+    assert_eq!(
+        kitchen.scheduled.map(|block| block.start),
+        Some(berlin(2024, 5, 10, 9))
+    );
+
+    assert_eq!(task(&list, "Pick the tiles").parent, Some(kitchen.uuid));
+}
+
+#[test]
+fn nextcloud_tasks_imports() {
+    let list = import(NEXTCLOUD_TASKS);
+    assert_eq!(list.name, "Work");
+
+    // The report's UID isn't a UUID, but its subtask still finds it
+    let report = task(&list, "Quarterly report");
+    assert_eq!(
+        Some(report.uuid),
+        compat::uid_to_uuid("20240415T093000Z-8812@caldav.example.com")
+    );
+    assert_eq!(report.tags, vec!["reports", "finance", "q2"]);
+    assert_eq!(report.due, date(2024, 4, 30));
+    assert_eq!(report.priority, Priority::new(3));
+
+    let numbers = task(&list, "Collect the numbers");
+    assert_eq!(numbers.parent, Some(report.uuid));
+    assert_eq!(numbers.status, Status::InProgress);
+    assert_eq!(numbers.progress.get(), 60);
+
+    // The report is collapsed, so the numbers are hidden under it
+    assert!(report.hide_subtasks);
+    let numbers = list.tasks.iter().position(|task| task.uuid == numbers.uuid);
+    assert!(list.hidden_by_parent(numbers.unwrap()));
+}
+
+#[test]
+fn nextcloud_tasks_properties_are_written_back() {
+    let ical = import(NEXTCLOUD_TASKS).to_ical_string();
+    assert_eq!(ical.matches("X-OC-HIDESUBTASKS:1\r\n").count(), 1);
+    assert!(ical.contains("RELATED-TO;RELTYPE=PARENT:"));
+}
+
+#[test]
+fn nextcloud_tasks_progress_goes_with_status() {
+    let mut list = import(NEXTCLOUD_TASKS);
+    for task in &mut list.tasks {
+        if task.summary == "Collect the numbers" {
+            task.completed = true;
+        } else {
+            task.progress = Percent::new(10);
+        }
+    }
+
+    let list = compat::for_nextcloud_tasks(&list);
+    let numbers = task(&list, "Collect the numbers");
+    assert_eq!(numbers.status, Status::Completed);
+    assert_eq!(numbers.progress.get(), 100);
+    assert_eq!(task(&list, "Quarterly report").status, Status::InProgress);
+
+    // Which is what's read back, and nothing else changes
+    let ical = list.to_ical_string();
+    assert!(ical.contains("PERCENT-COMPLETE:100\r\nSTATUS:COMPLETED\r\n"));
+    assert!(ical.contains("PERCENT-COMPLETE:10\r\nSTATUS:IN-PROCESS\r\n"));
+    assert_eq!(TaskList::from_ical_str(&ical).unwrap(), list);
+    assert_eq!(compat::for_nextcloud_tasks(&list), list);
+}
+
+#[test]
+fn opentasks_imports() {
+    let list = import(OPENTASKS);
+    assert_eq!(list.tasks.len(), 1);
+
+    // ENCODING comes after a quoted parameter with a colon in it
+    let plumber = task(&list, "Call the plumber");
+    assert_eq!(
+        plumber.description,
+        "Ask about the boiler\nand the leaking tap"
+    );
+    assert_eq!(plumber.due, date(2024, 5, 8));
+    assert_eq!(plumber.priority, Priority::new(9));
+    // A start date without a time isn't when the task is scheduled
+    assert_eq!(plumber.scheduled, None);
+}
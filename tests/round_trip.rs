@@ -0,0 +1,276 @@
+// ----------------------------------------------------------------------------
+// Property-based tests which check that any task list can be exported to an
+// iCal file or JSON and read back in without anything about it changing.
+// ----------------------------------------------------------------------------
+
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use proptest::prelude::*;
+
+use taskmaster_rs::board::Swimlanes;
+use taskmaster_rs::color::Color;
+use taskmaster_rs::filters::TaskFilter;
+use taskmaster_rs::layouts::ListLayout;
+use taskmaster_rs::parser::LineEnding;
+use taskmaster_rs::task::*;
+use taskmaster_rs::validator;
+
+fn parse(ical: &str) -> Result<TaskList, taskmaster_rs::parser::ParseFromFileError> {
+    TaskList::from_ical_str(ical)
+}
+
+// Text matching the pattern, with its line breaks the way they're read back.
+// Line breaks that were CRLF or CR are written as \n, like any other.
+fn text(pattern: &'static str) -> impl Strategy<Value = String> {
+    pattern.prop_map(|text| text.replace("\r\n", "\n").replace('\r', "\n"))
+}
+
+// Any date and time from 1970 to 2100, to the second, which is as precise as iCal files are.
+// Times are written in UTC, so ones that happen twice or never in local time, when the
+// clocks change, can't be read back the same and are left out.
+fn date_time() -> impl Strategy<Value = chrono::NaiveDateTime> {
+    (0..4_102_444_800_i64)
+        .prop_map(|seconds| {
+            NaiveDate::from_ymd_opt(1970, 1, 1)
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .expect("could not create the start date")
+                + Duration::seconds(seconds)
+        })
+        .prop_filter("time isn't once in local time", |time| {
+            Local.from_local_datetime(time).single().is_some()
+        })
+}
+
+// A repeat rule, like FREQ=WEEKLY;INTERVAL=2
+fn recurrence() -> impl Strategy<Value = String> {
+    (
+        prop::sample::select(vec!["DAILY", "WEEKLY", "MONTHLY", "YEARLY"]),
+        1..=12_u32,
+    )
+        .prop_map(|(frequency, interval)| format!("FREQ={frequency};INTERVAL={interval}"))
+}
+
+fn task() -> impl Strategy<Value = Task> {
+    (
+        any::<u128>(),
+        text(".*"),
+        text("(.|\n)*"),
+        proptest::option::of("https://[a-z]{1,20}\\.com/[a-z0-9/?=&]*"),
+        (0..=MAX_PROGRESS).prop_map(Percent::new),
+        (0..=MAX_PRIORITY).prop_map(Priority::new),
+        prop::sample::select(Status::iterator().cloned().collect::<Vec<_>>()),
+        proptest::option::of(date_time()),
+        date_time(),
+        proptest::option::of((date_time(), 0..=1440_u32)),
+        // Tags can have any characters apart from whitespace at either end or
+        // a colon at the start, and a task doesn't have the same tag twice
+        prop::collection::btree_set("[^\\s\\p{C}:](\\PC*[^\\s\\p{C}])?", 0..4),
+        // When the task was last touched, how long it's expected to take,
+        // the day it was picked for My Day, how it repeats, its parent task,
+        // when it was completed, where it goes in the manual order, whether it's pinned,
+        // whether its subtasks are hidden and where it is on the kanban board
+        (
+            date_time(),
+            proptest::option::of(0..=10_000_u32),
+            proptest::option::of(date_time()),
+            proptest::option::of(recurrence()),
+            prop::collection::btree_set(date_time().prop_map(|time| time.date()), 0..4),
+            proptest::option::of(any::<u128>()),
+            proptest::option::of(date_time()),
+            proptest::option::of(any::<i64>()),
+            any::<bool>(),
+            any::<bool>(),
+            proptest::option::of(any::<i64>()),
+        ),
+    )
+        .prop_map(
+            |(
+                uuid,
+                summary,
+                description,
+                url,
+                progress,
+                priority,
+                status,
+                due,
+                created,
+                scheduled,
+                tags,
+                (
+                    last_touched,
+                    estimate,
+                    my_day,
+                    recurrence,
+                    exception_dates,
+                    parent,
+                    completed_at,
+                    sort_order,
+                    pinned,
+                    hide_subtasks,
+                    board_position,
+                ),
+            )| Task {
+                uuid: uuid::Uuid::from_u128(uuid),
+                summary,
+                // A completed task is always written with the completed status
+                completed: status == Status::Completed,
+                // Only completed tasks have a completion time
+                completed_at: completed_at.filter(|_| status == Status::Completed),
+                description,
+                url,
+                progress,
+                priority,
+                status,
+                due: due.map(|due| due.date()),
+                created,
+                scheduled: scheduled.map(|(start, minutes)| TimeBlock { start, minutes }),
+                tags: tags.into_iter().collect(),
+                last_touched,
+                estimate,
+                my_day: my_day.map(|day| day.date()),
+                recurrence,
+                exception_dates: exception_dates.into_iter().collect(),
+                parent: parent.map(uuid::Uuid::from_u128),
+                sort_order,
+                pinned,
+                hide_subtasks,
+                board_position,
+            },
+        )
+}
+
+fn layout() -> impl Strategy<Value = ListLayout> {
+    (
+        text(".*"),
+        any::<bool>(),
+        prop::sample::select(TaskSort::iterator().copied().collect::<Vec<_>>()),
+        any::<bool>(),
+        prop::sample::select(TaskFilter::iterator().copied().collect::<Vec<_>>()),
+        prop::collection::btree_map(
+            prop::sample::select(Status::iterator().cloned().collect::<Vec<_>>()),
+            1..100usize,
+            0..4,
+        ),
+        any::<bool>(),
+        prop::sample::select(Swimlanes::iterator().copied().collect::<Vec<_>>()),
+        prop::collection::btree_set(any::<u128>().prop_map(uuid::Uuid::from_u128), 0..4),
+    )
+        .prop_map(
+            |(
+                view,
+                show_completed_tasks,
+                sort_by,
+                sort_descending,
+                filter,
+                column_limits,
+                block_full_columns,
+                swimlanes,
+                expanded_cards,
+            )| ListLayout {
+                view,
+                show_completed_tasks,
+                sort_by,
+                sort_descending,
+                filter,
+                column_limits,
+                block_full_columns,
+                swimlanes,
+                expanded_cards,
+            },
+        )
+}
+
+fn task_list() -> impl Strategy<Value = TaskList> {
+    (
+        any::<u128>(),
+        text(".*"),
+        proptest::option::of(text("(.|\n)*")),
+        any::<(u8, u8, u8)>(),
+        prop::collection::vec(task(), 0..20),
+        date_time(),
+        date_time(),
+        proptest::option::of(layout()),
+        // Tag names are like the ones on tasks
+        prop::collection::btree_map(
+            "[^\\s\\p{C}:](\\PC*[^\\s\\p{C}])?",
+            any::<(u8, u8, u8)>().prop_map(|(r, g, b)| Color::from_rgb(r, g, b)),
+            0..4,
+        ),
+    )
+        .prop_map(
+            |(uuid, name, description, (r, g, b), tasks, created, modified, layout, tag_colors)| {
+                TaskList {
+                    uuid: uuid::Uuid::from_u128(uuid),
+                    name,
+                    description,
+                    tasks,
+                    color: Color::from_rgb(r, g, b),
+                    created,
+                    modified,
+                    layout,
+                    tag_colors,
+                    ..Default::default()
+                }
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn task_list_round_trips(list in task_list()) {
+        for line_ending in LineEnding::iterator() {
+            let ical = list.to_ical_string_with(*line_ending);
+            prop_assert_eq!(parse(&ical).ok(), Some(list.clone()), "{}", ical);
+        }
+    }
+
+    #[test]
+    fn task_list_round_trips_through_json(list in task_list()) {
+        let json = serde_json::to_string(&list).expect("could not serialize the list");
+        let parsed: TaskList = serde_json::from_str(&json).expect("could not deserialize the list");
+        prop_assert_eq!(parsed, list, "{}", json);
+    }
+
+    // Statuses other apps made up are written back the way they were read
+    #[test]
+    fn custom_status_round_trips(mut list in task_list(), status in "X-[A-Z]{1,20}") {
+        for task in &mut list.tasks {
+            task.completed = false;
+            task.completed_at = None;
+            task.status = Status::Custom(status.clone());
+        }
+        let ical = list.to_ical_string();
+        prop_assert_eq!(parse(&ical).ok(), Some(list), "{}", ical);
+    }
+
+    // Numbers that are out of range are brought into it rather than failing the import
+    #[test]
+    fn out_of_range_numbers_are_clamped(progress in any::<i32>(), priority in any::<i32>()) {
+        let ical = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nPERCENT-COMPLETE:{progress}\r\nPRIORITY:{priority}\r\nEND:VTODO\r\nEND:VCALENDAR\r\n"
+        );
+        let list = parse(&ical).expect("could not parse the list");
+        prop_assert_eq!(i32::from(list.tasks[0].progress.get()), progress.clamp(0, 100));
+        prop_assert_eq!(i32::from(list.tasks[0].priority.get()), priority.clamp(0, 9));
+    }
+
+    #[test]
+    fn exported_list_is_valid(list in task_list()) {
+        let warnings = validator::validate(&list.to_ical_string());
+        prop_assert!(warnings.is_empty(), "{:?}", warnings);
+    }
+
+    // Whatever the file contains, reading it gives an error rather than crashing
+    #[test]
+    fn parsing_never_panics(ical in "(\\PC|\r|\n)*") {
+        let _ = parse(&ical);
+    }
+
+    // Real files are mostly made of valid lines, which random text hardly ever is
+    #[test]
+    fn parsing_lines_never_panics(lines in prop::collection::vec(
+        "(BEGIN|END|UID|SUMMARY|DUE|DTSTART|DURATION|CREATED|PRIORITY|STATUS|X-WR-CALNAME)(;[A-Z]+=[^:]*)?:\\PC*",
+        0..20,
+    )) {
+        let _ = parse(&lines.join("\r\n"));
+    }
+}